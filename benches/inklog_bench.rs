@@ -1,13 +1,14 @@
 // Copyright (c) 2026 Kirky.X
 // SPDX-License-Identifier: MIT
 use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use inklog::domain::core::{LoggerSubscriber, ThreadBatchConfig};
 use inklog::masking::{self, DataMasker};
 #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
 use inklog::sink::database::convert_logs_to_parquet;
 use inklog::tokio::runtime::Runtime;
 use inklog::tracing::Level;
 use inklog::{
-    InklogConfig, LoggerManager,
+    InklogConfig, LoggerManager, Metrics,
     config::{FileSinkConfig, PerformanceConfig},
     log_record::LogRecord,
     template::LogTemplate,
@@ -18,6 +19,7 @@ use rayon::prelude::*;
 #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tempfile::TempDir;
 use tracing_subscriber::prelude::*;
@@ -538,6 +540,103 @@ fn bench_concurrency(c: &mut Criterion) {
     group.finish();
 }
 
+// ============ Producer Contention Benchmark ============
+
+/// Logs `per_thread` events from each of `threads` producers through a bare
+/// `LoggerSubscriber`, with a drain thread standing in for the workers.
+fn run_contended_producers(
+    threads: usize,
+    per_thread: usize,
+    batching: Option<ThreadBatchConfig>,
+) -> Duration {
+    let (console_tx, console_rx) = crossbeam_channel::bounded(1024);
+    let (async_tx, async_rx) = crossbeam_channel::bounded(65536);
+    let mut layer = LoggerSubscriber::new(console_tx, async_tx, Arc::new(Metrics::new()));
+    let batch_rx = match batching {
+        Some(config) => {
+            let (batch_tx, batch_rx) = crossbeam_channel::bounded(65536);
+            layer = layer.with_thread_batching(config, batch_tx, batch_rx.clone());
+            batch_rx
+        }
+        None => crossbeam_channel::never(),
+    };
+    let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(layer));
+
+    let total = threads * per_thread;
+    let drain = std::thread::spawn(move || {
+        let mut received = 0;
+        while received < total {
+            let _ = console_rx.try_recv();
+            let count = crossbeam_channel::select! {
+                recv(async_rx) -> record => record.map_or(0, |_| 1),
+                recv(batch_rx) -> batch => batch.map_or(0, |batch| batch.len()),
+                default(Duration::from_secs(5)) => 0,
+            };
+            if count == 0 {
+                break;
+            }
+            received += count;
+        }
+        received
+    });
+
+    let start = Instant::now();
+    let producers: Vec<_> = (0..threads)
+        .map(|t| {
+            let dispatch = dispatch.clone();
+            std::thread::spawn(move || {
+                tracing::dispatcher::with_default(&dispatch, || {
+                    for i in 0..per_thread {
+                        tracing::info!(thread = t, iteration = i, "contended log");
+                    }
+                });
+            })
+        })
+        .collect();
+    for producer in producers {
+        producer.join().expect("Benchmark setup failed");
+    }
+    let received = drain.join().expect("Benchmark setup failed");
+    let elapsed = start.elapsed();
+    assert_eq!(received, total, "every record should reach the channel");
+    elapsed
+}
+
+fn bench_thread_batching(c: &mut Criterion) {
+    let mut group = c.benchmark_group("producer_contention");
+    group.measurement_time(Duration::from_secs(10));
+    group.throughput(Throughput::Elements(8 * 2000));
+
+    // Every event sends to the shared channel on its own
+    group.bench_function("8_threads_unbatched", |b| {
+        b.iter_custom(|iters| {
+            (0..iters)
+                .map(|_| run_contended_producers(8, 2000, None))
+                .sum()
+        })
+    });
+
+    // Each thread sends its events to the channel in bursts of 32
+    group.bench_function("8_threads_batched_32", |b| {
+        b.iter_custom(|iters| {
+            (0..iters)
+                .map(|_| {
+                    run_contended_producers(
+                        8,
+                        2000,
+                        Some(ThreadBatchConfig {
+                            max_records: 32,
+                            max_delay: Duration::from_millis(5),
+                        }),
+                    )
+                })
+                .sum()
+        })
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_log_creation,
@@ -552,6 +651,7 @@ criterion_group!(
     bench_masking,
     bench_backpressure,
     bench_concurrency,
+    bench_thread_batching,
     bench_object_pool,
     bench_zero_allocation
 );
@@ -39,6 +39,8 @@ async fn basic_console() -> Result<(), Box<dyn std::error::Error>> {
         colored: false,
         stderr_levels: vec![],
         masking_enabled: false,
+        synchronous: false,
+        ..Default::default()
     };
     let sink = ConsoleSink::new(config, inklog::LogTemplate::default());
 
@@ -85,6 +87,8 @@ async fn colored_console() -> Result<(), Box<dyn std::error::Error>> {
         colored: true,
         stderr_levels: vec![],
         masking_enabled: false,
+        synchronous: false,
+        ..Default::default()
     };
     let sink = ConsoleSink::new(config, inklog::LogTemplate::default());
 
@@ -140,6 +144,8 @@ async fn stderr_levels() -> Result<(), Box<dyn std::error::Error>> {
         colored: true,
         stderr_levels: vec!["error".to_string(), "warn".to_string()],
         masking_enabled: false,
+        synchronous: false,
+        ..Default::default()
     };
     let sink = ConsoleSink::new(config, inklog::LogTemplate::default());
 
@@ -105,6 +105,7 @@ fn memory_database() -> Result<(), Box<dyn std::error::Error>> {
         table_name: "logs".to_string(),
         archive_format: "json".to_string(),
         parquet_config: Default::default(),
+        ..Default::default()
     };
 
     println!("数据库配置:");
@@ -242,6 +243,7 @@ fn batch_write() -> Result<(), Box<dyn std::error::Error>> {
         table_name: "logs".to_string(),
         archive_format: "json".to_string(),
         parquet_config: Default::default(),
+        ..Default::default()
     };
 
     println!("批次配置:");
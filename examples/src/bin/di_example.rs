@@ -47,6 +47,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 模式 3: 使用 with_dependencies（真实适配器）
     println!("\n3. 使用 with_dependencies (真实适配器):");
+    // 启用数据库 feature 时 `..Default::default()` 会补全 database 字段
+    #[allow(clippy::needless_update)]
     let deps = LoggerDependencies {
         cache: Some(Arc::new(OxCacheAdapter::new()?)),
         config: Some(Arc::new(InklogConfigAdapter::from_config(
@@ -165,13 +167,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 模式 9: 使用 Mock 类型创建 Logger（测试场景）
     println!("\n9. 使用 Mock 类型创建 Logger:");
-    let container = InklogContainer::builder()
+    let builder = InklogContainer::builder()
         .cache(Arc::new(MockCache::new()))
         .config(Arc::new(
             MockConfig::new().with_value("global.level", "info"),
-        ))
-        .database(Arc::new(MockDatabaseAdapter::new()))
-        .build()?;
+        ));
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    let builder = builder.database(Arc::new(MockDatabaseAdapter::new()));
+    let container = builder.build()?;
 
     let _logger = container.create_logger().await?;
     println!("   - 使用 Mock 依赖创建 Logger 成功!");
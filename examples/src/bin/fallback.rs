@@ -166,6 +166,8 @@ async fn multi_sink_config() -> Result<(), Box<dyn std::error::Error>> {
         colored: true,
         stderr_levels: vec!["error".to_string(), "warn".to_string()],
         masking_enabled: false,
+        synchronous: false,
+        ..Default::default()
     };
     let console_sink = ConsoleSink::new(console_config, LogTemplate::new("[{level}] {message}"));
     println!("Console Sink: 已配置");
@@ -187,6 +189,7 @@ async fn multi_sink_config() -> Result<(), Box<dyn std::error::Error>> {
         batch_size: 10,
         flush_interval_ms: 100,
         masking_enabled: false,
+        ..Default::default()
     };
     let file_sink = FileSink::new(file_config)?;
     println!("File Sink: 已配置");
@@ -294,6 +297,7 @@ async fn simulate_failure() -> Result<(), Box<dyn std::error::Error>> {
         batch_size: 1,
         flush_interval_ms: 10,
         masking_enabled: false,
+        ..Default::default()
     };
     let backup_sink = FileSink::new(backup_config)?;
     println!("备用 File Sink: 已就绪");
@@ -304,6 +308,8 @@ async fn simulate_failure() -> Result<(), Box<dyn std::error::Error>> {
         colored: true,
         stderr_levels: vec![],
         masking_enabled: false,
+        synchronous: false,
+        ..Default::default()
     };
     let console_sink = ConsoleSink::new(primary_config, LogTemplate::new("[{level}] {message}"));
 
@@ -469,6 +475,7 @@ async fn fallback_demo() -> Result<(), Box<dyn std::error::Error>> {
         batch_size: 10,
         flush_interval_ms: 100,
         masking_enabled: false,
+        ..Default::default()
     };
     let primary_sink = FileSink::new(primary_config)?;
 
@@ -488,6 +495,7 @@ async fn fallback_demo() -> Result<(), Box<dyn std::error::Error>> {
         batch_size: 10,
         flush_interval_ms: 100,
         masking_enabled: false,
+        ..Default::default()
     };
     let fallback_sink = FileSink::new(fallback_config)?;
 
@@ -66,6 +66,7 @@ async fn basic_file() -> Result<(), Box<dyn std::error::Error>> {
         batch_size: 100,
         flush_interval_ms: 1000,
         masking_enabled: false,
+        ..Default::default()
     };
 
     // 创建 FileSink
@@ -132,6 +133,7 @@ async fn file_rotation() -> Result<(), Box<dyn std::error::Error>> {
         batch_size: 1,         // 每条日志立即 flush
         flush_interval_ms: 10, // 10ms flush 间隔
         masking_enabled: false,
+        ..Default::default()
     };
 
     // 创建 FileSink
@@ -227,6 +229,7 @@ async fn file_compression() -> Result<(), Box<dyn std::error::Error>> {
         batch_size: 1,         // 每条日志立即 flush
         flush_interval_ms: 10, // 10ms flush 间隔
         masking_enabled: false,
+        ..Default::default()
     };
 
     // 创建 FileSink
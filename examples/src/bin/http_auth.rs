@@ -152,12 +152,15 @@ fn show_full_secure_config() {
         port: 9090,
         metrics_path: "/metrics".to_string(),
         health_path: "/health".to_string(),
+        ready_path: "/ready".to_string(),
+        ready_channel_watermark: 0.9,
         error_mode: HttpErrorMode::Strict,
         auth: Some(HttpAuthConfig {
             enabled: true,
             token_env: "INKLOG_HTTP_AUTH_TOKEN".to_string(),
         }),
         ip_whitelist: Some(vec!["10.0.0.0/8".to_string(), "127.0.0.1".to_string()]),
+        allow_unauthenticated_level_change: false,
     };
 
     println!("生产级 HttpServerConfig：");
@@ -24,6 +24,8 @@ pub fn create_console_config(colored: bool, stderr_levels: Vec<String>) -> Conso
         colored,
         stderr_levels,
         masking_enabled: false,
+        synchronous: false,
+        ..Default::default()
     }
 }
 
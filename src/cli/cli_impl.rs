@@ -1,11 +1,13 @@
 // Copyright (c) 2026 Kirky.X
 // SPDX-License-Identifier: MIT
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use clap::Parser;
+use inklog::{ConfigFormat, LineDelimiter};
 use std::path::PathBuf;
 
+use super::tail::{TailFilter, TailOptions};
 use super::{Cli, Commands};
-use super::{decrypt, generate, validate};
+use super::{decrypt, generate, read, rotate, tail, validate};
 
 pub fn run_cli() -> Result<()> {
     let args = Cli::parse();
@@ -17,7 +19,20 @@ pub fn run_cli() -> Result<()> {
             key_env,
             recursive,
             batch,
+            stdout,
         } => {
+            if stdout {
+                if !input.is_file() {
+                    return Err(anyhow!(
+                        "--stdout requires a single input file: {}",
+                        input.display()
+                    ));
+                }
+                let stdout = std::io::stdout();
+                decrypt::decrypt_file_to_writer(&input, &mut stdout.lock(), &key_env)?;
+                return Ok(());
+            }
+
             let output = output.unwrap_or_else(|| {
                 if input.is_file() {
                     input.with_extension("decrypted.log")
@@ -45,7 +60,10 @@ pub fn run_cli() -> Result<()> {
             output,
             config_type,
             env_example,
+            format,
+            schema,
         } => {
+            let format: ConfigFormat = format.parse().map_err(|e| anyhow!("{}", e))?;
             let output_path = output.unwrap_or_else(|| PathBuf::from("."));
             let output_path = if output_path.is_dir() {
                 output_path
@@ -56,16 +74,70 @@ pub fn run_cli() -> Result<()> {
                     .to_path_buf()
             };
 
-            generate::generate_config(&output_path, &config_type)?;
+            if schema {
+                generate::generate_schema(&output_path)?;
+                return Ok(());
+            }
+            generate::generate_config(&output_path, &config_type, format)?;
 
             if env_example {
                 generate::generate_env_example(&output_path)?;
             }
         }
 
+        Commands::Read {
+            input,
+            delimiter,
+            max_line_bytes,
+        } => {
+            let delimiter: LineDelimiter = delimiter.parse().map_err(|e: String| anyhow!(e))?;
+            let max_record_bytes = (max_line_bytes > 0).then_some(max_line_bytes);
+            let stdout = std::io::stdout();
+            read::read_log_file(&input, delimiter, max_record_bytes, &mut stdout.lock())?;
+        }
+
+        Commands::Rotate { config, name } => {
+            let stdout = std::io::stdout();
+            rotate::rotate(&config, name.as_deref(), &mut stdout.lock())?;
+        }
+
+        Commands::Tail {
+            config,
+            source,
+            level,
+            target,
+            since,
+            lines,
+            follow,
+        } => {
+            let source = source
+                .map(|s| s.parse().map_err(|e: String| anyhow!(e)))
+                .transpose()?;
+            let level = level
+                .map(|l| {
+                    l.parse::<inklog::tracing::Level>()
+                        .map_err(|_| anyhow!("Invalid level '{}'", l))
+                })
+                .transpose()?;
+            let since = since.as_deref().map(tail::parse_since).transpose()?;
+            let options = TailOptions {
+                source,
+                filter: TailFilter {
+                    level,
+                    target,
+                    since,
+                },
+                lines,
+                follow,
+            };
+            let stdout = std::io::stdout();
+            tail::tail(&config, &options, &mut stdout.lock())?;
+        }
+
         Commands::Validate {
             config,
             prerequisites,
+            strict,
         } => {
             if prerequisites {
                 validate::check_prerequisites();
@@ -73,7 +145,11 @@ pub fn run_cli() -> Result<()> {
             }
 
             let config_path = config.unwrap_or_else(|| PathBuf::from("inklog_config.toml"));
-            validate::validate_config(&config_path)?;
+            if strict {
+                validate::validate_config_strict(&config_path)?;
+            } else {
+                validate::validate_config(&config_path)?;
+            }
         }
     }
 
@@ -4,7 +4,11 @@ use aes_gcm::Aes256Gcm;
 use aes_gcm::aead::{Aead, KeyInit};
 use anyhow::{Context, Result, anyhow};
 use base64::{Engine as _, engine::general_purpose};
-use inklog::sink::encryption::derive_key_from_password;
+use inklog::InklogError;
+use inklog::sink::encryption::{
+    CHUNKED_FORMAT_VERSION, ENCRYPTED_FILE_MAGIC, EncryptionKey, decrypt_chunked,
+    derive_key_from_password,
+};
 #[cfg(test)]
 use sha2::Digest as Sha256Digest;
 #[cfg(test)]
@@ -113,7 +117,7 @@ fn validate_glob_pattern(pattern: &str) -> Result<()> {
     Ok(())
 }
 
-const MAGIC_HEADER: &[u8] = b"ENCLOG1\0";
+const MAGIC_HEADER: &[u8] = ENCRYPTED_FILE_MAGIC;
 
 #[allow(dead_code)]
 pub fn decrypt_file(input_path: &PathBuf, output_path: &PathBuf, key_env: &str) -> Result<()> {
@@ -166,25 +170,112 @@ pub fn decrypt_file(input_path: &PathBuf, output_path: &PathBuf, key_env: &str)
     Ok(())
 }
 
-pub fn decrypt_file_compatible(
-    input_path: &PathBuf,
-    output_path: &PathBuf,
-    key_env: &str,
-) -> Result<()> {
-    let mut file = File::open(input_path)
-        .with_context(|| format!("Failed to open input file: {}", input_path.display()))?;
+/// 明文写出时的块大小
+const WRITE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 从 `reader` 读取直到填满 `buf` 或遇到 EOF，返回读取的字节数
+///
+/// 管道等流式输入单次 `read` 可能只返回部分数据，头部与 nonce 需按此方式读取。
+fn read_prefix(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
 
+/// 加载 `-k` 指定的解密密钥
+///
+/// 每项为 `ENV` 或 `ID=ENV`：`ENV` 为保存密钥的环境变量名，`ID` 为加密时写入
+/// 文件头部的密钥标识，省略时以变量名作为标识（与 FileSink 的默认行为一致）。
+fn load_keys(key_envs: &[String]) -> Result<Vec<EncryptionKey>> {
+    if key_envs.is_empty() {
+        return Err(anyhow!("No decryption key environment variable specified"));
+    }
+    key_envs
+        .iter()
+        .map(|spec| {
+            let (id, env_var) = spec.split_once('=').unwrap_or((spec, spec));
+            let key = get_encryption_key_cli(env_var).with_context(|| {
+                format!("Failed to get encryption key from env var: {}", env_var)
+            })?;
+            EncryptionKey::new(id, key).map_err(|e| anyhow!("{}", e))
+        })
+        .collect()
+}
+
+/// 将加密流解密写入 `out`
+///
+/// 分块格式（版本 2）按头部的密钥标识选择密钥，逐帧校验并写出，内存占用只有一帧；
+/// V1、旧格式与无头部的旧文件是单个 AES-GCM 密文，须整体读入并依次尝试各个密钥，
+/// 校验认证标签后才写出明文。
+fn decrypt_to_writer(
+    input: &mut impl Read,
+    out: &mut impl Write,
+    key_envs: &[String],
+    size_hint: usize,
+) -> Result<()> {
+    let keys = load_keys(key_envs)?;
     let mut header = [0u8; 24];
-    let read_count = file
-        .read(&mut header)
-        .with_context(|| "Failed to read file header")?;
+    let read_count =
+        read_prefix(input, &mut header).with_context(|| "Failed to read file header")?;
+
+    if read_count >= 10
+        && &header[..8] == MAGIC_HEADER
+        && u16::from_le_bytes([header[8], header[9]]) == CHUNKED_FORMAT_VERSION
+    {
+        let input = std::io::Cursor::new(&header[..read_count]).chain(input);
+        return decrypt_chunked(&keys, input, out).map_err(|e| match e {
+            InklogError::IoError(e) => anyhow::Error::new(e),
+            e => anyhow!("{}", e),
+        });
+    }
 
+    let plaintext = decrypt_single(&header[..read_count], input, &keys, size_hint)?;
+    for chunk in plaintext.chunks(WRITE_CHUNK_SIZE) {
+        out.write_all(chunk)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// 解密 V1 或旧格式的单块密文，`header` 为已读取的文件开头（至多 24 字节）
+///
+/// 不以魔数开头的文件按无头部的旧格式（`nonce(12) + 密文`）处理。
+fn decrypt_single(
+    header: &[u8],
+    input: &mut impl Read,
+    keys: &[EncryptionKey],
+    size_hint: usize,
+) -> Result<Vec<u8>> {
+    let read_count = header.len();
     if read_count < 10 {
         return Err(anyhow!("File too small to be a valid encrypted file"));
     }
 
     if &header[..8] != MAGIC_HEADER {
-        return Err(anyhow!("Invalid file header: not an encrypted inklog file"));
+        // 早期 FileSink 写出的文件：nonce (12 bytes) + ciphertext
+        // `header` 只是文件开头，须读完剩余部分后才能判断密文是否容纳认证标签
+        if read_count < 12 {
+            return Err(anyhow!("Invalid file header: not an encrypted inklog file"));
+        }
+        let nonce: [u8; 12] = header[..12].try_into().unwrap();
+        let mut ciphertext = Vec::with_capacity(size_hint.saturating_sub(12));
+        ciphertext.extend_from_slice(&header[12..]);
+        input
+            .read_to_end(&mut ciphertext)
+            .with_context(|| "Failed to read ciphertext")?;
+        if ciphertext.len() < 16 {
+            return Err(anyhow!("Invalid file header: not an encrypted inklog file"));
+        }
+        return decrypt_with_any_key(keys, nonce, &ciphertext).map_err(|_| {
+            anyhow!("Invalid file header: not an encrypted inklog file, or wrong decryption key")
+        });
     }
 
     let version = u16::from_le_bytes([header[8], header[9]]);
@@ -192,58 +283,93 @@ pub fn decrypt_file_compatible(
         return Err(anyhow!("Unsupported file version: {}", version));
     }
 
-    let key = get_encryption_key_cli(key_env)
-        .with_context(|| format!("Failed to get encryption key from env var: {}", key_env))?;
-
     let algo = u16::from_le_bytes([header[10], header[11]]);
-    let plaintext = if algo == 1 {
+    let mut ciphertext = Vec::with_capacity(size_hint.saturating_sub(read_count));
+    let nonce_bytes: [u8; 12] = if algo == 1 {
         if read_count < 24 {
             return Err(anyhow!("File too small for V1 format"));
         }
-        let nonce_slice: [u8; 12] = header[12..24].try_into().unwrap();
-        let nonce = aes_gcm::Nonce::from(nonce_slice);
-
-        let mut ciphertext = Vec::new();
-        file.read_to_end(&mut ciphertext)
-            .with_context(|| "Failed to read ciphertext")?;
-
-        let cipher = Aes256Gcm::new((&key).into());
-        cipher
-            .decrypt(&nonce, ciphertext.as_ref())
-            .map_err(|e| anyhow!("Decryption failed: {}", e))?
+        header[12..24].try_into().unwrap()
     } else {
         // Assume Legacy format (MAGIC + VER + NONCE + CIPHERTEXT)
         // Legacy header is 22 bytes (8 MAGIC + 2 VER + 12 NONCE)
         if read_count < 22 {
             return Err(anyhow!("File too small to be a valid encrypted file"));
         }
+        // If we read more than 22 bytes, the extras are part of the ciphertext
+        ciphertext.extend_from_slice(&header[22..read_count]);
+        header[10..22].try_into().unwrap()
+    };
 
-        let mut nonce_bytes = [0u8; 12];
-        nonce_bytes.copy_from_slice(&header[10..22]);
-        let nonce = aes_gcm::Nonce::from(nonce_bytes);
+    input
+        .read_to_end(&mut ciphertext)
+        .with_context(|| "Failed to read ciphertext")?;
 
-        let mut ciphertext = Vec::new();
-        // If we read more than 22 bytes, the extras are part of the ciphertext
-        if read_count > 22 {
-            ciphertext.extend_from_slice(&header[22..read_count]);
+    decrypt_with_any_key(keys, nonce_bytes, &ciphertext)
+}
+
+/// 依次用各个密钥解密单块密文，返回第一个通过认证的结果
+fn decrypt_with_any_key(
+    keys: &[EncryptionKey],
+    nonce: [u8; 12],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>> {
+    let mut last_error = anyhow!("No decryption key provided");
+    for key in keys {
+        let cipher = Aes256Gcm::new(key.bytes().into());
+        match cipher.decrypt(&aes_gcm::Nonce::from(nonce), ciphertext) {
+            Ok(plaintext) => return Ok(plaintext),
+            Err(e) => last_error = anyhow!("Decryption failed: {}", e),
         }
-        file.read_to_end(&mut ciphertext)
-            .with_context(|| "Failed to read ciphertext")?;
+    }
+    Err(last_error)
+}
 
-        let cipher = Aes256Gcm::new((&key).into());
-        cipher
-            .decrypt(&nonce, ciphertext.as_ref())
-            .map_err(|e| anyhow!("Decryption failed: {}", e))?
-    };
+fn open_encrypted(input_path: &Path) -> Result<(std::io::BufReader<File>, usize)> {
+    let file = File::open(input_path)
+        .with_context(|| format!("Failed to open input file: {}", input_path.display()))?;
+    let size_hint = file.metadata().map(|m| m.len() as usize).unwrap_or(0);
+    Ok((std::io::BufReader::new(file), size_hint))
+}
 
-    let mut output_file = File::create(output_path)
+pub fn decrypt_file_compatible(
+    input_path: &Path,
+    output_path: &Path,
+    key_envs: &[String],
+) -> Result<()> {
+    let (mut input, size_hint) = open_encrypted(input_path)?;
+
+    let output_file = File::create(output_path)
         .with_context(|| format!("Failed to create output file: {}", output_path.display()))?;
+    let mut output = std::io::BufWriter::new(output_file);
 
-    output_file
-        .write_all(&plaintext)
-        .with_context(|| "Failed to write decrypted data")?;
+    let result = decrypt_to_writer(&mut input, &mut output, key_envs, size_hint);
+    drop(output);
+    if result.is_err() {
+        // 不保留未完成或未通过校验的输出
+        let _ = std::fs::remove_file(output_path);
+    }
+    result
+}
 
-    Ok(())
+/// 解密文件并将明文写入 `out`（如标准输出），不创建输出文件
+///
+/// 下游提前关闭管道（如 `| head`）时视为正常结束。
+pub fn decrypt_file_to_writer(
+    input_path: &Path,
+    out: &mut impl Write,
+    key_envs: &[String],
+) -> Result<()> {
+    let (mut input, size_hint) = open_encrypted(input_path)?;
+    match decrypt_to_writer(&mut input, out, key_envs, size_hint) {
+        Err(e)
+            if e.downcast_ref::<std::io::Error>()
+                .is_some_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe) =>
+        {
+            Ok(())
+        }
+        result => result,
+    }
 }
 
 fn get_encryption_key_cli(env_var: &str) -> Result<[u8; 32]> {
@@ -295,7 +421,7 @@ fn get_encryption_key_cli(env_var: &str) -> Result<[u8; 32]> {
 pub fn decrypt_directory_compatible(
     input_dir: &PathBuf,
     output_dir: &PathBuf,
-    key_env: &str,
+    key_envs: &[String],
     recursive: bool,
 ) -> Result<()> {
     if !input_dir.exists() {
@@ -346,7 +472,7 @@ pub fn decrypt_directory_compatible(
                     output_path.display()
                 );
 
-                if let Err(e) = decrypt_file_compatible(&path, &output_path, key_env) {
+                if let Err(e) = decrypt_file_compatible(&path, &output_path, key_envs) {
                     eprintln!("Failed to decrypt {}: {}", path.display(), e);
                 }
             }
@@ -364,14 +490,14 @@ pub fn decrypt_directory_compatible(
                 continue;
             }
 
-            decrypt_directory_compatible(&path, &sub_output_dir, key_env, recursive)?;
+            decrypt_directory_compatible(&path, &sub_output_dir, key_envs, recursive)?;
         }
     }
 
     Ok(())
 }
 
-pub fn batch_decrypt(input_pattern: &str, output_dir: &PathBuf, key_env: &str) -> Result<()> {
+pub fn batch_decrypt(input_pattern: &str, output_dir: &PathBuf, key_envs: &[String]) -> Result<()> {
     // 验证 glob 模式安全性 - 防止路径遍历
     validate_glob_pattern(input_pattern)?;
 
@@ -412,7 +538,7 @@ pub fn batch_decrypt(input_pattern: &str, output_dir: &PathBuf, key_env: &str) -
             output_path.display()
         );
 
-        if let Err(e) = decrypt_file_compatible(&path, &output_path, key_env) {
+        if let Err(e) = decrypt_file_compatible(&path, &output_path, key_envs) {
             eprintln!("Failed to decrypt {}: {}", path.display(), e);
         }
     }
@@ -622,7 +748,7 @@ mod tests {
         let v1_text = b"V1 Content";
         create_encrypted_file_v1(&v1_file, v1_text, &test_key).unwrap();
 
-        decrypt_file_compatible(&v1_file, &v1_out, "TEST_KEY_COMPAT").unwrap();
+        decrypt_file_compatible(&v1_file, &v1_out, &["TEST_KEY_COMPAT".to_string()]).unwrap();
         assert_eq!(std::fs::read(&v1_out).unwrap(), v1_text);
 
         // Test Legacy format
@@ -631,7 +757,8 @@ mod tests {
         let legacy_text = b"Legacy Content";
         create_encrypted_file_legacy(&legacy_file, legacy_text, &test_key).unwrap();
 
-        decrypt_file_compatible(&legacy_file, &legacy_out, "TEST_KEY_COMPAT").unwrap();
+        decrypt_file_compatible(&legacy_file, &legacy_out, &["TEST_KEY_COMPAT".to_string()])
+            .unwrap();
         assert_eq!(std::fs::read(&legacy_out).unwrap(), legacy_text);
 
         unsafe {
@@ -739,4 +866,229 @@ mod tests {
             std::env::remove_var("TEST_LONG_KEY");
         };
     }
+
+    /// 每次 `read` 只返回一个字节，模拟管道的短读
+    struct OneByteReader<'a>(&'a [u8]);
+
+    impl Read for OneByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match (self.0.split_first(), buf.first_mut()) {
+                (Some((byte, rest)), Some(slot)) => {
+                    *slot = *byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn test_decrypt_to_writer_with_short_reads() {
+        let key = generate_test_key();
+        unsafe {
+            std::env::set_var("TEST_STREAM_KEY", general_purpose::STANDARD.encode(key));
+        };
+        let temp_dir = tempfile::tempdir().unwrap();
+        let plaintext = b"streamed line one\nstreamed line two\n";
+
+        for (name, create) in [
+            (
+                "v1.enc",
+                create_encrypted_file_v1 as fn(&PathBuf, &[u8], &[u8; 32]) -> Result<()>,
+            ),
+            ("legacy.enc", create_encrypted_file_legacy),
+        ] {
+            let path = temp_dir.path().join(name);
+            create(&path, plaintext, &key).unwrap();
+            let encrypted = std::fs::read(&path).unwrap();
+
+            let mut decrypted = Vec::new();
+            decrypt_to_writer(
+                &mut OneByteReader(&encrypted),
+                &mut decrypted,
+                &["TEST_STREAM_KEY".to_string()],
+                0,
+            )
+            .unwrap();
+            assert_eq!(decrypted, plaintext, "{name}");
+
+            let mut out = Vec::new();
+            decrypt_file_to_writer(&path, &mut out, &["TEST_STREAM_KEY".to_string()]).unwrap();
+            assert_eq!(out, plaintext, "{name}");
+        }
+
+        unsafe {
+            std::env::remove_var("TEST_STREAM_KEY");
+        };
+    }
+
+    #[test]
+    fn test_decrypt_chunked_file() {
+        let key = generate_test_key();
+        unsafe {
+            std::env::set_var("TEST_CHUNKED_KEY", general_purpose::STANDARD.encode(key));
+        };
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input_path = temp_dir.path().join("chunked.enc");
+        let output_path = temp_dir.path().join("chunked.log");
+        let plaintext: Vec<u8> = (0..10_000u32).flat_map(|i| i.to_le_bytes()).collect();
+
+        let mut encrypted = Vec::new();
+        let encryption_key = EncryptionKey::new("TEST_CHUNKED_KEY", key).unwrap();
+        inklog::sink::encryption::encrypt_chunked(
+            &encryption_key,
+            &plaintext[..],
+            &mut encrypted,
+            1000,
+        )
+        .unwrap();
+        std::fs::write(&input_path, &encrypted).unwrap();
+
+        decrypt_file_compatible(&input_path, &output_path, &["TEST_CHUNKED_KEY".to_string()])
+            .unwrap();
+        assert_eq!(std::fs::read(&output_path).unwrap(), plaintext);
+
+        let mut streamed = Vec::new();
+        decrypt_to_writer(
+            &mut OneByteReader(&encrypted),
+            &mut streamed,
+            &["TEST_CHUNKED_KEY".to_string()],
+            0,
+        )
+        .unwrap();
+        assert_eq!(streamed, plaintext);
+
+        // 截断最后一帧后解密失败，且不保留输出文件
+        std::fs::write(&input_path, &encrypted[..encrypted.len() - 1016]).unwrap();
+        std::fs::remove_file(&output_path).unwrap();
+        assert!(
+            decrypt_file_compatible(&input_path, &output_path, &["TEST_CHUNKED_KEY".to_string()])
+                .is_err()
+        );
+        assert!(!output_path.exists());
+
+        unsafe {
+            std::env::remove_var("TEST_CHUNKED_KEY");
+        };
+    }
+
+    #[test]
+    fn test_decrypt_selects_key_by_id_after_rotation() {
+        let old_key = get_test_key("old");
+        let new_key = get_test_key("new");
+        unsafe {
+            std::env::set_var(
+                "TEST_ROTATED_OLD_KEY",
+                general_purpose::STANDARD.encode(old_key),
+            );
+            std::env::set_var(
+                "TEST_ROTATED_NEW_KEY",
+                general_purpose::STANDARD.encode(new_key),
+            );
+        };
+        let temp_dir = tempfile::tempdir().unwrap();
+        let keys = [
+            "2026-10=TEST_ROTATED_NEW_KEY".to_string(),
+            "2026-09=TEST_ROTATED_OLD_KEY".to_string(),
+        ];
+
+        for (id, key, plaintext) in [
+            ("2026-09", old_key, b"before rotation".as_slice()),
+            ("2026-10", new_key, b"after rotation".as_slice()),
+        ] {
+            let input_path = temp_dir.path().join(format!("{id}.enc"));
+            let output_path = temp_dir.path().join(format!("{id}.log"));
+            let mut encrypted = Vec::new();
+            inklog::sink::encryption::encrypt_chunked(
+                &EncryptionKey::new(id, key).unwrap(),
+                plaintext,
+                &mut encrypted,
+                4,
+            )
+            .unwrap();
+            std::fs::write(&input_path, &encrypted).unwrap();
+
+            decrypt_file_compatible(&input_path, &output_path, &keys).unwrap();
+            assert_eq!(std::fs::read(&output_path).unwrap(), plaintext, "{id}");
+        }
+
+        // V1 与无头部的旧文件没有密钥标识，依次尝试各个密钥
+        let v1_path = temp_dir.path().join("v1.enc");
+        create_encrypted_file_v1(&v1_path, b"v1 with old key", &old_key).unwrap();
+        let mut out = Vec::new();
+        decrypt_file_to_writer(&v1_path, &mut out, &keys).unwrap();
+        assert_eq!(out, b"v1 with old key");
+
+        let headerless_path = temp_dir.path().join("headerless.enc");
+        let nonce = [7u8; 12];
+        let ciphertext = Aes256Gcm::new((&old_key).into())
+            .encrypt(&aes_gcm::Nonce::from(nonce), b"headerless".as_slice())
+            .unwrap();
+        std::fs::write(&headerless_path, [&nonce[..], &ciphertext].concat()).unwrap();
+        let mut out = Vec::new();
+        decrypt_file_to_writer(&headerless_path, &mut out, &keys).unwrap();
+        assert_eq!(out, b"headerless");
+
+        // 只提供新密钥时旧文件无法解密
+        let only_new = ["TEST_ROTATED_NEW_KEY".to_string()];
+        assert!(decrypt_file_to_writer(&v1_path, &mut Vec::new(), &only_new).is_err());
+        assert!(decrypt_file_to_writer(&headerless_path, &mut Vec::new(), &only_new).is_err());
+
+        unsafe {
+            std::env::remove_var("TEST_ROTATED_OLD_KEY");
+            std::env::remove_var("TEST_ROTATED_NEW_KEY");
+        };
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_file_rotated_by_file_sink() {
+        use inklog::sink::{FileSink, LogSink};
+
+        let key = get_test_key("sink");
+        unsafe {
+            std::env::set_var(
+                "TEST_SINK_ROTATE_KEY",
+                general_purpose::STANDARD.encode(key),
+            );
+        };
+        let temp_dir = tempfile::tempdir().unwrap();
+        let sink = FileSink::new(inklog::FileSinkConfig {
+            enabled: true,
+            path: temp_dir.path().join("app.log"),
+            encrypt: true,
+            compress: false,
+            encryption_key_env: Some("TEST_SINK_ROTATE_KEY".to_string()),
+            encryption_key_id: Some("2026-10".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let record = inklog::LogRecord {
+            message: "written by the sink".to_string(),
+            ..Default::default()
+        };
+        sink.write(&record).await.unwrap();
+        let encrypted = sink
+            .rotate_now()
+            .unwrap()
+            .expect("non-empty log file should rotate");
+        let _ = sink.shutdown().await;
+
+        let mut out = Vec::new();
+        decrypt_file_to_writer(
+            &encrypted,
+            &mut out,
+            &["2026-10=TEST_SINK_ROTATE_KEY".to_string()],
+        )
+        .unwrap();
+        assert!(
+            String::from_utf8(out)
+                .unwrap()
+                .contains("written by the sink")
+        );
+
+        unsafe {
+            std::env::remove_var("TEST_SINK_ROTATE_KEY");
+        };
+    }
 }
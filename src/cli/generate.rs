@@ -1,6 +1,10 @@
 // Copyright (c) 2026 Kirky.X
 // SPDX-License-Identifier: MIT
 use anyhow::{Context, Result};
+use inklog::{
+    ConfigFormat, FileSinkConfig, GlobalConfig, HttpAuthConfig, HttpErrorMode, HttpServerConfig,
+    InklogConfig, PerformanceConfig,
+};
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
@@ -8,11 +12,12 @@ use std::path::Path;
 /// Generate configuration template
 ///
 /// Generates configuration templates with four levels: minimal, full, database, and file.
-/// Templates are hardcoded TOML strings.
-pub fn generate_config(output_path: &Path, config_type: &str) -> Result<()> {
+/// Templates are hardcoded TOML strings; YAML and JSON output is converted from them
+/// (comments are dropped).
+pub fn generate_config(output_path: &Path, config_type: &str, format: ConfigFormat) -> Result<()> {
     // Determine output path
     let output_file = if output_path.is_dir() {
-        output_path.join("inklog_config.toml")
+        output_path.join(format!("inklog_config.{}", format.extension()))
     } else {
         output_path.to_path_buf()
     };
@@ -29,6 +34,17 @@ pub fn generate_config(output_path: &Path, config_type: &str) -> Result<()> {
             ));
         }
     };
+    let config_content = match format {
+        ConfigFormat::Toml => config_content,
+        format => {
+            let table: toml::Table = config_content
+                .parse()
+                .with_context(|| "Failed to parse config template")?;
+            format
+                .to_string(&table)
+                .map_err(|e| anyhow::anyhow!("{}", e))?
+        }
+    };
 
     let mut file = File::create(&output_file)
         .with_context(|| format!("Failed to create config file: {}", output_file.display()))?;
@@ -92,6 +108,7 @@ host = "127.0.0.1"
 port = 9090
 metrics_path = "/metrics"
 health_path = "/health"
+ready_path = "/ready"
 
 # Database sink (optional)
 # [database]
@@ -193,47 +210,217 @@ worker_threads = 2
     .to_string()
 }
 
+/// `.env.example` 中的单个环境变量条目
+struct EnvVar {
+    name: String,
+    default: String,
+    description: &'static str,
+    sensitive: bool,
+}
+
+impl EnvVar {
+    fn new(name: &str, default: impl ToString, description: &'static str) -> Self {
+        Self {
+            name: name.to_string(),
+            default: default.to_string(),
+            description,
+            sensitive: false,
+        }
+    }
+
+    /// 敏感变量：不输出默认值，并在注释中标记
+    fn secret(name: &str, description: &'static str) -> Self {
+        Self {
+            name: name.to_string(),
+            default: String::new(),
+            description,
+            sensitive: true,
+        }
+    }
+}
+
+/// `.env.example` 中按子系统分组的一节
+struct EnvSection {
+    title: &'static str,
+    note: &'static str,
+    vars: Vec<EnvVar>,
+}
+
+/// 按子系统列出 inklog 读取的环境变量，默认值取自各配置类型的 `Default` 实现
+fn env_sections() -> Vec<EnvSection> {
+    let global = GlobalConfig::default();
+    let file = FileSinkConfig::default();
+    let http = HttpServerConfig::default();
+    let auth = HttpAuthConfig::default();
+    let performance = PerformanceConfig::default();
+    let error_mode = match http.error_mode {
+        HttpErrorMode::Warn => "warn",
+        HttpErrorMode::Strict => "strict",
+    };
+
+    vec![
+        EnvSection {
+            title: "Config file",
+            note: "Searched first, before ./inklog_config.toml, ~/.config/inklog/config.toml and /etc/inklog/config.toml.",
+            vars: vec![EnvVar::new(
+                "INKLOG_CONFIG_PATH",
+                "inklog_config.toml",
+                "Path to the TOML configuration file",
+            )],
+        },
+        EnvSection {
+            title: "Global",
+            note: "Applies to every sink.",
+            vars: vec![
+                EnvVar::new(
+                    "INKLOG_LEVEL",
+                    &global.level,
+                    "Minimum level: trace, debug, info, warn, error (INKLOG_GLOBAL_LEVEL takes precedence)",
+                ),
+                EnvVar::new(
+                    "INKLOG_GLOBAL_FORMAT",
+                    &global.format,
+                    "Message template ({timestamp}, {level}, {target}, {message}, ...)",
+                ),
+                EnvVar::new(
+                    "INKLOG_GLOBAL_MASKING_ENABLED",
+                    global.masking_enabled,
+                    "Mask sensitive data (emails, phone numbers, card numbers, ...)",
+                ),
+                EnvVar::new(
+                    "INKLOG_GLOBAL_AUTO_FALLBACK",
+                    global.auto_fallback,
+                    "Fall back to other sinks when a sink keeps failing",
+                ),
+            ],
+        },
+        EnvSection {
+            title: "File sink",
+            note: "Setting INKLOG_FILE_SINK_ENABLED=true enables the file sink even if the config file omits it.",
+            vars: vec![
+                EnvVar::new(
+                    "INKLOG_FILE_SINK_ENABLED",
+                    file.enabled,
+                    "Write logs to rotating files",
+                ),
+                EnvVar::new(
+                    "INKLOG_FILE_SINK_PATH",
+                    file.path.display(),
+                    "Active log file path",
+                ),
+                EnvVar::new(
+                    "INKLOG_FILE_SINK_MAX_SIZE",
+                    &file.max_size,
+                    "Rotate when the file reaches this size (e.g. 100MB)",
+                ),
+                EnvVar::secret(
+                    "INKLOG_ENCRYPTION_KEY",
+                    "Base64 AES-256 key used when file_sink.encrypt = true",
+                ),
+            ],
+        },
+        EnvSection {
+            title: "Database sink",
+            note: "The database sink has no environment overrides; configure it in the [database_sink] section of the config file. Keep credentials out of the URL committed to version control.",
+            vars: Vec::new(),
+        },
+        EnvSection {
+            title: "HTTP server",
+            note: "Health and Prometheus metrics endpoints (requires the `http` feature).",
+            vars: vec![
+                EnvVar::new(
+                    "INKLOG_HTTP_SERVER_ENABLED",
+                    http.enabled,
+                    "Start the monitoring HTTP server",
+                ),
+                EnvVar::new("INKLOG_HTTP_SERVER_HOST", &http.host, "Bind address"),
+                EnvVar::new("INKLOG_HTTP_SERVER_PORT", http.port, "Bind port"),
+                EnvVar::new(
+                    "INKLOG_HTTP_SERVER_METRICS_PATH",
+                    &http.metrics_path,
+                    "Prometheus metrics route",
+                ),
+                EnvVar::new(
+                    "INKLOG_HTTP_SERVER_HEALTH_PATH",
+                    &http.health_path,
+                    "Liveness probe route",
+                ),
+                EnvVar::new(
+                    "INKLOG_HTTP_SERVER_READY_PATH",
+                    &http.ready_path,
+                    "Readiness probe route",
+                ),
+                EnvVar::new(
+                    "INKLOG_HTTP_SERVER_ERROR_MODE",
+                    error_mode,
+                    "Startup failure handling: warn (continue) or strict (fail)",
+                ),
+                EnvVar::secret(
+                    &auth.token_env,
+                    "Bearer token required when http_server.auth.enabled = true",
+                ),
+            ],
+        },
+        EnvSection {
+            title: "Performance",
+            note: "Channel and worker sizing.",
+            vars: vec![
+                EnvVar::new(
+                    "INKLOG_PERFORMANCE_CHANNEL_CAPACITY",
+                    performance.channel_capacity,
+                    "Records buffered between producers and sink workers",
+                ),
+                EnvVar::new(
+                    "INKLOG_PERFORMANCE_WORKER_THREADS",
+                    performance.worker_threads,
+                    "Background worker threads",
+                ),
+            ],
+        },
+        EnvSection {
+            title: "CLI decryption",
+            note: "Used by `inklog-cli decrypt`.",
+            vars: vec![EnvVar::secret(
+                "INKLOG_DECRYPT_KEY",
+                "Base64 AES-256 key for decrypting encrypted log files",
+            )],
+        },
+    ]
+}
+
+/// 渲染按子系统分组、带注释的 `.env.example` 内容
+fn render_env_example() -> String {
+    let mut out = String::from(
+        "# inklog environment variables example\n\
+         # Copy this file to .env and customize values.\n\
+         # Values shown are the built-in defaults; variables marked [SECRET] have no\n\
+         # default and must never be committed.\n",
+    );
+
+    for section in env_sections() {
+        out.push_str("\n# ");
+        out.push_str(&"=".repeat(70));
+        out.push_str(&format!("\n# {}\n# {}\n# ", section.title, section.note));
+        out.push_str(&"=".repeat(70));
+        out.push('\n');
+
+        for var in section.vars {
+            if var.sensitive {
+                out.push_str(&format!("# [SECRET] {}\n", var.description));
+            } else {
+                out.push_str(&format!(
+                    "# {} (default: {})\n",
+                    var.description, var.default
+                ));
+            }
+            out.push_str(&format!("{}={}\n", var.name, var.default));
+        }
+    }
+    out
+}
+
 pub fn generate_env_example(output_path: &Path) -> Result<()> {
-    let env_content = r#"# inklog environment variables example
-# Copy this file to .env and customize values
-
-# Global settings
-INKLOG_LEVEL=info
-INKLOG_FORMAT={timestamp} [{level}] {target} - {message}
-
-# Console sink
-INKLOG_CONSOLE_ENABLED=true
-
-# File sink
-INKLOG_FILE_ENABLED=true
-INKLOG_FILE_PATH=logs/app.log
-INKLOG_FILE_MAX_SIZE=100MB
-INKLOG_FILE_ROTATION_TIME=daily
-INKLOG_FILE_KEEP_FILES=30
-INKLOG_FILE_COMPRESS=true
-INKLOG_FILE_ENCRYPT=false
-INKLOG_FILE_ENCRYPTION_KEY=your-encryption-key-here
-
-# Database sink
-INKLOG_DB_ENABLED=false
-INKLOG_DB_DRIVER=postgres
-INKLOG_DB_URL=postgres://localhost/logs
-INKLOG_DB_POOL_SIZE=10
-INKLOG_DB_TABLE_NAME=logs
-INKLOG_DB_BATCH_SIZE=100
-INKLOG_DB_FLUSH_INTERVAL_MS=500
-
-# Performance
-INKLOG_CHANNEL_CAPACITY=10000
-INKLOG_WORKER_THREADS=3
-
-# HTTP server
-INKLOG_HTTP_ENABLED=false
-INKLOG_HTTP_PORT=9090
-
-# Decryption
-INKLOG_DECRYPT_KEY=your-decryption-key-here
-"#;
+    let env_content = render_env_example();
 
     let output_file = if output_path.is_dir() {
         output_path.join(".env.example")
@@ -255,6 +442,25 @@ INKLOG_DECRYPT_KEY=your-decryption-key-here
     Ok(())
 }
 
+/// Generate the JSON Schema for inklog configuration files
+///
+/// Reference it from YAML/JSON configs (`$schema`) for editor validation.
+pub fn generate_schema(output_path: &Path) -> Result<()> {
+    let output_file = if output_path.is_dir() {
+        output_path.join("inklog_config.schema.json")
+    } else {
+        output_path.to_path_buf()
+    };
+
+    let schema = serde_json::to_string_pretty(&InklogConfig::json_schema())
+        .with_context(|| "Failed to serialize config schema")?;
+    std::fs::write(&output_file, schema + "\n")
+        .with_context(|| format!("Failed to write schema file: {}", output_file.display()))?;
+
+    println!("Generated schema file: {}", output_file.display());
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -264,7 +470,7 @@ mod tests {
     fn test_generate_config_minimal() {
         let dir = tempdir().unwrap();
         let output_path = dir.path().join("config.toml");
-        let result = generate_config(&output_path, "minimal");
+        let result = generate_config(&output_path, "minimal", ConfigFormat::Toml);
         assert!(result.is_ok());
         let content = std::fs::read_to_string(&output_path).unwrap();
         assert!(content.contains("inklog minimal configuration"));
@@ -275,19 +481,68 @@ mod tests {
     fn test_generate_config_to_directory() {
         // 覆盖 L17-18: output_path.is_dir() 为 true 的分支
         let dir = tempdir().unwrap();
-        let result = generate_config(dir.path(), "file");
+        let result = generate_config(dir.path(), "file", ConfigFormat::Toml);
         assert!(result.is_ok());
         let expected = dir.path().join("inklog_config.toml");
         let content = std::fs::read_to_string(&expected).unwrap();
         assert!(content.contains("inklog file configuration"));
     }
 
+    #[test]
+    fn test_generate_config_yaml_and_json() {
+        let dir = tempdir().unwrap();
+        let toml_table: toml::Table = generate_full_config().parse().unwrap();
+
+        generate_config(dir.path(), "full", ConfigFormat::Json).unwrap();
+        let json = std::fs::read_to_string(dir.path().join("inklog_config.json")).unwrap();
+        let from_json: toml::Table = ConfigFormat::Json.parse(&json).unwrap();
+        assert_eq!(from_json, toml_table);
+
+        generate_config(dir.path(), "full", ConfigFormat::Yaml).unwrap();
+        let yaml = std::fs::read_to_string(dir.path().join("inklog_config.yaml")).unwrap();
+        let from_yaml: toml::Table = ConfigFormat::Yaml.parse(&yaml).unwrap();
+        assert_eq!(from_yaml, toml_table);
+    }
+
+    #[test]
+    fn test_generate_schema() {
+        let dir = tempdir().unwrap();
+        generate_schema(dir.path()).unwrap();
+        let content =
+            std::fs::read_to_string(dir.path().join("inklog_config.schema.json")).unwrap();
+        let schema: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+        assert_eq!(schema["title"], "InklogConfig");
+        let properties = schema["properties"].as_object().unwrap();
+        for field in [
+            "global",
+            "console_sink",
+            "file_sink",
+            "database_sink",
+            "performance",
+            "http_server",
+        ] {
+            assert!(properties.contains_key(field), "missing {field}");
+        }
+        for field in [
+            "max_size",
+            "channel_capacity",
+            "metrics_path",
+            "encryption_key_env",
+        ] {
+            assert!(content.contains(&format!("\"{field}\"")), "missing {field}");
+        }
+
+        let db_url = &schema["$defs"]["DatabaseSinkConfig"]["properties"]["url"];
+        assert_eq!(db_url["writeOnly"], true);
+    }
+
     #[test]
     fn test_generate_config_unknown_type() {
         // 覆盖 L28-33: unknown config type 错误分支
         let dir = tempdir().unwrap();
         let output_path = dir.path().join("config.toml");
-        let result = generate_config(&output_path, "unknown");
+        let result = generate_config(&output_path, "unknown", ConfigFormat::Toml);
         assert!(result.is_err());
         let err = result.err().unwrap().to_string();
         assert!(err.contains("Unknown config type"));
@@ -305,6 +560,39 @@ mod tests {
         assert!(content.contains("INKLOG_DECRYPT_KEY"));
     }
 
+    #[test]
+    fn test_env_example_grouped_with_defaults_and_secrets() {
+        let content = render_env_example();
+
+        for section in [
+            "# Global",
+            "# File sink",
+            "# Database sink",
+            "# HTTP server",
+            "# Performance",
+        ] {
+            assert!(content.contains(section), "missing section {}", section);
+        }
+
+        let global = GlobalConfig::default();
+        assert!(content.contains(&format!("INKLOG_LEVEL={}\n", global.level)));
+        let http = HttpServerConfig::default();
+        assert!(content.contains(&format!("INKLOG_HTTP_SERVER_PORT={}\n", http.port)));
+
+        // 敏感变量不带默认值，并在注释中标记
+        assert!(content.contains("# [SECRET] Base64 AES-256 key used when"));
+        assert!(content.contains("INKLOG_ENCRYPTION_KEY=\n"));
+        assert!(content.contains("INKLOG_HTTP_AUTH_TOKEN=\n"));
+
+        // 每个变量都紧跟在说明注释之后
+        let lines: Vec<&str> = content.lines().collect();
+        for (idx, line) in lines.iter().enumerate() {
+            if line.starts_with("INKLOG_") {
+                assert!(lines[idx - 1].starts_with("# "), "{} lacks a comment", line);
+            }
+        }
+    }
+
     #[test]
     fn test_generate_env_example_to_directory() {
         // 覆盖 L241-243: output_path.is_dir() 为 true 的分支
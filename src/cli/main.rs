@@ -6,6 +6,9 @@ use std::path::PathBuf;
 mod cli_impl;
 mod decrypt;
 mod generate;
+mod read;
+mod rotate;
+mod tail;
 mod validate;
 
 pub use cli_impl::run_cli;
@@ -33,9 +36,17 @@ enum Commands {
         #[arg(help = "Output file or directory")]
         output: Option<PathBuf>,
 
-        #[arg(short, long, env = "INKLOG_DECRYPT_KEY")]
-        #[arg(help = "Environment variable name containing the decryption key")]
-        key_env: String,
+        #[arg(
+            short,
+            long,
+            env = "INKLOG_DECRYPT_KEY",
+            required = true,
+            value_delimiter = ','
+        )]
+        #[arg(
+            help = "Environment variable containing a decryption key, as ENV or KEY_ID=ENV; repeat for rotated keys"
+        )]
+        key_env: Vec<String>,
 
         #[arg(long)]
         #[arg(help = "Recursive decrypt directories")]
@@ -44,6 +55,10 @@ enum Commands {
         #[arg(long)]
         #[arg(help = "Batch mode: glob pattern for multiple files")]
         batch: bool,
+
+        #[arg(long, conflicts_with_all = ["output", "recursive", "batch"])]
+        #[arg(help = "Write the decrypted plaintext to stdout instead of a file")]
+        stdout: bool,
     },
 
     #[command(name = "generate")]
@@ -61,6 +76,82 @@ enum Commands {
         #[arg(long)]
         #[arg(help = "Generate environment variable example file")]
         env_example: bool,
+
+        #[arg(long)]
+        #[arg(help = "Config file format: toml, yaml, json")]
+        #[arg(default_value = "toml")]
+        format: String,
+
+        #[arg(long)]
+        #[arg(help = "Write the JSON Schema for config files instead of a template")]
+        schema: bool,
+    },
+
+    #[command(name = "read")]
+    #[command(about = "Read framed log files")]
+    Read {
+        #[arg(short, long)]
+        #[arg(help = "Log file to read")]
+        input: PathBuf,
+
+        #[arg(short, long)]
+        #[arg(help = "Record framing: newline, nul, length_prefixed")]
+        #[arg(default_value = "newline")]
+        delimiter: String,
+
+        #[arg(long)]
+        #[arg(help = "Truncate records longer than this many bytes (0 = unlimited)")]
+        #[arg(default_value_t = 16 * 1024 * 1024)]
+        max_line_bytes: usize,
+    },
+
+    #[command(name = "rotate")]
+    #[command(about = "Rotate, compress and encrypt the current file sink logs")]
+    Rotate {
+        #[arg(short, long)]
+        #[arg(help = "Path to configuration file")]
+        #[arg(default_value = "inklog_config.toml")]
+        config: PathBuf,
+
+        #[arg(short, long)]
+        #[arg(help = "Only rotate the file sink with this name")]
+        name: Option<String>,
+    },
+
+    #[command(name = "tail")]
+    #[command(about = "Show recent logs from the database or file sink")]
+    Tail {
+        #[arg(short, long)]
+        #[arg(help = "Path to configuration file")]
+        #[arg(default_value = "inklog_config.toml")]
+        config: PathBuf,
+
+        #[arg(long)]
+        #[arg(help = "Log source: database, file (default: database sink if enabled)")]
+        source: Option<String>,
+
+        #[arg(short, long)]
+        #[arg(help = "Minimum level: trace, debug, info, warn, error")]
+        level: Option<String>,
+
+        #[arg(short, long)]
+        #[arg(help = "Only show records whose target contains this string")]
+        target: Option<String>,
+
+        #[arg(long)]
+        #[arg(
+            help = "Only show records since an RFC 3339 time or a relative duration (30s, 15m, 2h, 1d)"
+        )]
+        since: Option<String>,
+
+        #[arg(short = 'n', long)]
+        #[arg(help = "Number of recent records to show")]
+        #[arg(default_value_t = 20)]
+        lines: usize,
+
+        #[arg(short, long)]
+        #[arg(help = "Keep waiting for new records")]
+        follow: bool,
     },
 
     #[command(name = "validate")]
@@ -73,6 +164,10 @@ enum Commands {
         #[arg(long)]
         #[arg(help = "Check system prerequisites")]
         prerequisites: bool,
+
+        #[arg(long)]
+        #[arg(help = "Treat unrecognized configuration keys as errors")]
+        strict: bool,
     },
 }
 
@@ -0,0 +1,280 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+use anyhow::{Context, Result};
+use inklog::LineDelimiter;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+/// 一次读取的统计
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReadSummary {
+    /// 读取的记录数
+    pub records: usize,
+    /// 超过长度上限被截断的记录数
+    pub truncated: usize,
+}
+
+/// 按 `delimiter` 分帧读取日志文件，每条记录输出为 `out` 中的一行
+///
+/// 超过 `max_record_bytes` 的记录被截断并在 stderr 输出警告，
+/// 单条记录占用的内存不会超过该上限。
+pub fn read_log_file(
+    input: &Path,
+    delimiter: LineDelimiter,
+    max_record_bytes: Option<usize>,
+    out: &mut impl Write,
+) -> Result<ReadSummary> {
+    let file = File::open(input)
+        .with_context(|| format!("Failed to open log file: {}", input.display()))?;
+    let mut reader = BufReader::new(file);
+
+    let mut summary = ReadSummary::default();
+    while let Some((record, truncated)) =
+        next_record_limited(&mut reader, delimiter, max_record_bytes).with_context(|| {
+            format!(
+                "Failed to read record {} from {}",
+                summary.records + 1,
+                input.display()
+            )
+        })?
+    {
+        summary.records += 1;
+        if truncated {
+            summary.truncated += 1;
+            eprintln!(
+                "Warning: record {} in {} exceeds {} bytes and was truncated",
+                summary.records,
+                input.display(),
+                record.len()
+            );
+        }
+        out.write_all(&record)?;
+        out.write_all(b"\n")?;
+    }
+    Ok(summary)
+}
+
+/// 读取下一条记录（不含分隔符或长度前缀），到达末尾时返回 `None`
+#[cfg(test)]
+pub fn next_record<R: BufRead>(
+    reader: &mut R,
+    delimiter: LineDelimiter,
+) -> Result<Option<Vec<u8>>> {
+    Ok(next_record_limited(reader, delimiter, None)?.map(|(record, _)| record))
+}
+
+/// 读取下一条记录，最多保留 `max_bytes` 字节
+///
+/// 超出部分被读取并丢弃，返回值中的布尔值表示记录是否被截断。
+pub fn next_record_limited<R: BufRead>(
+    reader: &mut R,
+    delimiter: LineDelimiter,
+    max_bytes: Option<usize>,
+) -> Result<Option<(Vec<u8>, bool)>> {
+    let limit = max_bytes.unwrap_or(usize::MAX);
+    match delimiter {
+        LineDelimiter::Newline | LineDelimiter::Nul => {
+            let terminator = if delimiter == LineDelimiter::Newline {
+                b'\n'
+            } else {
+                0
+            };
+            let mut record = Vec::new();
+            let mut truncated = false;
+            let mut read_any = false;
+            loop {
+                let buf = reader.fill_buf()?;
+                if buf.is_empty() {
+                    break;
+                }
+                read_any = true;
+                let (chunk, found) = match buf.iter().position(|&b| b == terminator) {
+                    Some(pos) => (&buf[..pos], true),
+                    None => (buf, false),
+                };
+                let keep = chunk.len().min(limit - record.len());
+                record.extend_from_slice(&chunk[..keep]);
+                truncated |= keep < chunk.len();
+                let consumed = chunk.len() + usize::from(found);
+                reader.consume(consumed);
+                if found {
+                    break;
+                }
+            }
+            if !read_any {
+                return Ok(None);
+            }
+            if delimiter == LineDelimiter::Newline && record.last() == Some(&b'\r') {
+                record.pop();
+            }
+            Ok(Some((record, truncated)))
+        }
+        LineDelimiter::LengthPrefixed => {
+            if reader.fill_buf()?.is_empty() {
+                return Ok(None);
+            }
+            let mut prefix = [0u8; 4];
+            reader
+                .read_exact(&mut prefix)
+                .context("Truncated length prefix")?;
+            let len = u32::from_be_bytes(prefix) as usize;
+            let keep = len.min(limit);
+            let mut record = vec![0u8; keep];
+            reader
+                .read_exact(&mut record)
+                .with_context(|| format!("Truncated record: expected {} bytes", len))?;
+            let skip = (len - keep) as u64;
+            if skip > 0 {
+                let skipped = std::io::copy(&mut reader.by_ref().take(skip), &mut std::io::sink())?;
+                if skipped < skip {
+                    anyhow::bail!("Truncated record: expected {} bytes", len);
+                }
+            }
+            Ok(Some((record, skip > 0)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use inklog::LogRecord;
+    use inklog::sink::file::frame_record;
+    use std::io::Cursor;
+
+    fn framed_file(delimiter: LineDelimiter, messages: &[&str]) -> Vec<u8> {
+        messages
+            .iter()
+            .flat_map(|message| {
+                let record =
+                    LogRecord::new(tracing::Level::INFO, "app".to_string(), message.to_string());
+                frame_record(&record, delimiter)
+            })
+            .collect()
+    }
+
+    fn read_all(data: Vec<u8>, delimiter: LineDelimiter) -> Vec<String> {
+        let mut reader = Cursor::new(data);
+        let mut records = Vec::new();
+        while let Some(record) = next_record(&mut reader, delimiter).unwrap() {
+            records.push(String::from_utf8(record).unwrap());
+        }
+        records
+    }
+
+    #[test]
+    fn test_read_newline_framing_keeps_escaped_newlines() {
+        let data = framed_file(LineDelimiter::Newline, &["line one\nline two", "single"]);
+        let records = read_all(data, LineDelimiter::Newline);
+        assert_eq!(records.len(), 2);
+        assert!(records[0].ends_with("line one\\nline two"));
+        assert!(records[1].ends_with("single"));
+    }
+
+    #[test]
+    fn test_read_nul_framing_preserves_newlines() {
+        let data = framed_file(LineDelimiter::Nul, &["multi\nline\nmessage", "next"]);
+        let records = read_all(data, LineDelimiter::Nul);
+        assert_eq!(records.len(), 2);
+        assert!(records[0].ends_with("multi\nline\nmessage"));
+        assert!(records[1].ends_with("next"));
+    }
+
+    #[test]
+    fn test_read_length_prefixed_framing_preserves_newlines() {
+        let data = framed_file(LineDelimiter::LengthPrefixed, &["a\nb", "", "c\r\nd"]);
+        let records = read_all(data, LineDelimiter::LengthPrefixed);
+        assert_eq!(records.len(), 3);
+        assert!(records[0].ends_with("a\nb"));
+        assert!(records[1].ends_with(" - "));
+        assert!(records[2].ends_with("c\r\nd"));
+    }
+
+    #[test]
+    fn test_read_length_prefixed_truncated_record_errors() {
+        let mut data = framed_file(LineDelimiter::LengthPrefixed, &["complete"]);
+        data.truncate(data.len() - 2);
+        let mut reader = Cursor::new(data);
+        assert!(next_record(&mut reader, LineDelimiter::LengthPrefixed).is_err());
+    }
+
+    #[test]
+    fn test_read_log_file_counts_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(
+            &path,
+            framed_file(LineDelimiter::Nul, &["first\nrecord", "second"]),
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        let summary = read_log_file(&path, LineDelimiter::Nul, None, &mut out).unwrap();
+        assert_eq!(summary.records, 2);
+        assert!(String::from_utf8(out).unwrap().contains("first\nrecord\n"));
+    }
+
+    #[test]
+    fn test_read_pathological_line_is_truncated_with_bounded_memory() {
+        const LIMIT: usize = 1024;
+        let mut data = vec![b'x'; 8 * 1024 * 1024];
+        data.extend_from_slice(b"\nnext\n");
+        let mut reader = Cursor::new(data);
+
+        let (record, truncated) =
+            next_record_limited(&mut reader, LineDelimiter::Newline, Some(LIMIT))
+                .unwrap()
+                .unwrap();
+        assert!(truncated);
+        assert_eq!(record.len(), LIMIT);
+        assert!(record.capacity() <= 4 * LIMIT, "buffer grew past the limit");
+
+        let (next, truncated) =
+            next_record_limited(&mut reader, LineDelimiter::Newline, Some(LIMIT))
+                .unwrap()
+                .unwrap();
+        assert!(!truncated);
+        assert_eq!(next, b"next");
+    }
+
+    #[test]
+    fn test_read_length_prefixed_oversized_record_is_truncated() {
+        let data = framed_file(LineDelimiter::LengthPrefixed, &[&"y".repeat(4096), "short"]);
+        let mut reader = Cursor::new(data);
+
+        let (record, truncated) =
+            next_record_limited(&mut reader, LineDelimiter::LengthPrefixed, Some(100))
+                .unwrap()
+                .unwrap();
+        assert!(truncated);
+        assert_eq!(record.len(), 100);
+
+        let (next, truncated) =
+            next_record_limited(&mut reader, LineDelimiter::LengthPrefixed, Some(100))
+                .unwrap()
+                .unwrap();
+        assert!(!truncated);
+        assert!(String::from_utf8(next).unwrap().ends_with("short"));
+    }
+
+    #[test]
+    fn test_read_log_file_reports_truncated_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("corrupt.log");
+        let mut data = vec![b'z'; 64 * 1024];
+        data.extend_from_slice(b"\nok\n");
+        std::fs::write(&path, data).unwrap();
+
+        let mut out = Vec::new();
+        let summary = read_log_file(&path, LineDelimiter::Newline, Some(256), &mut out).unwrap();
+        assert_eq!(
+            summary,
+            ReadSummary {
+                records: 2,
+                truncated: 1
+            }
+        );
+        assert_eq!(out.len(), 256 + 1 + 3);
+    }
+}
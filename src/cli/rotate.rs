@@ -0,0 +1,43 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+use anyhow::{Context, Result, anyhow};
+use inklog::InklogConfig;
+use inklog::sink::FileSink;
+use std::io::Write;
+use std::path::Path;
+
+/// 对配置中已启用的文件 sink 执行一次轮转（重命名 + 压缩 + 加密）
+///
+/// 与 [`FileSink::rotate_now`] 使用相同的逻辑，适合在 logrotate 的
+/// `postrotate` 钩子中调用；`name` 指定时只轮转同名的 sink。空文件不会轮转。
+/// 正在写入的进程会在下一次刷新时重新打开日志文件。
+pub fn rotate(config_path: &Path, name: Option<&str>, out: &mut impl Write) -> Result<()> {
+    let config = InklogConfig::from_file(config_path)?;
+
+    let sinks: Vec<_> = config
+        .enabled_file_sinks()
+        .filter(|sink| name.is_none_or(|name| sink.name == name))
+        .collect();
+    if sinks.is_empty() {
+        return Err(match name {
+            Some(name) => anyhow!("No enabled file sink named '{}' in config", name),
+            None => anyhow!("No enabled file sink in config"),
+        });
+    }
+
+    for sink_config in sinks {
+        let path = sink_config.path.clone();
+        let sink = FileSink::new(sink_config.clone())
+            .with_context(|| format!("Failed to open log file: {}", path.display()))?;
+        match sink
+            .rotate_now()
+            .with_context(|| format!("Failed to rotate log file: {}", path.display()))?
+        {
+            Some(archived) => {
+                writeln!(out, "Rotated: {} -> {}", path.display(), archived.display())?
+            }
+            None => writeln!(out, "Skipped: {} is empty", path.display())?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,412 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! `tail` 子命令：读取数据库 sink 或文件 sink 中最近的日志，可持续跟随
+//!
+//! 输出使用控制台的格式：`console_sink.output_format = "json"` 时输出 JSON，
+//! 否则按 `global.format` 模板渲染。文件 sink 中非 JSON 的文本行原样输出，
+//! 过滤条件仅作用于 JSON 行。
+
+use anyhow::{Context, Result, anyhow, bail};
+use inklog::chrono::{DateTime, Duration as ChronoDuration, Utc};
+use inklog::tracing::Level;
+use inklog::{FileFormat, InklogConfig, LogRecord, LogTemplate};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// 跟随模式下的轮询间隔
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 日志来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TailSource {
+    Database,
+    File,
+}
+
+impl FromStr for TailSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "database" | "db" => Ok(TailSource::Database),
+            "file" => Ok(TailSource::File),
+            other => Err(format!(
+                "Invalid source '{}'. Valid values: database, file",
+                other
+            )),
+        }
+    }
+}
+
+/// 记录过滤条件
+#[derive(Debug, Clone, Default)]
+pub struct TailFilter {
+    /// 仅输出不低于该级别的记录
+    pub level: Option<Level>,
+    /// 仅输出 target 包含该子串的记录
+    pub target: Option<String>,
+    /// 仅输出时间戳不早于该时刻的记录
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl TailFilter {
+    fn matches(&self, record: &LogRecord) -> bool {
+        if let Some(min) = self.level {
+            match Level::from_str(&record.level) {
+                Ok(level) if level <= min => {}
+                _ => return false,
+            }
+        }
+        if let Some(target) = &self.target
+            && !record.target.contains(target.as_str())
+        {
+            return false;
+        }
+        self.since.is_none_or(|since| record.timestamp >= since)
+    }
+}
+
+/// `tail` 的选项
+#[derive(Debug, Clone)]
+pub struct TailOptions {
+    /// 未指定时优先使用已启用的数据库 sink，其次为文件 sink
+    pub source: Option<TailSource>,
+    pub filter: TailFilter,
+    /// 初始输出的最大记录数
+    pub lines: usize,
+    /// 输出后继续等待新记录
+    pub follow: bool,
+}
+
+/// 按控制台配置渲染记录
+struct RecordFormatter {
+    template: LogTemplate,
+    json: bool,
+}
+
+impl RecordFormatter {
+    fn new(config: &InklogConfig) -> Self {
+        Self {
+            template: LogTemplate::new(&config.global.format),
+            json: config
+                .console_sink
+                .as_ref()
+                .is_some_and(|console| console.output_format == FileFormat::Json),
+        }
+    }
+
+    fn format(&self, record: &LogRecord) -> String {
+        if self.json {
+            serde_json::to_string(record).unwrap_or_else(|_| record.message.clone())
+        } else {
+            self.template.render(record)
+        }
+    }
+}
+
+/// 解析 `--since`：RFC 3339 时间，或相对当前时间的 `30s`、`15m`、`2h`、`1d`
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("Invalid --since '{}': missing unit (s, m, h, d)", value))?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| anyhow!("Invalid --since '{}': expected e.g. 15m or RFC 3339", value))?;
+    let duration = match unit {
+        "s" => ChronoDuration::seconds(amount),
+        "m" => ChronoDuration::minutes(amount),
+        "h" => ChronoDuration::hours(amount),
+        "d" => ChronoDuration::days(amount),
+        _ => bail!("Invalid --since '{}': unit must be s, m, h or d", value),
+    };
+    Ok(Utc::now() - duration)
+}
+
+/// 读取 `config_path` 指定的配置并输出最近的日志
+pub fn tail(config_path: &Path, options: &TailOptions, out: &mut impl Write) -> Result<()> {
+    let config = InklogConfig::from_file(config_path)?;
+    let formatter = RecordFormatter::new(&config);
+
+    let database_enabled = config.database_sink.as_ref().is_some_and(|db| db.enabled);
+    let source = match options.source {
+        Some(source) => source,
+        None if database_enabled => TailSource::Database,
+        None => TailSource::File,
+    };
+
+    match source {
+        TailSource::Database => {
+            let db = config
+                .database_sink
+                .as_ref()
+                .ok_or_else(|| anyhow!("No [database_sink] section in config"))?;
+            tail_database(db, options, &formatter, out)
+        }
+        TailSource::File => {
+            let file = config
+                .enabled_file_sinks()
+                .next()
+                .ok_or_else(|| anyhow!("No enabled file sink in config"))?;
+            tail_file(&file.path, options, &formatter, out)
+        }
+    }
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+fn tail_database(
+    db: &inklog::DatabaseSinkConfig,
+    options: &TailOptions,
+    formatter: &RecordFormatter,
+    out: &mut impl Write,
+) -> Result<()> {
+    use inklog::sink::{LogQuery, LogReader};
+
+    let filter = &options.filter;
+    let query = |since: Option<DateTime<Utc>>| {
+        let mut query = LogQuery::new();
+        if let Some(level) = filter.level {
+            query = query.min_level(level);
+        }
+        if let Some(target) = &filter.target {
+            query = query.target_contains(target.clone());
+        }
+        if let Some(since) = since {
+            query = query.since(since);
+        }
+        query
+    };
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Failed to create async runtime")?;
+    runtime.block_on(async {
+        let reader = LogReader::from_config(db).await?;
+        // 查询结果按时间倒序，输出前翻转为正序
+        let mut rows = reader
+            .query(&query(filter.since).limit(options.lines as u64))
+            .await?;
+        rows.reverse();
+        let mut last = rows.last().map(|row| (row.timestamp, row.id));
+        for row in rows {
+            writeln!(out, "{}", formatter.format(&record_from_model(row)))?;
+        }
+        out.flush()?;
+
+        if options.follow {
+            loop {
+                tokio::time::sleep(FOLLOW_POLL_INTERVAL).await;
+                let since = last
+                    .map(|(timestamp, _)| timestamp.and_utc())
+                    .or(filter.since);
+                let mut rows = reader.query(&query(since)).await?;
+                rows.retain(|row| last.is_none_or(|last| (row.timestamp, row.id) > last));
+                rows.reverse();
+                if let Some(row) = rows.last() {
+                    last = Some((row.timestamp, row.id));
+                }
+                for row in rows {
+                    writeln!(out, "{}", formatter.format(&record_from_model(row)))?;
+                }
+                out.flush()?;
+            }
+        }
+        Ok::<_, anyhow::Error>(())
+    })
+}
+
+#[cfg(not(any(feature = "sqlite", feature = "postgres", feature = "mysql")))]
+fn tail_database(
+    _db: &inklog::DatabaseSinkConfig,
+    _options: &TailOptions,
+    _formatter: &RecordFormatter,
+    _out: &mut impl Write,
+) -> Result<()> {
+    bail!("Database support is not compiled in; rebuild with the sqlite, postgres or mysql feature")
+}
+
+/// 数据库行转换为 [`LogRecord`]；`fields` 无法解析为 JSON 对象时留空
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+fn record_from_model(model: inklog::sink::entity::Model) -> LogRecord {
+    LogRecord {
+        timestamp: model.timestamp.and_utc(),
+        level: model.level,
+        target: model.target,
+        message: model.message,
+        fields: model
+            .fields
+            .and_then(|fields| serde_json::from_str(&fields).ok())
+            .unwrap_or_default(),
+        file: model.file,
+        line: model.line.and_then(|line| u32::try_from(line).ok()),
+        thread_id: model.thread_id,
+    }
+}
+
+/// 渲染文件中的一行：JSON 记录按过滤条件与格式输出，其余文本行原样输出
+fn render_line(line: &str, filter: &TailFilter, formatter: &RecordFormatter) -> Option<String> {
+    match serde_json::from_str::<LogRecord>(line) {
+        Ok(record) => filter.matches(&record).then(|| formatter.format(&record)),
+        Err(_) => Some(line.to_string()),
+    }
+}
+
+fn tail_file(
+    path: &Path,
+    options: &TailOptions,
+    formatter: &RecordFormatter,
+    out: &mut impl Write,
+) -> Result<()> {
+    let open = || {
+        File::open(path)
+            .map(BufReader::new)
+            .with_context(|| format!("Failed to open log file: {}", path.display()))
+    };
+    let mut reader = open()?;
+    let mut recent = VecDeque::with_capacity(options.lines.min(1024));
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        if let Some(text) = render_line(
+            line.trim_end_matches(['\r', '\n']),
+            &options.filter,
+            formatter,
+        ) && options.lines > 0
+        {
+            if recent.len() == options.lines {
+                recent.pop_front();
+            }
+            recent.push_back(text);
+        }
+        line.clear();
+    }
+    for text in recent {
+        writeln!(out, "{}", text)?;
+    }
+    out.flush()?;
+
+    if options.follow {
+        loop {
+            std::thread::sleep(FOLLOW_POLL_INTERVAL);
+            // 文件被截断或轮转后从新文件开头继续读取
+            let position = reader.stream_position()?;
+            if std::fs::metadata(path)
+                .map(|m| m.len() < position)
+                .unwrap_or(false)
+            {
+                reader = open()?;
+                line.clear();
+            }
+            loop {
+                let read = reader.read_line(&mut line)?;
+                // 未以换行结束的行可能仍在写入，留到下次轮询补全
+                if read == 0 || !line.ends_with('\n') {
+                    break;
+                }
+                if let Some(text) = render_line(
+                    line.trim_end_matches(['\r', '\n']),
+                    &options.filter,
+                    formatter,
+                ) {
+                    writeln!(out, "{}", text)?;
+                }
+                line.clear();
+            }
+            out.flush()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json_line(level: &str, target: &str, message: &str) -> String {
+        serde_json::to_string(&LogRecord {
+            level: level.to_string(),
+            target: target.to_string(),
+            message: message.to_string(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn options(filter: TailFilter, lines: usize) -> TailOptions {
+        TailOptions {
+            source: Some(TailSource::File),
+            filter,
+            lines,
+            follow: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let parsed = parse_since("2026-01-02T03:04:05Z").unwrap();
+        assert_eq!(parsed.to_rfc3339(), "2026-01-02T03:04:05+00:00");
+
+        let fifteen_minutes_ago = parse_since("15m").unwrap();
+        let delta = Utc::now() - fifteen_minutes_ago;
+        assert!(delta >= ChronoDuration::minutes(15) && delta < ChronoDuration::minutes(16));
+
+        assert!(parse_since("15").is_err());
+        assert!(parse_since("15w").is_err());
+        assert!(parse_since("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_tail_file_filters_json_lines_and_keeps_last_n() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join("app.log");
+        let lines = [
+            json_line("INFO", "app::http", "request served"),
+            json_line("ERROR", "app::db", "query failed"),
+            json_line("WARN", "app::http", "slow request"),
+            "plain text line".to_string(),
+            json_line("ERROR", "app::http", "upstream timeout"),
+        ];
+        std::fs::write(&log_path, lines.join("\n") + "\n").unwrap();
+        let config_path = dir.path().join("inklog.toml");
+        std::fs::write(
+            &config_path,
+            format!(
+                "[global]\nformat = \"[{{level}}] {{target}} {{message}}\"\n\n\
+                 [file_sink]\nenabled = true\npath = {:?}\n",
+                log_path
+            ),
+        )
+        .unwrap();
+
+        let filter = TailFilter {
+            level: Some(Level::WARN),
+            target: Some("http".to_string()),
+            since: None,
+        };
+        let mut out = Vec::new();
+        tail(&config_path, &options(filter, 2), &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "plain text line\n[ERROR] app::http upstream timeout\n"
+        );
+
+        let mut out = Vec::new();
+        tail(&config_path, &options(TailFilter::default(), 10), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), 5);
+    }
+
+    #[test]
+    fn test_tail_source_parse() {
+        assert_eq!("db".parse::<TailSource>(), Ok(TailSource::Database));
+        assert_eq!("File".parse::<TailSource>(), Ok(TailSource::File));
+        assert!("s3".parse::<TailSource>().is_err());
+    }
+}
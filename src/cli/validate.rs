@@ -1,10 +1,20 @@
 // Copyright (c) 2026 Kirky.X
 // SPDX-License-Identifier: MIT
 use anyhow::{Context, Result};
+use inklog::ConfigFormat;
 use std::path::PathBuf;
 use std::process::Command;
 
 pub fn validate_config(config_path: &PathBuf) -> Result<()> {
+    run_validation(config_path, false)
+}
+
+/// 严格模式校验：在常规校验之外，将未识别的配置键（如拼写错误的字段或段名）视为错误
+pub fn validate_config_strict(config_path: &PathBuf) -> Result<()> {
+    run_validation(config_path, true)
+}
+
+fn run_validation(config_path: &PathBuf, strict: bool) -> Result<()> {
     println!("Validating configuration file: {}", config_path.display());
 
     if !config_path.exists() {
@@ -15,11 +25,27 @@ pub fn validate_config(config_path: &PathBuf) -> Result<()> {
     }
 
     // Manual TOML parsing and validation
-    let content = std::fs::read_to_string(config_path)
+    let format = ConfigFormat::from_path(config_path).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut content = std::fs::read_to_string(config_path)
         .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
+    if format != ConfigFormat::Toml {
+        // YAML / JSON 先转换为 TOML 表，复用同一套校验
+        let table: toml::Table = format
+            .parse(&content)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        content = toml::to_string(&table).with_context(|| "Failed to convert config to TOML")?;
+    }
 
     validate_toml_content(&content, config_path)?;
 
+    if strict {
+        let table: toml::Table = content
+            .parse()
+            .with_context(|| "Failed to parse TOML content")?;
+        check_unknown_keys(&table)?;
+        println!("  ✓ No unknown keys (strict mode)");
+    }
+
     println!("✓ Configuration file is valid");
     Ok(())
 }
@@ -47,6 +73,11 @@ fn validate_toml_content(content: &str, config_path: &PathBuf) -> Result<()> {
     if let Some(file) = get_table(&config, "file").or(get_table(&config, "file_sink")) {
         validate_file_sink(file)?;
     }
+    if let Some(files) = config.get("file_sinks").and_then(|t| t.as_array()) {
+        for file in files.iter().filter_map(|t| t.as_table()) {
+            validate_file_sink(file)?;
+        }
+    }
 
     if let Some(perf) = config.get("performance").and_then(|t| t.as_table()) {
         validate_performance(perf)?;
@@ -107,6 +138,26 @@ fn validate_console_sink(console: &toml::Table) -> Result<()> {
         return Err(anyhow::anyhow!("console_sink.colored must be a boolean"));
     }
 
+    if let Some(color) = console.get("color") {
+        let value = color.as_str().unwrap_or("");
+        if !matches!(value, "auto" | "always" | "never") {
+            return Err(anyhow::anyhow!(
+                "Invalid console_sink.color '{}'. Valid values: auto, always, never",
+                value
+            ));
+        }
+    }
+
+    if let Some(format) = console.get("output_format") {
+        let value = format.as_str().unwrap_or("");
+        if !matches!(value, "text" | "json") {
+            return Err(anyhow::anyhow!(
+                "Invalid console_sink.output_format '{}'. Valid values: text, json",
+                value
+            ));
+        }
+    }
+
     if let Some(stderr_levels) = console.get("stderr_levels")
         && let Some(levels) = stderr_levels.as_array()
     {
@@ -172,6 +223,17 @@ fn validate_file_sink(file: &toml::Table) -> Result<()> {
         return Err(anyhow::anyhow!("file_sink.compress must be a boolean"));
     }
 
+    if let Some(delimiter) = file.get("line_delimiter") {
+        let value = delimiter.as_str().unwrap_or("");
+        if value.parse::<inklog::LineDelimiter>().is_err() {
+            return Err(anyhow::anyhow!(
+                "Invalid file_sink.line_delimiter '{}'. Valid values: newline, nul, length_prefixed",
+                value
+            ));
+        }
+        println!("  ✓ Line delimiter: {}", value);
+    }
+
     if let Some(encrypt) = file.get("encrypt") {
         if !encrypt.is_bool() {
             return Err(anyhow::anyhow!("file_sink.encrypt must be a boolean"));
@@ -355,14 +417,33 @@ fn validate_sections(config: &toml::Table, _config_path: &PathBuf) -> Result<()>
         // File variations
         "file",
         "file_sink",
+        "file_sinks",
         // Database variations
         "database",
         "db_config",
+        // NATS
+        "nats_sink",
+        // Elasticsearch
+        "elasticsearch_sink",
+        // OpenTelemetry
+        "otlp_sink",
+        // Syslog
+        "syslog_sink",
+        // Loki
+        "loki_sink",
+        // In-memory ring
+        "memory_ring_sink",
         // Performance
         "performance",
+        // Sampling
+        "sampling",
+        // Deduplication
+        "dedup",
         // HTTP variations
         "http",
         "http_server",
+        // Alerting
+        "alert_rules",
     ];
 
     for key in config.keys() {
@@ -390,6 +471,156 @@ fn validate_sections(config: &toml::Table, _config_path: &PathBuf) -> Result<()>
     Ok(())
 }
 
+/// 对照 `InklogConfig` 的 JSON Schema 查找未识别的键，报告完整字段路径
+fn check_unknown_keys(config: &toml::Table) -> Result<()> {
+    let schema = inklog::InklogConfig::json_schema();
+    let root_properties = schema_properties(&schema, &schema).unwrap_or_default();
+    let mut unknown = Vec::new();
+
+    for (key, value) in config {
+        // 兼容 validate 接受的旧段名
+        let canonical = match key.as_str() {
+            "console" => "console_sink",
+            "file" => "file_sink",
+            "http" => "http_server",
+            "database" | "db_config" => "database_sink",
+            other => other,
+        };
+        match root_properties.iter().find(|(name, _)| name == canonical) {
+            Some((_, property)) => {
+                let value = serde_json::to_value(value)
+                    .with_context(|| format!("Failed to inspect config section '{}'", key))?;
+                collect_unknown_keys(&schema, property, &value, key, &mut unknown);
+            }
+            None => unknown.push(describe_unknown_key(key, key, &root_properties)),
+        }
+    }
+
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow::anyhow!(
+        "Unknown configuration keys (strict mode):\n{}",
+        unknown
+            .iter()
+            .map(|line| format!("  - {}", line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    ))
+}
+
+fn collect_unknown_keys(
+    root: &serde_json::Value,
+    schema: &serde_json::Value,
+    value: &serde_json::Value,
+    path: &str,
+    unknown: &mut Vec<String>,
+) {
+    match value {
+        serde_json::Value::Object(map) => {
+            // 没有声明 properties 的对象（如 HashMap 字段）允许任意键
+            let Some(properties) = schema_properties(root, schema) else {
+                return;
+            };
+            for (key, item) in map {
+                let child = format!("{}.{}", path, key);
+                match properties.iter().find(|(name, _)| name == key) {
+                    Some((_, property)) => {
+                        collect_unknown_keys(root, property, item, &child, unknown)
+                    }
+                    None => unknown.push(describe_unknown_key(&child, key, &properties)),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            let Some(item_schema) = schema_variants(root, schema)
+                .into_iter()
+                .find_map(|variant| variant.get("items"))
+            else {
+                return;
+            };
+            for (index, item) in items.iter().enumerate() {
+                let child = format!("{}[{}]", path, index);
+                collect_unknown_keys(root, item_schema, item, &child, unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 展开 `$ref` 与 `anyOf`/`oneOf`/`allOf`（如 `Option<T>`），返回所有候选子 schema
+fn schema_variants<'a>(
+    root: &'a serde_json::Value,
+    schema: &'a serde_json::Value,
+) -> Vec<&'a serde_json::Value> {
+    let schema = match schema
+        .get("$ref")
+        .and_then(|r| r.as_str())
+        .and_then(|r| r.strip_prefix("#/$defs/"))
+    {
+        Some(name) => match root.get("$defs").and_then(|defs| defs.get(name)) {
+            Some(resolved) => resolved,
+            None => return Vec::new(),
+        },
+        None => schema,
+    };
+
+    let mut variants = vec![schema];
+    for combinator in ["anyOf", "oneOf", "allOf"] {
+        if let Some(subschemas) = schema.get(combinator).and_then(|s| s.as_array()) {
+            for subschema in subschemas {
+                variants.extend(schema_variants(root, subschema));
+            }
+        }
+    }
+    variants
+}
+
+fn schema_properties<'a>(
+    root: &'a serde_json::Value,
+    schema: &'a serde_json::Value,
+) -> Option<Vec<(String, &'a serde_json::Value)>> {
+    let mut properties = Vec::new();
+    let mut found = false;
+    for variant in schema_variants(root, schema) {
+        if let Some(map) = variant.get("properties").and_then(|p| p.as_object()) {
+            found = true;
+            properties.extend(map.iter().map(|(name, property)| (name.clone(), property)));
+        }
+    }
+    found.then_some(properties)
+}
+
+fn describe_unknown_key(
+    path: &str,
+    key: &str,
+    candidates: &[(String, &serde_json::Value)],
+) -> String {
+    let suggestion = candidates
+        .iter()
+        .map(|(name, _)| (edit_distance(key, name), name))
+        .filter(|(distance, _)| *distance <= 2.max(key.len() / 3))
+        .min_by_key(|(distance, _)| *distance);
+    match suggestion {
+        Some((_, name)) => format!("{} (did you mean '{}'?)", path, name),
+        None => path.to_string(),
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 fn parse_size(size_str: &str) -> Result<()> {
     let size_str = size_str.trim().to_uppercase();
     let (num_str, unit) = size_str.split_at(
@@ -1018,6 +1249,23 @@ enabled = "yes"
         );
     }
 
+    #[test]
+    fn test_validate_console_sink_invalid_color() {
+        let content = r#"
+[console]
+color = "sometimes"
+"#;
+        let file = write_config(content);
+        let result = validate_config(&file.path().to_path_buf());
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid console_sink.color 'sometimes'")
+        );
+    }
+
     #[test]
     fn test_validate_console_sink_colored_not_bool() {
         // 覆盖 console_sink.colored 非布尔类型
@@ -1157,6 +1405,23 @@ retention_days = 0
         );
     }
 
+    #[test]
+    fn test_validate_file_sink_invalid_line_delimiter() {
+        let content = r#"
+[file]
+line_delimiter = "crlf"
+"#;
+        let file = write_config(content);
+        let result = validate_config(&file.path().to_path_buf());
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid file_sink.line_delimiter")
+        );
+    }
+
     #[test]
     fn test_validate_file_sink_compress_not_bool() {
         // 覆盖 compress 非布尔类型
@@ -1553,4 +1818,68 @@ level = "INFO"
         let result = validate_config(&file.path().to_path_buf());
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_validate_strict_rejects_misspelled_key() {
+        let content = "[performance]\nchannel_capcity = 1000\n";
+        let file = write_config(content);
+        // 非严格模式下未知字段被忽略
+        assert!(validate_config(&file.path().to_path_buf()).is_ok());
+
+        let err = validate_config_strict(&file.path().to_path_buf())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("performance.channel_capcity"), "{err}");
+        assert!(err.contains("did you mean 'channel_capacity'"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_strict_rejects_unknown_section() {
+        let content = "[file_sync]\npath = \"logs/app.log\"\n";
+        let file = write_config(content);
+        let err = validate_config_strict(&file.path().to_path_buf())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("file_sync"), "{err}");
+        assert!(err.contains("did you mean 'file_sink'"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_strict_checks_nested_arrays() {
+        let content = r#"
+[[file_sinks]]
+name = "audit"
+path = "logs/audit.log"
+retension_days = 7
+"#;
+        let file = write_config(content);
+        let err = validate_config_strict(&file.path().to_path_buf())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("file_sinks[0].retension_days"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_strict_accepts_known_keys_and_legacy_sections() {
+        let content = r#"
+[global]
+level = "info"
+
+[console]
+enabled = true
+colored = false
+
+[file]
+enabled = true
+path = "logs/app.log"
+max_size = "10MB"
+
+[performance]
+channel_capacity = 1000
+worker_threads = 2
+"#;
+        let file = write_config(content);
+        let result = validate_config_strict(&file.path().to_path_buf());
+        assert!(result.is_ok(), "{:?}", result);
+    }
 }
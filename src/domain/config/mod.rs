@@ -4,9 +4,16 @@
 
 #[allow(clippy::module_inception)]
 pub mod config;
+mod interpolate;
 
+pub use config::compiled_features;
 pub use config::{
-    ChannelStrategy, ConsoleSinkConfig, DatabaseDriver, DatabaseSinkConfig, FileSinkConfig,
-    GlobalConfig, HttpAuthConfig, HttpErrorMode, HttpServerConfig, InklogConfig, ParquetConfig,
-    PartitionStrategy, PerformanceConfig,
+    AlertRuleConfig, ChannelStrategy, ColorMode, ConfigFormat, ConsoleSinkConfig, ControlCharMode,
+    DatabaseDriver, DatabaseSinkConfig, DedupConfig, ElasticsearchSinkConfig,
+    FileCompressionFormat, FileFormat, FileSinkConfig, GlobalConfig, HttpAuthConfig, HttpErrorMode,
+    HttpServerConfig, InklogConfig, LineDelimiter, LokiSinkConfig, MemoryRingSinkConfig,
+    MissingFeatureMode, NatsSinkConfig, OtlpProtocol, OtlpSinkConfig, OverflowPolicy,
+    ParquetCompression, ParquetConfig, ParquetFieldType, ParquetPromotedField, PartitionStrategy,
+    PerformanceConfig, SamplingConfig, SamplingMode, SamplingRuleConfig, SyslogFacility,
+    SyslogSinkConfig, SyslogTransport, TeeOutput,
 };
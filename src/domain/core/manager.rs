@@ -2,29 +2,33 @@
 // SPDX-License-Identifier: MIT
 #[allow(unused_imports)]
 use crate::ConsoleSinkConfig;
+use crate::DataMasker;
 use crate::InklogError;
 use crate::LogRecord;
 use crate::LogTemplate;
-use crate::domain::core::LoggerSubscriber;
+use crate::domain::core::{ByteBudget, LoggerSubscriber, ThreadBatchConfig, ThreadBatchRegistry};
 #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
 use crate::integrations::Database;
 use crate::integrations::{Cache, Config};
-use crate::support::io::ConsoleSink;
 #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
 use crate::support::io::DatabaseSink;
 use crate::support::io::FileSink;
 use crate::support::io::LogSink;
-use crate::{FileSinkConfig, InklogConfig};
+#[cfg(feature = "memory-ring")]
+use crate::support::io::sink::MemoryRingSink;
+use crate::support::io::{ConsoleCapture, ConsoleSink};
+use crate::support::processing::{LogDeduplicator, LogSampler};
+use crate::{FileSinkConfig, InklogConfig, OverflowPolicy};
 use crate::{HealthStatus, Metrics};
 use crate::{LogAdapter, LogLogger};
 use chrono::Utc;
-use crossbeam_channel::{Receiver, Sender, bounded};
+use crossbeam_channel::{Receiver, SendTimeoutError, Sender, TrySendError, bounded};
 #[allow(unused_imports)]
 use std::path::Path;
 use std::path::PathBuf;
 use std::string::ToString;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
 use std::thread;
 use std::time::{Duration, Instant};
 #[cfg(feature = "http")]
@@ -32,7 +36,10 @@ use subtle::ConstantTimeEq;
 use tracing::error;
 #[cfg(feature = "http")]
 use tracing::info;
+use tracing_subscriber::filter::{EnvFilter, Filtered};
+use tracing_subscriber::layer::Layered;
 use tracing_subscriber::prelude::*;
+use tracing_subscriber::{Registry, reload};
 
 // Control messages for sink recovery
 /// Messages used to control sink recovery and status queries.
@@ -41,6 +48,249 @@ use tracing_subscriber::prelude::*;
 enum SinkControlMessage {
     RecoverSink(String), // sink name
     GetStatus,
+    /// 仅 flush 指定 sink，完成后通过 `ack` 回报结果；过了 `deadline` 不再转发
+    FlushSink {
+        sink: String,
+        ack: Sender<Result<(), String>>,
+        deadline: Instant,
+    },
+}
+
+/// [`LoggerManager::flush_sink`] 等待 worker 确认的最长时间
+const FLUSH_SINK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 转发给 sink worker 的 flush 请求：sink 名称与确认用的 sender
+type FlushRequest = (String, Sender<Result<(), String>>);
+
+/// dispatcher 按 sink 名称把 flush 请求转发到对应 worker 的 channel
+///
+/// 每个 worker 拥有独立的 flush channel，只会收到发给自己的请求（文件 worker 为
+/// 其下所有文件 sink 注册同一个 channel）。worker 退出后其 receiver 随之释放，
+/// 转发失败时直接回报 sink 不可用，调用方无需等到超时。
+#[derive(Default)]
+struct FlushRoutes {
+    routes: std::collections::HashMap<String, Sender<FlushRequest>>,
+}
+
+impl FlushRoutes {
+    /// 为负责 `sinks` 的 worker 注册 flush channel，返回 worker 端的 receiver
+    fn register<'a>(&mut self, sinks: impl IntoIterator<Item = &'a str>) -> Receiver<FlushRequest> {
+        let (tx, rx) = bounded(16);
+        for sink in sinks {
+            self.routes.insert(sink.to_string(), tx.clone());
+        }
+        rx
+    }
+
+    /// 转发 flush 请求；过了 `deadline` 的请求直接丢弃，调用方已超时返回
+    fn forward(&self, msg: SinkControlMessage) {
+        let SinkControlMessage::FlushSink {
+            sink,
+            ack,
+            deadline,
+        } = msg
+        else {
+            return;
+        };
+        if Instant::now() >= deadline {
+            return;
+        }
+        let Some(route) = self.routes.get(&sink) else {
+            let _ = ack.send(Err(format!("no worker handles sink '{}'", sink)));
+            return;
+        };
+        match route.try_send((sink, ack)) {
+            Ok(()) => {}
+            Err(TrySendError::Full((sink, ack))) => {
+                let _ = ack.send(Err(format!("too many pending flushes for sink '{}'", sink)));
+            }
+            Err(TrySendError::Disconnected((sink, ack))) => {
+                let _ = ack.send(Err(format!(
+                    "sink '{}' is unavailable: its worker has stopped",
+                    sink
+                )));
+            }
+        }
+    }
+}
+
+/// HTTP 监控服务器的启动状态，供 [`LoggerManager::http_ready`] 等待
+#[cfg(feature = "http")]
+#[derive(Debug, Clone)]
+enum HttpServerState {
+    /// 未启动（配置未启用，或通过 `build_detached` 构建）
+    NotStarted,
+    Pending,
+    Ready(std::net::SocketAddr),
+    Failed(String),
+}
+
+/// dispatcher 分发给各 sink worker 的记录
+///
+/// 所有 worker 共享同一个 `Arc`，最后一个 worker 处理完并释放时才归还字节预算，
+/// 使 `max_buffered_bytes` 同时覆盖主 channel 与各 sink 的 channel。
+struct QueuedRecord {
+    record: Arc<LogRecord>,
+    budget: Option<Arc<ByteBudget>>,
+}
+
+impl std::ops::Deref for QueuedRecord {
+    type Target = LogRecord;
+
+    fn deref(&self) -> &LogRecord {
+        &self.record
+    }
+}
+
+impl Drop for QueuedRecord {
+    fn drop(&mut self) {
+        LoggerManager::release_byte_budget(&self.budget, &self.record);
+    }
+}
+
+/// sink worker channel 中的记录
+type SinkRecord = Arc<QueuedRecord>;
+
+/// sink channel 已满时 `OverflowPolicy::Block` 的最长等待时间，与主 channel 一致
+const SINK_SEND_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// dispatcher 向各 sink worker 分发记录的 channel 集合
+///
+/// 每个 sink 的 channel 独立应用 `performance.overflow_policy`：某个 sink
+/// 停滞时只丢弃发往它的记录（计入 `logs_dropped`），其他 sink 照常接收。
+struct SinkChannels {
+    channels: Vec<SinkChannel>,
+    capacity: usize,
+    overflow_policy: OverflowPolicy,
+    metrics: Arc<Metrics>,
+}
+
+/// dispatcher 持有的单个 sink channel
+struct SinkChannel {
+    sender: Sender<SinkRecord>,
+    /// `DropOldest` 用于逐出最旧记录的 receiver 副本
+    receiver: Receiver<SinkRecord>,
+    /// worker 释放 [`SinkReceiver`] 后失效；持有 receiver 副本时 channel 不会断开
+    alive: Weak<()>,
+}
+
+/// sink worker 持有的 channel 接收端，释放后 dispatcher 不再向其分发
+struct SinkReceiver {
+    receiver: Receiver<SinkRecord>,
+    _alive: Arc<()>,
+}
+
+impl std::ops::Deref for SinkReceiver {
+    type Target = Receiver<SinkRecord>;
+
+    fn deref(&self) -> &Receiver<SinkRecord> {
+        &self.receiver
+    }
+}
+
+impl SinkChannels {
+    fn new(capacity: usize, overflow_policy: OverflowPolicy, metrics: Arc<Metrics>) -> Self {
+        Self {
+            channels: Vec::new(),
+            capacity,
+            overflow_policy,
+            metrics,
+        }
+    }
+
+    /// 为一个 sink worker 创建独立的 channel
+    fn add(&mut self) -> SinkReceiver {
+        let (sender, receiver) = bounded(self.capacity);
+        let alive = Arc::new(());
+        self.channels.push(SinkChannel {
+            sender,
+            receiver: receiver.clone(),
+            alive: Arc::downgrade(&alive),
+        });
+        SinkReceiver {
+            receiver,
+            _alive: alive,
+        }
+    }
+
+    /// 把记录分发给每个 sink，移除 worker 已退出的 channel
+    fn send(&mut self, record: &SinkRecord) {
+        let (policy, metrics) = (self.overflow_policy, &self.metrics);
+        self.channels.retain(|channel| {
+            if channel.alive.strong_count() == 0 {
+                return false;
+            }
+            let record = Arc::clone(record);
+            let result = match policy {
+                OverflowPolicy::Block => channel
+                    .sender
+                    .send_timeout(record, SINK_SEND_TIMEOUT)
+                    .map_err(|e| match e {
+                        SendTimeoutError::Timeout(r) => TrySendError::Full(r),
+                        SendTimeoutError::Disconnected(r) => TrySendError::Disconnected(r),
+                    }),
+                OverflowPolicy::DropNewest => channel.sender.try_send(record),
+                OverflowPolicy::DropOldest => match channel.sender.try_send(record) {
+                    Err(TrySendError::Full(record)) => {
+                        // 逐出该 sink 最旧的记录后重试一次
+                        if channel.receiver.try_recv().is_ok() {
+                            metrics.inc_logs_dropped();
+                        }
+                        channel.sender.try_send(record)
+                    }
+                    other => other,
+                },
+            };
+            match result {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    metrics.inc_logs_dropped();
+                    true
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+    }
+}
+
+/// 采样与去重产生、尚未发出的汇总记录
+///
+/// 汇总平时在下一条记录到达时发出；洪峰之后没有新记录时由后台定时器
+/// （health check 线程的 tick）补发，shutdown 时全部发出。
+#[derive(Clone, Default)]
+struct PendingSummaries {
+    sampler: Option<Arc<LogSampler>>,
+    dedup: Option<Arc<LogDeduplicator>>,
+}
+
+impl PendingSummaries {
+    /// 定时器触发时已到期的汇总：超出窗口的重复段与到期的采样汇总
+    fn due(&self) -> Vec<LogRecord> {
+        let mut summaries: Vec<LogRecord> = self
+            .dedup
+            .as_ref()
+            .and_then(|dedup| dedup.expire())
+            .into_iter()
+            .collect();
+        if let Some(ref sampler) = self.sampler {
+            summaries.extend(sampler.take_summaries());
+        }
+        summaries
+    }
+
+    /// 所有未发出的汇总，包括未结束的重复段
+    fn finish(&self) -> Vec<LogRecord> {
+        let mut summaries: Vec<LogRecord> = self
+            .dedup
+            .as_ref()
+            .and_then(|dedup| dedup.finish())
+            .into_iter()
+            .collect();
+        if let Some(ref sampler) = self.sampler {
+            summaries.extend(sampler.flush());
+        }
+        summaries
+    }
 }
 
 // Parameters for worker threads
@@ -50,10 +300,18 @@ struct WorkerParams {
     console_receiver: Receiver<Arc<LogRecord>>,
     control_rx: Receiver<SinkControlMessage>,
     control_tx: Sender<SinkControlMessage>,
+    flush_rx: Receiver<SinkControlMessage>,
+    /// 线程批量发送的 channel（未启用时为 `never()`）
+    batch_rx: Receiver<Vec<Arc<LogRecord>>>,
     metrics: Arc<Metrics>,
     console_sink: Arc<Mutex<ConsoleSink>>,
     error_sink: Arc<Mutex<Option<FileSink>>>,
     effective_capacity: Arc<AtomicUsize>,
+    byte_budget: Option<Arc<ByteBudget>>,
+    /// 主 channel 与控制台 channel 的 sender，定时汇总经由它们入队
+    sender: Sender<Arc<LogRecord>>,
+    console_sender: Sender<Arc<LogRecord>>,
+    summaries: PendingSummaries,
     /// 注入的数据库依赖（DI 模式）
     #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
     database: Option<Arc<dyn Database>>,
@@ -63,6 +321,361 @@ struct WorkerParams {
 /// 第一项为 worker 线程句柄，第二项为每个 worker 对应的 shutdown 信号 sender。
 type WorkerStartResult = Result<(Vec<tokio::task::JoinHandle<()>>, Vec<Sender<()>>), InklogError>;
 
+/// [`LoggerManager::build_detached_reloadable`] 返回的级别过滤层
+///
+/// 以 `registry().with(subscriber).with(filter)` 安装，之后可通过
+/// [`LoggerManager::set_level`] 在运行时替换。
+pub type LevelFilterLayer = reload::Layer<EnvFilter, Layered<LoggerSubscriber, Registry>>;
+
+/// [`LoggerManager::layer`] 返回的可组合层
+///
+/// 级别过滤以 per-layer filter 挂在 [`LoggerSubscriber`] 上，只作用于 inklog
+/// 自身，不影响同一 registry 中的其他层（fmt、OpenTelemetry 等）。
+pub type InklogLayer<S> = Filtered<LoggerSubscriber, reload::Layer<EnvFilter, S>, S>;
+
+/// 运行时替换级别过滤器的回调，擦除 reload handle 所绑定的 subscriber 类型
+type LevelReloader = Arc<dyn Fn(EnvFilter) -> Result<(), reload::Error> + Send + Sync>;
+
+/// 运行时修改级别所需的状态，`set_level` 与 HTTP `PUT /level` 共用
+#[derive(Clone)]
+struct LevelControl {
+    /// 级别过滤层的 reload handle；`build_detached` 返回的静态过滤器没有
+    reloader: Option<LevelReloader>,
+    /// 是否由本实例安装了 `log` crate logger；是则同步 `log::max_level`
+    log_logger_installed: bool,
+    /// 当前全局级别，审计记录中的旧级别取自这里
+    current: Arc<Mutex<tracing::Level>>,
+    sender: Sender<Arc<LogRecord>>,
+    console_sender: Sender<Arc<LogRecord>>,
+    byte_budget: Option<Arc<ByteBudget>>,
+}
+
+impl LevelControl {
+    /// 替换全局级别，成功后发出审计记录；`source` 标明修改来源（`api`、`http`）
+    fn apply(&self, level: &str, source: &str) -> Result<(), InklogError> {
+        let new_level = level
+            .trim()
+            .parse::<tracing::Level>()
+            .map_err(|_| InklogError::ConfigError(format!("Invalid log level '{}'", level)))?;
+        let reloader = self.reloader.as_ref().ok_or_else(|| {
+            InklogError::RuntimeError(
+                "Level filter is not reloadable; build with build_detached_reloadable".to_string(),
+            )
+        })?;
+
+        // 持锁完成替换，并发修改时审计记录中的旧级别与实际顺序一致
+        let mut current = match self.current.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        reloader(LoggerManager::level_filter(new_level)).map_err(|e| {
+            InklogError::RuntimeError(format!("Failed to reload level filter: {}", e))
+        })?;
+        if self.log_logger_installed {
+            log::set_max_level(LoggerManager::log_level_filter(new_level));
+        }
+        let old_level = std::mem::replace(&mut *current, new_level);
+        drop(current);
+
+        let old_name = LoggerManager::level_name(old_level);
+        let new_name = LoggerManager::level_name(new_level);
+        let record = LogRecord {
+            timestamp: Utc::now(),
+            level: "INFO".to_string(),
+            target: "inklog::level".to_string(),
+            message: format!(
+                "Log level changed from {} to {} via {}",
+                old_name, new_name, source
+            ),
+            fields: [
+                ("old_level", old_name),
+                ("new_level", new_name),
+                ("source", source),
+            ]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), serde_json::Value::from(value)))
+            .collect(),
+            file: None,
+            line: None,
+            thread_id: thread::current().name().unwrap_or("unknown").to_string(),
+        };
+        // 审计记录绕过级别过滤，提高到 WARN 以上时同样会写入
+        LoggerManager::enqueue_internal(
+            &self.sender,
+            &self.console_sender,
+            &self.byte_budget,
+            Arc::new(record),
+        );
+        Ok(())
+    }
+}
+
+/// worker 写入失败时的重试与自动恢复策略，取自 [`crate::PerformanceConfig`]
+#[derive(Debug, Clone, Copy)]
+struct WriteRetryPolicy {
+    /// 首次写入失败后的重试次数
+    max_retries: u32,
+    /// 第一次重试前的等待时间，之后每次翻倍
+    base_delay: Duration,
+    /// 连续失败超过该次数才触发自动恢复
+    recovery_failures: u32,
+    /// 最近一次失败距今超过该时长才触发自动恢复
+    recovery_after: Duration,
+}
+
+impl WriteRetryPolicy {
+    fn from_config(performance: &crate::PerformanceConfig) -> Self {
+        Self {
+            max_retries: performance.max_write_retries,
+            base_delay: Duration::from_millis(performance.retry_base_delay_ms),
+            recovery_failures: performance.recovery_failure_threshold,
+            recovery_after: Duration::from_secs(performance.recovery_delay_secs),
+        }
+    }
+
+    /// 第 `retry` 次重试（从 1 开始）前的等待时间
+    fn backoff(&self, retry: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(1u32 << retry.saturating_sub(1).min(16))
+    }
+
+    /// 执行 `write`，失败时按指数退避重试
+    ///
+    /// 每次失败都会调用 `on_error`；重试耗尽后返回最后一次的错误。
+    fn run<E>(
+        &self,
+        mut write: impl FnMut() -> Result<(), E>,
+        mut on_error: impl FnMut(&E),
+    ) -> Result<(), E> {
+        let mut retry = 0;
+        loop {
+            match write() {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    on_error(&e);
+                    if retry >= self.max_retries {
+                        return Err(e);
+                    }
+                    retry += 1;
+                    thread::sleep(self.backoff(retry));
+                }
+            }
+        }
+    }
+
+    /// 连续失败次数与最近一次失败时间是否满足自动恢复条件
+    fn should_recover(&self, consecutive_failures: u32, last_failure: Option<Instant>) -> bool {
+        consecutive_failures > self.recovery_failures
+            && last_failure.is_some_and(|at| at.elapsed() > self.recovery_after)
+    }
+}
+
+/// file worker 中的单个文件 sink 及其失败状态
+struct FileSinkSlot {
+    config: FileSinkConfig,
+    sink: FileSink,
+    consecutive_failures: u32,
+    last_failure_time: Option<Instant>,
+}
+
+impl FileSinkSlot {
+    /// 按配置重建 sink 并清除失败状态；失败时保留原 sink
+    fn recreate(&mut self, masker: &DataMasker) -> bool {
+        match FileSink::new(self.config.clone()) {
+            Ok(sink) => {
+                self.sink = sink.with_masker(masker.clone());
+                self.consecutive_failures = 0;
+                self.last_failure_time = None;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// 所有文件 sink 共用的 worker 状态
+///
+/// 文件 sink 由同一个 worker 承载，共用 dispatcher 分发的一个 channel，
+/// 每条记录按各 sink 的 `targets` / `min_level` 写入所有匹配的 sink。
+struct FileWorker {
+    slots: Vec<FileSinkSlot>,
+    runtime_handle: tokio::runtime::Handle,
+    retry_policy: WriteRetryPolicy,
+    metrics: Arc<Metrics>,
+    error_sink: Arc<Mutex<Option<FileSink>>>,
+    console_sink: Arc<Mutex<ConsoleSink>>,
+    masker: DataMasker,
+}
+
+impl FileWorker {
+    /// 为每个配置创建 sink；创建失败的 sink 通过控制台告警后跳过
+    fn new(
+        configs: Vec<FileSinkConfig>,
+        runtime_handle: tokio::runtime::Handle,
+        retry_policy: WriteRetryPolicy,
+        metrics: Arc<Metrics>,
+        error_sink: Arc<Mutex<Option<FileSink>>>,
+        console_sink: Arc<Mutex<ConsoleSink>>,
+        masker: DataMasker,
+    ) -> Self {
+        let slots = configs
+            .into_iter()
+            .filter_map(|config| match FileSink::new(config.clone()) {
+                Ok(sink) => Some(FileSinkSlot {
+                    config,
+                    sink: sink.with_masker(masker.clone()),
+                    consecutive_failures: 0,
+                    last_failure_time: None,
+                }),
+                Err(e) => {
+                    LoggerManager::warn_via_console(
+                        &console_sink,
+                        format!("File sink '{}' disabled: {}", config.name, e),
+                    );
+                    None
+                }
+            })
+            .collect();
+        Self {
+            slots,
+            runtime_handle,
+            retry_policy,
+            metrics,
+            error_sink,
+            console_sink,
+            masker,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// 是否承载名为 `name` 的 sink
+    fn owns(&self, name: &str) -> bool {
+        self.slots.iter().any(|slot| slot.config.name == name)
+    }
+
+    /// 写入所有匹配该记录的 sink，重试耗尽后回退到控制台
+    ///
+    /// `logs_written` 每条记录只计一次（至少一个 sink 写入成功时），各 sink 的
+    /// 写入次数见 `record_sink_write`。`auto_recover` 为 true 时，连续失败满足
+    /// 恢复条件的 sink 会被重建。
+    fn write(&mut self, record: &LogRecord, auto_recover: bool) {
+        let mut console_fallback = false;
+        let mut written = false;
+        for slot in self
+            .slots
+            .iter_mut()
+            .filter(|slot| slot.config.accepts(&record.target, &record.level))
+        {
+            let sink = &mut slot.sink;
+            let write_started = Instant::now();
+            let write_result = self.retry_policy.run(
+                || {
+                    self.runtime_handle
+                        .block_on(async { sink.write(record).await })
+                },
+                |e| {
+                    slot.consecutive_failures += 1;
+                    slot.last_failure_time = Some(Instant::now());
+                    LoggerManager::write_error_log(
+                        &self.runtime_handle,
+                        &self.error_sink,
+                        format!("File sink '{}' error: {}", slot.config.name, e),
+                    );
+                },
+            );
+            self.metrics
+                .record_sink_write(&slot.config.name, write_started.elapsed());
+            match write_result {
+                Ok(()) => {
+                    written = true;
+                    self.metrics
+                        .update_sink_health(&slot.config.name, true, None);
+                    slot.consecutive_failures = 0;
+                    slot.last_failure_time = None;
+                }
+                Err(e) => {
+                    self.metrics.inc_sink_error();
+                    self.metrics
+                        .update_sink_health(&slot.config.name, false, Some(e.to_string()));
+                    // 同一条记录只回退到控制台一次
+                    console_fallback = true;
+
+                    if auto_recover
+                        && self
+                            .retry_policy
+                            .should_recover(slot.consecutive_failures, slot.last_failure_time)
+                    {
+                        eprintln!(
+                            "File sink '{}': Triggering auto-recovery due to consecutive failures",
+                            slot.config.name
+                        );
+                        if slot.recreate(&self.masker) {
+                            self.metrics
+                                .update_sink_health(&slot.config.name, true, None);
+                            eprintln!("File sink '{}': Auto-recovery successful", slot.config.name);
+                        }
+                    }
+                }
+            }
+        }
+
+        if written {
+            self.metrics.inc_logs_written();
+        }
+        if console_fallback && let Ok(cs) = self.console_sink.lock() {
+            let _ = self
+                .runtime_handle
+                .block_on(async { cs.write(record).await });
+        }
+    }
+
+    /// 处理 `RecoverSink`：重建名为 `name` 的 sink
+    fn recover(&mut self, name: &str) {
+        let Some(slot) = self.slots.iter_mut().find(|slot| slot.config.name == name) else {
+            return;
+        };
+        eprintln!("File sink '{}': Received recovery command", name);
+        if slot.recreate(&self.masker) {
+            self.metrics.update_sink_health(name, true, None);
+            eprintln!("File sink '{}': Successfully recovered", name);
+        } else {
+            eprintln!("File sink '{}': Recovery failed", name);
+        }
+    }
+
+    /// flush 名为 `name` 的 sink
+    fn flush(&mut self, name: &str) -> Result<(), String> {
+        let slot = self
+            .slots
+            .iter_mut()
+            .find(|slot| slot.config.name == name)
+            .ok_or_else(|| format!("unknown file sink '{}'", name))?;
+        self.runtime_handle
+            .block_on(async { slot.sink.flush().await })
+            .map_err(|e| e.to_string())
+    }
+
+    /// 空闲时 flush 所有 sink 的缓冲
+    fn flush_all(&mut self) {
+        for slot in &mut self.slots {
+            let _ = self
+                .runtime_handle
+                .block_on(async { slot.sink.flush().await });
+        }
+    }
+
+    fn shutdown(&mut self) {
+        for slot in &mut self.slots {
+            let _ = self
+                .runtime_handle
+                .block_on(async { slot.sink.shutdown().await });
+        }
+    }
+}
+
 /// LoggerManager 的依赖集合
 ///
 /// 用于依赖注入模式，允许外部提供缓存、配置和数据库实现。
@@ -151,14 +764,30 @@ pub struct LoggerManager {
     sender: Sender<Arc<LogRecord>>,
     console_sender: Sender<Arc<LogRecord>>,
     shutdown_txs: Vec<Sender<()>>,
-    #[allow(dead_code)]
     console_sink: Arc<Mutex<ConsoleSink>>,
     metrics: Arc<Metrics>,
     worker_handles: Mutex<Vec<tokio::task::JoinHandle<()>>>,
     control_tx: Sender<SinkControlMessage>,
+    /// flush 请求先交给 dispatcher，由它在分发完已排队的记录后转发给 worker
+    flush_tx: Sender<SinkControlMessage>,
     effective_capacity: Arc<AtomicUsize>,
+    byte_budget: Option<Arc<ByteBudget>>,
+    /// 各线程未发送的批量记录，flush 与 shutdown 时统一发出
+    thread_batches: Arc<ThreadBatchRegistry>,
+    /// 运行时修改级别（`set_level`、HTTP `PUT /level`）所需的状态
+    level_control: LevelControl,
+    /// `with_config` 构建的 subscriber；reload handle 只持有其弱引用，
+    /// 未能安装为全局默认时由这里保持存活，级别修改不依赖全局状态
+    dispatch: Option<tracing::Dispatch>,
+    /// 最近记录的内存环形缓冲区（`memory_ring_sink` 启用时存在）
+    #[cfg(feature = "memory-ring")]
+    memory_ring: Option<Arc<MemoryRingSink>>,
+    /// 采样与去重的汇总，shutdown 时发出尚未发出的部分
+    summaries: PendingSummaries,
     #[cfg(feature = "http")]
     http_server_handle: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    #[cfg(feature = "http")]
+    http_state: tokio::sync::watch::Sender<HttpServerState>,
     /// 注入的缓存依赖
     cache: Option<Arc<dyn Cache>>,
     /// 注入的数据库依赖（需要 dbnexus feature）
@@ -298,7 +927,7 @@ impl LoggerManager {
         let database = deps.database;
 
         // 使用解析后的配置调用现有的构建逻辑
-        let (mut manager, _subscriber, _filter) = Self::build_detached(
+        let (mut manager, _subscriber, _filter) = Self::build_detached_reloadable(
             config,
             #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
             database.clone(),
@@ -349,7 +978,7 @@ impl LoggerManager {
             "Logger manager initialized"
         );
 
-        let (manager, subscriber, filter) = Self::build_detached(
+        let (mut manager, subscriber, filter) = Self::build_detached_reloadable(
             config.clone(),
             #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
             None,
@@ -357,46 +986,57 @@ impl LoggerManager {
         .await?;
 
         // 1. 安装 tracing subscriber
-        let registry = tracing_subscriber::registry().with(subscriber).with(filter);
+        let dispatch =
+            tracing::Dispatch::new(tracing_subscriber::registry().with(subscriber).with(filter));
         // `SetGlobalDefaultError` 的唯一含义是"全局 subscriber 已被设置"——通常是宿主
         // 应用已先行安装。属良性条件：tracing 事件会流向已安装的 subscriber，降级为
         // debug 与下方 log logger 处理保持一致，避免噪音。
-        if let Err(ref e) = registry.try_init() {
+        if let Err(ref e) = tracing::dispatcher::set_global_default(dispatch.clone()) {
             tracing::debug!(error = %e, "global subscriber already set; skipping inklog registry");
         }
+        manager.dispatch = Some(dispatch);
 
         // 2. 安装 log crate logger（原生支持，无需 tracing_log）
-        let log_adapter = LogAdapter::new(
+        let mut log_adapter = LogAdapter::new(
             manager.console_sender.clone(),
             manager.sender.clone(),
             manager.metrics.clone(),
         );
+        if let Some(ref budget) = manager.byte_budget {
+            log_adapter = log_adapter.with_byte_budget(budget.clone());
+        }
         let max_level = config
             .global
             .level
             .parse::<tracing::Level>()
             .unwrap_or(tracing::Level::INFO);
-        let log_level = match max_level {
-            tracing::Level::TRACE => log::LevelFilter::Trace,
-            tracing::Level::DEBUG => log::LevelFilter::Debug,
-            tracing::Level::INFO => log::LevelFilter::Info,
-            tracing::Level::WARN => log::LevelFilter::Warn,
-            tracing::Level::ERROR => log::LevelFilter::Error,
-        };
-        let log_logger = LogLogger::new(log_adapter, log_level);
+        let log_logger = LogLogger::new(log_adapter, Self::log_level_filter(max_level));
         // `log::SetLoggerError` 的唯一含义是"全局 logger 已被设置"——通常是宿主
         // 应用（如 tracing-opentelemetry → tracing-log 桥接）已先行安装。属良性条件：
         // log 记录仍会流入已安装的 logger，不应视为故障，降级为 debug 避免噪音。
-        if let Err(e) = log_logger.install() {
-            tracing::debug!(error = %e, "log crate logger already set; skipping inklog LogLogger");
+        match log_logger.install() {
+            Ok(()) => manager.level_control.log_logger_installed = true,
+            Err(e) => {
+                tracing::debug!(error = %e, "log crate logger already set; skipping inklog LogLogger");
+            }
         }
 
         // 3. 启动HTTP监控服务器（如果配置启用）
+        manager.start_configured_http_server(&config).await?;
+
+        Ok(manager)
+    }
+
+    /// 按配置启动 HTTP 监控服务器；`error_mode = warn` 时启动失败仅记录警告
+    #[cfg_attr(not(feature = "http"), allow(unused_variables))]
+    async fn start_configured_http_server(&self, config: &InklogConfig) -> Result<(), InklogError> {
         #[cfg(feature = "http")]
         if let Some(ref http_cfg) = config.http_server
             && http_cfg.enabled
-            && let Err(e) = manager.start_http_server(http_cfg).await
+            && let Err(e) = self.start_http_server(http_cfg).await
         {
+            self.http_state
+                .send_replace(HttpServerState::Failed(e.to_string()));
             match http_cfg.error_mode {
                 crate::HttpErrorMode::Warn => {
                     tracing::warn!("HTTP server startup failed (continuing): {}", e);
@@ -406,69 +1046,233 @@ impl LoggerManager {
                 }
             }
         }
-
-        Ok(manager)
+        Ok(())
     }
 
     /// 构建LoggerManager但不安装全局订阅者。
     /// 这主要用于测试和基准测试。
+    ///
+    /// 返回的级别过滤器是静态的，`set_level` 返回 `RuntimeError`；需要运行时修改
+    /// 级别时使用 [`build_detached_reloadable`](Self::build_detached_reloadable)。
     pub async fn build_detached(
         config: InklogConfig,
         #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))] database: Option<
             Arc<dyn Database>,
         >,
-    ) -> Result<
-        (
-            Self,
-            LoggerSubscriber,
-            tracing_subscriber::filter::EnvFilter,
-        ),
-        InklogError,
-    > {
+    ) -> Result<(Self, LoggerSubscriber, EnvFilter), InklogError> {
+        let filter = Self::configured_level_filter(&config);
+        let (manager, subscriber) = Self::build_parts(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            database,
+            None,
+        )?;
+        Ok((manager, subscriber, filter))
+    }
+
+    /// 与 [`build_detached`](Self::build_detached) 相同，但级别过滤器位于 reload 层中，
+    /// 可通过 `set_level` 在运行时替换
+    pub async fn build_detached_reloadable(
+        config: InklogConfig,
+        #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))] database: Option<
+            Arc<dyn Database>,
+        >,
+    ) -> Result<(Self, LoggerSubscriber, LevelFilterLayer), InklogError> {
+        let (filter, level_handle) = reload::Layer::new(Self::configured_level_filter(&config));
+        let (manager, subscriber) = Self::build_parts(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            database,
+            Some(Self::level_reloader(level_handle)),
+        )?;
+        Ok((manager, subscriber, filter))
+    }
+
+    /// 构建可加入宿主 registry 的 inklog 层，不安装任何全局 subscriber 或 log logger
+    ///
+    /// 适用于宿主应用自行管理全局 subscriber 的场景，可与 `fmt`、OpenTelemetry
+    /// 等层组合。级别过滤只作用于 inklog 层本身；worker 线程与 sink 由返回的
+    /// `LoggerManager` 持有，需保持其存活并在退出前调用 [`LoggerManager::shutdown`]。
+    /// 若配置启用了 HTTP 服务器，同样会启动。
+    ///
+    /// # Example
+    /// ```ignore
+    /// use inklog::{InklogConfig, LoggerManager};
+    /// use tracing_subscriber::prelude::*;
+    ///
+    /// let (layer, manager) = LoggerManager::layer(InklogConfig::default()).await?;
+    /// tracing_subscriber::registry()
+    ///     .with(tracing_subscriber::fmt::layer())
+    ///     .with(layer)
+    ///     .init();
+    /// ```
+    pub async fn layer<S>(config: InklogConfig) -> Result<(InklogLayer<S>, Self), InklogError>
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a> + 'static,
+    {
+        let (filter, level_handle) =
+            reload::Layer::<EnvFilter, S>::new(Self::configured_level_filter(&config));
+        let (manager, subscriber) = Self::build_parts(
+            config.clone(),
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+            Some(Self::level_reloader(level_handle)),
+        )?;
+        manager.start_configured_http_server(&config).await?;
+        Ok((subscriber.with_filter(filter), manager))
+    }
+
+    fn level_reloader<S: 'static>(handle: reload::Handle<EnvFilter, S>) -> LevelReloader {
+        Arc::new(move |filter| handle.reload(filter))
+    }
+
+    fn build_parts(
+        config: InklogConfig,
+        #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))] database: Option<
+            Arc<dyn Database>,
+        >,
+        level_reloader: Option<LevelReloader>,
+    ) -> Result<(Self, LoggerSubscriber), InklogError> {
         let metrics = Arc::new(Metrics::new());
+        metrics.set_dropped_degraded_threshold(config.performance.dropped_logs_degraded_threshold);
+        metrics.set_sink_latency_buckets(&config.performance.sink_latency_buckets);
+        metrics.set_static_labels(&config.global.metric_labels);
         let (sender, receiver) = bounded(config.performance.channel_capacity);
         let (console_sender, console_receiver) = bounded(config.performance.channel_capacity);
         let (control_tx, control_rx) = bounded(10); // Control channel for recovery commands
+        let (flush_tx, flush_rx) = bounded(10);
         let effective_capacity = Arc::new(AtomicUsize::new(config.performance.channel_capacity));
 
-        let console_sink = Arc::new(Mutex::new(ConsoleSink::new(
-            config.console_sink.clone().unwrap_or_default(),
-            LogTemplate::new(&config.global.format),
-        )));
-
-        // Initialize tracing subscriber with console_sender channel
-        let subscriber =
-            LoggerSubscriber::new(console_sender.clone(), sender.clone(), metrics.clone());
+        let console_sink = Arc::new(Mutex::new(
+            ConsoleSink::new(
+                config.console_sink.clone().unwrap_or_default(),
+                LogTemplate::new(&config.global.format),
+            )
+            .with_masker(Self::masker(&config)),
+        ));
+        if config.global.reopen_on_sighup {
+            #[cfg(unix)]
+            crate::sink::install_sighup_handler()?;
+            #[cfg(not(unix))]
+            Self::warn_via_console(
+                &console_sink,
+                "global.reopen_on_sighup is only supported on Unix; ignoring".to_string(),
+            );
+        }
 
-        // Filter — use EnvFilter to support RUST_LOG per-module filtering
-        // Configured level serves as the global default; RUST_LOG overrides
-        // specific modules (e.g. RUST_LOG=nebulaid=debug,hyper=warn).
-        let level = config
-            .global
-            .level
-            .parse::<tracing::Level>()
-            .unwrap_or(tracing::Level::INFO);
-        let level_str = match level {
-            tracing::Level::TRACE => "trace",
-            tracing::Level::DEBUG => "debug",
-            tracing::Level::INFO => "info",
-            tracing::Level::WARN => "warn",
-            tracing::Level::ERROR => "error",
-        };
-        let filter = match std::env::var("RUST_LOG") {
-            Ok(val) if !val.is_empty() => {
-                tracing_subscriber::filter::EnvFilter::new(format!("{},{}", level_str, val))
-            }
-            _ => tracing_subscriber::filter::EnvFilter::new(level_str),
-        };
+        let byte_budget = config
+            .performance
+            .max_buffered_bytes
+            .map(|limit| Arc::new(ByteBudget::new(limit)));
 
-        // Create error sink for logging system errors
+        // Initialize tracing subscriber with console_sender channel
+        let mut subscriber =
+            LoggerSubscriber::new(console_sender.clone(), sender.clone(), metrics.clone())
+                .with_overflow_policy(config.performance.overflow_policy, receiver.clone());
+        if let Some(ref budget) = byte_budget {
+            subscriber = subscriber.with_byte_budget(budget.clone());
+        }
+        if let Some(max_bytes) = config.global.max_field_value_bytes {
+            subscriber = subscriber.with_field_value_limit(max_bytes);
+        }
+        if let Some(mode) = config.global.sanitize_control_chars {
+            subscriber = subscriber.with_control_char_sanitization(mode);
+        }
+        if !config.global.strip_fields_for_targets.is_empty() {
+            subscriber = subscriber
+                .with_stripped_field_targets(config.global.strip_fields_for_targets.clone());
+        }
+        if config
+            .console_sink
+            .as_ref()
+            .is_some_and(|console| console.synchronous)
+        {
+            subscriber = subscriber.with_sync_console(console_sink.clone());
+        }
+        #[cfg(feature = "alerts")]
+        if !config.alert_rules.is_empty() {
+            subscriber = subscriber.with_alerts(Arc::new(Self::spawn_alert_dispatcher(
+                &config.alert_rules,
+                Self::remote_masker(&config),
+            )?));
+        }
+        #[cfg(feature = "memory-ring")]
+        let memory_ring = config
+            .memory_ring_sink
+            .as_ref()
+            .filter(|ring| ring.enabled)
+            .map(|ring_config| {
+                let ring = Arc::new(
+                    MemoryRingSink::from_config(
+                        ring_config,
+                        LogTemplate::new(&config.global.format),
+                    )
+                    .with_masker(Self::masker(&config)),
+                );
+                if let Some(ref path) = ring_config.panic_dump_path {
+                    ring.install_panic_hook(path);
+                }
+                ring
+            });
+        #[cfg(feature = "memory-ring")]
+        if let Some(ref ring) = memory_ring {
+            subscriber = subscriber.with_memory_ring(ring.clone());
+        }
+        let dedup = config
+            .dedup
+            .enabled
+            .then(|| Arc::new(LogDeduplicator::new(&config.dedup)));
+        if let Some(ref dedup) = dedup {
+            subscriber = subscriber.with_dedup(dedup.clone());
+        }
+        let sampler = if config.sampling.rules.is_empty() {
+            None
+        } else {
+            Some(Arc::new(LogSampler::new(&config.sampling)?))
+        };
+        if let Some(ref sampler) = sampler {
+            subscriber = subscriber.with_sampler(sampler.clone());
+        }
+        let summaries = PendingSummaries { sampler, dedup };
+        let batch_rx = if config.performance.thread_batch_size > 1 {
+            // 每个 batch 占一个槽位，按平均批量折算容量
+            let (batch_tx, batch_rx) = bounded(
+                (config.performance.channel_capacity / config.performance.thread_batch_size).max(1),
+            );
+            subscriber = subscriber.with_thread_batching(
+                ThreadBatchConfig {
+                    max_records: config.performance.thread_batch_size,
+                    max_delay: Duration::from_millis(config.performance.thread_batch_max_delay_ms),
+                },
+                batch_tx,
+                batch_rx.clone(),
+            );
+            batch_rx
+        } else {
+            crossbeam_channel::never()
+        };
+
+        // Create error sink for logging system errors
         let error_sink_config = FileSinkConfig {
             enabled: true,
-            path: PathBuf::from("logs/error.log"),
+            path: config.error_log_path.clone(),
             ..Default::default()
         };
-        let error_sink = Arc::new(Mutex::new(FileSink::new(error_sink_config).ok()));
+        let error_sink = match FileSink::new(error_sink_config) {
+            Ok(sink) => Some(sink),
+            Err(e) => {
+                Self::warn_via_console(
+                    &console_sink,
+                    format!(
+                        "Failed to create error log {}: {}",
+                        config.error_log_path.display(),
+                        e
+                    ),
+                );
+                None
+            }
+        };
+        let error_sink = Arc::new(Mutex::new(error_sink));
 
         let (handles, shutdown_txs) = Self::start_workers(WorkerParams {
             config: config.clone(),
@@ -476,14 +1280,28 @@ impl LoggerManager {
             console_receiver,
             control_rx,
             control_tx: control_tx.clone(),
+            flush_rx,
+            batch_rx,
             metrics: metrics.clone(),
             console_sink: console_sink.clone(),
             error_sink: error_sink.clone(),
             effective_capacity: effective_capacity.clone(),
+            byte_budget: byte_budget.clone(),
+            sender: sender.clone(),
+            console_sender: console_sender.clone(),
+            summaries: summaries.clone(),
             #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
             database,
         })?;
 
+        let level_control = LevelControl {
+            reloader: level_reloader,
+            log_logger_installed: false,
+            current: Arc::new(Mutex::new(Self::configured_level(&config))),
+            sender: sender.clone(),
+            console_sender: console_sender.clone(),
+            byte_budget: byte_budget.clone(),
+        };
         let manager = Self {
             config,
             sender,
@@ -493,25 +1311,119 @@ impl LoggerManager {
             metrics,
             worker_handles: Mutex::new(handles),
             control_tx,
+            flush_tx,
             effective_capacity: effective_capacity.clone(),
+            byte_budget,
+            thread_batches: subscriber.thread_batches(),
+            level_control,
+            dispatch: None,
+            #[cfg(feature = "memory-ring")]
+            memory_ring,
+            summaries,
             #[cfg(feature = "http")]
             http_server_handle: Mutex::new(None),
+            #[cfg(feature = "http")]
+            http_state: tokio::sync::watch::Sender::new(HttpServerState::NotStarted),
             cache: None,
             #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
             database: None,
         };
 
-        Ok((manager, subscriber, filter))
+        Ok((manager, subscriber))
     }
 
     pub fn builder() -> LoggerBuilder {
         LoggerBuilder::default()
     }
 
+    /// 以配置的 `global.level` 为全局默认级别构造 `EnvFilter`
+    ///
+    /// `RUST_LOG` 中的模块级指令叠加其后（如 `RUST_LOG=nebulaid=debug,hyper=warn`）。
+    /// 过滤器位于 reload 层中时，`set_level` 可在运行时替换。
+    fn configured_level_filter(config: &InklogConfig) -> EnvFilter {
+        Self::level_filter(Self::configured_level(config))
+    }
+
+    /// 配置的 `global.level`，无法解析时为 INFO
+    fn configured_level(config: &InklogConfig) -> tracing::Level {
+        config
+            .global
+            .level
+            .parse::<tracing::Level>()
+            .unwrap_or(tracing::Level::INFO)
+    }
+
+    fn level_name(level: tracing::Level) -> &'static str {
+        match level {
+            tracing::Level::TRACE => "trace",
+            tracing::Level::DEBUG => "debug",
+            tracing::Level::INFO => "info",
+            tracing::Level::WARN => "warn",
+            tracing::Level::ERROR => "error",
+        }
+    }
+
+    fn level_filter(level: tracing::Level) -> EnvFilter {
+        let level_str = Self::level_name(level);
+        match std::env::var("RUST_LOG") {
+            Ok(val) if !val.is_empty() => EnvFilter::new(format!("{},{}", level_str, val)),
+            _ => EnvFilter::new(level_str),
+        }
+    }
+
+    /// 按 `global.masked_fields` 与 `global.builtin_detectors` 构造各 sink 共用的脱敏器
+    fn masker(config: &InklogConfig) -> DataMasker {
+        DataMasker::new()
+            .with_masked_fields(&config.global.masked_fields)
+            .with_builtin_detectors(&config.global.builtin_detectors)
+    }
+
+    /// 远程 sink 与告警 webhook 的 masker；二者没有单独的开关，由 `global.masking_enabled` 决定
+    #[cfg(any(
+        feature = "nats",
+        feature = "elasticsearch",
+        feature = "otlp",
+        feature = "syslog",
+        feature = "loki",
+        feature = "alerts"
+    ))]
+    fn remote_masker(config: &InklogConfig) -> Option<DataMasker> {
+        config.global.masking_enabled.then(|| Self::masker(config))
+    }
+
+    fn log_level_filter(level: tracing::Level) -> log::LevelFilter {
+        match level {
+            tracing::Level::TRACE => log::LevelFilter::Trace,
+            tracing::Level::DEBUG => log::LevelFilter::Debug,
+            tracing::Level::INFO => log::LevelFilter::Info,
+            tracing::Level::WARN => log::LevelFilter::Warn,
+            tracing::Level::ERROR => log::LevelFilter::Error,
+        }
+    }
+
+    /// 运行时修改全局日志级别，无需重启
+    ///
+    /// `level` 为 `trace`、`debug`、`info`、`warn` 或 `error`（大小写不敏感），
+    /// `RUST_LOG` 中的模块级指令继续生效。启用 `http` feature 时也可通过
+    /// HTTP 服务器的 `PUT /level` 调用。无效级别返回 `ConfigError`；由
+    /// [`build_detached`](Self::build_detached) 构建（静态过滤器）时返回 `RuntimeError`。
+    ///
+    /// 修改成功后经正常管道发出一条 target 为 `inklog::level` 的 INFO 审计记录，
+    /// 字段 `old_level`、`new_level` 与 `source`（本方法为 `api`，HTTP 为 `http`）；
+    /// 审计记录不受级别过滤影响。
+    pub fn set_level(&self, level: &str) -> Result<(), InklogError> {
+        self.level_control.apply(level, "api")
+    }
+
+    /// 返回编译进当前构建的 cargo feature 列表（同时通过 HTTP `/features` 暴露）
+    pub fn enabled_features() -> Vec<&'static str> {
+        crate::config::compiled_features()
+    }
+
     /// 从配置文件初始化LoggerManager
     ///
     /// # Arguments
-    /// * `path` - 配置文件路径（TOML格式）
+    /// * `path` - 配置文件路径（TOML、YAML 或 JSON，按扩展名识别）
     ///
     /// # Returns
     /// 成功返回LoggerManager实例，失败返回错误
@@ -527,10 +1439,7 @@ impl LoggerManager {
     /// }
     /// ```
     pub async fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, InklogError> {
-        let content = std::fs::read_to_string(path.as_ref())
-            .map_err(|e| InklogError::ConfigError(format!("Failed to read config file: {}", e)))?;
-        let config: InklogConfig = toml::from_str(&content)
-            .map_err(|e| InklogError::ConfigError(format!("Failed to parse config file: {}", e)))?;
+        let config = InklogConfig::from_file(path)?;
         Self::with_config(config).await
     }
 
@@ -573,12 +1482,19 @@ impl LoggerManager {
             http::{Request, StatusCode, header},
             middleware::{self, Next},
             response::{IntoResponse, Response},
-            routing::get,
+            routing::{get, put},
         };
         use std::net::SocketAddr;
 
+        self.http_state.send_replace(HttpServerState::Pending);
         let metrics = self.metrics.clone();
+        let level_control = self.level_control.clone();
+        // 未启用认证时，`PUT /level` 需显式开启
+        let level_change_allowed = config.auth.as_ref().is_some_and(|a| a.enabled)
+            || config.allow_unauthenticated_level_change;
         let health_path = config.health_path.clone();
+        let ready_path = config.ready_path.clone();
+        let ready_watermark = config.ready_channel_watermark;
         let metrics_path = config.metrics_path.clone();
 
         let health_status_getter = {
@@ -704,16 +1620,57 @@ impl LoggerManager {
 
         let app = Router::new()
             .route(
-                &health_path,
+                "/features",
+                get(|| async { axum::Json(Self::enabled_features()) }),
+            )
+            .route(&health_path, {
+                // 存活探针：进程运行即返回 200
+                let health_status_getter = health_status_getter.clone();
                 get(|| async move {
                     let status = health_status_getter();
                     axum::Json(serde_json::to_value(&status).unwrap_or_default())
+                })
+            })
+            .route(
+                &ready_path,
+                get(move || async move {
+                    // 就绪探针：sink 尚未成功写入、存在不可用 sink 或通道接近饱和时返回 503
+                    let status = health_status_getter();
+                    let ready = status.overall_status.is_operational()
+                        && status.channel_usage <= ready_watermark;
+                    let code = if ready {
+                        StatusCode::OK
+                    } else {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    };
+                    (
+                        code,
+                        axum::Json(serde_json::to_value(&status).unwrap_or_default()),
+                    )
+                        .into_response()
                 }),
             )
             .route(
                 &metrics_path,
                 get(move || async move { metrics.export_prometheus() }),
             )
+            .route(
+                "/level",
+                put(move |body: String| async move {
+                    if !level_change_allowed {
+                        return (
+                            StatusCode::FORBIDDEN,
+                            "PUT /level requires http_server.auth or \
+                             http_server.allow_unauthenticated_level_change",
+                        )
+                            .into_response();
+                    }
+                    match level_control.apply(&body, "http") {
+                        Ok(()) => (StatusCode::OK, body.trim().to_lowercase()).into_response(),
+                        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+                    }
+                }),
+            )
             .layer(middleware::from_fn_with_state(
                 auth_state.clone(),
                 auth_middleware,
@@ -727,18 +1684,18 @@ impl LoggerManager {
         let auth_enabled = config.auth.as_ref().map(|a| a.enabled).unwrap_or(false);
         let ip_whitelist = config.ip_whitelist.clone();
 
+        // 在 spawn 前完成绑定：端口冲突等错误同步返回给调用者，
+        // 且返回 Ok 时监听套接字已可接受连接
+        let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| {
+            InklogError::HttpServerError(format!("Failed to bind HTTP server to {}: {}", addr, e))
+        })?;
+        let local_addr = listener.local_addr()?;
+        info!(
+            "HTTP server started on {} (auth: {}, ip_whitelist: {:?})",
+            local_addr, auth_enabled, ip_whitelist
+        );
+
         let handle = tokio::spawn(async move {
-            let listener = match tokio::net::TcpListener::bind(addr).await {
-                Ok(l) => l,
-                Err(e) => {
-                    tracing::error!("Failed to bind HTTP server to {}: {}", addr, e);
-                    return;
-                }
-            };
-            info!(
-                "HTTP server started on {} (auth: {}, ip_whitelist: {:?})",
-                addr, auth_enabled, ip_whitelist
-            );
             match axum::serve(
                 listener,
                 app.into_make_service_with_connect_info::<SocketAddr>(),
@@ -757,10 +1714,58 @@ impl LoggerManager {
             }
         }
 
-        info!("HTTP monitoring server configured on {}", addr);
+        self.http_state
+            .send_replace(HttpServerState::Ready(local_addr));
+        info!("HTTP monitoring server configured on {}", local_addr);
         Ok(())
     }
 
+    /// 等待 HTTP 监控服务器就绪，返回实际监听的地址
+    ///
+    /// 返回时服务器已完成绑定，可直接发起连接而无需轮询或 sleep。
+    ///
+    /// # 错误
+    ///
+    /// - `InklogError::ConfigError` - 配置中未启用 HTTP 服务器，或服务器未启动
+    ///   （如通过 `build_detached` 构建）
+    /// - `InklogError::HttpServerError` - HTTP 服务器启动失败（如绑定失败，Warn 模式下）
+    #[cfg(feature = "http")]
+    pub async fn http_ready(&self) -> Result<std::net::SocketAddr, InklogError> {
+        let enabled = self
+            .config
+            .http_server
+            .as_ref()
+            .is_some_and(|cfg| cfg.enabled);
+        if !enabled {
+            return Err(InklogError::ConfigError(
+                "HTTP server is not enabled".to_string(),
+            ));
+        }
+        if matches!(*self.http_state.borrow(), HttpServerState::NotStarted) {
+            return Err(InklogError::ConfigError(
+                "HTTP server was not started; build_detached does not start it".to_string(),
+            ));
+        }
+
+        let mut rx = self.http_state.subscribe();
+        let state = rx
+            .wait_for(|state| {
+                !matches!(
+                    state,
+                    HttpServerState::NotStarted | HttpServerState::Pending
+                )
+            })
+            .await
+            .map_err(|e| InklogError::HttpServerError(e.to_string()))?;
+        match &*state {
+            HttpServerState::Ready(addr) => Ok(*addr),
+            HttpServerState::Failed(msg) => Err(InklogError::HttpServerError(msg.clone())),
+            HttpServerState::NotStarted | HttpServerState::Pending => {
+                unreachable!("wait_for only returns started, non-pending states")
+            }
+        }
+    }
+
     fn start_workers(params: WorkerParams) -> WorkerStartResult {
         let runtime_handle = tokio::runtime::Handle::current();
         let WorkerParams {
@@ -769,16 +1774,23 @@ impl LoggerManager {
             console_receiver,
             control_rx,
             control_tx,
+            flush_rx,
+            batch_rx,
             metrics,
             console_sink,
             error_sink,
             effective_capacity,
+            byte_budget,
+            sender,
+            console_sender,
+            summaries,
             #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
             database,
         } = params;
-        let file_config = config.file_sink.clone();
+        let file_configs: Vec<FileSinkConfig> = config.enabled_file_sinks().cloned().collect();
         #[allow(unused_variables)]
         let db_config = config.database_sink.clone();
+        let retry_policy = WriteRetryPolicy::from_config(&config.performance);
 
         // 确保 database 始终有效：如果配置了数据库但没有提供 DI 依赖，则创建默认实现
         #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
@@ -792,13 +1804,31 @@ impl LoggerManager {
                             let handle = tokio::runtime::Handle::current();
                             let cfg_url = cfg.url.clone();
                             let cfg_pool_size = cfg.pool_size;
-                            let adapter = handle.block_on(async {
-                                crate::integrations::infra::DbNexusAdapter::new(
-                                    &cfg_url,
-                                    cfg_pool_size,
+                            // 表名会拼接进 SQL，未经 validate() 的配置也需在此校验
+                            cfg.validate_table_name()?;
+                            cfg.validate_indexes()?;
+                            let adapter = handle
+                                .block_on(async {
+                                    crate::integrations::infra::DbNexusAdapter::with_table_name(
+                                        &cfg_url,
+                                        cfg_pool_size,
+                                        &cfg.table_name,
+                                    )
+                                    .await
+                                })?
+                                .with_fields_compression(cfg.compress_fields)
+                                .with_derived_timestamps(cfg.derived_timestamp_columns)
+                                .with_promoted_fields(
+                                    cfg.promoted_fields.clone(),
+                                    cfg.keep_promoted_fields,
                                 )
-                                .await
-                            })?;
+                                .with_indexes(cfg.indexes.clone())
+                                .with_driver(cfg.driver.clone());
+                            let adapter = if cfg.dedup {
+                                adapter.with_dedup(cfg.driver.clone())
+                            } else {
+                                adapter
+                            };
                             Some(Arc::new(adapter) as Arc<dyn crate::integrations::infra::Database>)
                         } else {
                             None
@@ -810,6 +1840,9 @@ impl LoggerManager {
             }
         };
 
+        // 每个 sink worker（含控制台）在这里注册自己的 flush channel，由 dispatcher 按名称转发
+        let mut flush_routes = FlushRoutes::default();
+
         // Thread 0: Console Sink (dedicated for lock-free hot path)
         // 每个 worker 拥有独立的 shutdown channel，确保广播信号能被每个 worker 接收
         // （MPMC channel 的 send() 只能被一个 receiver 消费，共享 channel 会导致
@@ -817,10 +1850,42 @@ impl LoggerManager {
         let (shutdown_tx_console, shutdown_console) = bounded(1);
         let metrics_console = metrics.clone();
         let console_sink_console = console_sink.clone();
+        let flush_rx_console = flush_routes.register(["console"]);
         let handle_console = {
             let runtime_handle = runtime_handle.clone();
             tokio::task::spawn_blocking(move || {
                 metrics_console.active_workers.inc();
+                let write_record = |record: &LogRecord| {
+                    let latency = Utc::now()
+                        .signed_duration_since(record.timestamp)
+                        .to_std()
+                        .unwrap_or(Duration::ZERO);
+                    metrics_console.record_latency(latency);
+
+                    // Hot path: use try_lock to avoid blocking
+                    match console_sink_console.try_lock() {
+                        Ok(sink) => {
+                            if runtime_handle
+                                .block_on(async { sink.write(record).await })
+                                .is_err()
+                            {
+                                metrics_console.inc_sink_error();
+                                metrics_console.update_sink_health(
+                                    "console",
+                                    false,
+                                    Some("Write error".to_string()),
+                                );
+                            } else {
+                                metrics_console.inc_logs_written();
+                                metrics_console.update_sink_health("console", true, None);
+                            }
+                        }
+                        Err(_) => {
+                            // Lock contention detected, increment metric and skip
+                            metrics_console.inc_lock_contention();
+                        }
+                    }
+                };
                 loop {
                     // Check for shutdown
                     if shutdown_console.try_recv().is_ok() {
@@ -856,39 +1921,23 @@ impl LoggerManager {
                         break;
                     }
 
+                    if let Ok((_, ack)) = flush_rx_console.try_recv() {
+                        // 先写入已排队的记录，使调用前产生的日志包含在本次 flush 中
+                        while let Ok(record) = console_receiver.try_recv() {
+                            write_record(&record);
+                        }
+                        let result = match console_sink_console.lock() {
+                            Ok(sink) => runtime_handle
+                                .block_on(async { sink.flush().await })
+                                .map_err(|e| e.to_string()),
+                            Err(e) => Err(e.to_string()),
+                        };
+                        let _ = ack.send(result);
+                    }
+
                     // Process console logs with timeout
                     match console_receiver.recv_timeout(Duration::from_millis(100)) {
-                        Ok(record) => {
-                            let latency = Utc::now()
-                                .signed_duration_since(record.timestamp)
-                                .to_std()
-                                .unwrap_or(Duration::ZERO);
-                            metrics_console.record_latency(latency);
-
-                            // Hot path: use try_lock to avoid blocking
-                            match console_sink_console.try_lock() {
-                                Ok(sink) => {
-                                    if runtime_handle
-                                        .block_on(async { sink.write(&record).await })
-                                        .is_err()
-                                    {
-                                        metrics_console.inc_sink_error();
-                                        metrics_console.update_sink_health(
-                                            "console",
-                                            false,
-                                            Some("Write error".to_string()),
-                                        );
-                                    } else {
-                                        metrics_console.inc_logs_written();
-                                        metrics_console.update_sink_health("console", true, None);
-                                    }
-                                }
-                                Err(_) => {
-                                    // Lock contention detected, increment metric and skip
-                                    metrics_console.inc_lock_contention();
-                                }
-                            }
-                        }
+                        Ok(record) => write_record(&record),
                         Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
                             // Timeout, continue loop
                         }
@@ -901,221 +1950,84 @@ impl LoggerManager {
             })
         };
 
-        // Thread 1: File Sink
-        let rx_file = receiver.clone();
+        // 每个 sink worker 拥有独立的 channel，由 dispatcher 把每条记录分发给所有 worker
+        let mut sink_channels = SinkChannels::new(
+            config.performance.channel_capacity,
+            config.performance.overflow_policy,
+            metrics.clone(),
+        );
+
+        // Thread 1: File Sinks（所有文件 sink 共用一个 worker，见 FileWorker）
+        let rx_file = sink_channels.add();
         let (shutdown_tx_file, shutdown_file) = bounded(1);
         let metrics_file = metrics.clone();
         let console_sink_file = console_sink.clone();
         let control_rx_file = control_rx.clone();
+        let flush_rx_file = flush_routes.register(file_configs.iter().map(|c| c.name.as_str()));
+        let masker_file = Self::masker(&config);
         let handle_file = {
             let runtime_handle = runtime_handle.clone();
             tokio::task::spawn_blocking(move || {
                 metrics_file.active_workers.inc();
-                if let Some(cfg) = file_config
-                    && cfg.enabled
-                {
-                    let cfg_clone = cfg.clone(); // Clone for recovery attempts
-                    if let Ok(mut sink) = FileSink::new(cfg) {
-                        let mut consecutive_failures = 0;
-                        #[allow(unused_assignments)]
-                        let mut last_failure_time = None::<Instant>;
-
-                        loop {
-                            // Check for shutdown
-                            if shutdown_file.try_recv().is_ok() {
-                                // Drain with 30s timeout
-                                let deadline = Instant::now() + Duration::from_secs(30);
-                                while let Ok(record) = rx_file.try_recv() {
-                                    let latency = Utc::now()
-                                        .signed_duration_since(record.timestamp)
-                                        .to_std()
-                                        .unwrap_or(Duration::ZERO);
-                                    metrics_file.record_latency(latency);
-
-                                    // Retry logic
-                                    let mut attempts = 0;
-                                    while attempts < 3 {
-                                        match runtime_handle
-                                            .block_on(async { sink.write(&record).await })
-                                        {
-                                            Ok(_) => {
-                                                metrics_file.inc_logs_written();
-                                                metrics_file.update_sink_health("file", true, None);
-                                                break;
-                                            }
-                                            Err(e) => {
-                                                attempts += 1;
-                                                // Log error to error.log
-                                                if let Ok(mut error_sink_guard) = error_sink.lock()
-                                                    && let Some(sink) = error_sink_guard.as_mut()
-                                                {
-                                                    let error_record = LogRecord {
-                                                        timestamp: Utc::now(),
-                                                        level: "ERROR".to_string(),
-                                                        target: "inklog::file_sink".to_string(),
-                                                        message: format!("File sink error: {}", e),
-                                                        fields: Default::default(),
-                                                        file: None,
-                                                        line: None,
-                                                        thread_id: thread::current()
-                                                            .name()
-                                                            .unwrap_or("unknown")
-                                                            .to_string(),
-                                                    };
-                                                    let _ = runtime_handle.block_on(async {
-                                                        sink.write(&error_record).await
-                                                    });
-                                                }
-
-                                                if attempts == 3 {
-                                                    metrics_file.inc_sink_error();
-                                                    metrics_file.update_sink_health(
-                                                        "file",
-                                                        false,
-                                                        Some(e.to_string()),
-                                                    );
-                                                    // Fallback to console
-                                                    if let Ok(cs) = console_sink_file.lock() {
-                                                        let _ = runtime_handle.block_on(async {
-                                                            cs.write(&record).await
-                                                        });
-                                                    }
-                                                } else {
-                                                    thread::sleep(Duration::from_millis(
-                                                        10 * attempts as u64,
-                                                    ));
-                                                }
-                                            }
-                                        }
-                                    }
+                let mut worker = FileWorker::new(
+                    file_configs,
+                    runtime_handle,
+                    retry_policy,
+                    metrics_file.clone(),
+                    error_sink,
+                    console_sink_file,
+                    masker_file,
+                );
+                while !worker.is_empty() {
+                    // Check for shutdown
+                    if shutdown_file.try_recv().is_ok() {
+                        // Drain with 30s timeout，直到 dispatcher 分发完毕并断开 channel
+                        let deadline = Instant::now() + Duration::from_secs(30);
+                        while let Ok(record) = rx_file.recv_deadline(deadline) {
+                            let latency = Utc::now()
+                                .signed_duration_since(record.timestamp)
+                                .to_std()
+                                .unwrap_or(Duration::ZERO);
+                            metrics_file.record_latency(latency);
+                            worker.write(&record, false);
+                        }
+                        worker.shutdown();
+                        break;
+                    }
 
-                                    if Instant::now() > deadline {
-                                        break;
-                                    }
-                                }
-                                let _ = runtime_handle.block_on(async { sink.shutdown().await });
-                                break;
+                    // Check for control messages
+                    if let Ok(control_msg) = control_rx_file.try_recv() {
+                        match control_msg {
+                            SinkControlMessage::RecoverSink(sink_name)
+                                if worker.owns(&sink_name) =>
+                            {
+                                worker.recover(&sink_name);
                             }
-
-                            // Check for control messages
-                            if let Ok(control_msg) = control_rx_file.try_recv() {
-                                match control_msg {
-                                    SinkControlMessage::RecoverSink(sink_name)
-                                        if sink_name == "file" =>
-                                    {
-                                        eprintln!("File sink: Received recovery command");
-                                        // Attempt to recreate the sink
-                                        if let Ok(new_sink) = FileSink::new(cfg_clone.clone()) {
-                                            sink = new_sink;
-                                            consecutive_failures = 0;
-                                            last_failure_time = None;
-                                            metrics_file.update_sink_health("file", true, None);
-                                            eprintln!("File sink: Successfully recovered");
-                                        } else {
-                                            eprintln!("File sink: Recovery failed");
-                                        }
-                                    }
-                                    SinkControlMessage::GetStatus => {
-                                        // Status is already tracked in metrics
-                                    }
-                                    _ => {} // Ignore messages for other sinks
-                                }
+                            SinkControlMessage::GetStatus => {
+                                // Status is already tracked in metrics
                             }
+                            _ => {} // Ignore messages for other sinks
+                        }
+                    }
 
-                            if let Ok(record) = rx_file.recv_timeout(Duration::from_millis(100)) {
-                                let latency = Utc::now()
-                                    .signed_duration_since(record.timestamp)
-                                    .to_std()
-                                    .unwrap_or(Duration::ZERO);
-                                metrics_file.record_latency(latency);
-
-                                // Retry logic with recovery detection
-                                let mut attempts = 0;
-                                let mut write_succeeded = false;
-                                while attempts < 3 {
-                                    match runtime_handle
-                                        .block_on(async { sink.write(&record).await })
-                                    {
-                                        Ok(_) => {
-                                            metrics_file.inc_logs_written();
-                                            metrics_file.update_sink_health("file", true, None);
-                                            consecutive_failures = 0;
-                                            last_failure_time = None;
-                                            write_succeeded = true;
-                                            break;
-                                        }
-                                        Err(e) => {
-                                            attempts += 1;
-                                            consecutive_failures += 1;
-                                            last_failure_time = Some(Instant::now());
-
-                                            // Log error to error.log
-                                            if let Ok(mut error_sink_guard) = error_sink.lock()
-                                                && let Some(sink) = error_sink_guard.as_mut()
-                                            {
-                                                let error_record = LogRecord {
-                                                    timestamp: Utc::now(),
-                                                    level: "ERROR".to_string(),
-                                                    target: "inklog::file_sink".to_string(),
-                                                    message: format!("File sink error: {}", e),
-                                                    fields: Default::default(),
-                                                    file: None,
-                                                    line: None,
-                                                    thread_id: thread::current()
-                                                        .name()
-                                                        .unwrap_or("unknown")
-                                                        .to_string(),
-                                                };
-                                                let _ = runtime_handle.block_on(async {
-                                                    sink.write(&error_record).await
-                                                });
-                                            }
-
-                                            if attempts == 3 {
-                                                metrics_file.inc_sink_error();
-                                                metrics_file.update_sink_health(
-                                                    "file",
-                                                    false,
-                                                    Some(e.to_string()),
-                                                );
-                                                // Fallback to console
-                                                if let Ok(cs) = console_sink_file.lock() {
-                                                    let _ = runtime_handle.block_on(async {
-                                                        cs.write(&record).await
-                                                    });
-                                                }
-                                            } else {
-                                                thread::sleep(Duration::from_millis(
-                                                    10 * attempts as u64,
-                                                ));
-                                            }
-                                        }
-                                    }
-                                }
-
-                                // Auto-recovery trigger: if we have too many consecutive failures
-                                if !write_succeeded
-                                    && consecutive_failures > 5
-                                    && let Some(last_failure) = last_failure_time
-                                    && last_failure.elapsed() > Duration::from_secs(60)
-                                {
-                                    eprintln!(
-                                        "File sink: Triggering auto-recovery due to consecutive failures"
-                                    );
-                                    // Attempt to recreate the sink
-                                    if let Ok(new_sink) = FileSink::new(cfg_clone.clone()) {
-                                        sink = new_sink;
-                                        consecutive_failures = 0;
-                                        last_failure_time = None;
-                                        metrics_file.update_sink_health("file", true, None);
-                                        eprintln!("File sink: Auto-recovery successful");
-                                    }
-                                }
-                            } else {
-                                // Timeout, flush buffer
-                                let _ = runtime_handle.block_on(async { sink.flush().await });
-                            }
+                    if let Ok((name, ack)) = flush_rx_file.try_recv() {
+                        // 先写入已排队的记录，使调用前产生的日志包含在本次 flush 中
+                        while let Ok(record) = rx_file.try_recv() {
+                            worker.write(&record, false);
                         }
+                        let _ = ack.send(worker.flush(&name));
+                    }
+
+                    if let Ok(record) = rx_file.recv_timeout(Duration::from_millis(100)) {
+                        let latency = Utc::now()
+                            .signed_duration_since(record.timestamp)
+                            .to_std()
+                            .unwrap_or(Duration::ZERO);
+                        metrics_file.record_latency(latency);
+                        worker.write(&record, true);
+                    } else {
+                        // Timeout, flush buffer
+                        worker.flush_all();
                     }
                 }
                 metrics_file.active_workers.dec();
@@ -1124,7 +2036,7 @@ impl LoggerManager {
 
         // Thread 2: DB Sink
         #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
-        let rx_db = receiver.clone();
+        let rx_db = sink_channels.add();
         #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
         let (shutdown_tx_db, shutdown_db) = bounded(1);
         #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
@@ -1134,6 +2046,10 @@ impl LoggerManager {
         #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
         let control_rx_db = control_rx.clone();
         #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+        let flush_rx_db = flush_routes.register(["database"]);
+        #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+        let masker_db = Self::masker(&config);
+        #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
         let handle_db = {
             let runtime_handle = runtime_handle.clone();
             tokio::task::spawn_blocking(
@@ -1146,96 +2062,132 @@ impl LoggerManager {
                     {
                         // Clone once before the loop for recovery use
                         let db_for_recovery = db.clone();
-                        if let Ok(sink_result) = DatabaseSink::new(db.clone()) {
+                        if let Ok(sink_result) =
+                            DatabaseSink::new_with_config(db.clone(), Some(cfg.clone()))
+                                .map(|sink| sink.with_masker(masker_db.clone()))
+                        {
                             let mut sink: DatabaseSink = sink_result;
+                            if let Some(reason) = sink.fallback_error() {
+                                Self::warn_via_console(&console_sink_db, reason.to_string());
+                            }
                             runtime_handle
                                 .block_on(async { sink.set_metrics(metrics_db.clone()).await });
                             let mut consecutive_failures = 0;
-                            #[allow(unused_assignments)]
                             let mut last_failure_time = None::<Instant>;
+                            let snapshot_interval = cfg
+                                .metrics_snapshot_enabled
+                                .then(|| Duration::from_millis(cfg.metrics_snapshot_interval_ms));
+                            let mut last_snapshot = Instant::now();
+
+                            // 写入一条记录：按重试策略重试，失败后回退到控制台，连续失败满足
+                            // 条件时重建 sink。主循环、flush 与 shutdown 排空共用
+                            let write_record =
+                                |sink: &mut DatabaseSink,
+                                 consecutive_failures: &mut u32,
+                                 last_failure_time: &mut Option<Instant>,
+                                 record: &LogRecord| {
+                                    let latency = Utc::now()
+                                        .signed_duration_since(record.timestamp)
+                                        .to_std()
+                                        .unwrap_or(Duration::ZERO);
+                                    metrics_db.record_latency(latency);
 
-                            loop {
-                                if shutdown_db.try_recv().is_ok() {
-                                    // Drain with 30s timeout
-                                    let deadline = Instant::now() + Duration::from_secs(30);
-                                    while let Ok(record) = rx_db.try_recv() {
-                                        let latency = Utc::now()
-                                            .signed_duration_since(record.timestamp)
-                                            .to_std()
-                                            .unwrap_or(Duration::ZERO);
-                                        metrics_db.record_latency(latency);
-
-                                        // Retry logic
-                                        let mut attempts = 0;
-                                        let mut write_succeeded = false;
-                                        let write_result: Result<(), InklogError> = runtime_handle
-                                            .block_on(async { sink.write(&record).await });
-                                        match write_result {
-                                            Ok(_) => {
-                                                metrics_db.inc_logs_written();
-                                                metrics_db
-                                                    .update_sink_health("database", true, None);
-                                                consecutive_failures = 0;
-                                                last_failure_time = None;
-                                                write_succeeded = true;
-                                            }
-                                            Err(ref e) => {
-                                                attempts += 1;
-                                                consecutive_failures += 1;
-                                                last_failure_time = Some(Instant::now());
-
-                                                if attempts == 3 {
-                                                    metrics_db.inc_sink_error();
-                                                    let error_msg =
-                                                        crate::InklogError::to_string(e);
-                                                    metrics_db.update_sink_health(
-                                                        "database",
-                                                        false,
-                                                        Some(error_msg),
-                                                    );
-                                                    // Fallback to console
-                                                    if let Ok(cs) = console_sink_db.lock() {
-                                                        let _ = runtime_handle.block_on(async {
-                                                            cs.write(&record).await
-                                                        });
-                                                    }
-                                                } else {
-                                                    thread::sleep(Duration::from_millis(
-                                                        10 * attempts as u64,
-                                                    ));
-                                                }
+                                    // Retry logic
+                                    // 写入失败时记录已留在缓冲区中，重试只需再次 flush
+                                    let mut buffered = false;
+                                    let write_started = Instant::now();
+                                    let write_result = retry_policy.run(
+                                        || {
+                                            if std::mem::replace(&mut buffered, true) {
+                                                runtime_handle
+                                                    .block_on(async { sink.flush().await })
+                                            } else {
+                                                runtime_handle
+                                                    .block_on(async { sink.write(record).await })
                                             }
+                                        },
+                                        |_| {
+                                            *consecutive_failures += 1;
+                                            *last_failure_time = Some(Instant::now());
+                                        },
+                                    );
+                                    metrics_db
+                                        .record_sink_write("database", write_started.elapsed());
+                                    let write_succeeded = write_result.is_ok();
+                                    match write_result {
+                                        Ok(()) => {
+                                            metrics_db.inc_logs_written();
+                                            metrics_db.update_sink_health("database", true, None);
+                                            *consecutive_failures = 0;
+                                            *last_failure_time = None;
                                         }
-
-                                        // Auto-recovery trigger
-                                        if !write_succeeded
-                                            && consecutive_failures > 5
-                                            && let Some(last_failure) = last_failure_time
-                                            && last_failure.elapsed() > Duration::from_secs(60)
-                                        {
-                                            eprintln!(
-                                                "Database sink: Triggering auto-recovery due to consecutive failures"
+                                        Err(e) => {
+                                            metrics_db.inc_sink_error();
+                                            metrics_db.update_sink_health(
+                                                "database",
+                                                false,
+                                                Some(e.to_string()),
                                             );
-                                            if let Ok(new_sink) =
-                                                DatabaseSink::new(db_for_recovery.clone())
-                                            {
-                                                sink = new_sink;
-                                                runtime_handle.block_on(async {
-                                                    sink.set_metrics(metrics_db.clone()).await
-                                                });
-                                                consecutive_failures = 0;
-                                                metrics_db
-                                                    .update_sink_health("database", true, None);
-                                                eprintln!(
-                                                    "Database sink: Auto-recovery successful"
-                                                );
+                                            // Fallback chain: DB -> File -> Console
+                                            if let Ok(cs) = console_sink_db.lock() {
+                                                let _ = runtime_handle
+                                                    .block_on(async { cs.write(record).await });
                                             }
                                         }
+                                    }
 
-                                        if Instant::now() > deadline {
-                                            break;
+                                    // Auto-recovery trigger
+                                    if !write_succeeded
+                                        && retry_policy.should_recover(
+                                            *consecutive_failures,
+                                            *last_failure_time,
+                                        )
+                                    {
+                                        eprintln!(
+                                            "Database sink: Triggering auto-recovery due to consecutive failures"
+                                        );
+                                        if let Ok(new_sink) = DatabaseSink::new_with_config(
+                                            db_for_recovery.clone(),
+                                            Some(cfg.clone()),
+                                        )
+                                        .map(|sink| sink.with_masker(masker_db.clone()))
+                                        {
+                                            *sink = new_sink;
+                                            runtime_handle.block_on(async {
+                                                sink.set_metrics(metrics_db.clone()).await
+                                            });
+                                            *consecutive_failures = 0;
+                                            metrics_db.update_sink_health("database", true, None);
+                                            eprintln!("Database sink: Auto-recovery successful");
                                         }
                                     }
+                                };
+
+                            loop {
+                                // 周期性写入 metrics 快照，供历史趋势分析
+                                if let Some(interval) = snapshot_interval
+                                    && last_snapshot.elapsed() >= interval
+                                {
+                                    last_snapshot = Instant::now();
+                                    Self::persist_metrics_snapshot(
+                                        &runtime_handle,
+                                        db,
+                                        &metrics_db,
+                                        &cfg.metrics_snapshot_table,
+                                    );
+                                }
+
+                                if shutdown_db.try_recv().is_ok() {
+                                    // Drain with 30s timeout，直到 dispatcher 分发完毕并断开 channel
+                                    let deadline = Instant::now() + Duration::from_secs(30);
+                                    while let Ok(record) = rx_db.recv_deadline(deadline) {
+                                        write_record(
+                                            &mut sink,
+                                            &mut consecutive_failures,
+                                            &mut last_failure_time,
+                                            &record,
+                                        );
+                                    }
                                     let _ =
                                         runtime_handle.block_on(async { sink.shutdown().await });
                                     break;
@@ -1249,8 +2201,11 @@ impl LoggerManager {
                                         {
                                             eprintln!("Database sink: Received recovery command");
                                             // Attempt to recreate the sink
-                                            if let Ok(new_sink) =
-                                                DatabaseSink::new(db_for_recovery.clone())
+                                            if let Ok(new_sink) = DatabaseSink::new_with_config(
+                                                db_for_recovery.clone(),
+                                                Some(cfg.clone()),
+                                            )
+                                            .map(|sink| sink.with_masker(masker_db.clone()))
                                             {
                                                 sink = new_sink;
                                                 runtime_handle.block_on(async {
@@ -1272,75 +2227,29 @@ impl LoggerManager {
                                     }
                                 }
 
-                                if let Ok(record) = rx_db.recv_timeout(Duration::from_millis(100)) {
-                                    let latency = Utc::now()
-                                        .signed_duration_since(record.timestamp)
-                                        .to_std()
-                                        .unwrap_or(Duration::ZERO);
-                                    metrics_db.record_latency(latency);
-
-                                    // Retry logic
-                                    let mut attempts = 0;
-                                    let mut write_succeeded = false;
-                                    let write_result: Result<(), InklogError> = runtime_handle
-                                        .block_on(async { sink.write(&record).await });
-                                    match write_result {
-                                        Ok(_) => {
-                                            metrics_db.inc_logs_written();
-                                            metrics_db.update_sink_health("database", true, None);
-                                            consecutive_failures = 0;
-                                            last_failure_time = None;
-                                            write_succeeded = true;
-                                        }
-                                        Err(ref e) => {
-                                            attempts += 1;
-                                            consecutive_failures += 1;
-                                            last_failure_time = Some(Instant::now());
-
-                                            if attempts == 3 {
-                                                metrics_db.inc_sink_error();
-                                                let error_msg = format!("{e}");
-                                                metrics_db.update_sink_health(
-                                                    "database",
-                                                    false,
-                                                    Some(error_msg),
-                                                );
-
-                                                // Fallback chain: DB -> File -> Console
-                                                if let Ok(cs) = console_sink_db.lock() {
-                                                    let _ = runtime_handle.block_on(async {
-                                                        cs.write(&record).await
-                                                    });
-                                                }
-                                            } else {
-                                                thread::sleep(Duration::from_millis(
-                                                    10 * attempts as u64,
-                                                ));
-                                            }
-                                        }
-                                    }
-
-                                    // Auto-recovery trigger
-                                    if !write_succeeded
-                                        && consecutive_failures > 5
-                                        && let Some(last_failure) = last_failure_time
-                                        && last_failure.elapsed() > Duration::from_secs(60)
-                                    {
-                                        eprintln!(
-                                            "Database sink: Triggering auto-recovery due to consecutive failures"
+                                if let Ok((_, ack)) = flush_rx_db.try_recv() {
+                                    // 先写入已排队的记录，使调用前产生的日志包含在本次 flush 中
+                                    while let Ok(record) = rx_db.try_recv() {
+                                        write_record(
+                                            &mut sink,
+                                            &mut consecutive_failures,
+                                            &mut last_failure_time,
+                                            &record,
                                         );
-                                        if let Ok(new_sink) =
-                                            DatabaseSink::new(db_for_recovery.clone())
-                                        {
-                                            sink = new_sink;
-                                            runtime_handle.block_on(async {
-                                                sink.set_metrics(metrics_db.clone()).await
-                                            });
-                                            consecutive_failures = 0;
-                                            metrics_db.update_sink_health("database", true, None);
-                                            eprintln!("Database sink: Auto-recovery successful");
-                                        }
                                     }
+                                    let result = runtime_handle
+                                        .block_on(async { sink.flush().await })
+                                        .map_err(|e| e.to_string());
+                                    let _ = ack.send(result);
+                                }
+
+                                if let Ok(record) = rx_db.recv_timeout(Duration::from_millis(100)) {
+                                    write_record(
+                                        &mut sink,
+                                        &mut consecutive_failures,
+                                        &mut last_failure_time,
+                                        &record,
+                                    );
                                 } else {
                                     // Timeout, flush buffer
                                     let _ = runtime_handle.block_on(async { sink.flush().await });
@@ -1356,6 +2265,91 @@ impl LoggerManager {
         #[cfg(not(any(feature = "sqlite", feature = "postgres", feature = "mysql")))]
         let _handle_db = tokio::task::spawn_blocking(|| {});
 
+        // Thread 3: NATS Sink（仅在启用时启动）
+        #[cfg(feature = "nats")]
+        let nats_worker = match config.nats_sink.clone() {
+            Some(cfg) if cfg.enabled => Some(Self::spawn_nats_worker(
+                &runtime_handle,
+                cfg,
+                Self::remote_masker(&config),
+                sink_channels.add(),
+                flush_routes.register(["nats"]),
+                metrics.clone(),
+                &console_sink,
+            )),
+            _ => None,
+        };
+
+        // Thread 4: Elasticsearch Sink（仅在启用时启动）
+        #[cfg(feature = "elasticsearch")]
+        let elasticsearch_worker = match config.elasticsearch_sink.clone() {
+            Some(cfg) if cfg.enabled => Some(Self::spawn_elasticsearch_worker(
+                &runtime_handle,
+                cfg,
+                Self::remote_masker(&config),
+                sink_channels.add(),
+                flush_routes.register(["elasticsearch"]),
+                metrics.clone(),
+                &console_sink,
+            )?),
+            _ => None,
+        };
+
+        // Thread 5: OTLP Sink（仅在启用时启动）
+        #[cfg(feature = "otlp")]
+        let otlp_worker = match config.otlp_sink.clone() {
+            Some(cfg) if cfg.enabled => Some(Self::spawn_otlp_worker(
+                &runtime_handle,
+                cfg,
+                Self::remote_masker(&config),
+                sink_channels.add(),
+                flush_routes.register(["otlp"]),
+                metrics.clone(),
+                &console_sink,
+            )?),
+            _ => None,
+        };
+
+        // Thread 6: Syslog Sink（仅在启用时启动）
+        #[cfg(feature = "syslog")]
+        let syslog_worker = match config.syslog_sink.clone() {
+            Some(cfg) if cfg.enabled => Some(Self::spawn_syslog_worker(
+                &runtime_handle,
+                cfg,
+                Self::remote_masker(&config),
+                sink_channels.add(),
+                flush_routes.register(["syslog"]),
+                metrics.clone(),
+                &console_sink,
+            )?),
+            _ => None,
+        };
+
+        // Thread 7: Loki Sink（仅在启用时启动）
+        #[cfg(feature = "loki")]
+        let loki_worker = match config.loki_sink.clone() {
+            Some(cfg) if cfg.enabled => Some(Self::spawn_loki_worker(
+                &runtime_handle,
+                cfg,
+                Self::remote_masker(&config),
+                sink_channels.add(),
+                flush_routes.register(["loki"]),
+                metrics.clone(),
+                &console_sink,
+            )?),
+            _ => None,
+        };
+
+        // Dispatcher：最后启动，此时所有 sink channel 均已创建
+        let (handle_dispatch, shutdown_tx_dispatch) = Self::spawn_dispatcher(
+            receiver.clone(),
+            batch_rx,
+            sink_channels,
+            flush_rx,
+            flush_routes,
+            byte_budget.clone(),
+        );
+
         // Health Check Thread
         let (shutdown_tx_health, shutdown_health) = bounded(1);
         let metrics_health = metrics.clone();
@@ -1364,12 +2358,35 @@ impl LoggerManager {
             let mut last_recovery_attempt = std::collections::HashMap::<String, Instant>::new();
             let mut low_usage_since: Option<Instant> = None;
             let check_interval = Duration::from_secs(1);
+            let process_interval = config
+                .performance
+                .process_metrics_interval_secs
+                .map(Duration::from_secs);
+            let mut last_process_sample: Option<Instant> = None;
 
             loop {
                 if shutdown_health.recv_timeout(check_interval).is_ok() {
                     break;
                 }
 
+                // 洪峰之后没有新记录时，到期的汇总由这里发出
+                for summary in summaries.due() {
+                    Self::enqueue_internal(
+                        &sender,
+                        &console_sender,
+                        &byte_budget,
+                        Arc::new(summary),
+                    );
+                }
+
+                // 周期性采样进程资源（fd / RSS / 线程数）
+                if let Some(interval) = process_interval
+                    && last_process_sample.is_none_or(|at| at.elapsed() >= interval)
+                {
+                    metrics_health.record_process_stats(&crate::ProcessStats::sample());
+                    last_process_sample = Some(Instant::now());
+                }
+
                 // Active recovery logic with control channel
                 let current_eff = effective_capacity_health.load(Ordering::Relaxed);
                 let channel_len_now = receiver.len();
@@ -1465,24 +2482,525 @@ impl LoggerManager {
         });
 
         #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
-        let handles = vec![handle_console, handle_file, handle_db, handle_health];
+        let handles = vec![
+            handle_dispatch,
+            handle_console,
+            handle_file,
+            handle_db,
+            handle_health,
+        ];
         #[cfg(not(any(feature = "sqlite", feature = "postgres", feature = "mysql")))]
-        let handles = vec![handle_console, handle_file, handle_health];
+        let handles = vec![handle_dispatch, handle_console, handle_file, handle_health];
 
         // shutdown_txs 与 handles 一一对应，保持 cfg 一致性
         #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
         let shutdown_txs = vec![
+            shutdown_tx_dispatch,
             shutdown_tx_console,
             shutdown_tx_file,
             shutdown_tx_db,
             shutdown_tx_health,
         ];
         #[cfg(not(any(feature = "sqlite", feature = "postgres", feature = "mysql")))]
-        let shutdown_txs = vec![shutdown_tx_console, shutdown_tx_file, shutdown_tx_health];
+        let shutdown_txs = vec![
+            shutdown_tx_dispatch,
+            shutdown_tx_console,
+            shutdown_tx_file,
+            shutdown_tx_health,
+        ];
+
+        #[cfg(feature = "nats")]
+        let (handles, shutdown_txs) = {
+            let (mut handles, mut shutdown_txs) = (handles, shutdown_txs);
+            if let Some((handle_nats, shutdown_tx_nats)) = nats_worker {
+                handles.push(handle_nats);
+                shutdown_txs.push(shutdown_tx_nats);
+            }
+            (handles, shutdown_txs)
+        };
+
+        #[cfg(feature = "elasticsearch")]
+        let (handles, shutdown_txs) = {
+            let (mut handles, mut shutdown_txs) = (handles, shutdown_txs);
+            if let Some((handle_es, shutdown_tx_es)) = elasticsearch_worker {
+                handles.push(handle_es);
+                shutdown_txs.push(shutdown_tx_es);
+            }
+            (handles, shutdown_txs)
+        };
+
+        #[cfg(feature = "otlp")]
+        let (handles, shutdown_txs) = {
+            let (mut handles, mut shutdown_txs) = (handles, shutdown_txs);
+            if let Some((handle_otlp, shutdown_tx_otlp)) = otlp_worker {
+                handles.push(handle_otlp);
+                shutdown_txs.push(shutdown_tx_otlp);
+            }
+            (handles, shutdown_txs)
+        };
+
+        #[cfg(feature = "syslog")]
+        let (handles, shutdown_txs) = {
+            let (mut handles, mut shutdown_txs) = (handles, shutdown_txs);
+            if let Some((handle_syslog, shutdown_tx_syslog)) = syslog_worker {
+                handles.push(handle_syslog);
+                shutdown_txs.push(shutdown_tx_syslog);
+            }
+            (handles, shutdown_txs)
+        };
+
+        #[cfg(feature = "loki")]
+        let (handles, shutdown_txs) = {
+            let (mut handles, mut shutdown_txs) = (handles, shutdown_txs);
+            if let Some((handle_loki, shutdown_tx_loki)) = loki_worker {
+                handles.push(handle_loki);
+                shutdown_txs.push(shutdown_tx_loki);
+            }
+            (handles, shutdown_txs)
+        };
 
         Ok((handles, shutdown_txs))
     }
 
+    /// 启动消费异步通道的 NATS worker
+    ///
+    /// 连接在 worker 首次发布时建立，不阻塞启动；连接成功前及发布失败的记录
+    /// 由 NatsSink 回退写入控制台。`masker` 为 `Some` 时发布前脱敏。
+    #[cfg(feature = "nats")]
+    fn spawn_nats_worker(
+        runtime_handle: &tokio::runtime::Handle,
+        cfg: crate::NatsSinkConfig,
+        masker: Option<DataMasker>,
+        rx_nats: SinkReceiver,
+        flush_rx: Receiver<FlushRequest>,
+        metrics_nats: Arc<Metrics>,
+        console_sink: &Arc<Mutex<ConsoleSink>>,
+    ) -> (tokio::task::JoinHandle<()>, Sender<()>) {
+        let mut sink = crate::sink::NatsSink::connect_lazy(cfg);
+        if let Some(masker) = masker {
+            sink = sink.with_masker(masker);
+        }
+        if let Ok(console) = console_sink.lock() {
+            sink = sink.with_console_fallback(console.clone());
+        }
+        Self::spawn_remote_sink_worker(
+            runtime_handle,
+            "nats",
+            sink,
+            rx_nats,
+            flush_rx,
+            metrics_nats,
+        )
+    }
+
+    /// 启动消费异步通道的 Elasticsearch worker
+    ///
+    /// 无法索引的记录由 ElasticsearchSink 回退写入控制台。`masker` 为 `Some` 时索引前脱敏。
+    #[cfg(feature = "elasticsearch")]
+    fn spawn_elasticsearch_worker(
+        runtime_handle: &tokio::runtime::Handle,
+        cfg: crate::ElasticsearchSinkConfig,
+        masker: Option<DataMasker>,
+        rx_es: SinkReceiver,
+        flush_rx: Receiver<FlushRequest>,
+        metrics_es: Arc<Metrics>,
+        console_sink: &Arc<Mutex<ConsoleSink>>,
+    ) -> Result<(tokio::task::JoinHandle<()>, Sender<()>), InklogError> {
+        let mut sink = crate::sink::ElasticsearchSink::new(cfg)?;
+        if let Some(masker) = masker {
+            sink = sink.with_masker(masker);
+        }
+        if let Ok(console) = console_sink.lock() {
+            sink = sink.with_console_fallback(console.clone());
+        }
+        Ok(Self::spawn_remote_sink_worker(
+            runtime_handle,
+            "elasticsearch",
+            sink,
+            rx_es,
+            flush_rx,
+            metrics_es,
+        ))
+    }
+
+    /// 启动消费异步通道的 OTLP worker
+    ///
+    /// 无法导出的记录由 OtlpSink 回退写入控制台。`masker` 为 `Some` 时导出前脱敏。
+    #[cfg(feature = "otlp")]
+    fn spawn_otlp_worker(
+        runtime_handle: &tokio::runtime::Handle,
+        cfg: crate::OtlpSinkConfig,
+        masker: Option<DataMasker>,
+        rx_otlp: SinkReceiver,
+        flush_rx: Receiver<FlushRequest>,
+        metrics_otlp: Arc<Metrics>,
+        console_sink: &Arc<Mutex<ConsoleSink>>,
+    ) -> Result<(tokio::task::JoinHandle<()>, Sender<()>), InklogError> {
+        // gRPC channel 需要在 runtime 上下文中创建
+        let mut sink = {
+            let _guard = runtime_handle.enter();
+            crate::sink::OtlpSink::new(cfg)?
+        };
+        if let Some(masker) = masker {
+            sink = sink.with_masker(masker);
+        }
+        if let Ok(console) = console_sink.lock() {
+            sink = sink.with_console_fallback(console.clone());
+        }
+        Ok(Self::spawn_remote_sink_worker(
+            runtime_handle,
+            "otlp",
+            sink,
+            rx_otlp,
+            flush_rx,
+            metrics_otlp,
+        ))
+    }
+
+    /// 启动消费异步通道的 Syslog worker
+    ///
+    /// 无法发送的记录由 SyslogSink 回退写入控制台。`masker` 为 `Some` 时发送前脱敏。
+    #[cfg(feature = "syslog")]
+    fn spawn_syslog_worker(
+        runtime_handle: &tokio::runtime::Handle,
+        cfg: crate::SyslogSinkConfig,
+        masker: Option<DataMasker>,
+        rx_syslog: SinkReceiver,
+        flush_rx: Receiver<FlushRequest>,
+        metrics_syslog: Arc<Metrics>,
+        console_sink: &Arc<Mutex<ConsoleSink>>,
+    ) -> Result<(tokio::task::JoinHandle<()>, Sender<()>), InklogError> {
+        let mut sink = crate::sink::SyslogSink::new(cfg)?;
+        if let Some(masker) = masker {
+            sink = sink.with_masker(masker);
+        }
+        if let Ok(console) = console_sink.lock() {
+            sink = sink.with_console_fallback(console.clone());
+        }
+        Ok(Self::spawn_remote_sink_worker(
+            runtime_handle,
+            "syslog",
+            sink,
+            rx_syslog,
+            flush_rx,
+            metrics_syslog,
+        ))
+    }
+
+    /// 启动消费异步通道的 Loki worker
+    ///
+    /// 无法推送的记录由 LokiSink 回退写入控制台。`masker` 为 `Some` 时推送前脱敏。
+    #[cfg(feature = "loki")]
+    fn spawn_loki_worker(
+        runtime_handle: &tokio::runtime::Handle,
+        cfg: crate::LokiSinkConfig,
+        masker: Option<DataMasker>,
+        rx_loki: SinkReceiver,
+        flush_rx: Receiver<FlushRequest>,
+        metrics_loki: Arc<Metrics>,
+        console_sink: &Arc<Mutex<ConsoleSink>>,
+    ) -> Result<(tokio::task::JoinHandle<()>, Sender<()>), InklogError> {
+        let mut sink = crate::sink::LokiSink::new(cfg)?;
+        if let Some(masker) = masker {
+            sink = sink.with_masker(masker);
+        }
+        if let Ok(console) = console_sink.lock() {
+            sink = sink.with_console_fallback(console.clone());
+        }
+        Ok(Self::spawn_remote_sink_worker(
+            runtime_handle,
+            "loki",
+            sink,
+            rx_loki,
+            flush_rx,
+            metrics_loki,
+        ))
+    }
+
+    /// 绕过 subscriber 与级别过滤，把 inklog 自身产生的记录送入控制台与异步 channel
+    ///
+    /// 不阻塞：channel 已满或字节预算不足时丢弃。
+    fn enqueue_internal(
+        sender: &Sender<Arc<LogRecord>>,
+        console_sender: &Sender<Arc<LogRecord>>,
+        byte_budget: &Option<Arc<ByteBudget>>,
+        record: Arc<LogRecord>,
+    ) {
+        let _ = console_sender.try_send(Arc::clone(&record));
+        if byte_budget
+            .as_ref()
+            .is_none_or(|budget| budget.try_acquire(record.estimated_size()))
+            && sender.try_send(Arc::clone(&record)).is_err()
+        {
+            Self::release_byte_budget(byte_budget, &record);
+        }
+    }
+
+    /// 启动 dispatcher：把主 channel 与线程批量 channel 中的每条记录分发到所有
+    /// sink worker 的 channel
+    ///
+    /// 字节预算在所有 worker 处理完记录后才归还（见 [`QueuedRecord`]）。
+    /// 每个 worker channel 各自按溢出策略处理已满的情况（见 [`SinkChannels`]），
+    /// 停滞的 sink 不会阻塞其他 sink；worker 已退出（如未配置文件 sink）的
+    /// channel 被移除。
+    ///
+    /// flush 请求先经过 dispatcher：此前入队的记录全部分发后才经 `routes`
+    /// （[`FlushRoutes`]）转发给对应 worker，worker 收到请求时这些记录已在其 channel 中。
+    /// shutdown 时分发剩余记录后退出并释放所有 sender，worker 的排空循环据此结束。
+    fn spawn_dispatcher(
+        receiver: Receiver<Arc<LogRecord>>,
+        mut batch_rx: Receiver<Vec<Arc<LogRecord>>>,
+        mut channels: SinkChannels,
+        flush_rx: Receiver<SinkControlMessage>,
+        routes: FlushRoutes,
+        budget: Option<Arc<ByteBudget>>,
+    ) -> (tokio::task::JoinHandle<()>, Sender<()>) {
+        let (shutdown_tx, shutdown_rx) = bounded::<()>(1);
+        let handle = tokio::task::spawn_blocking(move || {
+            let mut dispatch = |record: Arc<LogRecord>| {
+                let queued = Arc::new(QueuedRecord {
+                    record,
+                    budget: budget.clone(),
+                });
+                channels.send(&queued);
+            };
+            let drain = |dispatch: &mut dyn FnMut(Arc<LogRecord>),
+                         batch_rx: &Receiver<Vec<Arc<LogRecord>>>| {
+                while let Ok(batch) = batch_rx.try_recv() {
+                    batch.into_iter().for_each(&mut *dispatch);
+                }
+                while let Ok(record) = receiver.try_recv() {
+                    dispatch(record);
+                }
+            };
+            loop {
+                let mut batches_closed = false;
+                let running = crossbeam_channel::select! {
+                    recv(receiver) -> record => match record {
+                        Ok(record) => {
+                            dispatch(record);
+                            true
+                        }
+                        Err(_) => false,
+                    },
+                    recv(batch_rx) -> batch => {
+                        match batch {
+                            Ok(batch) => batch.into_iter().for_each(&mut dispatch),
+                            Err(_) => batches_closed = true,
+                        }
+                        true
+                    },
+                    recv(flush_rx) -> msg => match msg {
+                        Ok(msg) => {
+                            drain(&mut dispatch, &batch_rx);
+                            routes.forward(msg);
+                            true
+                        }
+                        Err(_) => false,
+                    },
+                    recv(shutdown_rx) -> _ => {
+                        drain(&mut dispatch, &batch_rx);
+                        false
+                    },
+                };
+                if batches_closed {
+                    // subscriber 已释放，不再监听批量 channel
+                    batch_rx = crossbeam_channel::never();
+                }
+                if !running {
+                    break;
+                }
+            }
+        });
+        (handle, shutdown_tx)
+    }
+
+    /// 将 sink 写入错误记录到内部错误日志（`error_log_path`）
+    fn write_error_log(
+        runtime_handle: &tokio::runtime::Handle,
+        error_sink: &Arc<Mutex<Option<FileSink>>>,
+        message: String,
+    ) {
+        if let Ok(mut guard) = error_sink.lock()
+            && let Some(sink) = guard.as_mut()
+        {
+            let error_record = LogRecord {
+                timestamp: Utc::now(),
+                level: "ERROR".to_string(),
+                target: "inklog::file_sink".to_string(),
+                message,
+                fields: Default::default(),
+                file: None,
+                line: None,
+                thread_id: thread::current().name().unwrap_or("unknown").to_string(),
+            };
+            let _ = runtime_handle.block_on(async { sink.write(&error_record).await });
+        }
+    }
+
+    /// 通过控制台 sink 输出一条内部警告（如降级日志文件无法创建）
+    fn warn_via_console(console_sink: &Arc<Mutex<ConsoleSink>>, message: String) {
+        let record = LogRecord {
+            timestamp: Utc::now(),
+            level: "WARN".to_string(),
+            target: "inklog".to_string(),
+            message,
+            fields: Default::default(),
+            file: None,
+            line: None,
+            thread_id: thread::current().name().unwrap_or("unknown").to_string(),
+        };
+        if let Ok(console) = console_sink.lock() {
+            let _ = console.write_sync(&record);
+        }
+    }
+
+    /// 远程 sink（NATS、Elasticsearch、OTLP、Syslog、Loki）共用的 worker 循环：逐条写入，空闲时 flush，
+    /// 并响应发给 `name` 的 flush 请求
+    #[cfg(any(
+        feature = "nats",
+        feature = "elasticsearch",
+        feature = "otlp",
+        feature = "syslog",
+        feature = "loki"
+    ))]
+    fn spawn_remote_sink_worker<S: LogSink + 'static>(
+        runtime_handle: &tokio::runtime::Handle,
+        name: &'static str,
+        sink: S,
+        rx_remote: SinkReceiver,
+        flush_rx: Receiver<FlushRequest>,
+        metrics_remote: Arc<Metrics>,
+    ) -> (tokio::task::JoinHandle<()>, Sender<()>) {
+        let (shutdown_tx_remote, shutdown_remote) = bounded(1);
+        let runtime_handle = runtime_handle.clone();
+        let handle = tokio::task::spawn_blocking(move || {
+            metrics_remote.active_workers.inc();
+            let write_record = |record: &LogRecord| match runtime_handle
+                .block_on(async { sink.write(record).await })
+            {
+                Ok(()) => {
+                    metrics_remote.inc_logs_written();
+                    metrics_remote.update_sink_health(name, true, None);
+                }
+                Err(e) => {
+                    metrics_remote.inc_sink_error();
+                    metrics_remote.update_sink_health(name, false, Some(e.to_string()));
+                }
+            };
+            loop {
+                if shutdown_remote.try_recv().is_ok() {
+                    // 排空直到 dispatcher 分发完毕并断开 channel
+                    let deadline = Instant::now() + Duration::from_secs(5);
+                    while let Ok(record) = rx_remote.recv_deadline(deadline) {
+                        if runtime_handle
+                            .block_on(async { sink.write(&record).await })
+                            .is_err()
+                        {
+                            metrics_remote.inc_sink_error();
+                        }
+                    }
+                    let _ = runtime_handle.block_on(async { sink.shutdown().await });
+                    break;
+                }
+
+                if let Ok((_, ack)) = flush_rx.try_recv() {
+                    // 先写入已排队的记录，使调用前产生的日志包含在本次 flush 中
+                    while let Ok(record) = rx_remote.try_recv() {
+                        write_record(&record);
+                    }
+                    let result = runtime_handle
+                        .block_on(async { sink.flush().await })
+                        .map_err(|e| e.to_string());
+                    let _ = ack.send(result);
+                }
+
+                match rx_remote.recv_timeout(Duration::from_millis(100)) {
+                    Ok(record) => write_record(&record),
+                    Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                        let _ = runtime_handle.block_on(async { sink.flush().await });
+                    }
+                    Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
+                        let _ = runtime_handle.block_on(async { sink.shutdown().await });
+                        break;
+                    }
+                }
+            }
+            metrics_remote.active_workers.dec();
+        });
+
+        (handle, shutdown_tx_remote)
+    }
+
+    /// 构建告警求值器，并启动将触发的告警 POST 到 webhook 的后台任务
+    ///
+    /// 求值器在日志调用线程上运行，只做非阻塞入队；队列满时丢弃告警。`masker` 为
+    /// `Some` 时告警消息在 POST 前脱敏。
+    #[cfg(feature = "alerts")]
+    fn spawn_alert_dispatcher(
+        rules: &[crate::AlertRuleConfig],
+        masker: Option<DataMasker>,
+    ) -> Result<crate::AlertEvaluator, InklogError> {
+        let (alert_tx, mut alert_rx) = tokio::sync::mpsc::channel::<crate::AlertEvent>(64);
+        let mut evaluator = crate::AlertEvaluator::new(rules, move |event| {
+            if let Err(e) = alert_tx.try_send(event) {
+                eprintln!("inklog: dropping alert: {}", e);
+            }
+        })?;
+        if let Some(masker) = masker {
+            evaluator = evaluator.with_masker(masker);
+        }
+
+        tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            while let Some(event) = alert_rx.recv().await {
+                let result = client
+                    .post(&event.webhook_url)
+                    .timeout(Duration::from_secs(10))
+                    .json(&event)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status());
+                if let Err(e) = result {
+                    eprintln!("inklog: failed to deliver alert '{}': {}", event.rule, e);
+                }
+            }
+        });
+        Ok(evaluator)
+    }
+
+    /// 将当前 metrics 序列化为 JSON 并写入快照表
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    fn persist_metrics_snapshot(
+        runtime_handle: &tokio::runtime::Handle,
+        database: &Arc<dyn Database>,
+        metrics: &Metrics,
+        table: &str,
+    ) {
+        let snapshot = metrics.get_status(0, 0).metrics;
+        let json = match serde_json::to_string(&snapshot) {
+            Ok(json) => json,
+            Err(e) => {
+                eprintln!("Database sink: Failed to serialize metrics snapshot: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = runtime_handle.block_on(async {
+            database
+                .insert_metrics_snapshot(table, Utc::now(), &json)
+                .await
+        }) {
+            eprintln!("Database sink: Failed to persist metrics snapshot: {}", e);
+        }
+    }
+
+    /// worker 从异步 channel 取出记录后归还其占用的字节预算
+    fn release_byte_budget(budget: &Option<Arc<ByteBudget>>, record: &LogRecord) {
+        if let Some(budget) = budget {
+            budget.release(record.estimated_size());
+        }
+    }
+
     pub fn get_health_status(&self) -> HealthStatus {
         let channel_len = self.sender.len();
         let channel_cap = self.effective_capacity.load(Ordering::Relaxed);
@@ -1497,6 +3015,140 @@ impl LoggerManager {
             })
     }
 
+    /// 只 flush 指定的 sink，并等待其 worker 确认
+    ///
+    /// `sink_name` 为文件 sink 名称（默认 `"file"`），或 `"console"`、`"database"`、
+    /// `"nats"`、`"elasticsearch"`、`"otlp"`、`"syslog"`、`"loki"`。调用前已进入队列的
+    /// 记录会先写入该 sink 再 flush，适合在"写后读"查询前建立检查点；其他 sink
+    /// 不受影响。sink 未启用时返回 `ConfigError`。
+    pub fn flush_sink(&self, sink_name: &str) -> Result<(), InklogError> {
+        if !self.is_flushable(sink_name) {
+            return Err(InklogError::ConfigError(format!(
+                "Cannot flush sink '{}': not an enabled sink",
+                sink_name
+            )));
+        }
+
+        self.thread_batches.flush_all();
+        let deadline = Instant::now() + FLUSH_SINK_TIMEOUT;
+        let ack_rx = self.request_flush(sink_name, deadline)?;
+        Self::await_flush_ack(sink_name, &ack_rx, deadline)
+    }
+
+    /// flush 所有已启用的 sink，并在 `timeout` 内等待各自 worker 确认
+    ///
+    /// 包括控制台、文件、数据库与远程 sink（NATS、Elasticsearch、OTLP、Syslog、Loki）。
+    /// 调用前已进入队列的记录会先写入对应 sink 再 flush；与 `shutdown()` 不同，
+    /// worker 会继续运行。各 sink 并行 flush，任一 sink 未在 `timeout` 内确认即
+    /// 返回 `ChannelError`。
+    pub fn flush(&self, timeout: Duration) -> Result<(), InklogError> {
+        // 各线程尚未发出的批量记录先入队，随后的 flush 请求会排在它们之后
+        self.thread_batches.flush_all();
+        let deadline = Instant::now() + timeout;
+        let pending = self
+            .flushable_sinks()
+            .into_iter()
+            .map(|sink_name| {
+                let ack_rx = self.request_flush(sink_name, deadline)?;
+                Ok((sink_name, ack_rx))
+            })
+            .collect::<Result<Vec<_>, InklogError>>()?;
+        for (sink_name, ack_rx) in pending {
+            Self::await_flush_ack(sink_name, &ack_rx, deadline)?;
+        }
+        Ok(())
+    }
+
+    /// 是否为已启用、可通过 flush 请求 flush 的 sink
+    fn is_flushable(&self, sink_name: &str) -> bool {
+        self.flushable_sinks().contains(&sink_name)
+    }
+
+    /// 已启用且有 worker 响应 flush 请求的 sink 名称；控制台 worker 总是运行
+    fn flushable_sinks(&self) -> Vec<&str> {
+        let mut sinks = vec!["console"];
+        sinks.extend(self.config.enabled_file_sinks().map(|c| c.name.as_str()));
+        #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+        if self
+            .config
+            .database_sink
+            .as_ref()
+            .is_some_and(|c| c.enabled)
+        {
+            sinks.push("database");
+        }
+        #[cfg(feature = "nats")]
+        if self.config.nats_sink.as_ref().is_some_and(|c| c.enabled) {
+            sinks.push("nats");
+        }
+        #[cfg(feature = "elasticsearch")]
+        if self
+            .config
+            .elasticsearch_sink
+            .as_ref()
+            .is_some_and(|c| c.enabled)
+        {
+            sinks.push("elasticsearch");
+        }
+        #[cfg(feature = "otlp")]
+        if self.config.otlp_sink.as_ref().is_some_and(|c| c.enabled) {
+            sinks.push("otlp");
+        }
+        #[cfg(feature = "syslog")]
+        if self.config.syslog_sink.as_ref().is_some_and(|c| c.enabled) {
+            sinks.push("syslog");
+        }
+        #[cfg(feature = "loki")]
+        if self.config.loki_sink.as_ref().is_some_and(|c| c.enabled) {
+            sinks.push("loki");
+        }
+        sinks
+    }
+
+    /// 向 sink 的 worker 发送 flush 请求，返回接收确认的 channel
+    fn request_flush(
+        &self,
+        sink_name: &str,
+        deadline: Instant,
+    ) -> Result<Receiver<Result<(), String>>, InklogError> {
+        let (ack_tx, ack_rx) = bounded(1);
+        self.flush_tx
+            .send(SinkControlMessage::FlushSink {
+                sink: sink_name.to_string(),
+                ack: ack_tx,
+                deadline,
+            })
+            .map_err(|e| {
+                InklogError::ChannelError(format!("Failed to send flush command: {}", e))
+            })?;
+        Ok(ack_rx)
+    }
+
+    /// 在 `deadline` 前等待 worker 的 flush 确认
+    fn await_flush_ack(
+        sink_name: &str,
+        ack_rx: &Receiver<Result<(), String>>,
+        deadline: Instant,
+    ) -> Result<(), InklogError> {
+        match ack_rx.recv_deadline(deadline) {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(InklogError::RuntimeError(format!(
+                "Flush of sink '{}' failed: {}",
+                sink_name, e
+            ))),
+            // worker 在处理请求前退出，请求随其 channel 一起被释放
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
+                Err(InklogError::ChannelError(format!(
+                    "Sink '{}' is unavailable: its worker has stopped",
+                    sink_name
+                )))
+            }
+            Err(crossbeam_channel::RecvTimeoutError::Timeout) => Err(InklogError::ChannelError(
+                format!("Sink '{}' did not confirm the flush", sink_name),
+            )),
+        }
+    }
+
     pub fn effective_channel_capacity(&self) -> usize {
         self.effective_capacity.load(Ordering::Relaxed)
     }
@@ -1505,6 +3157,40 @@ impl LoggerManager {
         self.sender.len()
     }
 
+    /// 返回内存环形缓冲区中最近的至多 `n` 条记录，按时间从旧到新排列
+    ///
+    /// 未启用 `memory_ring_sink` 时返回空列表。
+    #[cfg(feature = "memory-ring")]
+    pub fn dump_recent(&self, n: usize) -> Vec<LogRecord> {
+        self.memory_ring
+            .as_ref()
+            .map(|ring| ring.dump_recent(n))
+            .unwrap_or_default()
+    }
+
+    /// 未启用 `memory-ring` feature 时没有内存环形缓冲区，总是返回空列表
+    #[cfg(not(feature = "memory-ring"))]
+    pub fn dump_recent(&self, _n: usize) -> Vec<LogRecord> {
+        Vec::new()
+    }
+
+    /// 将控制台 sink 切换为写入捕获缓冲区
+    ///
+    /// 替换正在运行的 console worker 所使用的 sink，之后的控制台输出都会写入
+    /// `capture`。通常通过 `LoggerBuilder::capture_console()` 间接调用。
+    pub fn attach_console_capture(&self, capture: &ConsoleCapture) {
+        let sink = ConsoleSink::with_buffer(
+            self.config.console_sink.clone().unwrap_or_default(),
+            LogTemplate::new(&self.config.global.format),
+            capture.buffer(),
+        )
+        .with_masker(Self::masker(&self.config));
+        match self.console_sink.lock() {
+            Ok(mut guard) => *guard = sink,
+            Err(poisoned) => *poisoned.into_inner() = sink,
+        }
+    }
+
     pub fn trigger_recovery_for_unhealthy_sinks(&self) -> Result<Vec<String>, InklogError> {
         let health_status = self.get_health_status();
         let mut recovered_sinks = Vec::new();
@@ -1519,6 +3205,18 @@ impl LoggerManager {
     }
 
     pub fn shutdown(&self) -> Result<(), InklogError> {
+        // 未结束的重复段与未报告的采样丢弃在 worker 退出前补发汇总
+        for summary in self.summaries.finish() {
+            Self::enqueue_internal(
+                &self.sender,
+                &self.console_sender,
+                &self.byte_budget,
+                Arc::new(summary),
+            );
+        }
+
+        self.thread_batches.flush_all();
+
         // 向所有 worker 广播 shutdown 信号。每个 worker 持有独立的 channel receiver，
         // 必须逐个 send 才能确保全部收到（MPMC channel 的 send 仅被一个 receiver 消费）。
         // 历史缺陷：原先使用单一 `shutdown_tx`，send 一次只能让首个 worker 退出，
@@ -1616,6 +3314,7 @@ impl Drop for LoggerManager {
 pub struct LoggerBuilder {
     config: InklogConfig,
     deps: LoggerDependencies,
+    console_capture: Option<ConsoleCapture>,
 }
 
 impl LoggerBuilder {
@@ -1672,6 +3371,7 @@ impl LoggerBuilder {
             table_name: "logs".to_string(),
             archive_format: "json".to_string(),
             parquet_config: crate::ParquetConfig::default(),
+            ..Default::default()
         };
         self.config.database_sink = Some(config);
         self
@@ -1701,6 +3401,19 @@ impl LoggerBuilder {
         self
     }
 
+    /// 在记录日志的线程上同步写入并刷新控制台输出（测试/CI 中保证输出顺序确定）
+    pub fn console_synchronous(mut self, synchronous: bool) -> Self {
+        if let Some(ref mut console) = self.config.console_sink {
+            console.synchronous = synchronous;
+        } else if synchronous {
+            self.config.console_sink = Some(ConsoleSinkConfig {
+                synchronous,
+                ..Default::default()
+            });
+        }
+        self
+    }
+
     pub fn console_stderr_levels(mut self, levels: &[&str]) -> Self {
         if let Some(ref mut console) = self.config.console_sink {
             console.stderr_levels = levels.iter().map(|s| (*s).to_string()).collect();
@@ -1713,12 +3426,33 @@ impl LoggerBuilder {
         self
     }
 
-    // === File 配置快捷方法 ===
-
-    pub fn file_max_size(mut self, max_size: impl Into<String>) -> Self {
-        if let Some(ref mut file) = self.config.file_sink {
-            file.max_size = max_size.into();
-        } else {
+    /// 将控制台输出重定向到内存缓冲区，并返回读取句柄
+    ///
+    /// 主要用于测试：`build()` 之后的控制台输出（包括 stderr 级别）都会写入
+    /// 返回的 [`ConsoleCapture`]，而不是进程的 stdout/stderr。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let (builder, capture) = LoggerManager::builder().capture_console();
+    /// let logger = builder.build().await?;
+    /// tracing::info!("hello");
+    /// logger.shutdown()?;
+    /// assert!(capture.contents().contains("hello"));
+    /// ```
+    pub fn capture_console(mut self) -> (Self, ConsoleCapture) {
+        let capture = self
+            .console_capture
+            .get_or_insert_with(ConsoleCapture::new)
+            .clone();
+        (self, capture)
+    }
+
+    // === File 配置快捷方法 ===
+
+    pub fn file_max_size(mut self, max_size: impl Into<String>) -> Self {
+        if let Some(ref mut file) = self.config.file_sink {
+            file.max_size = max_size.into();
+        } else {
             self.config.file_sink = Some(FileSinkConfig {
                 max_size: max_size.into(),
                 ..Default::default()
@@ -1763,6 +3497,12 @@ impl LoggerBuilder {
         self
     }
 
+    /// 收到 SIGHUP 时让所有文件 sink 重新打开当前文件（仅 Unix），用于配合 logrotate
+    pub fn reopen_on_sighup(mut self, enabled: bool) -> Self {
+        self.config.global.reopen_on_sighup = enabled;
+        self
+    }
+
     // === HTTP Server 配置快捷方法 ===
 
     /// 启用或禁用HTTP监控服务器
@@ -1841,6 +3581,23 @@ impl LoggerBuilder {
         self
     }
 
+    /// 设置HTTP服务器就绪探针路径
+    ///
+    /// # Arguments
+    /// * `path` - 就绪探针端点路径（默认 "/ready"）
+    #[cfg(feature = "http")]
+    pub fn http_ready_path(mut self, path: impl Into<String>) -> Self {
+        if let Some(ref mut http) = self.config.http_server {
+            http.ready_path = path.into();
+        } else {
+            self.config.http_server = Some(crate::HttpServerConfig {
+                ready_path: path.into(),
+                ..Default::default()
+            });
+        }
+        self
+    }
+
     /// 设置HTTP服务器健康检查路径
     ///
     /// # Arguments
@@ -1957,6 +3714,15 @@ impl LoggerBuilder {
     /// # Returns
     /// 成功返回 `Ok(LoggerManager)`，失败返回 `Err(InklogError)`
     pub async fn build(self) -> Result<LoggerManager, InklogError> {
+        let console_capture = self.console_capture.clone();
+        let manager = self.build_manager().await?;
+        if let Some(capture) = console_capture {
+            manager.attach_console_capture(&capture);
+        }
+        Ok(manager)
+    }
+
+    async fn build_manager(self) -> Result<LoggerManager, InklogError> {
         // 如果有任何注入的依赖，使用 with_dependencies
         let has_deps = self.deps.cache.is_some() || self.deps.config.is_some() || {
             #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
@@ -2358,6 +4124,224 @@ mod tests {
         assert_eq!(console.stderr_levels, vec!["error".to_string()]);
     }
 
+    #[test]
+    fn test_builder_capture_console_returns_shared_handle() {
+        let (builder, capture) = LoggerBuilder::new().capture_console();
+        let stored = builder
+            .console_capture
+            .as_ref()
+            .expect("capture_console should store the capture handle");
+        assert!(Arc::ptr_eq(&stored.buffer(), &capture.buffer()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_attach_console_capture_records_output() {
+        let config = InklogConfig {
+            performance: crate::PerformanceConfig {
+                channel_capacity: 100,
+                worker_threads: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (manager, subscriber, filter) = LoggerManager::build_detached(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached should succeed");
+
+        let capture = ConsoleCapture::new();
+        manager.attach_console_capture(&capture);
+
+        let dispatch = tracing_subscriber::registry().with(subscriber).with(filter);
+        tracing::subscriber::with_default(dispatch, || {
+            tracing::info!("captured through pipeline");
+            tracing::error!("captured error through pipeline");
+        });
+
+        // shutdown 会排空 console channel，保证断言前输出已落入缓冲区
+        manager.shutdown().expect("shutdown should succeed");
+
+        let output = capture.contents();
+        assert!(
+            output.contains("captured through pipeline"),
+            "got: {}",
+            output
+        );
+        assert!(
+            output.contains("captured error through pipeline"),
+            "got: {}",
+            output
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_synchronous_console_output_visible_immediately() {
+        let config = InklogConfig {
+            console_sink: Some(ConsoleSinkConfig {
+                synchronous: true,
+                ..Default::default()
+            }),
+            performance: crate::PerformanceConfig {
+                channel_capacity: 100,
+                worker_threads: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (manager, subscriber, filter) = LoggerManager::build_detached(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached should succeed");
+
+        let capture = ConsoleCapture::new();
+        manager.attach_console_capture(&capture);
+
+        let dispatch = tracing_subscriber::registry().with(subscriber).with(filter);
+        tracing::subscriber::with_default(dispatch, || {
+            tracing::info!("written synchronously");
+            // 同步模式下无需等待 worker 或 shutdown，记录调用返回时输出已写入
+            let output = capture.contents();
+            assert!(output.contains("written synchronously"), "got: {}", output);
+        });
+
+        manager.shutdown().expect("shutdown should succeed");
+        assert_eq!(
+            capture.lines().len(),
+            1,
+            "console worker must not write the record a second time"
+        );
+    }
+
+    #[cfg(feature = "alerts")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_alert_rule_fires_webhook_once() {
+        let received = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        let received_clone = received.clone();
+        let app = axum::Router::new().route(
+            "/alert",
+            axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+                let received = received_clone.clone();
+                async move {
+                    received.lock().unwrap().push(body);
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind webhook receiver");
+        let addr = listener.local_addr().expect("local addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let config = InklogConfig {
+            console_sink: None,
+            alert_rules: vec![crate::AlertRuleConfig {
+                name: "payment-errors".to_string(),
+                target: Some("payments::*".to_string()),
+                level: "error".to_string(),
+                pattern: Some("declined".to_string()),
+                threshold: 3,
+                window_secs: 60,
+                cooldown_secs: None,
+                webhook_url: format!("http://{}/alert", addr),
+            }],
+            ..Default::default()
+        };
+        let (manager, subscriber, filter) = LoggerManager::build_detached(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached should succeed");
+
+        let dispatch = tracing_subscriber::registry().with(subscriber).with(filter);
+        tracing::subscriber::with_default(dispatch, || {
+            for _ in 0..10 {
+                tracing::error!(target: "payments::card", "card declined");
+            }
+            tracing::error!(target: "inventory", "card declined");
+        });
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while received.lock().unwrap().is_empty() && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(25)).await;
+        }
+        // 留出时间暴露可能的重复投递
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1, "rule should fire exactly once");
+        assert_eq!(received[0]["rule"], "payment-errors");
+        assert_eq!(received[0]["count"], 3);
+        assert_eq!(received[0]["target"], "payments::card");
+
+        manager.shutdown().expect("shutdown should succeed");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_console_tee_does_not_double_count_metrics() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let tee_path = dir.path().join("errors.log");
+        let config = InklogConfig {
+            console_sink: Some(ConsoleSinkConfig {
+                synchronous: true,
+                tee_error_to: Some(crate::TeeOutput::File(tee_path.clone())),
+                ..Default::default()
+            }),
+            performance: crate::PerformanceConfig {
+                channel_capacity: 100,
+                worker_threads: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (manager, subscriber, filter) = LoggerManager::build_detached(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached should succeed");
+
+        let capture = ConsoleCapture::new();
+        manager.attach_console_capture(&capture);
+
+        let dispatch = tracing_subscriber::registry().with(subscriber).with(filter);
+        tracing::subscriber::with_default(dispatch, || {
+            tracing::error!("teed error");
+        });
+
+        assert!(capture.contents().contains("teed error"));
+        let teed = std::fs::read_to_string(&tee_path).expect("tee file should exist");
+        assert!(teed.contains("teed error"));
+        assert_eq!(manager.metrics.logs_written(), 1);
+
+        manager.shutdown().expect("shutdown should succeed");
+    }
+
+    #[test]
+    fn test_builder_console_synchronous_creates_new_when_absent() {
+        let mut builder = LoggerBuilder::new();
+        builder.config.console_sink = None;
+        let builder = builder.console_synchronous(true);
+        assert!(
+            builder
+                .config
+                .console_sink
+                .as_ref()
+                .expect("console_sink should be created")
+                .synchronous
+        );
+    }
+
     #[test]
     fn test_builder_console_colored_true_creates_new_when_absent() {
         // colored=true 且 console_sink 为 None → 创建新配置
@@ -3060,6 +5044,38 @@ worker_threads = 1
         }
     }
 
+    /// Strict 模式：端口已被另一个 manager 占用时，绑定错误同步返回给调用者
+    #[cfg(feature = "http")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[serial_test::serial]
+    async fn test_with_config_http_strict_mode_returns_error_on_port_conflict() {
+        let port = find_available_http_port();
+        let first = LoggerManager::with_config(http_test_config(port))
+            .await
+            .expect("First manager should bind the port");
+        first
+            .http_ready()
+            .await
+            .expect("First server should be ready");
+
+        let mut config = http_test_config(port);
+        if let Some(http) = config.http_server.as_mut() {
+            http.error_mode = crate::HttpErrorMode::Strict;
+        }
+        match LoggerManager::with_config(config).await {
+            Err(InklogError::HttpServerError(msg)) => {
+                assert!(
+                    msg.contains("Failed to bind HTTP server"),
+                    "Error should mention the bind failure, got: {}",
+                    msg
+                );
+            }
+            Err(other) => panic!("Expected HttpServerError, got {:?}", other),
+            Ok(_) => panic!("Strict mode should return Err when the port is taken"),
+        }
+        let _ = first.shutdown();
+    }
+
     /// /health 端点返回 200 和 JSON 格式的健康状态
     #[cfg(feature = "http")]
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
@@ -3091,109 +5107,372 @@ worker_threads = 1
         let _ = manager.shutdown();
     }
 
-    /// /metrics 端点返回 200 和 Prometheus 格式文本
+    /// http_ready 返回后可立即连接，无需轮询或 sleep
     #[cfg(feature = "http")]
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
     #[serial_test::serial]
-    async fn test_http_server_metrics_endpoint_returns_prometheus() {
+    async fn test_http_ready_allows_immediate_connection() {
         let port = find_available_http_port();
         let manager = LoggerManager::with_config(http_test_config(port))
             .await
             .expect("Manager should start with HTTP server");
-        assert!(
-            wait_for_http_server("127.0.0.1", port).await,
-            "HTTP server should become reachable on port {}",
-            port
-        );
-        let resp = reqwest::get(format!("http://127.0.0.1:{}/metrics", port))
+        let addr = manager
+            .http_ready()
             .await
-            .expect("GET /metrics should succeed");
-        assert_eq!(
-            resp.status(),
-            reqwest::StatusCode::OK,
-            "metrics endpoint should return 200"
-        );
-        let body = resp.text().await.expect("body should be text");
-        assert!(
-            body.contains("# HELP") && body.contains("inklog_"),
-            "metrics response should be in Prometheus format, got: {}",
-            body
-        );
+            .expect("HTTP server should be ready");
+        assert_eq!(addr.port(), port);
+        let resp = reqwest::get(format!("http://{}/health", addr))
+            .await
+            .expect("GET /health should succeed right after http_ready");
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
         let _ = manager.shutdown();
     }
 
-    /// 自定义 health_path 和 metrics_path 应生效，默认路径不再可访问
+    /// 端口被占用时绑定错误同步返回：Strict 模式直接失败，Warn 模式下 http_ready 报错
     #[cfg(feature = "http")]
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
     #[serial_test::serial]
-    async fn test_http_server_custom_paths_work() {
-        let port = find_available_http_port();
-        let mut config = http_test_config(port);
-        {
-            let http = config
-                .http_server
-                .as_mut()
-                .expect("http_server should be set");
-            http.health_path = "/custom-health".to_string();
-            http.metrics_path = "/custom-metrics".to_string();
+    async fn test_http_bind_conflict_is_reported_synchronously() {
+        let occupied = std::net::TcpListener::bind("127.0.0.1:0").expect("bind should succeed");
+        let port = occupied.local_addr().expect("local addr").port();
+
+        let mut strict = http_test_config(port);
+        if let Some(http) = strict.http_server.as_mut() {
+            http.error_mode = crate::HttpErrorMode::Strict;
         }
-        let manager = LoggerManager::with_config(config)
-            .await
-            .expect("Manager should start with HTTP server");
-        assert!(
-            wait_for_http_server("127.0.0.1", port).await,
-            "HTTP server should become reachable"
-        );
-        // 自定义路径应返回 200
-        let resp = reqwest::get(format!("http://127.0.0.1:{}/custom-health", port))
-            .await
-            .expect("GET /custom-health should succeed");
-        assert_eq!(
-            resp.status(),
-            reqwest::StatusCode::OK,
-            "custom health path should return 200"
-        );
-        let resp = reqwest::get(format!("http://127.0.0.1:{}/custom-metrics", port))
+        match LoggerManager::with_config(strict).await {
+            Err(InklogError::HttpServerError(msg)) => {
+                assert!(
+                    msg.contains("Failed to bind"),
+                    "unexpected message: {}",
+                    msg
+                );
+            }
+            Err(other) => panic!("Expected HttpServerError, got {:?}", other),
+            Ok(_) => panic!("Strict mode should fail when the port is taken"),
+        }
+
+        let manager = LoggerManager::with_config(http_test_config(port))
             .await
-            .expect("GET /custom-metrics should succeed");
-        assert_eq!(
-            resp.status(),
-            reqwest::StatusCode::OK,
-            "custom metrics path should return 200"
-        );
-        // 默认路径应返回 404
-        let resp = reqwest::get(format!("http://127.0.0.1:{}/health", port))
+            .expect("Warn mode should continue despite bind failure");
+        assert!(matches!(
+            manager.http_ready().await,
+            Err(InklogError::HttpServerError(_))
+        ));
+        let _ = manager.shutdown();
+        drop(occupied);
+    }
+
+    /// build_detached 不启动 HTTP 服务器，http_ready 应立即返回错误而不是挂起
+    #[cfg(feature = "http")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_http_ready_errors_when_server_not_started() {
+        let (manager, _subscriber, _filter) = LoggerManager::build_detached(
+            http_test_config(find_available_http_port()),
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached should succeed");
+
+        let result = tokio::time::timeout(Duration::from_secs(5), manager.http_ready())
             .await
-            .expect("GET /health should succeed");
-        assert_eq!(
-            resp.status(),
-            reqwest::StatusCode::NOT_FOUND,
-            "default health path should return 404 when customized"
-        );
+            .expect("http_ready should not wait for a server that was never started");
+        assert!(matches!(result, Err(InklogError::ConfigError(_))));
         let _ = manager.shutdown();
     }
 
-    /// auth 禁用时，无 Authorization header 也能访问
+    /// /features 端点返回编译进当前构建的 feature 列表
     #[cfg(feature = "http")]
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
     #[serial_test::serial]
-    async fn test_http_server_auth_disabled_allows_access_without_header() {
+    async fn test_http_server_features_endpoint_lists_features() {
         let port = find_available_http_port();
         let manager = LoggerManager::with_config(http_test_config(port))
             .await
             .expect("Manager should start with HTTP server");
-        assert!(
-            wait_for_http_server("127.0.0.1", port).await,
-            "HTTP server should become reachable"
-        );
-        let resp = reqwest::get(format!("http://127.0.0.1:{}/health", port))
+        let addr = manager
+            .http_ready()
             .await
-            .expect("GET /health should succeed");
-        assert_eq!(
-            resp.status(),
-            reqwest::StatusCode::OK,
-            "auth disabled should allow access without Authorization header"
-        );
+            .expect("HTTP server should be ready");
+        let features: Vec<String> = reqwest::get(format!("http://{}/features", addr))
+            .await
+            .expect("GET /features should succeed")
+            .json()
+            .await
+            .expect("body should be a JSON array");
+        assert!(features.iter().any(|f| f == "http"));
+        assert_eq!(features, LoggerManager::enabled_features());
+        let _ = manager.shutdown();
+    }
+
+    /// PUT /level 在运行时修改日志级别，无效级别返回 400
+    #[cfg(feature = "http")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[serial_test::serial]
+    async fn test_http_server_put_level_updates_level() {
+        let port = find_available_http_port();
+        let mut config = http_test_config(port);
+        if let Some(http) = config.http_server.as_mut() {
+            http.allow_unauthenticated_level_change = true;
+        }
+        let manager = LoggerManager::with_config(config)
+            .await
+            .expect("Manager should start with HTTP server");
+        let addr = manager
+            .http_ready()
+            .await
+            .expect("HTTP server should be ready");
+        let client = reqwest::Client::new();
+
+        let resp = client
+            .put(format!("http://{}/level", addr))
+            .body("debug")
+            .send()
+            .await
+            .expect("PUT /level should succeed");
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        assert_eq!(resp.text().await.expect("body should be text"), "debug");
+
+        let resp = client
+            .put(format!("http://{}/level", addr))
+            .body("loud")
+            .send()
+            .await
+            .expect("PUT /level should return a response");
+        assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        manager.set_level("info").expect("info is a valid level");
+        let _ = manager.shutdown();
+    }
+
+    /// PUT /level 的审计记录标明来源为 http，并带有正确的旧级别与新级别
+    #[cfg(feature = "http")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[serial_test::serial]
+    async fn test_http_server_put_level_emits_audit_record() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("http_level_audit.log");
+        let port = find_available_http_port();
+        let mut config = http_test_config(port);
+        config.console_sink = None;
+        config.file_sink = Some(FileSinkConfig {
+            path: log_path.clone(),
+            format: crate::FileFormat::Json,
+            ..Default::default()
+        });
+        if let Some(http) = config.http_server.as_mut() {
+            http.allow_unauthenticated_level_change = true;
+        }
+        // 先占用全局 subscriber，无论测试顺序如何都覆盖"已有宿主 subscriber"的情形
+        let _ = tracing::subscriber::set_global_default(tracing_subscriber::registry());
+        let manager = LoggerManager::with_config(config)
+            .await
+            .expect("Manager should start with HTTP server");
+        let addr = manager
+            .http_ready()
+            .await
+            .expect("HTTP server should be ready");
+
+        let resp = reqwest::Client::new()
+            .put(format!("http://{}/level", addr))
+            .body("warn")
+            .send()
+            .await
+            .expect("PUT /level should succeed");
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+        manager
+            .flush(Duration::from_secs(5))
+            .expect("flush should succeed");
+        let contents = std::fs::read_to_string(&log_path).expect("log file should exist");
+        let audit: serde_json::Value = contents
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .find(|record| record["target"] == "inklog::level")
+            .unwrap_or_else(|| panic!("audit record missing, got: {}", contents));
+        assert_eq!(audit["level"], "INFO");
+        assert_eq!(audit["fields"]["old_level"], "info");
+        assert_eq!(audit["fields"]["new_level"], "warn");
+        assert_eq!(audit["fields"]["source"], "http");
+
+        manager.set_level("info").expect("info is a valid level");
+        let _ = manager.shutdown();
+    }
+
+    /// 未启用认证且未显式开启时，PUT /level 返回 403 且级别不变
+    #[cfg(feature = "http")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[serial_test::serial]
+    async fn test_http_server_put_level_requires_auth_or_opt_in() {
+        let port = find_available_http_port();
+        let manager = LoggerManager::with_config(http_test_config(port))
+            .await
+            .expect("Manager should start with HTTP server");
+        let addr = manager
+            .http_ready()
+            .await
+            .expect("HTTP server should be ready");
+
+        let resp = reqwest::Client::new()
+            .put(format!("http://{}/level", addr))
+            .body("trace")
+            .send()
+            .await
+            .expect("PUT /level should return a response");
+        assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+        assert_eq!(
+            *manager.level_control.current.lock().unwrap(),
+            tracing::Level::INFO
+        );
+
+        let _ = manager.shutdown();
+    }
+
+    /// /metrics 端点返回 200 和 Prometheus 格式文本
+    #[cfg(feature = "http")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[serial_test::serial]
+    async fn test_http_server_metrics_endpoint_returns_prometheus() {
+        let port = find_available_http_port();
+        let manager = LoggerManager::with_config(http_test_config(port))
+            .await
+            .expect("Manager should start with HTTP server");
+        assert!(
+            wait_for_http_server("127.0.0.1", port).await,
+            "HTTP server should become reachable on port {}",
+            port
+        );
+        let resp = reqwest::get(format!("http://127.0.0.1:{}/metrics", port))
+            .await
+            .expect("GET /metrics should succeed");
+        assert_eq!(
+            resp.status(),
+            reqwest::StatusCode::OK,
+            "metrics endpoint should return 200"
+        );
+        let body = resp.text().await.expect("body should be text");
+        assert!(
+            body.contains("# HELP") && body.contains("inklog_"),
+            "metrics response should be in Prometheus format, got: {}",
+            body
+        );
+        let _ = manager.shutdown();
+    }
+
+    /// /health 为存活探针始终返回 200；/ready 在存在不可用 sink 时返回 503
+    #[cfg(feature = "http")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[serial_test::serial]
+    async fn test_http_server_liveness_and_readiness() {
+        let port = find_available_http_port();
+        let manager = LoggerManager::with_config(http_test_config(port))
+            .await
+            .expect("Manager should start with HTTP server");
+        assert!(
+            wait_for_http_server("127.0.0.1", port).await,
+            "HTTP server should become reachable"
+        );
+
+        // 使用本配置中不存在的 sink 名，避免被 console worker 的写入覆盖
+        manager.metrics.update_sink_health(
+            "database",
+            false,
+            Some("connection refused".to_string()),
+        );
+        let ready = reqwest::get(format!("http://127.0.0.1:{}/ready", port))
+            .await
+            .expect("GET /ready should succeed");
+        assert_eq!(ready.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        let health = reqwest::get(format!("http://127.0.0.1:{}/health", port))
+            .await
+            .expect("GET /health should succeed");
+        assert_eq!(
+            health.status(),
+            reqwest::StatusCode::OK,
+            "liveness ignores sink health"
+        );
+
+        manager.metrics.update_sink_health("database", true, None);
+        let ready = reqwest::get(format!("http://127.0.0.1:{}/ready", port))
+            .await
+            .expect("GET /ready should succeed");
+        assert_eq!(ready.status(), reqwest::StatusCode::OK);
+        let _ = manager.shutdown();
+    }
+
+    /// 自定义 health_path 和 metrics_path 应生效，默认路径不再可访问
+    #[cfg(feature = "http")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[serial_test::serial]
+    async fn test_http_server_custom_paths_work() {
+        let port = find_available_http_port();
+        let mut config = http_test_config(port);
+        {
+            let http = config
+                .http_server
+                .as_mut()
+                .expect("http_server should be set");
+            http.health_path = "/custom-health".to_string();
+            http.metrics_path = "/custom-metrics".to_string();
+        }
+        let manager = LoggerManager::with_config(config)
+            .await
+            .expect("Manager should start with HTTP server");
+        assert!(
+            wait_for_http_server("127.0.0.1", port).await,
+            "HTTP server should become reachable"
+        );
+        // 自定义路径应返回 200
+        let resp = reqwest::get(format!("http://127.0.0.1:{}/custom-health", port))
+            .await
+            .expect("GET /custom-health should succeed");
+        assert_eq!(
+            resp.status(),
+            reqwest::StatusCode::OK,
+            "custom health path should return 200"
+        );
+        let resp = reqwest::get(format!("http://127.0.0.1:{}/custom-metrics", port))
+            .await
+            .expect("GET /custom-metrics should succeed");
+        assert_eq!(
+            resp.status(),
+            reqwest::StatusCode::OK,
+            "custom metrics path should return 200"
+        );
+        // 默认路径应返回 404
+        let resp = reqwest::get(format!("http://127.0.0.1:{}/health", port))
+            .await
+            .expect("GET /health should succeed");
+        assert_eq!(
+            resp.status(),
+            reqwest::StatusCode::NOT_FOUND,
+            "default health path should return 404 when customized"
+        );
+        let _ = manager.shutdown();
+    }
+
+    /// auth 禁用时，无 Authorization header 也能访问
+    #[cfg(feature = "http")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[serial_test::serial]
+    async fn test_http_server_auth_disabled_allows_access_without_header() {
+        let port = find_available_http_port();
+        let manager = LoggerManager::with_config(http_test_config(port))
+            .await
+            .expect("Manager should start with HTTP server");
+        assert!(
+            wait_for_http_server("127.0.0.1", port).await,
+            "HTTP server should become reachable"
+        );
+        let resp = reqwest::get(format!("http://127.0.0.1:{}/health", port))
+            .await
+            .expect("GET /health should succeed");
+        assert_eq!(
+            resp.status(),
+            reqwest::StatusCode::OK,
+            "auth disabled should allow access without Authorization header"
+        );
         let _ = manager.shutdown();
     }
 
@@ -3757,6 +6036,326 @@ worker_threads = 1
         let _ = manager.shutdown();
     }
 
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_metrics_snapshots_accumulate_in_database() {
+        use crate::integrations::MockDatabaseAdapter;
+
+        let mock = Arc::new(MockDatabaseAdapter::new());
+        let config = InklogConfig {
+            database_sink: Some(crate::DatabaseSinkConfig {
+                enabled: true,
+                metrics_snapshot_enabled: true,
+                metrics_snapshot_interval_ms: 50,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let (manager, _subscriber, _filter) =
+            LoggerManager::build_detached(config, Some(mock.clone() as Arc<dyn Database>))
+                .await
+                .expect("build_detached should succeed");
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let _ = manager.shutdown();
+
+        let snapshots = mock.get_metrics_snapshots();
+        assert!(
+            snapshots.len() >= 2,
+            "expected snapshots to accumulate, got {}",
+            snapshots.len()
+        );
+        let parsed: serde_json::Value =
+            serde_json::from_str(&snapshots[0].1).expect("snapshot should be valid JSON");
+        assert!(parsed.get("logs_written").is_some());
+        assert!(snapshots[0].0 <= snapshots[1].0);
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_flush_sink_makes_database_records_queryable() {
+        use crate::integrations::MockDatabaseAdapter;
+
+        let mock = Arc::new(MockDatabaseAdapter::new());
+        let config = InklogConfig {
+            console_sink: None,
+            database_sink: Some(crate::DatabaseSinkConfig {
+                enabled: true,
+                batch_size: 1000,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let (manager, subscriber, filter) =
+            LoggerManager::build_detached(config, Some(mock.clone() as Arc<dyn Database>))
+                .await
+                .expect("build_detached should succeed");
+
+        let dispatch = tracing_subscriber::registry().with(subscriber).with(filter);
+        tracing::subscriber::with_default(dispatch, || {
+            for i in 0..5 {
+                tracing::info!(i, "checkpointed record");
+            }
+        });
+
+        manager
+            .flush_sink("database")
+            .expect("database flush should be confirmed");
+        assert_eq!(mock.record_count(), 5);
+
+        // 未启用的 sink 不能单独 flush
+        assert!(matches!(
+            manager.flush_sink("file"),
+            Err(InklogError::ConfigError(_))
+        ));
+
+        let _ = manager.shutdown();
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_flush_drains_all_sinks_and_keeps_workers_running() {
+        use crate::integrations::MockDatabaseAdapter;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let log_path = dir.path().join("flush.log");
+        let mock = Arc::new(MockDatabaseAdapter::new());
+        let config = InklogConfig {
+            console_sink: None,
+            file_sink: Some(FileSinkConfig {
+                enabled: true,
+                path: log_path.clone(),
+                batch_size: 1000,
+                flush_interval_ms: 60_000,
+                ..Default::default()
+            }),
+            database_sink: Some(crate::DatabaseSinkConfig {
+                enabled: true,
+                batch_size: 1000,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let (manager, subscriber, filter) =
+            LoggerManager::build_detached(config, Some(mock.clone() as Arc<dyn Database>))
+                .await
+                .expect("build_detached should succeed");
+
+        let dispatch =
+            tracing::Dispatch::new(tracing_subscriber::registry().with(subscriber).with(filter));
+        let log_batch = |count: usize| {
+            tracing::dispatcher::with_default(&dispatch, || {
+                for i in 0..count {
+                    tracing::info!(i, "flushed record");
+                }
+            });
+        };
+        // dispatcher 把每条记录分发给所有 sink，flush 返回时两个 sink 都已写入
+        let written = || {
+            (
+                std::fs::read_to_string(&log_path)
+                    .unwrap_or_default()
+                    .lines()
+                    .count(),
+                mock.record_count(),
+            )
+        };
+
+        log_batch(4);
+        manager
+            .flush(Duration::from_secs(10))
+            .expect("flush should be confirmed");
+        assert_eq!(written(), (4, 4));
+
+        // flush 不会停止 worker
+        log_batch(3);
+        manager
+            .flush(Duration::from_secs(10))
+            .expect("second flush should be confirmed");
+        assert_eq!(written(), (7, 7));
+
+        let _ = manager.shutdown();
+    }
+
+    /// 在 `dir` 下创建 SQLite 连接池，并为每个 `tables` 建立日志表
+    #[cfg(feature = "sqlite")]
+    async fn sqlite_log_pool(
+        dir: &Path,
+        tables: &[&str],
+    ) -> (dbnexus::database::pool::DbPool, String) {
+        use dbnexus::database::pool::DbPool;
+        use dbnexus::foundation::config::DbConfig;
+
+        let perm_path = dir.join("permissions.yaml");
+        std::fs::write(
+            &perm_path,
+            r#"roles:
+  admin:
+    tables:
+      - name: "*"
+        operations: ["select", "insert", "update", "delete"]
+"#,
+        )
+        .expect("Failed to write permissions file");
+        let url = format!("sqlite:{}?mode=rwc", dir.join("logs.db").display());
+        let pool = DbPool::with_config(DbConfig {
+            url: url.clone(),
+            max_connections: 2,
+            min_connections: 1,
+            idle_timeout: 300,
+            acquire_timeout: 30000,
+            permissions_path: Some(perm_path.to_string_lossy().to_string()),
+            migrations_dir: None,
+            auto_migrate: false,
+            migration_timeout: 60,
+            admin_role: "admin".to_string(),
+            warmup_timeout: 60,
+            warmup_retries: 5,
+            cache_config: dbnexus::foundation::config::CacheConfig::default(),
+        })
+        .await
+        .expect("Failed to create pool");
+        let session = pool.get_session("admin").await.expect("session");
+        for table in tables {
+            session
+                .execute_raw_ddl(&format!(
+                    "CREATE TABLE IF NOT EXISTS {} (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    timestamp TEXT NOT NULL,
+                    level TEXT NOT NULL,
+                    target TEXT NOT NULL,
+                    message TEXT NOT NULL,
+                    fields TEXT,
+                    file TEXT,
+                    line INTEGER,
+                    thread_id TEXT NOT NULL,
+                    module_path TEXT,
+                    metadata TEXT
+                )",
+                    table
+                ))
+                .await
+                .expect("Failed to create table");
+        }
+        drop(session);
+        (pool, url)
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_flush_makes_records_readable_from_sqlite() {
+        use crate::sink::entity::{Column, Entity};
+        use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (pool, url) = sqlite_log_pool(dir.path(), &["logs"]).await;
+        let adapter = crate::integrations::infra::DbNexusAdapter::from_pool(pool, "logs");
+
+        let config = InklogConfig {
+            console_sink: None,
+            database_sink: Some(crate::DatabaseSinkConfig {
+                enabled: true,
+                batch_size: 1000,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let (manager, subscriber, filter) =
+            LoggerManager::build_detached(config, Some(Arc::new(adapter) as Arc<dyn Database>))
+                .await
+                .expect("build_detached should succeed");
+
+        let dispatch = tracing_subscriber::registry().with(subscriber).with(filter);
+        tracing::subscriber::with_default(dispatch, || {
+            for i in 0..3 {
+                tracing::info!(i, "read after flush");
+            }
+        });
+        manager
+            .flush(Duration::from_secs(10))
+            .expect("flush should be confirmed");
+
+        let conn = sea_orm::Database::connect(&url)
+            .await
+            .expect("Failed to connect to database");
+        let stored = Entity::find()
+            .filter(Column::Message.eq("read after flush"))
+            .count(&conn)
+            .await
+            .expect("Failed to count logs");
+        assert_eq!(stored, 3);
+
+        let _ = manager.shutdown();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_database_sink_writes_to_configured_table() {
+        use sea_orm::{ConnectionTrait, DbBackend, Statement};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (pool, url) = sqlite_log_pool(dir.path(), &["logs", "tenant_a_logs"]).await;
+        let db_config = crate::DatabaseSinkConfig {
+            enabled: true,
+            batch_size: 1000,
+            table_name: "tenant_a_logs".to_string(),
+            ..Default::default()
+        };
+        db_config
+            .validate_table_name()
+            .expect("table name should be valid");
+        let adapter =
+            crate::integrations::infra::DbNexusAdapter::from_pool(pool, &db_config.table_name);
+
+        let config = InklogConfig {
+            console_sink: None,
+            database_sink: Some(db_config),
+            ..Default::default()
+        };
+        let (manager, subscriber, filter) =
+            LoggerManager::build_detached(config, Some(Arc::new(adapter) as Arc<dyn Database>))
+                .await
+                .expect("build_detached should succeed");
+
+        let dispatch = tracing_subscriber::registry().with(subscriber).with(filter);
+        tracing::subscriber::with_default(dispatch, || {
+            for i in 0..2 {
+                tracing::info!(i, "tenant record");
+            }
+        });
+        manager
+            .flush(Duration::from_secs(10))
+            .expect("flush should be confirmed");
+
+        let conn = sea_orm::Database::connect(&url)
+            .await
+            .expect("Failed to connect to database");
+        let count = |table: &str| {
+            Statement::from_string(
+                DbBackend::Sqlite,
+                format!(
+                    "SELECT COUNT(*) AS n FROM {} WHERE message = 'tenant record'",
+                    table
+                ),
+            )
+        };
+        let stored = |row: Option<sea_orm::QueryResult>| {
+            row.expect("COUNT returns a row")
+                .try_get::<i64>("", "n")
+                .expect("n is an integer")
+        };
+        let custom = conn
+            .query_one_raw(count("tenant_a_logs"))
+            .await
+            .expect("query");
+        assert_eq!(stored(custom), 2);
+        let default = conn.query_one_raw(count("logs")).await.expect("query");
+        assert_eq!(stored(default), 0);
+
+        let _ = manager.shutdown();
+    }
+
     // ============================================================================
     // build_detached 直接调用测试 (lines 832-884)
     //
@@ -3802,7 +6401,7 @@ worker_threads = 1
             "channel_len should be 0 for fresh manager"
         );
 
-        // filter 现在是 EnvFilter，验证其字符串表示包含配置的 level "warn"
+        // 验证 EnvFilter 的字符串表示包含配置的 level "warn"
         let filter_str = filter.to_string();
         assert!(
             filter_str.contains("warn"),
@@ -3814,36 +6413,381 @@ worker_threads = 1
     }
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn test_build_detached_invalid_level_falls_back_to_info() {
-        // 验证 build_detached 中 level.parse() 失败时回退到 INFO
+    async fn test_build_detached_invalid_level_falls_back_to_info() {
+        // 验证 build_detached 中 level.parse() 失败时回退到 INFO
+        let config = InklogConfig {
+            global: crate::GlobalConfig {
+                level: "invalid_level".to_string(),
+                ..Default::default()
+            },
+            performance: crate::PerformanceConfig {
+                channel_capacity: 1000,
+                worker_threads: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (manager, _subscriber, filter) = LoggerManager::build_detached(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached should succeed even with invalid level");
+
+        // 无效 level 应回退到 INFO
+        let filter_str = filter.to_string();
+        assert!(
+            filter_str.contains("info"),
+            "EnvFilter should fall back to 'info' for invalid level, got: {}",
+            filter_str
+        );
+
+        let _ = manager.shutdown();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_set_level_changes_filtering_at_runtime() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("set_level.log");
+        let config = InklogConfig {
+            global: crate::GlobalConfig {
+                level: "info".to_string(),
+                ..Default::default()
+            },
+            console_sink: Some(ConsoleSinkConfig {
+                enabled: false,
+                ..Default::default()
+            }),
+            file_sink: Some(FileSinkConfig {
+                path: log_path.clone(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let (manager, subscriber, filter) = LoggerManager::build_detached_reloadable(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached_reloadable should succeed");
+        let dispatch =
+            tracing::Dispatch::new(tracing_subscriber::registry().with(subscriber).with(filter));
+
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::debug!("debug before set_level");
+        });
+        manager.set_level("debug").expect("debug is a valid level");
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::debug!("debug after set_level");
+        });
+        manager
+            .set_level("WARN")
+            .expect("level is case-insensitive");
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::info!("info after raising to warn");
+        });
+        assert!(matches!(
+            manager.set_level("verbose"),
+            Err(InklogError::ConfigError(_))
+        ));
+
+        manager
+            .flush(Duration::from_secs(5))
+            .expect("flush should succeed");
+        let contents = std::fs::read_to_string(&log_path).expect("log file should exist");
+        assert!(!contents.contains("debug before set_level"));
+        assert!(contents.contains("debug after set_level"));
+        assert!(!contents.contains("info after raising to warn"));
+
+        let _ = manager.shutdown();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_set_level_emits_audit_record() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("level_audit.log");
+        let config = InklogConfig {
+            console_sink: None,
+            file_sink: Some(FileSinkConfig {
+                path: log_path.clone(),
+                format: crate::FileFormat::Json,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let (manager, _subscriber, _filter) = LoggerManager::build_detached_reloadable(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached_reloadable should succeed");
+
+        manager.set_level("debug").expect("debug is a valid level");
+        // 提高到 ERROR 后审计记录仍然写入
+        manager.set_level("error").expect("error is a valid level");
+        assert!(manager.set_level("verbose").is_err());
+
+        manager
+            .flush(Duration::from_secs(5))
+            .expect("flush should succeed");
+        let contents = std::fs::read_to_string(&log_path).expect("log file should exist");
+        let audits: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line should be JSON"))
+            .filter(|record: &serde_json::Value| record["target"] == "inklog::level")
+            .collect();
+        assert_eq!(audits.len(), 2, "got: {}", contents);
+        assert_eq!(audits[0]["level"], "INFO");
+        assert_eq!(audits[0]["fields"]["old_level"], "info");
+        assert_eq!(audits[0]["fields"]["new_level"], "debug");
+        assert_eq!(audits[0]["fields"]["source"], "api");
+        assert_eq!(audits[1]["fields"]["old_level"], "debug");
+        assert_eq!(audits[1]["fields"]["new_level"], "error");
+
+        let _ = manager.shutdown();
+    }
+
+    /// 构建带采样规则（`test::flood` 每 10 条保留 1 条）的 manager 并写入 50 条洪峰记录
+    async fn sampled_flood_manager(log_path: &Path, summary_interval_ms: u64) -> LoggerManager {
+        let config = InklogConfig {
+            console_sink: None,
+            file_sink: Some(FileSinkConfig {
+                path: log_path.to_path_buf(),
+                format: crate::FileFormat::Json,
+                ..Default::default()
+            }),
+            sampling: crate::SamplingConfig {
+                rules: vec![crate::SamplingRuleConfig {
+                    target: Some("test::flood".to_string()),
+                    levels: Vec::new(),
+                    mode: crate::SamplingMode::Fixed,
+                    rate: 10,
+                }],
+                summary_interval_ms,
+            },
+            ..Default::default()
+        };
+        let (manager, subscriber, filter) = LoggerManager::build_detached(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached should succeed");
+
+        let dispatch = tracing_subscriber::registry().with(subscriber).with(filter);
+        tracing::subscriber::with_default(dispatch, || {
+            for i in 0..50 {
+                tracing::warn!(target: "test::flood", i, "flood");
+            }
+        });
+        manager
+    }
+
+    fn sampling_summaries(log_path: &Path) -> Vec<serde_json::Value> {
+        std::fs::read_to_string(log_path)
+            .expect("log file should exist")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line should be JSON"))
+            .filter(|record: &serde_json::Value| {
+                record["target"] == crate::support::processing::SAMPLING_SUMMARY_TARGET
+            })
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_sampling_summary_is_emitted_without_later_events() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("sampling_timer.log");
+        let manager = sampled_flood_manager(&log_path, 500).await;
+
+        // 洪峰之后不再有记录，汇总由定时器发出
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+        manager
+            .flush(Duration::from_secs(5))
+            .expect("flush should succeed");
+        let summaries = sampling_summaries(&log_path);
+        assert_eq!(summaries.len(), 1, "got: {:?}", summaries);
+        assert_eq!(summaries[0]["fields"]["suppressed"], 45);
+
+        let _ = manager.shutdown();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_shutdown_flushes_pending_sampling_summary() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("sampling_shutdown.log");
+        let manager = sampled_flood_manager(&log_path, 60_000).await;
+
+        manager.shutdown().expect("shutdown should succeed");
+        let summaries = sampling_summaries(&log_path);
+        assert_eq!(summaries.len(), 1, "got: {:?}", summaries);
+        assert_eq!(summaries[0]["fields"]["suppressed"], 45);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_dedup_summary_is_emitted_after_window_without_later_events() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("dedup_timer.log");
+        let config = InklogConfig {
+            console_sink: None,
+            file_sink: Some(FileSinkConfig {
+                path: log_path.clone(),
+                format: crate::FileFormat::Json,
+                ..Default::default()
+            }),
+            dedup: crate::DedupConfig {
+                enabled: true,
+                window_ms: 200,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (manager, subscriber, filter) = LoggerManager::build_detached(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached should succeed");
+
+        let dispatch = tracing_subscriber::registry().with(subscriber).with(filter);
+        tracing::subscriber::with_default(dispatch, || {
+            for _ in 0..5 {
+                tracing::warn!(target: "test::dedup", "connection refused");
+            }
+        });
+
+        // 重复段之后不再有记录，窗口结束后由定时器发出汇总
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+        manager
+            .flush(Duration::from_secs(5))
+            .expect("flush should succeed");
+        let contents = std::fs::read_to_string(&log_path).expect("log file should exist");
+        let messages: Vec<String> = contents
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("JSON line"))
+            .filter(|record| record["target"] == "test::dedup")
+            .map(|record| record["message"].as_str().unwrap_or_default().to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            vec!["connection refused", "last message repeated 4 times"]
+        );
+
+        let _ = manager.shutdown();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_set_level_requires_reloadable_filter() {
+        let (manager, _subscriber, _filter) = LoggerManager::build_detached(
+            InklogConfig {
+                console_sink: None,
+                ..Default::default()
+            },
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached should succeed");
+
+        assert!(matches!(
+            manager.set_level("debug"),
+            Err(InklogError::RuntimeError(_))
+        ));
+        let _ = manager.shutdown();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_layer_composes_with_fmt_layer() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("layer.log");
+        let config = InklogConfig {
+            global: crate::GlobalConfig {
+                level: "info".to_string(),
+                ..Default::default()
+            },
+            console_sink: Some(ConsoleSinkConfig {
+                enabled: false,
+                ..Default::default()
+            }),
+            file_sink: Some(FileSinkConfig {
+                path: log_path.clone(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let (layer, manager) = LoggerManager::layer(config)
+            .await
+            .expect("layer should build without installing a global subscriber");
+        let dispatch = tracing::Dispatch::new(
+            tracing_subscriber::registry()
+                .with(tracing_subscriber::fmt::layer().with_writer(std::io::sink))
+                .with(layer),
+        );
+
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::info!("info through composed layer");
+            tracing::debug!("debug filtered by inklog level");
+        });
+        manager.set_level("debug").expect("debug is a valid level");
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::debug!("debug after set_level");
+        });
+
+        manager
+            .flush(Duration::from_secs(5))
+            .expect("flush should succeed");
+        let contents = std::fs::read_to_string(&log_path).expect("log file should exist");
+        assert!(contents.contains("info through composed layer"));
+        assert!(!contents.contains("debug filtered by inklog level"));
+        assert!(contents.contains("debug after set_level"));
+
+        let _ = manager.shutdown();
+    }
+
+    #[cfg(feature = "memory-ring")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_dump_recent_returns_latest_records_in_order() {
         let config = InklogConfig {
-            global: crate::GlobalConfig {
-                level: "invalid_level".to_string(),
-                ..Default::default()
-            },
-            performance: crate::PerformanceConfig {
-                channel_capacity: 1000,
-                worker_threads: 1,
+            console_sink: Some(ConsoleSinkConfig {
+                enabled: false,
                 ..Default::default()
-            },
+            }),
+            memory_ring_sink: Some(crate::MemoryRingSinkConfig {
+                enabled: true,
+                capacity: 4,
+                panic_dump_path: None,
+            }),
             ..Default::default()
         };
 
-        let (manager, _subscriber, filter) = LoggerManager::build_detached(
-            config,
-            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
-            None,
-        )
-        .await
-        .expect("build_detached should succeed even with invalid level");
+        let (layer, manager) = LoggerManager::layer(config)
+            .await
+            .expect("layer should build");
+        let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(layer));
+        tracing::dispatcher::with_default(&dispatch, || {
+            for i in 0..10 {
+                tracing::info!("ring record {}", i);
+            }
+        });
 
-        // 无效 level 应回退到 INFO
-        let filter_str = filter.to_string();
-        assert!(
-            filter_str.contains("info"),
-            "EnvFilter should fall back to 'info' for invalid level, got: {}",
-            filter_str
+        let messages: Vec<String> = manager
+            .dump_recent(3)
+            .into_iter()
+            .map(|record| record.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec!["ring record 7", "ring record 8", "ring record 9"]
         );
+        assert_eq!(manager.dump_recent(100).len(), 4);
 
         let _ = manager.shutdown();
     }
@@ -4040,6 +6984,82 @@ worker_threads = 1
         let _ = manager.shutdown();
     }
 
+    #[test]
+    fn test_write_retry_policy_succeeds_before_retries_run_out() {
+        let policy = WriteRetryPolicy::from_config(&crate::PerformanceConfig {
+            max_write_retries: 4,
+            retry_base_delay_ms: 1,
+            ..Default::default()
+        });
+        // 前 N-1 次失败、第 N 次成功：不应走降级路径
+        let mut calls = 0;
+        let mut errors = 0;
+        let result = policy.run(
+            || {
+                calls += 1;
+                if calls < 5 { Err("busy") } else { Ok(()) }
+            },
+            |_| errors += 1,
+        );
+        assert!(result.is_ok());
+        assert_eq!((calls, errors), (5, 4));
+
+        // 重试耗尽后返回最后一次错误
+        let mut calls = 0;
+        let result = policy.run(
+            || {
+                calls += 1;
+                Err(calls)
+            },
+            |_| {},
+        );
+        assert_eq!(result, Err(5));
+    }
+
+    #[test]
+    fn test_write_retry_policy_backoff_and_recovery() {
+        let policy = WriteRetryPolicy::from_config(&crate::PerformanceConfig {
+            retry_base_delay_ms: 10,
+            recovery_failure_threshold: 2,
+            recovery_delay_secs: 0,
+            ..Default::default()
+        });
+        assert_eq!(policy.backoff(1), Duration::from_millis(10));
+        assert_eq!(policy.backoff(2), Duration::from_millis(20));
+        assert_eq!(policy.backoff(3), Duration::from_millis(40));
+
+        let failed_at = Some(Instant::now() - Duration::from_millis(5));
+        assert!(!policy.should_recover(2, failed_at));
+        assert!(policy.should_recover(3, failed_at));
+        assert!(!policy.should_recover(3, None));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_build_detached_uses_configured_error_log_path() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let error_log_path = dir.path().join("internal").join("errors.log");
+        let config = InklogConfig {
+            performance: crate::PerformanceConfig {
+                channel_capacity: 1000,
+                worker_threads: 1,
+                ..Default::default()
+            },
+            error_log_path: error_log_path.clone(),
+            ..Default::default()
+        };
+
+        let (manager, _subscriber, _filter) = LoggerManager::build_detached(
+            config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached should succeed");
+
+        assert!(error_log_path.exists());
+        let _ = manager.shutdown();
+    }
+
     // ============================================================================
     // LoggerDependencies Debug 实现测试 (lines 118-131)
     //
@@ -4250,4 +7270,582 @@ worker_threads = 1
 
         let _ = manager.shutdown();
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_file_worker_counts_each_record_once() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let config = InklogConfig::default();
+        let configs = ["a", "b"]
+            .into_iter()
+            .map(|name| FileSinkConfig {
+                name: name.to_string(),
+                path: dir.path().join(format!("{name}.log")),
+                ..Default::default()
+            })
+            .collect();
+        let metrics = Arc::new(Metrics::new());
+        let console_sink = Arc::new(Mutex::new(ConsoleSink::new(
+            ConsoleSinkConfig::default(),
+            LogTemplate::new(&config.global.format),
+        )));
+        let runtime_handle = tokio::runtime::Handle::current();
+        let worker_metrics = metrics.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut worker = FileWorker::new(
+                configs,
+                runtime_handle,
+                WriteRetryPolicy::from_config(&config.performance),
+                worker_metrics,
+                Arc::new(Mutex::new(None)),
+                console_sink,
+                LoggerManager::masker(&config),
+            );
+            // 两个 sink 都接收这两条记录
+            for i in 0..2 {
+                worker.write(&dispatcher_record(&format!("msg-{i}")), false);
+            }
+            worker.shutdown();
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(metrics.logs_written(), 2);
+        for name in ["a", "b"] {
+            let content = std::fs::read_to_string(dir.path().join(format!("{name}.log"))).unwrap();
+            assert_eq!(content.lines().count(), 2, "file sink '{name}'");
+        }
+    }
+
+    /// 容量 100、阻塞溢出策略的 sink channel 集合
+    fn dispatcher_channels() -> SinkChannels {
+        SinkChannels::new(100, OverflowPolicy::Block, Arc::new(Metrics::new()))
+    }
+
+    fn dispatcher_record(message: &str) -> Arc<LogRecord> {
+        Arc::new(LogRecord {
+            timestamp: Utc::now(),
+            level: "INFO".to_string(),
+            target: "test".to_string(),
+            message: message.to_string(),
+            fields: Default::default(),
+            file: None,
+            line: None,
+            thread_id: "main".to_string(),
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_dispatcher_fans_out_every_record_to_each_sink() {
+        let (main_tx, main_rx) = bounded(100);
+        let (_flush_tx, flush_rx) = bounded(1);
+        let mut channels = dispatcher_channels();
+        let file_rx = channels.add();
+        let remote_rx = channels.add();
+
+        for i in 0..5 {
+            main_tx
+                .send(dispatcher_record(&format!("msg-{i}")))
+                .unwrap();
+        }
+        let (handle, shutdown_tx) = LoggerManager::spawn_dispatcher(
+            main_rx,
+            crossbeam_channel::never(),
+            channels,
+            flush_rx,
+            FlushRoutes::default(),
+            None,
+        );
+        shutdown_tx.send(()).unwrap();
+        handle.await.unwrap();
+
+        // 两个 sink 各自收到全部记录，dispatcher 退出后 channel 断开
+        for rx in [file_rx, remote_rx] {
+            let messages: Vec<String> = rx.iter().map(|r| r.message.clone()).collect();
+            assert_eq!(
+                messages,
+                (0..5).map(|i| format!("msg-{i}")).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_byte_budget_covers_blocked_sink_queue() {
+        let size = dispatcher_record("msg-0").estimated_size();
+        let budget = Arc::new(ByteBudget::new(size * 3));
+        let (main_tx, main_rx) = bounded(100);
+        let (_flush_tx, flush_rx) = bounded(1);
+        let mut channels = dispatcher_channels();
+        let fast_rx = channels.add();
+        // 阻塞的 sink：worker 不读取其 channel
+        let blocked_rx = channels.add();
+
+        let (handle, shutdown_tx) = LoggerManager::spawn_dispatcher(
+            main_rx,
+            crossbeam_channel::never(),
+            channels,
+            flush_rx,
+            FlushRoutes::default(),
+            Some(budget.clone()),
+        );
+        let fast_worker = thread::spawn(move || fast_rx.iter().count());
+
+        let mut accepted = 0;
+        for i in 0..10 {
+            let record = dispatcher_record(&format!("msg-{i}"));
+            if budget.try_acquire(record.estimated_size()) {
+                main_tx.send(record).unwrap();
+                accepted += 1;
+            }
+            thread::sleep(Duration::from_millis(10));
+            assert!(budget.used() <= budget.limit());
+        }
+
+        // 快速 sink 已处理完，记录仍在阻塞 sink 的 channel 中，预算不归还
+        assert_eq!(accepted, 3);
+        assert_eq!(blocked_rx.len(), 3);
+        assert_eq!(budget.used(), size * 3);
+
+        shutdown_tx.send(()).unwrap();
+        handle.await.unwrap();
+        assert_eq!(fast_worker.join().unwrap(), 3);
+        assert_eq!(blocked_rx.iter().count(), 3);
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_stalled_sink_does_not_block_other_sinks() {
+        let cases = [
+            (OverflowPolicy::Block, ["msg-0", "msg-1"]),
+            (OverflowPolicy::DropNewest, ["msg-0", "msg-1"]),
+            (OverflowPolicy::DropOldest, ["msg-8", "msg-9"]),
+        ];
+        for (policy, kept) in cases {
+            let metrics = Arc::new(Metrics::new());
+            let (main_tx, main_rx) = bounded(100);
+            let (_flush_tx, flush_rx) = bounded(1);
+            let mut channels = SinkChannels::new(2, policy, metrics.clone());
+            let fast_rx = channels.add();
+            // 停滞的 sink：worker 不读取其 channel
+            let stalled_rx = channels.add();
+
+            let (handle, shutdown_tx) = LoggerManager::spawn_dispatcher(
+                main_rx,
+                crossbeam_channel::never(),
+                channels,
+                flush_rx,
+                FlushRoutes::default(),
+                None,
+            );
+            let fast_worker = thread::spawn(move || {
+                fast_rx
+                    .iter()
+                    .map(|r| r.message.clone())
+                    .collect::<Vec<_>>()
+            });
+            for i in 0..10 {
+                main_tx
+                    .send(dispatcher_record(&format!("msg-{i}")))
+                    .unwrap();
+                thread::sleep(Duration::from_millis(10));
+            }
+            shutdown_tx.send(()).unwrap();
+            handle.await.unwrap();
+
+            assert_eq!(
+                fast_worker.join().unwrap(),
+                (0..10).map(|i| format!("msg-{i}")).collect::<Vec<_>>(),
+                "{policy:?}: fast sink should receive every record"
+            );
+            let stalled: Vec<String> = stalled_rx.iter().map(|r| r.message.clone()).collect();
+            assert_eq!(stalled, kept, "{policy:?}");
+            assert_eq!(metrics.logs_dropped(), 8, "{policy:?}");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_dispatcher_forwards_flush_after_queued_records() {
+        let (main_tx, main_rx) = bounded(100);
+        let (flush_tx, flush_rx) = bounded(1);
+        let mut channels = dispatcher_channels();
+        let sink_rx = channels.add();
+        // 已退出的 worker 不应阻塞其他 sink
+        drop(channels.add());
+        let mut routes = FlushRoutes::default();
+        let file_flush_rx = routes.register(["file"]);
+
+        let (handle, shutdown_tx) = LoggerManager::spawn_dispatcher(
+            main_rx,
+            crossbeam_channel::never(),
+            channels,
+            flush_rx,
+            routes,
+            None,
+        );
+        for i in 0..3 {
+            main_tx
+                .send(dispatcher_record(&format!("msg-{i}")))
+                .unwrap();
+        }
+        let (ack, _ack_rx) = bounded(1);
+        flush_tx
+            .send(SinkControlMessage::FlushSink {
+                sink: "file".to_string(),
+                ack,
+                deadline: Instant::now() + Duration::from_secs(5),
+            })
+            .unwrap();
+
+        let (sink, _) = file_flush_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(sink, "file");
+        // flush 请求到达 worker 时，之前入队的记录已全部在其 channel 中
+        assert_eq!(sink_rx.len(), 3);
+
+        shutdown_tx.send(()).unwrap();
+        handle.await.unwrap();
+    }
+
+    #[test]
+    fn test_flush_routes_deliver_only_to_owning_worker() {
+        let mut routes = FlushRoutes::default();
+        let console_rx = routes.register(["console"]);
+        let file_rx = routes.register(["file", "audit"]);
+        let flush = |sink: &str| {
+            let (ack, ack_rx) = bounded(1);
+            routes.forward(SinkControlMessage::FlushSink {
+                sink: sink.to_string(),
+                ack,
+                deadline: Instant::now() + Duration::from_secs(5),
+            });
+            ack_rx
+        };
+
+        let _audit_ack = flush("audit");
+        let _console_ack = flush("console");
+        assert_eq!(file_rx.try_recv().unwrap().0, "audit");
+        assert_eq!(console_rx.try_recv().unwrap().0, "console");
+        assert!(file_rx.is_empty() && console_rx.is_empty());
+
+        let unknown = flush("nats").try_recv().unwrap();
+        assert!(unknown.unwrap_err().contains("no worker"));
+    }
+
+    #[test]
+    fn test_flush_to_stopped_worker_reports_unavailable() {
+        let mut routes = FlushRoutes::default();
+        // 例如数据库 sink 创建失败，worker 已退出
+        drop(routes.register(["database"]));
+
+        let (ack, ack_rx) = bounded(1);
+        routes.forward(SinkControlMessage::FlushSink {
+            sink: "database".to_string(),
+            ack,
+            deadline: Instant::now() + Duration::from_secs(5),
+        });
+        let error = ack_rx.try_recv().unwrap().unwrap_err();
+        assert!(error.contains("unavailable"), "{error}");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_dispatcher_forwards_thread_batches() {
+        let (_main_tx, main_rx) = bounded(100);
+        let (batch_tx, batch_rx) = bounded(10);
+        let (_flush_tx, flush_rx) = bounded(1);
+        let mut channels = dispatcher_channels();
+        let sink_rx = channels.add();
+
+        batch_tx
+            .send(vec![
+                dispatcher_record("first"),
+                dispatcher_record("second"),
+            ])
+            .unwrap();
+        let (handle, shutdown_tx) = LoggerManager::spawn_dispatcher(
+            main_rx,
+            batch_rx,
+            channels,
+            flush_rx,
+            FlushRoutes::default(),
+            None,
+        );
+        shutdown_tx.send(()).unwrap();
+        handle.await.unwrap();
+
+        let messages: Vec<String> = sink_rx.iter().map(|r| r.message.clone()).collect();
+        assert_eq!(messages, vec!["first", "second"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_flush_sends_pending_thread_batches() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("thread_batch.log");
+        let mut builder = LoggerManager::builder().worker_threads(1).file(&log_path);
+        builder.config.console_sink = None;
+        builder.config.performance.thread_batch_size = 10;
+        builder.config.performance.thread_batch_max_delay_ms = 60_000;
+        let (manager, subscriber, filter) = LoggerManager::build_detached(
+            builder.config,
+            #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+            None,
+        )
+        .await
+        .expect("build_detached should succeed");
+
+        let dispatch =
+            tracing::Dispatch::new(tracing_subscriber::registry().with(subscriber).with(filter));
+        let (logged_tx, logged_rx) = bounded::<()>(1);
+        let (done_tx, done_rx) = bounded::<()>(1);
+        let producer = thread::spawn(move || {
+            tracing::dispatcher::with_default(&dispatch, || {
+                tracing::info!("batched_record_a");
+                tracing::info!("batched_record_b");
+            });
+            logged_tx.send(()).unwrap();
+            // 生产线程保持存活，batch 既未满也不会因线程退出而发送
+            done_rx.recv().unwrap();
+        });
+        logged_rx.recv().unwrap();
+
+        manager
+            .flush(Duration::from_secs(5))
+            .expect("flush should succeed");
+        let content = std::fs::read_to_string(&log_path).expect("Log file should exist");
+        assert!(content.contains("batched_record_a"), "got: {}", content);
+        assert!(content.contains("batched_record_b"), "got: {}", content);
+
+        done_tx.send(()).unwrap();
+        producer.join().unwrap();
+        manager.shutdown().expect("shutdown should succeed");
+    }
+
+    // ============================================================================
+    // 文件 sink 与远程 sink 同时启用时，两者都应收到全部记录
+    // ============================================================================
+
+    /// 启动只记录请求体的 HTTP mock，返回基础 URL 和累计收到的请求体
+    #[cfg(any(feature = "elasticsearch", feature = "otlp", feature = "loki"))]
+    async fn mock_http_sink(
+        path: &'static str,
+        response: &'static str,
+    ) -> (String, Arc<Mutex<Vec<u8>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        let app = axum::Router::new().route(
+            path,
+            axum::routing::post(move |body: bytes::Bytes| {
+                let received = received_clone.clone();
+                async move {
+                    received.lock().unwrap().extend_from_slice(&body);
+                    response
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock sink");
+        let addr = listener.local_addr().expect("local addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        (format!("http://{}", addr), received)
+    }
+
+    /// 向文件 sink + 指定远程 sink 的 manager 写入记录并关闭，返回文件内容
+    #[cfg(any(
+        feature = "nats",
+        feature = "elasticsearch",
+        feature = "otlp",
+        feature = "syslog",
+        feature = "loki"
+    ))]
+    async fn run_fanout_manager(
+        log_path: &Path,
+        configure: impl FnOnce(&mut InklogConfig),
+    ) -> String {
+        let mut builder = LoggerManager::builder()
+            .channel_capacity(500)
+            .worker_threads(1)
+            .file(log_path);
+        configure(&mut builder.config);
+        let manager = builder.build().await.expect("Failed to build manager");
+
+        for i in 0..10u32 {
+            manager
+                .sender
+                .send(dispatcher_record(&format!("fanout_record_{:02}", i)))
+                .expect("Failed to send record");
+        }
+        let _ = manager.shutdown();
+
+        std::fs::read_to_string(log_path).expect("Log file should exist")
+    }
+
+    #[cfg(any(
+        feature = "nats",
+        feature = "elasticsearch",
+        feature = "otlp",
+        feature = "syslog",
+        feature = "loki"
+    ))]
+    fn assert_has_all_fanout_records(sink: &str, content: &str) {
+        for i in 0..10u32 {
+            let marker = format!("fanout_record_{:02}", i);
+            assert!(
+                content.contains(&marker),
+                "{} sink should contain '{}'",
+                sink,
+                marker
+            );
+        }
+    }
+
+    #[cfg(feature = "nats")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_unreachable_nats_server_does_not_block_startup() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("fanout_nats.log");
+
+        let content = run_fanout_manager(&log_path, |config| {
+            config.nats_sink = Some(crate::NatsSinkConfig {
+                enabled: true,
+                servers: vec!["nats://127.0.0.1:1".to_string()],
+                batch_size: 5,
+                ..Default::default()
+            });
+        })
+        .await;
+
+        assert_has_all_fanout_records("file", &content);
+    }
+
+    #[cfg(feature = "elasticsearch")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_file_and_elasticsearch_sinks_both_receive_every_record() {
+        let (url, received) = mock_http_sink("/_bulk", r#"{"errors":false,"items":[]}"#).await;
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("fanout_es.log");
+
+        let content = run_fanout_manager(&log_path, |config| {
+            config.elasticsearch_sink = Some(crate::ElasticsearchSinkConfig {
+                enabled: true,
+                url,
+                batch_size: 5,
+                ..Default::default()
+            });
+        })
+        .await;
+
+        assert_has_all_fanout_records("file", &content);
+        let body = String::from_utf8_lossy(&received.lock().unwrap()).into_owned();
+        assert_has_all_fanout_records("elasticsearch", &body);
+    }
+
+    #[cfg(feature = "otlp")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_file_and_otlp_sinks_both_receive_every_record() {
+        let (endpoint, received) = mock_http_sink("/v1/logs", "").await;
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("fanout_otlp.log");
+
+        let content = run_fanout_manager(&log_path, |config| {
+            config.otlp_sink = Some(crate::OtlpSinkConfig {
+                enabled: true,
+                endpoint,
+                batch_size: 5,
+                ..Default::default()
+            });
+        })
+        .await;
+
+        assert_has_all_fanout_records("file", &content);
+        // protobuf 中字符串字段按原样编码，可直接在请求体中查找
+        let body = String::from_utf8_lossy(&received.lock().unwrap()).into_owned();
+        assert_has_all_fanout_records("otlp", &body);
+    }
+
+    #[cfg(feature = "syslog")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_file_and_syslog_sinks_both_receive_every_record() {
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").expect("bind syslog receiver");
+        socket
+            .set_read_timeout(Some(Duration::from_millis(500)))
+            .expect("set read timeout");
+        let port = socket.local_addr().expect("local addr").port();
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("fanout_syslog.log");
+
+        let content = run_fanout_manager(&log_path, |config| {
+            config.syslog_sink = Some(crate::SyslogSinkConfig {
+                enabled: true,
+                host: "127.0.0.1".to_string(),
+                port,
+                transport: crate::SyslogTransport::Udp,
+                ..Default::default()
+            });
+        })
+        .await;
+
+        assert_has_all_fanout_records("file", &content);
+        let mut datagrams = String::new();
+        let mut buf = [0u8; 2048];
+        while let Ok(len) = socket.recv(&mut buf) {
+            datagrams.push_str(&String::from_utf8_lossy(&buf[..len]));
+            datagrams.push('\n');
+        }
+        assert_has_all_fanout_records("syslog", &datagrams);
+    }
+
+    #[cfg(feature = "loki")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_file_and_loki_sinks_both_receive_every_record() {
+        let (url, received) = mock_http_sink("/loki/api/v1/push", "").await;
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let log_path = dir.path().join("fanout_loki.log");
+
+        let content = run_fanout_manager(&log_path, |config| {
+            config.loki_sink = Some(crate::LokiSinkConfig {
+                enabled: true,
+                url,
+                batch_size: 5,
+                ..Default::default()
+            });
+        })
+        .await;
+
+        assert_has_all_fanout_records("file", &content);
+        let body = String::from_utf8_lossy(&received.lock().unwrap()).into_owned();
+        assert_has_all_fanout_records("loki", &body);
+    }
+
+    #[cfg(feature = "loki")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_flush_pushes_buffered_remote_records() {
+        let (url, received) = mock_http_sink("/loki/api/v1/push", "").await;
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let mut builder = LoggerManager::builder()
+            .channel_capacity(500)
+            .worker_threads(1)
+            .file(dir.path().join("flush_loki.log"));
+        builder.config.loki_sink = Some(crate::LokiSinkConfig {
+            enabled: true,
+            url,
+            batch_size: 1000,
+            ..Default::default()
+        });
+        let manager = builder.build().await.expect("Failed to build manager");
+
+        for i in 0..10u32 {
+            manager
+                .sender
+                .send(dispatcher_record(&format!("fanout_record_{:02}", i)))
+                .expect("Failed to send record");
+        }
+        // 批次未满，flush 返回时记录已推送到 Loki
+        manager
+            .flush(Duration::from_secs(10))
+            .expect("flush should be confirmed by every sink");
+        let body = String::from_utf8_lossy(&received.lock().unwrap()).into_owned();
+        assert_has_all_fanout_records("loki", &body);
+
+        let _ = manager.shutdown();
+    }
 }
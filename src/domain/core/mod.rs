@@ -7,5 +7,7 @@ pub mod manager;
 pub mod subscriber;
 
 pub use container::{InklogContainer, InklogContainerBuilder};
-pub use manager::{LoggerBuilder, LoggerDependencies, LoggerManager};
-pub use subscriber::LoggerSubscriber;
+pub use manager::{
+    InklogLayer, LevelFilterLayer, LoggerBuilder, LoggerDependencies, LoggerManager,
+};
+pub use subscriber::{ByteBudget, LoggerSubscriber, ThreadBatchConfig, ThreadBatchRegistry};
@@ -1,12 +1,21 @@
 // Copyright (c) 2026 Kirky.X
 // SPDX-License-Identifier: MIT
+use crate::ControlCharMode;
 use crate::LogRecord;
 use crate::Metrics;
-use crossbeam_channel::Sender;
-use std::collections::VecDeque;
-use std::sync::Arc;
+use crate::OverflowPolicy;
+use crate::support::io::ConsoleSink;
+#[cfg(feature = "memory-ring")]
+use crate::support::io::sink::MemoryRingSink;
+use crate::support::observability::AlertEvaluator;
+use crate::support::processing::{LogDeduplicator, LogSampler};
+use crossbeam_channel::{Receiver, Sender, TrySendError};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Mutex;
-use std::time::Duration;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 use tracing::{Event, Subscriber};
 use tracing_subscriber::Layer;
 use tracing_subscriber::layer::Context;
@@ -14,6 +23,361 @@ use tracing_subscriber::layer::Context;
 const DEFAULT_SEND_TIMEOUT_MS: u64 = 100;
 const FALLBACK_BUFFER_SIZE: usize = 100;
 
+/// Approximate byte budget for records queued on the async channel.
+///
+/// `channel_capacity` bounds the number of queued records; this bounds their
+/// estimated size (see [`LogRecord::estimated_size`]). Producers acquire bytes
+/// before sending; they are released once every sink worker has processed the
+/// record, so records waiting in per-sink queues stay charged.
+#[derive(Debug)]
+pub struct ByteBudget {
+    limit: usize,
+    used: AtomicUsize,
+}
+
+impl ByteBudget {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    /// Reserves `bytes` if the budget allows it; returns `false` when exhausted.
+    pub fn try_acquire(&self, bytes: usize) -> bool {
+        self.used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                let next = used.saturating_add(bytes);
+                (next <= self.limit).then_some(next)
+            })
+            .is_ok()
+    }
+
+    /// Returns `bytes` previously reserved with [`try_acquire`](Self::try_acquire).
+    pub fn release(&self, bytes: usize) {
+        let _ = self
+            .used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                Some(used.saturating_sub(bytes))
+            });
+    }
+
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Acquire)
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+/// Per-thread batching of async-channel sends.
+///
+/// Each producing thread accumulates up to `max_records` records and sends them
+/// to the batch channel as one `Vec`, so concurrent producers touch a shared
+/// channel once per batch instead of once per record. A batch is flushed when it
+/// is full, when it is older than `max_delay` on the thread's next event, when an
+/// ERROR/FATAL record arrives, on [`LoggerSubscriber::flush_thread_batch`], when
+/// the thread exits, and for every thread at once on
+/// [`ThreadBatchRegistry::flush_all`]. Records from one thread keep their order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThreadBatchConfig {
+    pub max_records: usize,
+    pub max_delay: Duration,
+}
+
+/// Resolved [`OverflowPolicy`] of the async channel
+#[derive(Clone)]
+enum Overflow {
+    Block,
+    DropNewest,
+    /// Receiver clone used to evict the oldest queued record
+    DropOldest(Receiver<Arc<LogRecord>>),
+}
+
+static NEXT_SUBSCRIBER_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// Pending batches of the current thread, keyed by subscriber id
+    static THREAD_BATCHES: RefCell<HashMap<u64, Arc<Mutex<ThreadBatch>>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Batch channel of [`ThreadBatchConfig`]: the sender and a receiver clone used
+/// to evict the oldest queued batch under [`OverflowPolicy::DropOldest`]
+type BatchChannel = (Sender<Vec<Arc<LogRecord>>>, Receiver<Vec<Arc<LogRecord>>>);
+
+/// Records buffered by one thread for one subscriber; flushed on drop (thread exit).
+struct ThreadBatch {
+    records: Vec<Arc<LogRecord>>,
+    started: Instant,
+    channel: BatchChannel,
+    metrics: Arc<Metrics>,
+    send_timeout_ms: u64,
+    overflow: Overflow,
+    fallback_buffer: Arc<Mutex<VecDeque<Arc<LogRecord>>>>,
+    byte_budget: Option<Arc<ByteBudget>>,
+}
+
+impl ThreadBatch {
+    /// Sends the pending records as one `Vec`; the byte budget applies per
+    /// record and the overflow policy to the batch as a whole.
+    fn flush(&mut self) {
+        let budget = self.byte_budget.as_deref();
+        let batch: Vec<Arc<LogRecord>> = self
+            .records
+            .drain(..)
+            .filter_map(|record| {
+                acquire_budget(&self.metrics, &self.fallback_buffer, budget, record)
+            })
+            .collect();
+        if batch.is_empty() {
+            return;
+        }
+
+        let (sender, receiver) = &self.channel;
+        let result = match self.overflow {
+            Overflow::Block => {
+                let timeout = Duration::from_millis(self.send_timeout_ms);
+                sender.send_timeout(batch, timeout).map_err(|e| match e {
+                    crossbeam_channel::SendTimeoutError::Timeout(b) => TrySendError::Full(b),
+                    crossbeam_channel::SendTimeoutError::Disconnected(b) => {
+                        TrySendError::Disconnected(b)
+                    }
+                })
+            }
+            Overflow::DropNewest => sender.try_send(batch),
+            Overflow::DropOldest(_) => match sender.try_send(batch) {
+                Err(TrySendError::Full(batch)) => {
+                    // Swap: evict the oldest queued batch, then retry once
+                    if let Ok(oldest) = receiver.try_recv() {
+                        release_batch(budget, &oldest);
+                        for record in oldest {
+                            shed(&self.metrics, &self.fallback_buffer, record);
+                        }
+                    }
+                    sender.try_send(batch)
+                }
+                other => other,
+            },
+        };
+        match result {
+            Ok(_) => {}
+            Err(TrySendError::Full(batch)) => {
+                release_batch(budget, &batch);
+                for record in batch {
+                    shed(&self.metrics, &self.fallback_buffer, record);
+                }
+            }
+            Err(TrySendError::Disconnected(batch)) => {
+                release_batch(budget, &batch);
+                for _ in batch {
+                    self.metrics.inc_logs_dropped();
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ThreadBatch {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Live thread batches of one subscriber, so their pending records can be sent
+/// from outside the producing threads, e.g. by the manager on flush and shutdown.
+#[derive(Default)]
+pub struct ThreadBatchRegistry {
+    batches: Mutex<Vec<Weak<Mutex<ThreadBatch>>>>,
+}
+
+impl ThreadBatchRegistry {
+    fn register(&self, batch: &Arc<Mutex<ThreadBatch>>) {
+        let mut batches = match self.batches.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        // 顺带清理已退出线程留下的条目
+        batches.retain(|batch| batch.strong_count() > 0);
+        batches.push(Arc::downgrade(batch));
+    }
+
+    /// Sends the pending batch of every live thread.
+    pub fn flush_all(&self) {
+        let batches: Vec<_> = match self.batches.lock() {
+            Ok(guard) => guard.iter().filter_map(Weak::upgrade).collect(),
+            Err(poisoned) => poisoned
+                .into_inner()
+                .iter()
+                .filter_map(Weak::upgrade)
+                .collect(),
+        };
+        for batch in batches {
+            lock_batch(&batch).flush();
+        }
+    }
+}
+
+fn lock_batch(batch: &Mutex<ThreadBatch>) -> std::sync::MutexGuard<'_, ThreadBatch> {
+    match batch.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Glob match of `target` against `pattern`, where `*` matches any run of characters.
+pub(crate) fn target_matches(pattern: &str, target: &str) -> bool {
+    let pattern = pattern.as_bytes();
+    let target = target.as_bytes();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the target index it is currently matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < target.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p + 1, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == target[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+fn is_critical_level(level: &str) -> bool {
+    level == "ERROR" || level == "FATAL"
+}
+
+fn push_fallback(fallback_buffer: &Mutex<VecDeque<Arc<LogRecord>>>, record: Arc<LogRecord>) {
+    let mut buffer = match fallback_buffer.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            // Mutex poison 只在持有锁的线程 panic 时发生
+            // 这时我们恢复互斥锁并继续使用（因为数据可能仍然有效）
+            tracing::warn!("Fallback buffer mutex poisoned, recovering");
+            poisoned.into_inner()
+        }
+    };
+    if buffer.len() >= FALLBACK_BUFFER_SIZE {
+        buffer.pop_front();
+    }
+    buffer.push_back(record);
+}
+
+/// Drops `record` because the async channel is full; ERROR/FATAL records are
+/// moved to the fallback buffer instead.
+fn shed(
+    metrics: &Metrics,
+    fallback_buffer: &Mutex<VecDeque<Arc<LogRecord>>>,
+    record: Arc<LogRecord>,
+) {
+    if is_critical_level(&record.level) {
+        push_fallback(fallback_buffer, record);
+    } else {
+        metrics.inc_channel_blocked();
+        metrics.inc_logs_dropped();
+    }
+}
+
+/// Reserves the byte budget for `record`; once the estimated queued bytes are
+/// exhausted the record is shed (ERROR/FATAL go to the fallback buffer),
+/// independent of the record-count capacity.
+fn acquire_budget(
+    metrics: &Metrics,
+    fallback_buffer: &Mutex<VecDeque<Arc<LogRecord>>>,
+    byte_budget: Option<&ByteBudget>,
+    record: Arc<LogRecord>,
+) -> Option<Arc<LogRecord>> {
+    if let Some(budget) = byte_budget
+        && !budget.try_acquire(record.estimated_size())
+    {
+        metrics.inc_channel_blocked();
+        if is_critical_level(&record.level) {
+            push_fallback(fallback_buffer, record);
+        } else {
+            metrics.inc_logs_dropped();
+        }
+        return None;
+    }
+    Some(record)
+}
+
+/// Returns the byte budget reserved for records that never reached the channel.
+fn release_batch(byte_budget: Option<&ByteBudget>, records: &[Arc<LogRecord>]) {
+    if let Some(budget) = byte_budget {
+        budget.release(records.iter().map(|r| r.estimated_size()).sum());
+    }
+}
+
+/// Sends one record to the async channel, applying the byte budget, the
+/// overflow policy and the critical-level fallback buffer.
+fn send_async(
+    async_sender: &Sender<Arc<LogRecord>>,
+    metrics: &Metrics,
+    send_timeout_ms: u64,
+    overflow: &Overflow,
+    fallback_buffer: &Mutex<VecDeque<Arc<LogRecord>>>,
+    byte_budget: Option<&ByteBudget>,
+    record: Arc<LogRecord>,
+) {
+    let Some(record) = acquire_budget(metrics, fallback_buffer, byte_budget, record) else {
+        return;
+    };
+    let record_bytes = byte_budget.map_or(0, |_| record.estimated_size());
+
+    // Slow path: Async sinks - the overflow policy decides what happens when full
+    let result = match overflow {
+        Overflow::Block => {
+            let timeout = Duration::from_millis(send_timeout_ms);
+            async_sender
+                .send_timeout(record, timeout)
+                .map_err(|e| match e {
+                    crossbeam_channel::SendTimeoutError::Timeout(r) => TrySendError::Full(r),
+                    crossbeam_channel::SendTimeoutError::Disconnected(r) => {
+                        TrySendError::Disconnected(r)
+                    }
+                })
+        }
+        Overflow::DropNewest => async_sender.try_send(record),
+        Overflow::DropOldest(receiver) => match async_sender.try_send(record) {
+            Err(TrySendError::Full(record)) => {
+                // Swap: evict the oldest queued record, then retry once
+                if let Ok(oldest) = receiver.try_recv() {
+                    if let Some(budget) = byte_budget {
+                        budget.release(oldest.estimated_size());
+                    }
+                    shed(metrics, fallback_buffer, oldest);
+                }
+                async_sender.try_send(record)
+            }
+            other => other,
+        },
+    };
+    match result {
+        Ok(_) => {}
+        Err(TrySendError::Full(record)) => {
+            if let Some(budget) = byte_budget {
+                budget.release(record_bytes);
+            }
+            shed(metrics, fallback_buffer, record);
+        }
+        Err(TrySendError::Disconnected(_)) => {
+            if let Some(budget) = byte_budget {
+                budget.release(record_bytes);
+            }
+            metrics.inc_logs_dropped();
+        }
+    }
+}
+
 /// High-performance logging subscriber with lock-free hot path.
 ///
 /// Uses crossbeam channels for both console and async sinks to eliminate
@@ -29,8 +393,35 @@ pub struct LoggerSubscriber {
     metrics: Arc<Metrics>,
     /// Timeout for async channel send (milliseconds)
     send_timeout_ms: u64,
+    /// Handling of a full async channel
+    overflow: Overflow,
     /// Fallback buffer for critical logs
     fallback_buffer: Arc<Mutex<VecDeque<Arc<LogRecord>>>>,
+    /// Optional byte budget for the async channel
+    byte_budget: Option<Arc<ByteBudget>>,
+    /// Optional per-thread batching of async sends, with its batch channel
+    thread_batch: Option<(ThreadBatchConfig, BatchChannel)>,
+    /// Live thread batches of this subscriber
+    thread_batches: Arc<ThreadBatchRegistry>,
+    /// Key of this subscriber's entry in each thread's batch map
+    id: u64,
+    /// Optional per-field value size limit (bytes)
+    max_field_value_bytes: Option<usize>,
+    /// Target globs whose fields are cleared before sending
+    strip_fields_targets: Vec<String>,
+    /// Optional control character sanitization of message and string fields
+    control_char_mode: Option<ControlCharMode>,
+    /// Console sink written on the calling thread instead of via the console channel
+    sync_console: Option<Arc<Mutex<ConsoleSink>>>,
+    /// Alert rules evaluated against every record
+    alerts: Option<Arc<AlertEvaluator>>,
+    /// In-memory ring receiving every record on the calling thread
+    #[cfg(feature = "memory-ring")]
+    memory_ring: Option<Arc<MemoryRingSink>>,
+    /// Sampling rules dropping records before they reach the channels
+    sampler: Option<Arc<LogSampler>>,
+    /// Collapsing of consecutive identical records
+    dedup: Option<Arc<LogDeduplicator>>,
 }
 
 impl LoggerSubscriber {
@@ -44,7 +435,21 @@ impl LoggerSubscriber {
             async_sender,
             metrics,
             send_timeout_ms: DEFAULT_SEND_TIMEOUT_MS,
+            overflow: Overflow::Block,
             fallback_buffer: Arc::new(Mutex::new(VecDeque::with_capacity(FALLBACK_BUFFER_SIZE))),
+            byte_budget: None,
+            thread_batch: None,
+            thread_batches: Arc::new(ThreadBatchRegistry::default()),
+            id: NEXT_SUBSCRIBER_ID.fetch_add(1, Ordering::Relaxed),
+            max_field_value_bytes: None,
+            strip_fields_targets: Vec::new(),
+            control_char_mode: None,
+            sync_console: None,
+            alerts: None,
+            #[cfg(feature = "memory-ring")]
+            memory_ring: None,
+            sampler: None,
+            dedup: None,
         }
     }
 
@@ -53,8 +458,230 @@ impl LoggerSubscriber {
         self
     }
 
-    fn is_critical_level(level: &str) -> bool {
-        level == "ERROR" || level == "FATAL"
+    /// Sets how a full async channel is handled. `receiver` must be a clone of
+    /// the async channel's receiver; only [`OverflowPolicy::DropOldest`] keeps it,
+    /// to evict the oldest queued record.
+    pub fn with_overflow_policy(
+        mut self,
+        policy: OverflowPolicy,
+        receiver: Receiver<Arc<LogRecord>>,
+    ) -> Self {
+        self.overflow = match policy {
+            OverflowPolicy::Block => Overflow::Block,
+            OverflowPolicy::DropNewest => Overflow::DropNewest,
+            OverflowPolicy::DropOldest => Overflow::DropOldest(receiver),
+        };
+        self
+    }
+
+    pub fn with_byte_budget(mut self, budget: Arc<ByteBudget>) -> Self {
+        self.byte_budget = Some(budget);
+        self
+    }
+
+    /// Truncates field values larger than `max_bytes` before records are sent.
+    pub fn with_field_value_limit(mut self, max_bytes: usize) -> Self {
+        self.max_field_value_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Clears the fields of records whose target matches one of `patterns`
+    /// (`*` matches any run of characters); the message is kept.
+    pub fn with_stripped_field_targets(mut self, patterns: Vec<String>) -> Self {
+        self.strip_fields_targets = patterns;
+        self
+    }
+
+    /// Sanitizes control characters in the message and string fields before records are sent.
+    pub fn with_control_char_sanitization(mut self, mode: ControlCharMode) -> Self {
+        self.control_char_mode = Some(mode);
+        self
+    }
+
+    /// Evaluates alert rules against every record before it is sent.
+    pub fn with_alerts(mut self, evaluator: Arc<AlertEvaluator>) -> Self {
+        self.alerts = Some(evaluator);
+        self
+    }
+
+    /// Writes console output synchronously through `sink`, bypassing the console
+    /// channel and worker. Other sinks are unaffected.
+    pub fn with_sync_console(mut self, sink: Arc<Mutex<ConsoleSink>>) -> Self {
+        self.sync_console = Some(sink);
+        self
+    }
+
+    /// Also push every record into an in-memory ring, before any channel send
+    #[cfg(feature = "memory-ring")]
+    pub fn with_memory_ring(mut self, ring: Arc<MemoryRingSink>) -> Self {
+        self.memory_ring = Some(ring);
+        self
+    }
+
+    /// Drop records rejected by `sampler`; its suppression summaries are sent
+    /// like ordinary records
+    pub fn with_sampler(mut self, sampler: Arc<LogSampler>) -> Self {
+        self.sampler = Some(sampler);
+        self
+    }
+
+    /// Drop consecutive duplicates; a "last message repeated N times" record is
+    /// sent when a streak ends
+    pub fn with_dedup(mut self, dedup: Arc<LogDeduplicator>) -> Self {
+        self.dedup = Some(dedup);
+        self
+    }
+
+    /// Writes and flushes `record` on the calling thread.
+    fn write_console_sync(&self, sink: &Mutex<ConsoleSink>, record: &LogRecord) {
+        let sink = match sink.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        match sink.write_sync(record).and_then(|_| sink.flush_sync()) {
+            Ok(_) => self.metrics.inc_logs_written(),
+            Err(_) => self.metrics.inc_sink_error(),
+        }
+    }
+
+    /// Enables per-thread batching; a `max_records` of 0 or 1 leaves it disabled.
+    ///
+    /// Batches go to their own channel: `sender` carries one `Vec` per batch and
+    /// `receiver` must be a clone of its receiver, used by
+    /// [`OverflowPolicy::DropOldest`] to evict the oldest queued batch.
+    pub fn with_thread_batching(
+        mut self,
+        config: ThreadBatchConfig,
+        sender: Sender<Vec<Arc<LogRecord>>>,
+        receiver: Receiver<Vec<Arc<LogRecord>>>,
+    ) -> Self {
+        self.thread_batch = (config.max_records > 1).then_some((config, (sender, receiver)));
+        self
+    }
+
+    /// Registry of this subscriber's thread batches, for flushing them from
+    /// another thread.
+    pub fn thread_batches(&self) -> Arc<ThreadBatchRegistry> {
+        Arc::clone(&self.thread_batches)
+    }
+
+    /// Sends the current thread's pending batch for this subscriber.
+    pub fn flush_thread_batch(&self) {
+        let batch = THREAD_BATCHES
+            .try_with(|batches| {
+                batches
+                    .try_borrow_mut()
+                    .ok()
+                    .and_then(|mut batches| batches.remove(&self.id))
+            })
+            .ok()
+            .flatten();
+        if let Some(batch) = batch {
+            lock_batch(&batch).flush();
+        }
+    }
+
+    fn send_async(&self, record: Arc<LogRecord>) {
+        send_async(
+            &self.async_sender,
+            &self.metrics,
+            self.send_timeout_ms,
+            &self.overflow,
+            &self.fallback_buffer,
+            self.byte_budget.as_deref(),
+            record,
+        );
+    }
+
+    /// Sends a finished record to the memory ring, the console and the async channel
+    fn dispatch(&self, record: Arc<LogRecord>) {
+        #[cfg(feature = "memory-ring")]
+        if let Some(ref ring) = self.memory_ring {
+            ring.push(Arc::clone(&record));
+        }
+
+        // Synchronous console mode: write on the calling thread for deterministic output
+        if let Some(ref sink) = self.sync_console {
+            self.write_console_sync(sink, &record);
+        } else {
+            // Fast path: Console - lock-free try_send, never block
+            match self.console_sender.try_send(Arc::clone(&record)) {
+                Ok(_) => {}
+                Err(crossbeam_channel::TrySendError::Full(_)) => {
+                    // Channel full, drop the message and record metric
+                    // Hot path should never block
+                    self.metrics.inc_channel_blocked();
+                    self.metrics.inc_logs_dropped();
+                }
+                Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
+                    self.metrics.inc_logs_dropped();
+                }
+            }
+        }
+
+        // Optional per-thread batching; falls back to a direct send when the
+        // thread's batch is unavailable
+        let record = match self.thread_batch {
+            Some((config, ref channel)) => match self.push_thread_batch(config, channel, record) {
+                Some(record) => record,
+                None => return,
+            },
+            None => record,
+        };
+
+        self.send_async(record);
+    }
+
+    fn new_thread_batch(&self, capacity: usize, channel: &BatchChannel) -> ThreadBatch {
+        ThreadBatch {
+            records: Vec::with_capacity(capacity),
+            started: Instant::now(),
+            channel: channel.clone(),
+            metrics: Arc::clone(&self.metrics),
+            send_timeout_ms: self.send_timeout_ms,
+            overflow: self.overflow.clone(),
+            fallback_buffer: Arc::clone(&self.fallback_buffer),
+            byte_budget: self.byte_budget.clone(),
+        }
+    }
+
+    /// Buffers `record` in the current thread's batch; returns it back when the
+    /// batch is unavailable (re-entrant call or thread-local already destroyed).
+    fn push_thread_batch(
+        &self,
+        config: ThreadBatchConfig,
+        channel: &BatchChannel,
+        record: Arc<LogRecord>,
+    ) -> Option<Arc<LogRecord>> {
+        let mut pending = Some(record);
+        let _ = THREAD_BATCHES.try_with(|batches| {
+            let Ok(mut batches) = batches.try_borrow_mut() else {
+                return;
+            };
+            let Some(record) = pending.take() else {
+                return;
+            };
+            let critical = is_critical_level(&record.level);
+            let batch = batches.entry(self.id).or_insert_with(|| {
+                let batch = Arc::new(Mutex::new(
+                    self.new_thread_batch(config.max_records, channel),
+                ));
+                self.thread_batches.register(&batch);
+                batch
+            });
+            let mut batch = lock_batch(batch);
+            if batch.records.is_empty() {
+                batch.started = Instant::now();
+            }
+            batch.records.push(record);
+            if critical
+                || batch.records.len() >= config.max_records
+                || batch.started.elapsed() >= config.max_delay
+            {
+                batch.flush();
+            }
+        });
+        pending
     }
 
     pub fn try_flush_fallback(&self) {
@@ -68,12 +695,23 @@ impl LoggerSubscriber {
             }
         };
         while let Some(record) = buffer.front() {
+            let bytes = record.estimated_size();
+            if let Some(budget) = &self.byte_budget
+                && !budget.try_acquire(bytes)
+            {
+                break;
+            }
             let timeout = Duration::from_millis(self.send_timeout_ms);
             match self.async_sender.send_timeout(Arc::clone(record), timeout) {
                 Ok(_) => {
                     buffer.pop_front();
                 }
-                Err(_) => break,
+                Err(_) => {
+                    if let Some(budget) = &self.byte_budget {
+                        budget.release(bytes);
+                    }
+                    break;
+                }
             }
         }
     }
@@ -84,52 +722,45 @@ where
     S: Subscriber,
 {
     fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
-        let record = LogRecord::from_event(event);
-        let record = Arc::new(record);
+        let mut record = LogRecord::from_event(event);
+        if let Some(mode) = self.control_char_mode
+            && record.sanitize_control_chars(mode)
+        {
+            self.metrics.inc_records_sanitized();
+        }
+        if let Some(ref alerts) = self.alerts {
+            alerts.observe(&record);
+        }
+        if self
+            .strip_fields_targets
+            .iter()
+            .any(|pattern| target_matches(pattern, &record.target))
+        {
+            record.fields.clear();
+        } else if let Some(max_bytes) = self.max_field_value_bytes {
+            record.truncate_field_values(max_bytes);
+        }
 
-        // Fast path: Console - lock-free try_send, never block
-        match self.console_sender.try_send(Arc::clone(&record)) {
-            Ok(_) => {}
-            Err(crossbeam_channel::TrySendError::Full(_)) => {
-                // Channel full, drop the message and record metric
-                // Hot path should never block
-                self.metrics.inc_channel_blocked();
-                self.metrics.inc_logs_dropped();
+        // Dedup and sampling run after alerting so alert thresholds still see every record
+        if let Some(ref dedup) = self.dedup {
+            let outcome = dedup.observe(&record);
+            if let Some(summary) = outcome.summary {
+                self.dispatch(Arc::new(summary));
             }
-            Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
-                self.metrics.inc_logs_dropped();
+            if !outcome.keep {
+                return;
             }
         }
-
-        // Slow path: Async sinks - use timeout for backpressure handling
-        let timeout = Duration::from_millis(self.send_timeout_ms);
-        match self.async_sender.send_timeout(Arc::clone(&record), timeout) {
-            Ok(_) => {}
-            Err(crossbeam_channel::SendTimeoutError::Timeout(_)) => {
-                // For critical logs, add to fallback buffer
-                if Self::is_critical_level(&record.level) {
-                    let mut buffer = match self.fallback_buffer.lock() {
-                        Ok(guard) => guard,
-                        Err(poisoned) => {
-                            // Mutex poison 只在持有锁的线程 panic 时发生
-                            // 这时我们恢复互斥锁并继续使用（因为数据可能仍然有效）
-                            tracing::warn!("Fallback buffer mutex poisoned, recovering");
-                            poisoned.into_inner()
-                        }
-                    };
-                    if buffer.len() >= FALLBACK_BUFFER_SIZE {
-                        buffer.pop_front();
-                    }
-                    buffer.push_back(record);
-                } else {
-                    self.metrics.inc_channel_blocked();
-                    self.metrics.inc_logs_dropped();
-                }
+        if let Some(ref sampler) = self.sampler {
+            for summary in sampler.take_summaries() {
+                self.dispatch(Arc::new(summary));
             }
-            Err(crossbeam_channel::SendTimeoutError::Disconnected(_)) => {
-                self.metrics.inc_logs_dropped();
+            if !sampler.sample(&record) {
+                self.metrics.inc_records_sampled();
+                return;
             }
         }
+        self.dispatch(Arc::new(record));
     }
 }
 
@@ -167,6 +798,187 @@ mod tests {
         assert_eq!(async_received.message, "hello");
     }
 
+    #[test]
+    fn test_sampling_bounds_flood_and_emits_summary() {
+        let (console_tx, _console_rx) = bounded(2000);
+        let (async_tx, async_rx) = bounded(2000);
+        let metrics = Arc::new(Metrics::new());
+        let sampler = LogSampler::new(&crate::SamplingConfig {
+            rules: vec![crate::SamplingRuleConfig {
+                target: Some("test::flood".to_string()),
+                levels: vec!["warn".to_string()],
+                mode: crate::SamplingMode::Fixed,
+                rate: 100,
+            }],
+            summary_interval_ms: 20,
+        })
+        .unwrap();
+
+        let layer = LoggerSubscriber::new(console_tx, async_tx, metrics.clone())
+            .with_sampler(Arc::new(sampler));
+        let registry = tracing_subscriber::registry().with(layer);
+
+        with_default(registry, || {
+            for _ in 0..1000 {
+                tracing::warn!(target: "test::flood", "disk almost full");
+            }
+            std::thread::sleep(Duration::from_millis(30));
+            tracing::warn!(target: "test::flood", "disk almost full");
+        });
+
+        let received: Vec<_> = async_rx.try_iter().collect();
+        let summaries: Vec<_> = received
+            .iter()
+            .filter(|r| r.target == crate::SAMPLING_SUMMARY_TARGET)
+            .collect();
+        assert_eq!(received.len() - summaries.len(), 11);
+        assert_eq!(summaries.len(), 1);
+        assert!(
+            summaries[0].message.contains("suppressed 990 messages"),
+            "{}",
+            summaries[0].message
+        );
+        assert_eq!(metrics.records_sampled(), 990);
+    }
+
+    #[test]
+    fn test_dedup_collapses_identical_records() {
+        let (console_tx, _console_rx) = bounded(10);
+        let (async_tx, async_rx) = bounded(10);
+        let metrics = Arc::new(Metrics::new());
+        let dedup = LogDeduplicator::new(&crate::DedupConfig {
+            enabled: true,
+            ..Default::default()
+        });
+
+        let layer =
+            LoggerSubscriber::new(console_tx, async_tx, metrics).with_dedup(Arc::new(dedup));
+        let registry = tracing_subscriber::registry().with(layer);
+
+        with_default(registry, || {
+            for _ in 0..5 {
+                tracing::warn!(target: "test::dedup", "connection refused");
+            }
+            tracing::info!(target: "test::dedup", "connected");
+        });
+
+        let messages: Vec<String> = async_rx.try_iter().map(|r| r.message.clone()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "connection refused",
+                "last message repeated 4 times",
+                "connected"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_on_event_truncates_oversized_field_values() {
+        let (console_tx, console_rx) = bounded(10);
+        let (async_tx, async_rx) = bounded(10);
+        let metrics = Arc::new(Metrics::new());
+
+        let layer = LoggerSubscriber::new(console_tx, async_tx, metrics).with_field_value_limit(16);
+        let registry = tracing_subscriber::registry().with(layer);
+
+        let stack = "at frame ".repeat(20);
+        with_default(registry, || {
+            tracing::error!(target: "test::subscriber", stack = %stack, code = 42u64, "failed");
+        });
+
+        for received in [console_rx.recv().unwrap(), async_rx.recv().unwrap()] {
+            assert_eq!(received.message, "failed");
+            assert_eq!(received.fields["code"], serde_json::json!(42));
+            assert_eq!(
+                received.fields["stack"],
+                serde_json::json!(format!("at frame at fram{}", LogRecord::TRUNCATION_MARKER))
+            );
+            assert_eq!(
+                received.fields[LogRecord::TRUNCATED_FIELDS_KEY],
+                serde_json::json!(["stack"])
+            );
+        }
+    }
+
+    #[test]
+    fn test_target_matches_globs() {
+        assert!(target_matches("hyper::*", "hyper::proto::h1"));
+        assert!(target_matches("*::pool", "sqlx::pool"));
+        assert!(target_matches("h2", "h2"));
+        assert!(target_matches("*", "anything"));
+        assert!(target_matches("a*b*c", "a::x::b::y::c"));
+        assert!(!target_matches("hyper::*", "hyperlocal"));
+        assert!(!target_matches("h2", "h2::codec"));
+        assert!(!target_matches("*::pool", "sqlx::pool::inner"));
+    }
+
+    #[test]
+    fn test_on_event_strips_fields_for_matching_targets() {
+        let (console_tx, console_rx) = bounded(10);
+        let (async_tx, async_rx) = bounded(10);
+        let metrics = Arc::new(Metrics::new());
+
+        let layer = LoggerSubscriber::new(console_tx, async_tx, metrics)
+            .with_stripped_field_targets(vec!["noisy::*".to_string()]);
+        let registry = tracing_subscriber::registry().with(layer);
+
+        with_default(registry, || {
+            tracing::info!(target: "noisy::client", payload = "large blob", "request sent");
+            tracing::info!(target: "app::handler", user_id = 7u64, "request handled");
+        });
+
+        let stripped = async_rx.recv().unwrap();
+        assert_eq!(stripped.target, "noisy::client");
+        assert_eq!(stripped.message, "request sent");
+        assert!(stripped.fields.is_empty());
+
+        let kept = async_rx.recv().unwrap();
+        assert_eq!(kept.target, "app::handler");
+        assert_eq!(kept.fields["user_id"], serde_json::json!(7));
+
+        // 控制台收到的是同一条已处理记录
+        assert!(console_rx.recv().unwrap().fields.is_empty());
+    }
+
+    #[test]
+    fn test_on_event_sanitizes_control_characters() {
+        let (console_tx, _console_rx) = bounded(10);
+        let (async_tx, async_rx) = bounded(10);
+        let metrics = Arc::new(Metrics::new());
+
+        let layer = LoggerSubscriber::new(console_tx, async_tx, metrics.clone())
+            .with_control_char_sanitization(ControlCharMode::Escape);
+        let registry = tracing_subscriber::registry().with(layer);
+
+        // 非法 UTF-8 字节无法进入 &str；以 &[u8] 记录时 tracing 输出十六进制文本
+        let invalid_utf8: &[u8] = &[0x66, 0xff, 0xfe, 0x00];
+        with_default(registry, || {
+            tracing::info!(
+                payload = "bin\0ary\x1b[31m",
+                tab = "a\tb",
+                "nul\0 in message"
+            );
+            tracing::info!(raw = invalid_utf8, "bytes field");
+            tracing::info!("clean\nmultiline");
+        });
+
+        let record = async_rx.recv().unwrap();
+        assert_eq!(record.message, "nul\\x00 in message");
+        assert_eq!(record.fields["payload"], "bin\\x00ary\\x1b[31m");
+        assert_eq!(record.fields["tab"], "a\tb");
+
+        let bytes = async_rx.recv().unwrap();
+        let raw = bytes.fields["raw"].as_str().unwrap();
+        assert!(raw.chars().all(|c| !c.is_control()), "raw = {raw:?}");
+        assert!(serde_json::to_string(&*bytes).is_ok());
+
+        let clean = async_rx.recv().unwrap();
+        assert_eq!(clean.message, "clean\nmultiline");
+
+        assert_eq!(metrics.records_sanitized(), 1);
+    }
+
     #[test]
     fn test_on_event_handles_full_channel() {
         // Create a channel with capacity 1
@@ -282,6 +1094,76 @@ mod tests {
         assert_eq!(subscriber.send_timeout_ms, 500);
     }
 
+    fn queued_record(level: &str, message: &str) -> Arc<LogRecord> {
+        Arc::new(LogRecord {
+            level: level.to_string(),
+            message: message.to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_overflow_policy_on_full_channel() {
+        for (policy, kept) in [
+            (OverflowPolicy::Block, ["a", "b"]),
+            (OverflowPolicy::DropNewest, ["a", "b"]),
+            (OverflowPolicy::DropOldest, ["b", "c"]),
+        ] {
+            let (console_tx, _console_rx) = bounded(10);
+            let (async_tx, async_rx) = bounded(2);
+            let metrics = Arc::new(Metrics::new());
+            let subscriber = LoggerSubscriber::new(console_tx, async_tx, metrics.clone())
+                .with_timeout(10)
+                .with_overflow_policy(policy, async_rx.clone());
+
+            for message in ["a", "b", "c"] {
+                subscriber.send_async(queued_record("INFO", message));
+            }
+
+            assert_eq!(metrics.logs_dropped(), 1, "{:?}", policy);
+            let queued: Vec<String> = async_rx.try_iter().map(|r| r.message.clone()).collect();
+            assert_eq!(queued, kept, "{:?}", policy);
+        }
+    }
+
+    #[test]
+    fn test_full_channel_drops_are_exported() {
+        let (console_tx, _console_rx) = bounded(10);
+        let (async_tx, async_rx) = bounded(1);
+        let metrics = Arc::new(Metrics::new());
+        let subscriber = LoggerSubscriber::new(console_tx, async_tx, metrics.clone())
+            .with_overflow_policy(OverflowPolicy::DropNewest, async_rx.clone());
+
+        for message in ["a", "b", "c"] {
+            subscriber.send_async(queued_record("INFO", message));
+        }
+
+        assert_eq!(metrics.logs_dropped(), 2);
+        assert!(
+            metrics
+                .export_prometheus()
+                .contains("inklog_logs_dropped_total 2\n")
+        );
+    }
+
+    #[test]
+    fn test_drop_oldest_moves_evicted_error_to_fallback() {
+        let (console_tx, _console_rx) = bounded(10);
+        let (async_tx, async_rx) = bounded(1);
+        let metrics = Arc::new(Metrics::new());
+        let subscriber = LoggerSubscriber::new(console_tx, async_tx, metrics.clone())
+            .with_overflow_policy(OverflowPolicy::DropOldest, async_rx.clone());
+
+        subscriber.send_async(queued_record("ERROR", "critical"));
+        subscriber.send_async(queued_record("INFO", "newer"));
+
+        assert_eq!(metrics.logs_dropped(), 0);
+        assert_eq!(async_rx.try_recv().unwrap().message, "newer");
+        let fallback = subscriber.fallback_buffer.lock().unwrap();
+        assert_eq!(fallback.len(), 1);
+        assert_eq!(fallback[0].message, "critical");
+    }
+
     // =========================================================================
     // try_flush_fallback() 测试 - 覆盖成功弹出和失败中断分支
     // =========================================================================
@@ -612,4 +1494,216 @@ mod tests {
             "critical level should not increment logs_dropped"
         );
     }
+
+    // =========================================================================
+    // ByteBudget 测试
+    // =========================================================================
+
+    #[test]
+    fn test_byte_budget_acquire_and_release() {
+        let budget = ByteBudget::new(100);
+        assert!(budget.try_acquire(60));
+        assert!(!budget.try_acquire(50), "60 + 50 exceeds the limit");
+        assert_eq!(budget.used(), 60);
+        budget.release(60);
+        assert_eq!(budget.used(), 0);
+        assert!(budget.try_acquire(100));
+        // 多余的 release 不应下溢
+        budget.release(1000);
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[test]
+    fn test_byte_budget_triggers_before_count_budget() {
+        let (console_tx, _console_rx) = bounded(100);
+        let (async_tx, async_rx) = bounded(100);
+        let metrics = Arc::new(Metrics::new());
+        let budget = Arc::new(ByteBudget::new(10 * 1024));
+
+        let layer = LoggerSubscriber::new(console_tx, async_tx, metrics.clone())
+            .with_byte_budget(budget.clone());
+        let registry = tracing_subscriber::registry().with(layer);
+
+        let large = "x".repeat(4 * 1024);
+        with_default(registry, || {
+            for _ in 0..5 {
+                tracing::info!(target: "test::subscriber", message = %large);
+            }
+        });
+
+        // 通道容量为 100 条，但 10KB 字节预算只能容纳 2 条 4KB 记录
+        assert_eq!(async_rx.len(), 2);
+        assert_eq!(metrics.logs_dropped(), 3);
+        assert!(budget.used() <= budget.limit());
+
+        // worker 消费后归还预算，新记录即可再次进入
+        while let Ok(record) = async_rx.try_recv() {
+            budget.release(record.estimated_size());
+        }
+        assert_eq!(budget.used(), 0);
+    }
+
+    type BatchReceiver = crossbeam_channel::Receiver<Vec<Arc<LogRecord>>>;
+
+    fn batching_layer(
+        max_records: usize,
+    ) -> (
+        LoggerSubscriber,
+        crossbeam_channel::Receiver<Arc<LogRecord>>,
+        BatchReceiver,
+    ) {
+        let (console_tx, console_rx) = bounded(100);
+        let (async_tx, _async_rx) = bounded(100);
+        let (batch_tx, batch_rx) = bounded(100);
+        let layer = LoggerSubscriber::new(console_tx, async_tx, Arc::new(Metrics::new()))
+            .with_thread_batching(
+                ThreadBatchConfig {
+                    max_records,
+                    max_delay: Duration::from_secs(60),
+                },
+                batch_tx,
+                batch_rx.clone(),
+            );
+        (layer, console_rx, batch_rx)
+    }
+
+    fn batch_messages(batch: &[Arc<LogRecord>]) -> Vec<String> {
+        batch.iter().map(|r| r.message.clone()).collect()
+    }
+
+    #[test]
+    fn test_thread_batching_sends_full_batch_in_order() {
+        let (layer, console_rx, batch_rx) = batching_layer(3);
+        let registry = tracing_subscriber::registry().with(layer);
+
+        with_default(registry, || {
+            tracing::info!("first");
+            tracing::info!("second");
+            assert!(batch_rx.is_empty(), "batch should hold records until full");
+            assert_eq!(console_rx.len(), 2, "console path is not batched");
+            tracing::info!("third");
+        });
+
+        // 整批记录通过一次 channel 操作送出
+        let batches: Vec<_> = batch_rx.try_iter().collect();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(
+            batch_messages(&batches[0]),
+            vec!["first", "second", "third"]
+        );
+    }
+
+    #[test]
+    fn test_thread_batching_flushes_on_critical_level() {
+        let (layer, _console_rx, batch_rx) = batching_layer(10);
+        let registry = tracing_subscriber::registry().with(layer);
+
+        with_default(registry, || {
+            tracing::info!("pending");
+            tracing::error!("failure");
+        });
+
+        let batches: Vec<_> = batch_rx.try_iter().collect();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batch_messages(&batches[0]), vec!["pending", "failure"]);
+    }
+
+    #[test]
+    fn test_thread_batching_flushes_on_thread_exit() {
+        let (layer, _console_rx, batch_rx) = batching_layer(10);
+        let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(layer));
+
+        std::thread::spawn(move || {
+            tracing::dispatcher::with_default(&dispatch, || {
+                tracing::info!("one");
+                tracing::info!("two");
+            });
+        })
+        .join()
+        .unwrap();
+
+        let batches: Vec<_> = batch_rx.try_iter().collect();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batch_messages(&batches[0]), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_flush_thread_batch_sends_pending_records() {
+        let (layer, _console_rx, batch_rx) = batching_layer(10);
+        let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(layer));
+
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::info!("buffered");
+        });
+        assert!(batch_rx.is_empty());
+
+        dispatch
+            .downcast_ref::<LoggerSubscriber>()
+            .expect("layer should be reachable through the dispatch")
+            .flush_thread_batch();
+        assert_eq!(batch_rx.len(), 1);
+    }
+
+    #[test]
+    fn test_registry_flushes_batches_of_other_threads() {
+        let (layer, _console_rx, batch_rx) = batching_layer(10);
+        let batches = layer.thread_batches();
+        let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(layer));
+        let (logged_tx, logged_rx) = bounded::<()>(1);
+        let (done_tx, done_rx) = bounded::<()>(1);
+
+        let producer = std::thread::spawn(move || {
+            tracing::dispatcher::with_default(&dispatch, || {
+                tracing::info!("idle thread");
+            });
+            logged_tx.send(()).unwrap();
+            // 线程保持存活，batch 不会因线程退出而发送
+            done_rx.recv().unwrap();
+        });
+
+        logged_rx.recv().unwrap();
+        assert!(batch_rx.is_empty());
+        batches.flush_all();
+        let batch = batch_rx
+            .try_recv()
+            .expect("registry should flush the idle batch");
+        assert_eq!(batch_messages(&batch), vec!["idle thread"]);
+
+        done_tx.send(()).unwrap();
+        producer.join().unwrap();
+        assert!(
+            batch_rx.is_empty(),
+            "flushed batch should not be sent twice"
+        );
+    }
+
+    #[test]
+    fn test_thread_batch_drop_oldest_evicts_whole_batch() {
+        let (console_tx, _console_rx) = bounded(100);
+        let (async_tx, async_rx) = bounded(100);
+        let (batch_tx, batch_rx) = bounded(1);
+        let metrics = Arc::new(Metrics::new());
+        let layer = LoggerSubscriber::new(console_tx, async_tx, metrics.clone())
+            .with_overflow_policy(OverflowPolicy::DropOldest, async_rx)
+            .with_thread_batching(
+                ThreadBatchConfig {
+                    max_records: 2,
+                    max_delay: Duration::from_secs(60),
+                },
+                batch_tx,
+                batch_rx.clone(),
+            );
+        let registry = tracing_subscriber::registry().with(layer);
+
+        with_default(registry, || {
+            for i in 0..4 {
+                tracing::info!("record {}", i);
+            }
+        });
+
+        let batches: Vec<_> = batch_rx.try_iter().collect();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batch_messages(&batches[0]), vec!["record 2", "record 3"]);
+        assert_eq!(metrics.logs_dropped(), 2);
+    }
 }
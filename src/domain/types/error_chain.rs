@@ -0,0 +1,172 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! Structured error chains for log records.
+//!
+//! Errors recorded as `dyn Error` tracing fields (or attached with
+//! [`LogRecord::attach_error_chain`](crate::LogRecord::attach_error_chain)) keep
+//! their full `source()` chain in a `<field>.chain` array instead of only the
+//! top-level `Display` text.
+
+use std::error::Error;
+
+/// Collects the `Display` text of `err` and each of its sources, outermost first.
+///
+/// # Example
+///
+/// ```
+/// let err = std::io::Error::other("disk full");
+/// assert_eq!(inklog::error_chain(&err), vec!["disk full".to_string()]);
+/// ```
+pub fn error_chain(err: &(dyn Error + 'static)) -> Vec<String> {
+    let mut chain = Vec::new();
+    let mut current = Some(err);
+    while let Some(e) = current {
+        chain.push(e.to_string());
+        current = e.source();
+    }
+    chain
+}
+
+/// Logs the error of a `Result` with its full source chain.
+///
+/// The error is emitted as an ERROR event with an `error` field recorded as
+/// `dyn Error`, so inklog stores both `error` and `error.chain`.
+///
+/// # Example
+///
+/// ```
+/// use inklog::LogResultExt;
+///
+/// fn load() -> std::io::Result<String> {
+///     std::fs::read_to_string("/nonexistent/config.toml").log_err("failed to load config")
+/// }
+/// assert!(load().is_err());
+/// ```
+pub trait LogResultExt<T, E> {
+    /// Emits an ERROR event with `message` when `self` is `Err`, then returns `self` unchanged.
+    fn log_err(self, message: &str) -> Result<T, E>;
+}
+
+impl<T, E> LogResultExt<T, E> for Result<T, E>
+where
+    E: Error + 'static,
+{
+    fn log_err(self, message: &str) -> Result<T, E> {
+        if let Err(ref e) = self {
+            tracing::error!(error = e as &(dyn Error + 'static), "{}", message);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogRecord;
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+    use tracing::subscriber::with_default;
+    use tracing_subscriber::Layer;
+    use tracing_subscriber::layer::Context;
+    use tracing_subscriber::prelude::*;
+
+    #[derive(Debug)]
+    struct QueryError {
+        source: ConnectionError,
+    }
+
+    impl fmt::Display for QueryError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "query failed")
+        }
+    }
+
+    impl Error for QueryError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.source)
+        }
+    }
+
+    #[derive(Debug)]
+    struct ConnectionError {
+        source: std::io::Error,
+    }
+
+    impl fmt::Display for ConnectionError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "connection lost")
+        }
+    }
+
+    impl Error for ConnectionError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.source)
+        }
+    }
+
+    fn nested_error() -> QueryError {
+        QueryError {
+            source: ConnectionError {
+                source: std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset by peer"),
+            },
+        }
+    }
+
+    struct CaptureLayer(Arc<Mutex<Vec<LogRecord>>>);
+
+    impl<S: tracing::Subscriber> Layer<S> for CaptureLayer {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            self.0.lock().unwrap().push(LogRecord::from_event(event));
+        }
+    }
+
+    fn capture(f: impl FnOnce()) -> Vec<LogRecord> {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let registry = tracing_subscriber::registry().with(CaptureLayer(captured.clone()));
+        with_default(registry, f);
+        captured.lock().unwrap().drain(..).collect()
+    }
+
+    #[test]
+    fn test_error_chain_collects_nested_sources() {
+        assert_eq!(
+            error_chain(&nested_error()),
+            vec!["query failed", "connection lost", "reset by peer"]
+        );
+    }
+
+    #[test]
+    fn test_dyn_error_field_records_chain() {
+        let err = nested_error();
+        let records = capture(|| {
+            tracing::error!(error = &err as &(dyn Error + 'static), "request failed");
+        });
+
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.message, "request failed");
+        assert_eq!(record.fields["error"], serde_json::json!("query failed"));
+        assert_eq!(
+            record.fields["error.chain"],
+            serde_json::json!(["query failed", "connection lost", "reset by peer"])
+        );
+    }
+
+    #[test]
+    fn test_log_err_logs_chain_and_returns_result() {
+        let mut result: Result<(), QueryError> = Ok(());
+        let records = capture(|| {
+            result = Err(nested_error()).log_err("sync failed");
+            assert!(Ok::<(), QueryError>(()).log_err("not logged").is_ok());
+        });
+
+        assert!(result.is_err());
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].level, "ERROR");
+        assert_eq!(records[0].message, "sync failed");
+        assert_eq!(
+            records[0].fields[LogRecord::ERROR_CHAIN_KEY],
+            serde_json::json!(["query failed", "connection lost", "reset by peer"])
+        );
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) 2026 Kirky.X
 // SPDX-License-Identifier: MIT
+use crate::ControlCharMode;
 use crate::DataMasker;
+use crate::domain::types::error_chain::error_chain;
 use crate::{get_log_record, get_string_buffer};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -323,6 +325,184 @@ impl LogRecord {
             }
         }
     }
+
+    /// Field holding the source chain of the conventional `error` field.
+    pub const ERROR_CHAIN_KEY: &'static str = "error.chain";
+
+    /// Attaches `err` as the `error` field and its full source chain as
+    /// [`ERROR_CHAIN_KEY`](Self::ERROR_CHAIN_KEY), outermost message first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inklog::log_record::LogRecord;
+    /// use tracing::Level;
+    ///
+    /// let mut record = LogRecord::new(Level::ERROR, "app".to_string(), "failed".to_string());
+    /// record.attach_error_chain(&std::io::Error::other("disk full"));
+    ///
+    /// assert_eq!(record.fields["error.chain"], serde_json::json!(["disk full"]));
+    /// ```
+    pub fn attach_error_chain(&mut self, err: &(dyn std::error::Error + 'static)) {
+        self.fields
+            .insert("error".to_string(), Value::String(err.to_string()));
+        self.fields.insert(
+            Self::ERROR_CHAIN_KEY.to_string(),
+            Value::Array(error_chain(err).into_iter().map(Value::String).collect()),
+        );
+    }
+
+    /// Suffix appended to field values cut by [`truncate_field_values`](Self::truncate_field_values).
+    pub const TRUNCATION_MARKER: &'static str = "...[truncated]";
+
+    /// Field listing the names of values cut by [`truncate_field_values`](Self::truncate_field_values).
+    pub const TRUNCATED_FIELDS_KEY: &'static str = "_truncated_fields";
+
+    /// Truncates individual field values larger than `max_bytes`.
+    ///
+    /// String values are cut at a UTF-8 boundary and suffixed with
+    /// [`TRUNCATION_MARKER`](Self::TRUNCATION_MARKER). Non-string values are left
+    /// untouched unless their serialized JSON exceeds `max_bytes`, in which case
+    /// they are replaced by the truncated JSON text. The names of all truncated
+    /// fields are recorded, sorted, in the [`TRUNCATED_FIELDS_KEY`](Self::TRUNCATED_FIELDS_KEY)
+    /// field; other fields are kept as-is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inklog::log_record::LogRecord;
+    /// use serde_json::Value;
+    /// use tracing::Level;
+    ///
+    /// let mut record = LogRecord::new(Level::ERROR, "app".to_string(), "failed".to_string());
+    /// record.fields.insert("stack".to_string(), Value::String("x".repeat(100)));
+    /// record.fields.insert("code".to_string(), Value::from(500));
+    ///
+    /// record.truncate_field_values(16);
+    ///
+    /// assert_eq!(record.fields["code"], Value::from(500));
+    /// assert_eq!(record.fields["_truncated_fields"], serde_json::json!(["stack"]));
+    /// ```
+    pub fn truncate_field_values(&mut self, max_bytes: usize) {
+        let mut truncated = Vec::new();
+        for (key, value) in self.fields.iter_mut() {
+            let text = match value {
+                Value::String(s) if s.len() > max_bytes => std::mem::take(s),
+                Value::String(_) | Value::Null | Value::Bool(_) | Value::Number(_) => continue,
+                _ => match serde_json::to_string(&*value) {
+                    Ok(json) if json.len() > max_bytes => json,
+                    _ => continue,
+                },
+            };
+            let mut end = max_bytes;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            *value = Value::String(format!("{}{}", &text[..end], Self::TRUNCATION_MARKER));
+            truncated.push(Value::String(key.clone()));
+        }
+
+        if !truncated.is_empty() {
+            truncated.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+            self.fields.insert(
+                Self::TRUNCATED_FIELDS_KEY.to_string(),
+                Value::Array(truncated),
+            );
+        }
+    }
+
+    /// Rewrites control characters in the message and string field values.
+    ///
+    /// `\n` and `\t` are kept. Strings nested in array or object fields are
+    /// sanitized too. Returns `true` when anything was changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inklog::{ControlCharMode, LogRecord};
+    ///
+    /// let mut record = LogRecord::new(tracing::Level::INFO, "app".into(), "a\0b".into());
+    /// assert!(record.sanitize_control_chars(ControlCharMode::Escape));
+    /// assert_eq!(record.message, "a\\x00b");
+    /// ```
+    pub fn sanitize_control_chars(&mut self, mode: ControlCharMode) -> bool {
+        let mut changed = sanitize_string(&mut self.message, mode);
+        for value in self.fields.values_mut() {
+            changed |= sanitize_value(value, mode);
+        }
+        changed
+    }
+
+    /// Returns a cheap approximation of the heap and inline memory held by this record.
+    ///
+    /// Used for byte-based buffering budgets; it does not serialize the record.
+    pub fn estimated_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.level.len()
+            + self.target.len()
+            + self.message.len()
+            + self.thread_id.len()
+            + self.file.as_ref().map_or(0, String::len)
+            + self
+                .fields
+                .iter()
+                .map(|(k, v)| k.len() + Self::estimated_value_size(v))
+                .sum::<usize>()
+    }
+
+    fn estimated_value_size(value: &Value) -> usize {
+        match value {
+            Value::String(s) => std::mem::size_of::<Value>() + s.len(),
+            Value::Array(items) => {
+                std::mem::size_of::<Value>()
+                    + items.iter().map(Self::estimated_value_size).sum::<usize>()
+            }
+            Value::Object(map) => {
+                std::mem::size_of::<Value>()
+                    + map
+                        .iter()
+                        .map(|(k, v)| k.len() + Self::estimated_value_size(v))
+                        .sum::<usize>()
+            }
+            _ => std::mem::size_of::<Value>(),
+        }
+    }
+}
+
+fn sanitize_value(value: &mut Value, mode: ControlCharMode) -> bool {
+    match value {
+        Value::String(s) => sanitize_string(s, mode),
+        Value::Array(items) => items
+            .iter_mut()
+            .fold(false, |changed, item| sanitize_value(item, mode) | changed),
+        Value::Object(map) => map
+            .values_mut()
+            .fold(false, |changed, item| sanitize_value(item, mode) | changed),
+        _ => false,
+    }
+}
+
+fn sanitize_string(s: &mut String, mode: ControlCharMode) -> bool {
+    let is_unsafe = |c: char| c.is_control() && c != '\n' && c != '\t';
+    if !s.chars().any(is_unsafe) {
+        return false;
+    }
+    let mut sanitized = String::with_capacity(s.len() + 8);
+    for c in s.chars() {
+        if !is_unsafe(c) {
+            sanitized.push(c);
+            continue;
+        }
+        match mode {
+            ControlCharMode::Escape if (c as u32) <= 0xff => {
+                sanitized.push_str(&format!("\\x{:02x}", c as u32))
+            }
+            ControlCharMode::Escape => sanitized.push_str(&format!("\\u{:04x}", c as u32)),
+            ControlCharMode::Replace => sanitized.push(char::REPLACEMENT_CHARACTER),
+        }
+    }
+    *s = sanitized;
+    true
 }
 
 struct LogVisitor<'a> {
@@ -366,6 +546,20 @@ impl<'a> tracing::field::Visit for LogVisitor<'a> {
             .insert(field.name().to_string(), Value::Number(value.into()));
     }
 
+    fn record_error(
+        &mut self,
+        field: &tracing::field::Field,
+        value: &(dyn std::error::Error + 'static),
+    ) {
+        let name = field.name();
+        self.fields
+            .insert(name.to_string(), Value::String(value.to_string()));
+        self.fields.insert(
+            format!("{}.chain", name),
+            Value::Array(error_chain(value).into_iter().map(Value::String).collect()),
+        );
+    }
+
     fn record_f64(&mut self, field: &tracing::field::Field, value: f64) {
         if let Some(n) = serde_json::Number::from_f64(value) {
             self.fields
@@ -378,6 +572,27 @@ impl<'a> tracing::field::Visit for LogVisitor<'a> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_sanitize_control_chars_replace_mode_covers_nested_values() {
+        let mut record = LogRecord::new(Level::INFO, "app".to_string(), "ok".to_string());
+        record.fields.insert(
+            "nested".to_string(),
+            serde_json::json!({"items": ["\u{7}bell", 42], "del": "x\u{7f}"}),
+        );
+        record
+            .fields
+            .insert("c1".to_string(), Value::String("\u{85}next".to_string()));
+
+        assert!(record.sanitize_control_chars(ControlCharMode::Replace));
+        assert_eq!(record.message, "ok");
+        assert_eq!(
+            record.fields["nested"],
+            serde_json::json!({"items": ["\u{fffd}bell", 42], "del": "x\u{fffd}"})
+        );
+        assert_eq!(record.fields["c1"], "\u{fffd}next");
+        assert!(!record.sanitize_control_chars(ControlCharMode::Replace));
+    }
+
     #[test]
     fn test_mask_sensitive_fields() {
         let mut record = LogRecord::new(Level::INFO, "test".to_string(), "message".to_string());
@@ -517,6 +732,76 @@ mod tests {
 
     // === LogRecord Basic Tests ===
 
+    #[test]
+    fn test_truncate_field_values_mixed_sizes() {
+        let mut record = LogRecord::new(Level::ERROR, "test".to_string(), "boom".to_string());
+        record
+            .fields
+            .insert("stack".to_string(), Value::String("frame\n".repeat(50)));
+        record
+            .fields
+            .insert("user".to_string(), Value::String("alice".to_string()));
+        record.fields.insert("status".to_string(), Value::from(503));
+        record
+            .fields
+            .insert("small_obj".to_string(), serde_json::json!({"retry": true}));
+        record.fields.insert(
+            "large_obj".to_string(),
+            serde_json::json!({"payload": "y".repeat(64)}),
+        );
+
+        record.truncate_field_values(32);
+
+        let stack = record.fields["stack"].as_str().unwrap();
+        assert!(stack.starts_with("frame\n"));
+        assert!(stack.ends_with(LogRecord::TRUNCATION_MARKER));
+        assert_eq!(stack.len(), 32 + LogRecord::TRUNCATION_MARKER.len());
+
+        assert_eq!(record.fields["user"], Value::String("alice".to_string()));
+        assert_eq!(record.fields["status"], Value::from(503));
+        assert_eq!(
+            record.fields["small_obj"],
+            serde_json::json!({"retry": true})
+        );
+
+        let large = record.fields["large_obj"].as_str().unwrap();
+        assert!(large.starts_with("{\"payload\":"));
+        assert!(large.ends_with(LogRecord::TRUNCATION_MARKER));
+
+        assert_eq!(
+            record.fields[LogRecord::TRUNCATED_FIELDS_KEY],
+            serde_json::json!(["large_obj", "stack"])
+        );
+    }
+
+    #[test]
+    fn test_truncate_field_values_respects_utf8_boundary() {
+        let mut record = LogRecord::new(Level::INFO, "test".to_string(), "msg".to_string());
+        record
+            .fields
+            .insert("note".to_string(), Value::String("日志".repeat(10)));
+
+        record.truncate_field_values(4);
+
+        assert_eq!(
+            record.fields["note"],
+            Value::String(format!("日{}", LogRecord::TRUNCATION_MARKER))
+        );
+    }
+
+    #[test]
+    fn test_truncate_field_values_no_oversized_fields() {
+        let mut record = LogRecord::new(Level::INFO, "test".to_string(), "msg".to_string());
+        record
+            .fields
+            .insert("user".to_string(), Value::String("bob".to_string()));
+
+        record.truncate_field_values(32);
+
+        assert_eq!(record.fields.len(), 1);
+        assert!(!record.fields.contains_key(LogRecord::TRUNCATED_FIELDS_KEY));
+    }
+
     #[test]
     fn test_log_record_default() {
         let record = LogRecord::default();
@@ -528,6 +813,20 @@ mod tests {
         assert!(record.line.is_none());
     }
 
+    #[test]
+    fn test_log_record_estimated_size_grows_with_content() {
+        let small = LogRecord::default();
+        let mut large = LogRecord {
+            message: "x".repeat(4096),
+            ..Default::default()
+        };
+        large
+            .fields
+            .insert("payload".to_string(), Value::String("y".repeat(1024)));
+        assert!(small.estimated_size() >= std::mem::size_of::<LogRecord>());
+        assert!(large.estimated_size() >= small.estimated_size() + 4096 + 1024);
+    }
+
     #[test]
     fn test_log_record_new() {
         let record = LogRecord::new(
@@ -2,4 +2,5 @@
 // SPDX-License-Identifier: MIT
 //! Domain types module - core data types for inklog.
 
+pub mod error_chain;
 pub mod log_record;
@@ -98,6 +98,7 @@ use crate::InklogError;
 /// | `file_sink.compress` | `config.file_sink.compress` |
 /// | `console_sink.enabled` | `config.console_sink.enabled` |
 /// | `console_sink.colored` | `config.console_sink.colored` |
+/// | `console_sink.synchronous` | `config.console_sink.synchronous` |
 /// | `database_sink.enabled` | `config.database_sink.enabled` |
 /// | `database_sink.url` | `config.database_sink.url` |
 /// | `database_sink.pool_size` | `config.database_sink.pool_size` |
@@ -247,6 +248,11 @@ impl Config for InklogConfigAdapter {
                 .console_sink
                 .as_ref()
                 .map(|c| c.masking_enabled.to_string()),
+            "console_sink.synchronous" => self
+                .config
+                .console_sink
+                .as_ref()
+                .map(|c| c.synchronous.to_string()),
 
             // File sink
             "file_sink.enabled" => self
@@ -792,6 +798,8 @@ mod tests {
                 colored: false,
                 stderr_levels: vec!["error".to_string(), "fatal".to_string()],
                 masking_enabled: true,
+                synchronous: true,
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -805,6 +813,7 @@ mod tests {
             Some("error,fatal".to_string())
         );
         assert_eq!(adapter.get_bool("console_sink.masking_enabled"), Some(true));
+        assert_eq!(adapter.get_bool("console_sink.synchronous"), Some(true));
     }
 
     #[test]
@@ -841,6 +850,7 @@ mod tests {
                 batch_size: 200,
                 flush_interval_ms: 50,
                 masking_enabled: false,
+                ..Default::default()
             }),
             ..Default::default()
         };
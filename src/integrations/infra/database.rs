@@ -6,7 +6,10 @@
 
 use crate::InklogError;
 use crate::LogRecord;
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+use crate::sink::compression::compress_fields_column;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 /// Database trait - 抽象数据库操作
 ///
@@ -53,7 +56,8 @@ pub trait Database: Send + Sync {
     ///
     /// # 注意
     ///
-    /// 实现应该保证原子性，要么全部插入成功，要么全部失败
+    /// 实现应该保证原子性，要么全部插入成功，要么全部失败。
+    /// 启用去重时，因键冲突被跳过的行同样计入返回值。
     async fn insert_batch(&self, records: &[LogRecord]) -> Result<usize, InklogError>;
 
     /// 检查数据库健康状态
@@ -66,6 +70,29 @@ pub trait Database: Send + Sync {
     ///
     /// 此方法应该是轻量级的，适合频繁调用
     async fn is_healthy(&self) -> bool;
+
+    /// 写入一条 metrics 快照
+    ///
+    /// # 参数
+    ///
+    /// * `table` - 快照表名
+    /// * `timestamp` - 快照时间
+    /// * `snapshot_json` - 序列化后的 counters/gauges
+    ///
+    /// # 注意
+    ///
+    /// 默认实现返回错误，表示该实现不支持持久化 metrics 快照
+    async fn insert_metrics_snapshot(
+        &self,
+        table: &str,
+        timestamp: DateTime<Utc>,
+        snapshot_json: &str,
+    ) -> Result<(), InklogError> {
+        let _ = (table, timestamp, snapshot_json);
+        Err(InklogError::DatabaseError(
+            "metrics snapshot persistence is not supported by this database".to_string(),
+        ))
+    }
 }
 
 // ============================================================================
@@ -106,6 +133,19 @@ use dbnexus::foundation::config::DbConfig;
 pub struct DbNexusAdapter {
     pool: DbPool,
     table_name: String,
+    compress_fields: bool,
+    derived_timestamps: bool,
+    promoted_fields: Vec<String>,
+    keep_promoted_fields: bool,
+    indexes: Vec<String>,
+    /// 启用去重时按驱动选择冲突语法
+    dedup: Option<crate::DatabaseDriver>,
+    /// 数据库驱动，决定 DDL 方言；未知时按支持 `IF NOT EXISTS` 处理
+    driver: Option<crate::DatabaseDriver>,
+    /// 派生时间戳列、提升列、去重列与索引是否已确保存在
+    extra_columns_ready: std::sync::atomic::AtomicBool,
+    /// metrics 快照表是否已确保存在
+    metrics_table_ready: std::sync::atomic::AtomicBool,
 }
 
 #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
@@ -178,6 +218,15 @@ impl DbNexusAdapter {
         Ok(Self {
             pool,
             table_name: table_name.to_string(),
+            compress_fields: false,
+            derived_timestamps: false,
+            promoted_fields: Vec::new(),
+            keep_promoted_fields: false,
+            indexes: Vec::new(),
+            dedup: None,
+            driver: url.split(':').next().and_then(|scheme| scheme.parse().ok()),
+            extra_columns_ready: std::sync::atomic::AtomicBool::new(false),
+            metrics_table_ready: std::sync::atomic::AtomicBool::new(false),
         })
     }
 
@@ -193,9 +242,81 @@ impl DbNexusAdapter {
         Self {
             pool,
             table_name: table_name.to_string(),
+            compress_fields: false,
+            derived_timestamps: false,
+            promoted_fields: Vec::new(),
+            keep_promoted_fields: false,
+            indexes: Vec::new(),
+            dedup: None,
+            driver: None,
+            extra_columns_ready: std::sync::atomic::AtomicBool::new(false),
+            metrics_table_ready: std::sync::atomic::AtomicBool::new(false),
         }
     }
 
+    /// 启用 `fields` 列压缩
+    ///
+    /// 启用后 `fields` 以带标记前缀的压缩文本存储，读取时使用
+    /// [`decompress_fields_column`](crate::sink::compression::decompress_fields_column) 还原。
+    pub fn with_fields_compression(mut self, enabled: bool) -> Self {
+        self.compress_fields = enabled;
+        self
+    }
+
+    /// 启用派生时间戳列
+    ///
+    /// 启用后每行额外写入 `timestamp_epoch_ms` 与 `timestamp_str`，
+    /// 首次写入前通过 `ALTER TABLE ... ADD COLUMN` 为已有表补齐这两列。
+    pub fn with_derived_timestamps(mut self, enabled: bool) -> Self {
+        self.derived_timestamps = enabled;
+        self
+    }
+
+    /// 将 `fields` 中的指定键提升为独立的可空 TEXT 列
+    ///
+    /// 首次写入前通过 `ALTER TABLE ... ADD COLUMN` 补齐列；`keep_in_fields` 为
+    /// `false` 时这些键不再写入 `fields` JSON。列名须为合法标识符，由
+    /// [`DatabaseSinkConfig::validate_promoted_fields`](crate::DatabaseSinkConfig::validate_promoted_fields) 校验。
+    pub fn with_promoted_fields(mut self, fields: Vec<String>, keep_in_fields: bool) -> Self {
+        self.promoted_fields = fields;
+        self.keep_promoted_fields = keep_in_fields;
+        self
+    }
+
+    /// 首次写入前为指定列创建 `idx_<table>_<column>` 索引
+    ///
+    /// PostgreSQL/SQLite 使用 `CREATE INDEX IF NOT EXISTS`；MySQL 不支持该子句，
+    /// 使用普通 `CREATE INDEX` 并忽略索引已存在的错误，TEXT 列（`message`、
+    /// `fields` 与提升列）加 191 字符前缀长度。列名须为日志表的列，由
+    /// [`DatabaseSinkConfig::validate_indexes`](crate::DatabaseSinkConfig::validate_indexes) 校验。
+    pub fn with_indexes(mut self, columns: Vec<String>) -> Self {
+        self.indexes = columns;
+        self
+    }
+
+    /// 启用幂等批量写入
+    ///
+    /// 每行写入由 `timestamp`、`thread_id` 与 `message` 计算的 `dedup_key`，
+    /// 首次写入前补齐该列并创建唯一索引 `uniq_<table>_dedup_key`；键已存在的行
+    /// 被跳过（PostgreSQL/SQLite 使用 `ON CONFLICT DO NOTHING`，MySQL 使用
+    /// `INSERT IGNORE`）。失败重试的批次因此不会产生重复行。
+    ///
+    /// `insert_batch` 返回提交的记录数，其中包含被跳过的重复行。
+    pub fn with_dedup(mut self, driver: crate::DatabaseDriver) -> Self {
+        self.driver = Some(driver.clone());
+        self.dedup = Some(driver);
+        self
+    }
+
+    /// 指定数据库驱动，用于生成该驱动支持的 DDL
+    ///
+    /// `with_table_name` 按连接字符串的协议推断驱动；`from_pool` 创建的适配器
+    /// 在 MySQL 上需显式指定。
+    pub fn with_driver(mut self, driver: crate::DatabaseDriver) -> Self {
+        self.driver = Some(driver);
+        self
+    }
+
     /// 获取底层连接池引用
     pub fn pool(&self) -> &DbPool {
         &self.pool
@@ -222,43 +343,113 @@ impl Database for DbNexusAdapter {
             .await
             .map_err(|e| InklogError::DatabaseError(format!("Failed to get session: {}", e)))?;
 
-        // 构建所有记录的 INSERT SQL 语句
-        let sqls: Vec<String> = records
-            .iter()
-            .map(|record| {
-                let timestamp = record.timestamp.to_rfc3339();
-                let level = &record.level;
-                let target = &record.target;
-                let message = record.message.replace('\'', "''");
-                let fields_json =
-                    serde_json::to_string(&record.fields).unwrap_or_else(|_| "{}".to_string());
-                let fields_escaped = fields_json.replace('\'', "''");
-                let file = record
-                    .file
-                    .as_ref()
-                    .map(|f| format!("'{}'", f.replace('\'', "''")))
-                    .unwrap_or_else(|| "NULL".to_string());
-                let line = record
-                    .line
-                    .map(|l| l.to_string())
-                    .unwrap_or_else(|| "NULL".to_string());
-                let thread_id = &record.thread_id;
-
-                format!(
-                    "INSERT INTO {} (timestamp, level, target, message, fields, file, line, thread_id) \
-                     VALUES ('{}', '{}', '{}', '{}', '{}', {}, {}, '{}')",
-                    self.table_name,
-                    timestamp,
-                    level,
-                    target.replace('\'', "''"),
-                    message,
-                    fields_escaped,
-                    file,
-                    line,
-                    thread_id.replace('\'', "''")
-                )
-            })
-            .collect();
+        if (self.derived_timestamps
+            || !self.promoted_fields.is_empty()
+            || !self.indexes.is_empty()
+            || self.dedup.is_some())
+            && !self
+                .extra_columns_ready
+                .load(std::sync::atomic::Ordering::Acquire)
+        {
+            let derived: &[(&str, &str)] = if self.derived_timestamps {
+                &[
+                    ("timestamp_epoch_ms", "BIGINT"),
+                    ("timestamp_str", "VARCHAR(32)"),
+                ]
+            } else {
+                &[]
+            };
+            let promoted = self
+                .promoted_fields
+                .iter()
+                .map(|field| (field.as_str(), "TEXT"));
+            let dedup: &[(&str, &str)] = if self.dedup.is_some() {
+                &[("dedup_key", "VARCHAR(64)")]
+            } else {
+                &[]
+            };
+            for (column, column_type) in derived
+                .iter()
+                .copied()
+                .chain(promoted)
+                .chain(dedup.iter().copied())
+            {
+                // 列已存在时 ALTER 会失败，忽略即可；其余错误下次写入时重试
+                if let Err(e) = session
+                    .execute_raw_ddl(&format!(
+                        "ALTER TABLE {} ADD COLUMN {} {}",
+                        self.table_name, column, column_type
+                    ))
+                    .await
+                {
+                    let message = e.to_string();
+                    if !is_existing_object_error(&message) {
+                        return Err(InklogError::DatabaseError(format!(
+                            "Failed to add column {}: {}",
+                            column, message
+                        )));
+                    }
+                    tracing::debug!("Column {} already exists: {}", column, message);
+                }
+            }
+            for column in &self.indexes {
+                let text_column = matches!(column.as_str(), "message" | "fields")
+                    || self.promoted_fields.contains(column);
+                if let Err(e) = session
+                    .execute_raw_ddl(&create_index_sql(
+                        self.driver.as_ref(),
+                        &self.table_name,
+                        &format!("idx_{}_{}", self.table_name, column),
+                        column,
+                        false,
+                        text_column,
+                    ))
+                    .await
+                {
+                    let message = e.to_string();
+                    if !is_existing_object_error(&message) {
+                        return Err(InklogError::DatabaseError(format!(
+                            "Failed to create index on {}: {}",
+                            column, message
+                        )));
+                    }
+                }
+            }
+            // 唯一索引是去重的前提，创建失败时不能继续写入
+            if self.dedup.is_some()
+                && let Err(e) = session
+                    .execute_raw_ddl(&create_index_sql(
+                        self.driver.as_ref(),
+                        &self.table_name,
+                        &format!("uniq_{}_dedup_key", self.table_name),
+                        "dedup_key",
+                        true,
+                        false,
+                    ))
+                    .await
+            {
+                let message = e.to_string();
+                if !is_existing_object_error(&message) {
+                    return Err(InklogError::DatabaseError(format!(
+                        "Failed to create dedup index: {}",
+                        message
+                    )));
+                }
+            }
+            self.extra_columns_ready
+                .store(true, std::sync::atomic::Ordering::Release);
+        }
+
+        // 按行数与语句长度分块的多行 INSERT
+        let sqls = build_batch_insert_sqls(
+            &self.table_name,
+            records,
+            self.compress_fields,
+            self.derived_timestamps,
+            &self.promoted_fields,
+            self.keep_promoted_fields,
+            self.dedup.as_ref(),
+        )?;
 
         // 在事务中执行全部语句——原子性：全部成功或全部失败
         let sql_refs: Vec<&str> = sqls.iter().map(|s| s.as_str()).collect();
@@ -273,6 +464,70 @@ impl Database for DbNexusAdapter {
         Ok(records.len())
     }
 
+    async fn insert_metrics_snapshot(
+        &self,
+        table: &str,
+        timestamp: DateTime<Utc>,
+        snapshot_json: &str,
+    ) -> Result<(), InklogError> {
+        use sea_orm::{ConnectionTrait, DbBackend, Statement};
+
+        let session = self
+            .pool
+            .get_session("admin")
+            .await
+            .map_err(|e| InklogError::DatabaseError(format!("Failed to get session: {}", e)))?;
+        let conn = session
+            .connection()
+            .map_err(|e| InklogError::DatabaseError(format!("Failed to get connection: {}", e)))?;
+        let backend = conn.get_database_backend();
+
+        if !self
+            .metrics_table_ready
+            .load(std::sync::atomic::Ordering::Acquire)
+        {
+            let timestamp_type = match backend {
+                DbBackend::Postgres => "TIMESTAMPTZ",
+                DbBackend::MySql => "DATETIME(6)",
+                _ => "TIMESTAMP",
+            };
+            session
+                .execute_raw_ddl(&format!(
+                    "CREATE TABLE IF NOT EXISTS {} (timestamp {} NOT NULL, snapshot TEXT NOT NULL)",
+                    table, timestamp_type
+                ))
+                .await
+                .map_err(|e| {
+                    InklogError::DatabaseError(format!(
+                        "Failed to create metrics snapshot table: {}",
+                        e
+                    ))
+                })?;
+            self.metrics_table_ready
+                .store(true, std::sync::atomic::Ordering::Release);
+        }
+
+        // 表名已由配置校验为 SQL 标识符，值通过参数绑定
+        let placeholders = match backend {
+            DbBackend::Postgres => "$1, $2",
+            _ => "?, ?",
+        };
+        conn.execute_raw(Statement::from_sql_and_values(
+            backend,
+            format!(
+                "INSERT INTO {} (timestamp, snapshot) VALUES ({})",
+                table, placeholders
+            ),
+            [timestamp.into(), snapshot_json.into()],
+        ))
+        .await
+        .map_err(|e| {
+            InklogError::DatabaseError(format!("Metrics snapshot insert failed: {}", e))
+        })?;
+
+        Ok(())
+    }
+
     async fn is_healthy(&self) -> bool {
         // 健康检查：仅验证连接池能获取 admin 会话。
         //
@@ -298,6 +553,266 @@ impl Database for DbNexusAdapter {
     }
 }
 
+/// 由主时间戳派生的 `(timestamp_epoch_ms, timestamp_str)` 列值
+pub fn derived_timestamp_values(timestamp: &DateTime<Utc>) -> (i64, String) {
+    (
+        timestamp.timestamp_millis(),
+        timestamp.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+    )
+}
+
+/// 提升列的 SQL 值：字符串原样存储，其余 JSON 值存其文本形式，缺失或 null 为 NULL
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+fn promoted_column_value(value: Option<&serde_json::Value>) -> String {
+    match value {
+        None | Some(serde_json::Value::Null) => "NULL".to_string(),
+        Some(serde_json::Value::String(s)) => format!("'{}'", s.replace('\'', "''")),
+        Some(other) => format!("'{}'", other.to_string().replace('\'', "''")),
+    }
+}
+
+/// MySQL 为 TEXT 列建索引时使用的前缀长度（utf8mb4 下不超过 767 字节）
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+const MYSQL_INDEX_PREFIX_LEN: usize = 191;
+
+/// 按驱动构建 `CREATE [UNIQUE] INDEX` 语句
+///
+/// MySQL 不支持 `CREATE INDEX IF NOT EXISTS`，生成不带该子句的语句，索引已存在
+/// 时的 1061 错误由 [`is_existing_object_error`] 识别；`prefix` 为 `true` 时为
+/// TEXT 列加上 [`MYSQL_INDEX_PREFIX_LEN`] 前缀长度。其他驱动使用 `IF NOT EXISTS`。
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+fn create_index_sql(
+    driver: Option<&crate::DatabaseDriver>,
+    table_name: &str,
+    index_name: &str,
+    column: &str,
+    unique: bool,
+    prefix: bool,
+) -> String {
+    let unique = if unique { "UNIQUE " } else { "" };
+    match driver {
+        Some(crate::DatabaseDriver::MySQL) => {
+            let column = if prefix {
+                format!("{}({})", column, MYSQL_INDEX_PREFIX_LEN)
+            } else {
+                column.to_string()
+            };
+            format!(
+                "CREATE {}INDEX {} ON {} ({})",
+                unique, index_name, table_name, column
+            )
+        }
+        _ => format!(
+            "CREATE {}INDEX IF NOT EXISTS {} ON {} ({})",
+            unique, index_name, table_name, column
+        ),
+    }
+}
+
+/// DDL 错误是否表示列或索引已存在
+///
+/// 覆盖 SQLite（`duplicate column name`）、PostgreSQL（`already exists`）与
+/// MySQL（1060 `Duplicate column name`、1061 `Duplicate key name`）。
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+fn is_existing_object_error(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    message.contains("duplicate column")
+        || message.contains("duplicate key name")
+        || message.contains("already exists")
+}
+
+/// 单条多行 INSERT 最多包含的记录数
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+const MAX_ROWS_PER_INSERT: usize = 500;
+
+/// 单条 INSERT 语句的长度上限（字节）
+///
+/// dbnexus 在执行前解析每条语句做权限检查，超过 10,000 字节的语句会被拒绝；
+/// 该值同时远低于各数据库自身的语句长度限制。超过该长度的单条记录独占一条语句。
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+const MAX_INSERT_STATEMENT_BYTES: usize = 9 * 1024;
+
+/// 单条记录 INSERT 的组成部分
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+struct InsertParts {
+    /// `INSERT INTO table (columns)`，同一批记录的列相同
+    head: String,
+    /// `(values)` 元组
+    values: String,
+    /// 去重时的冲突子句
+    on_conflict: &'static str,
+}
+
+/// 将一批记录构建为多行 INSERT 语句
+///
+/// 每条语句至多 [`MAX_ROWS_PER_INSERT`] 行、约 [`MAX_INSERT_STATEMENT_BYTES`]
+/// 字节，以适应各数据库对语句长度的限制。所有语句应在同一事务中执行。
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+fn build_batch_insert_sqls(
+    table_name: &str,
+    records: &[LogRecord],
+    compress_fields: bool,
+    derived_timestamps: bool,
+    promoted_fields: &[String],
+    keep_promoted_fields: bool,
+    dedup: Option<&crate::DatabaseDriver>,
+) -> Result<Vec<String>, InklogError> {
+    let mut sqls = Vec::new();
+    let mut current = String::new();
+    let mut rows = 0;
+    let mut on_conflict = "";
+    for record in records {
+        let parts = build_insert_parts(
+            table_name,
+            record,
+            compress_fields,
+            derived_timestamps,
+            promoted_fields,
+            keep_promoted_fields,
+            dedup,
+        )?;
+        if rows > 0
+            && (rows >= MAX_ROWS_PER_INSERT
+                || current.len() + 2 + parts.values.len() + parts.on_conflict.len()
+                    > MAX_INSERT_STATEMENT_BYTES)
+        {
+            current.push_str(on_conflict);
+            sqls.push(std::mem::take(&mut current));
+            rows = 0;
+        }
+        if rows == 0 {
+            current.push_str(&parts.head);
+            current.push_str(" VALUES ");
+        } else {
+            current.push_str(", ");
+        }
+        current.push_str(&parts.values);
+        on_conflict = parts.on_conflict;
+        rows += 1;
+    }
+    if rows > 0 {
+        current.push_str(on_conflict);
+        sqls.push(current);
+    }
+    Ok(sqls)
+}
+
+/// 构建单条记录 INSERT 的语句头、VALUES 元组与冲突子句
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+fn build_insert_parts(
+    table_name: &str,
+    record: &LogRecord,
+    compress_fields: bool,
+    derived_timestamps: bool,
+    promoted_fields: &[String],
+    keep_promoted_fields: bool,
+    dedup: Option<&crate::DatabaseDriver>,
+) -> Result<InsertParts, InklogError> {
+    let timestamp = record.timestamp.to_rfc3339();
+    let level = &record.level;
+    let target = &record.target;
+    let message = record.message.replace('\'', "''");
+    let fields_json = if promoted_fields.is_empty() || keep_promoted_fields {
+        serde_json::to_string(&record.fields)
+    } else {
+        let remaining: std::collections::HashMap<_, _> = record
+            .fields
+            .iter()
+            .filter(|(key, _)| !promoted_fields.contains(key))
+            .collect();
+        serde_json::to_string(&remaining)
+    };
+    let mut fields_json = fields_json.unwrap_or_else(|_| "{}".to_string());
+    if compress_fields {
+        fields_json = compress_fields_column(&fields_json)?;
+    }
+    let fields_escaped = fields_json.replace('\'', "''");
+    let file = record
+        .file
+        .as_ref()
+        .map(|f| format!("'{}'", f.replace('\'', "''")))
+        .unwrap_or_else(|| "NULL".to_string());
+    let line = record
+        .line
+        .map(|l| l.to_string())
+        .unwrap_or_else(|| "NULL".to_string());
+    let thread_id = &record.thread_id;
+
+    let (derived_columns, derived_values) = if derived_timestamps {
+        let (epoch_ms, timestamp_str) = derived_timestamp_values(&record.timestamp);
+        (
+            ", timestamp_epoch_ms, timestamp_str".to_string(),
+            format!(", {}, '{}'", epoch_ms, timestamp_str),
+        )
+    } else {
+        (String::new(), String::new())
+    };
+    let (promoted_columns, promoted_values): (String, String) = promoted_fields
+        .iter()
+        .map(|field| {
+            (
+                format!(", {}", field),
+                format!(", {}", promoted_column_value(record.fields.get(field))),
+            )
+        })
+        .unzip();
+    let (insert, on_conflict) = match dedup {
+        Some(crate::DatabaseDriver::MySQL) => ("INSERT IGNORE", ""),
+        Some(_) => ("INSERT", " ON CONFLICT DO NOTHING"),
+        None => ("INSERT", ""),
+    };
+    let (dedup_column, dedup_value) = if dedup.is_some() {
+        (
+            ", dedup_key",
+            format!(
+                ", '{}'",
+                dedup_key(&timestamp, &record.thread_id, &record.message)
+            ),
+        )
+    } else {
+        ("", String::new())
+    };
+
+    Ok(InsertParts {
+        head: format!(
+            "{} INTO {} (timestamp, level, target, message, fields, file, line, thread_id{}{}{})",
+            insert, table_name, derived_columns, promoted_columns, dedup_column,
+        ),
+        values: format!(
+            "('{}', '{}', '{}', '{}', '{}', {}, {}, '{}'{}{}{})",
+            timestamp,
+            level,
+            target.replace('\'', "''"),
+            message,
+            fields_escaped,
+            file,
+            line,
+            thread_id.replace('\'', "''"),
+            derived_values,
+            promoted_values,
+            dedup_value,
+        ),
+        on_conflict,
+    })
+}
+
+/// 去重键：`timestamp`、`thread_id` 与 `message` 的 SHA-256（十六进制）
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+fn dedup_key(timestamp: &str, thread_id: &str, message: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    for part in [timestamp, thread_id, message] {
+        hasher.update(part.as_bytes());
+        hasher.update([0u8]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
 // ============================================================================
 // 非 dbnexus feature 时的占位实现
 // ============================================================================
@@ -370,6 +885,8 @@ use std::sync::{Arc, RwLock};
 pub struct MockDatabaseAdapter {
     /// 存储的日志记录
     records: RwLock<Vec<LogRecord>>,
+    /// 存储的 metrics 快照（时间, JSON）
+    metrics_snapshots: RwLock<Vec<(DateTime<Utc>, String)>>,
     /// 健康状态
     healthy: Arc<AtomicBool>,
 }
@@ -381,6 +898,7 @@ impl MockDatabaseAdapter {
     pub fn new() -> Self {
         Self {
             records: RwLock::new(Vec::new()),
+            metrics_snapshots: RwLock::new(Vec::new()),
             healthy: Arc::new(AtomicBool::new(true)),
         }
     }
@@ -410,11 +928,19 @@ impl MockDatabaseAdapter {
         self.records.read().unwrap().clone()
     }
 
+    /// 获取所有存储的 metrics 快照
+    ///
+    /// 返回 `(时间, JSON)` 的克隆，用于测试验证。
+    pub fn get_metrics_snapshots(&self) -> Vec<(DateTime<Utc>, String)> {
+        self.metrics_snapshots.read().unwrap().clone()
+    }
+
     /// 清空所有记录
     ///
     /// 用于测试重置状态。
     pub fn clear(&self) {
         self.records.write().unwrap().clear();
+        self.metrics_snapshots.write().unwrap().clear();
     }
 }
 
@@ -444,6 +970,19 @@ impl Database for MockDatabaseAdapter {
         Ok(records.len())
     }
 
+    async fn insert_metrics_snapshot(
+        &self,
+        _table: &str,
+        timestamp: DateTime<Utc>,
+        snapshot_json: &str,
+    ) -> Result<(), InklogError> {
+        self.metrics_snapshots
+            .write()
+            .unwrap()
+            .push((timestamp, snapshot_json.to_string()));
+        Ok(())
+    }
+
     async fn is_healthy(&self) -> bool {
         self.healthy.load(Ordering::SeqCst)
     }
@@ -462,27 +1001,45 @@ mod tests {
     // DbNexusAdapter 测试 (需要 feature)
     // ============================================================================
 
+    /// 测试用 logs 表结构
     #[cfg(feature = "sqlite")]
-    #[tokio::test]
-    async fn test_dbnexus_adapter_health_check() {
-        // 创建临时权限配置文件
-        let temp_dir = std::env::temp_dir();
-        let perm_path = temp_dir.join("inklog_health_perm.yaml");
-        let perm_content = r#"roles:
+    const LOGS_TABLE_DDL: &str = "CREATE TABLE IF NOT EXISTS logs (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp TEXT NOT NULL,
+        level TEXT NOT NULL,
+        target TEXT NOT NULL,
+        message TEXT NOT NULL,
+        fields TEXT,
+        file TEXT,
+        line INTEGER,
+        thread_id TEXT NOT NULL
+    )";
+
+    /// 临时目录中 SQLite 数据库的连接字符串
+    #[cfg(feature = "sqlite")]
+    fn sqlite_url(dir: &tempfile::TempDir) -> String {
+        format!("sqlite:{}?mode=rwc", dir.path().join("logs.db").display())
+    }
+
+    /// 在临时目录中创建 SQLite 连接池、admin 权限配置与 `create_table` 建立的
+    /// logs 表；返回的 `TempDir` 需在测试结束前保持存活
+    #[cfg(feature = "sqlite")]
+    async fn sqlite_adapter(create_table: &str) -> (tempfile::TempDir, DbNexusAdapter) {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let perm_path = dir.path().join("permissions.yaml");
+        std::fs::write(
+            &perm_path,
+            r#"roles:
   admin:
     tables:
       - name: "*"
         operations: ["select", "insert", "update", "delete"]
-"#;
-        std::fs::write(&perm_path, perm_content).expect("Failed to write permissions file");
-
-        // 创建 DbConfig（使用不同的数据库文件）
-        let db_path = temp_dir.join("inklog_health.db");
-        let db_url = format!("sqlite:{}?mode=rwc", db_path.to_string_lossy());
-
+"#,
+        )
+        .expect("Failed to write permissions file");
         let config = DbConfig {
-            url: db_url,
-            max_connections: 1,
+            url: sqlite_url(&dir),
+            max_connections: 2,
             min_connections: 1,
             idle_timeout: 300,
             acquire_timeout: 30000,
@@ -495,35 +1052,27 @@ mod tests {
             warmup_retries: 5,
             cache_config: dbnexus::foundation::config::CacheConfig::default(),
         };
-
         let pool = DbPool::with_config(config)
             .await
             .expect("Failed to create pool");
         let db = DbNexusAdapter::from_pool(pool, "logs");
-
-        // 创建表用于健康检查
         let session = db
             .pool
             .get_session("admin")
             .await
             .expect("Failed to get session");
         session
-            .execute_raw_ddl(
-                "CREATE TABLE IF NOT EXISTS logs (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                timestamp TEXT NOT NULL,
-                level TEXT NOT NULL,
-                target TEXT NOT NULL,
-                message TEXT NOT NULL,
-                fields TEXT,
-                file TEXT,
-                line INTEGER,
-                thread_id TEXT NOT NULL
-            )",
-            )
+            .execute_raw_ddl(create_table)
             .await
             .expect("Failed to create table");
         drop(session);
+        (dir, db)
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_dbnexus_adapter_health_check() {
+        let (_dir, db) = sqlite_adapter(LOGS_TABLE_DDL).await;
 
         // 直接测试健康检查逻辑 - 使用有效的表名进行查询
         let session = db
@@ -538,106 +1087,570 @@ mod tests {
             result.err()
         );
         drop(session);
+    }
 
-        drop(db);
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_dbnexus_adapter_insert_batch() {
+        let (_dir, db) = sqlite_adapter(LOGS_TABLE_DDL).await;
 
-        let _ = std::fs::remove_file(&perm_path);
-        let _ = std::fs::remove_file(&db_path);
+        let records = vec![LogRecord::new(
+            tracing::Level::INFO,
+            "test::module".to_string(),
+            "Test message".to_string(),
+        )];
+
+        let count = db.insert_batch(&records).await.expect("Failed to insert");
+        assert_eq!(count, 1);
+    }
+
+    #[cfg(not(any(feature = "sqlite", feature = "postgres", feature = "mysql")))]
+    #[allow(deprecated)]
+    #[tokio::test]
+    async fn test_dbnexus_adapter_not_available_without_feature() {
+        let result = DbNexusAdapter::new("test", 1).await;
+        assert!(result.is_err());
+        if let Err(InklogError::DatabaseError(_)) = result {
+            // Expected
+        } else {
+            panic!("Expected DatabaseError");
+        }
+    }
+
+    #[test]
+    fn test_derived_timestamp_values_match_primary_timestamp() {
+        let timestamp = DateTime::parse_from_rfc3339("2026-02-14T08:30:05.123456Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let (epoch_ms, timestamp_str) = derived_timestamp_values(&timestamp);
+        assert_eq!(epoch_ms, timestamp.timestamp_millis());
+        assert_eq!(timestamp_str, "2026-02-14 08:30:05.123");
+        assert_eq!(
+            DateTime::from_timestamp_millis(epoch_ms).unwrap(),
+            DateTime::parse_from_rfc3339("2026-02-14T08:30:05.123Z").unwrap()
+        );
+    }
+
+    /// 单条记录的 INSERT 语句
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    fn build_insert_sql(
+        table_name: &str,
+        record: &LogRecord,
+        compress_fields: bool,
+        derived_timestamps: bool,
+        promoted_fields: &[String],
+        keep_promoted_fields: bool,
+        dedup: Option<&crate::DatabaseDriver>,
+    ) -> Result<String, InklogError> {
+        let mut sqls = build_batch_insert_sqls(
+            table_name,
+            std::slice::from_ref(record),
+            compress_fields,
+            derived_timestamps,
+            promoted_fields,
+            keep_promoted_fields,
+            dedup,
+        )?;
+        assert_eq!(sqls.len(), 1);
+        Ok(sqls.remove(0))
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    #[test]
+    fn test_build_batch_insert_sqls_chunks_rows() {
+        let records: Vec<LogRecord> = (0..10_000)
+            .map(|i| LogRecord::new(Level::INFO, "bulk".to_string(), format!("record {}", i)))
+            .collect();
+        let sqls = build_batch_insert_sqls(
+            "logs",
+            &records,
+            false,
+            false,
+            &[],
+            false,
+            Some(&crate::DatabaseDriver::SQLite),
+        )
+        .unwrap();
+
+        assert!(sqls.len() >= 10_000 / MAX_ROWS_PER_INSERT);
+        for sql in &sqls {
+            assert!(sql.len() <= MAX_INSERT_STATEMENT_BYTES);
+            assert!(sql.matches("'bulk'").count() <= MAX_ROWS_PER_INSERT);
+            assert_eq!(sql.matches("INSERT INTO logs").count(), 1);
+            assert!(sql.ends_with(") ON CONFLICT DO NOTHING"));
+        }
+        let rows: usize = sqls.iter().map(|sql| sql.matches("'bulk'").count()).sum();
+        assert_eq!(rows, 10_000);
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    #[test]
+    fn test_build_batch_insert_sqls_splits_on_statement_size() {
+        let big = "x".repeat(MAX_INSERT_STATEMENT_BYTES / 3);
+        let records: Vec<LogRecord> = (0..4)
+            .map(|_| LogRecord::new(Level::INFO, "bulk".to_string(), big.clone()))
+            .collect();
+        let sqls =
+            build_batch_insert_sqls("logs", &records, false, false, &[], false, None).unwrap();
+
+        assert_eq!(sqls.len(), 2);
+        assert!(
+            sqls.iter()
+                .all(|sql| sql.len() <= MAX_INSERT_STATEMENT_BYTES)
+        );
+        assert!(
+            build_batch_insert_sqls("logs", &[], false, false, &[], false, None)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    #[test]
+    fn test_build_insert_sql_with_derived_timestamps() {
+        let record = LogRecord::new(Level::INFO, "app".to_string(), "it's up".to_string());
+        let (epoch_ms, timestamp_str) = derived_timestamp_values(&record.timestamp);
+
+        let sql = build_insert_sql("logs", &record, false, true, &[], false, None).unwrap();
+        assert!(sql.contains("thread_id, timestamp_epoch_ms, timestamp_str)"));
+        assert!(sql.contains(&record.timestamp.to_rfc3339()));
+        assert!(sql.ends_with(&format!(", {}, '{}')", epoch_ms, timestamp_str)));
+        assert!(sql.contains("it''s up"));
+
+        let plain = build_insert_sql("logs", &record, false, false, &[], false, None).unwrap();
+        assert!(!plain.contains("timestamp_epoch_ms"));
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    #[test]
+    fn test_build_insert_sql_with_promoted_fields() {
+        let mut record = LogRecord::new(Level::INFO, "app".to_string(), "handled".to_string());
+        record
+            .fields
+            .insert("request_id".to_string(), serde_json::json!("req-'7"));
+        record
+            .fields
+            .insert("status".to_string(), serde_json::json!(503));
+        record
+            .fields
+            .insert("user".to_string(), serde_json::json!("alice"));
+        let promoted = vec![
+            "request_id".to_string(),
+            "status".to_string(),
+            "tenant".to_string(),
+        ];
+
+        let sql = build_insert_sql("logs", &record, false, false, &promoted, false, None).unwrap();
+        assert!(sql.contains("thread_id, request_id, status, tenant)"));
+        assert!(sql.ends_with(", 'req-''7', '503', NULL)"));
+        // 提升的键移出 fields JSON，其余保留
+        assert!(sql.contains(r#"'{"user":"alice"}'"#));
+
+        let kept = build_insert_sql("logs", &record, false, false, &promoted, true, None).unwrap();
+        assert!(kept.contains(r#""request_id":"req-''7""#));
+        assert!(kept.ends_with(", 'req-''7', '503', NULL)"));
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    #[test]
+    fn test_build_insert_sql_with_dedup() {
+        use crate::DatabaseDriver;
+
+        let record = LogRecord::new(Level::INFO, "app".to_string(), "retry me".to_string());
+        let sqlite = build_insert_sql(
+            "logs",
+            &record,
+            false,
+            false,
+            &[],
+            false,
+            Some(&DatabaseDriver::SQLite),
+        )
+        .unwrap();
+        assert!(sqlite.starts_with("INSERT INTO logs"));
+        assert!(sqlite.contains("thread_id, dedup_key)"));
+        assert!(sqlite.ends_with(" ON CONFLICT DO NOTHING"));
+
+        let mysql = build_insert_sql(
+            "logs",
+            &record,
+            false,
+            false,
+            &[],
+            false,
+            Some(&DatabaseDriver::MySQL),
+        )
+        .unwrap();
+        assert!(mysql.starts_with("INSERT IGNORE INTO logs"));
+        assert!(!mysql.contains("ON CONFLICT"));
+
+        // 同一记录得到相同的键，消息不同则键不同
+        let key = dedup_key(
+            &record.timestamp.to_rfc3339(),
+            &record.thread_id,
+            &record.message,
+        );
+        assert_eq!(key.len(), 64);
+        assert!(sqlite.contains(&key));
+        assert!(mysql.contains(&key));
+        assert_ne!(
+            key,
+            dedup_key(&record.timestamp.to_rfc3339(), &record.thread_id, "other")
+        );
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    #[test]
+    fn test_create_dedup_index_sql_per_driver() {
+        use crate::DatabaseDriver;
+
+        assert_eq!(
+            create_index_sql(
+                Some(&DatabaseDriver::MySQL),
+                "logs",
+                "uniq_logs_dedup_key",
+                "dedup_key",
+                true,
+                false,
+            ),
+            "CREATE UNIQUE INDEX uniq_logs_dedup_key ON logs (dedup_key)"
+        );
+        for driver in [
+            Some(&DatabaseDriver::SQLite),
+            Some(&DatabaseDriver::PostgreSQL),
+            None,
+        ] {
+            assert_eq!(
+                create_index_sql(
+                    driver,
+                    "logs",
+                    "uniq_logs_dedup_key",
+                    "dedup_key",
+                    true,
+                    false
+                ),
+                "CREATE UNIQUE INDEX IF NOT EXISTS uniq_logs_dedup_key ON logs (dedup_key)"
+            );
+        }
+
+        // 已存在的列与索引被忽略，其余 DDL 错误需上报
+        assert!(is_existing_object_error(
+            "Execution Error: error returned from database: 1061 (42000): Duplicate key name 'uniq_logs_dedup_key'"
+        ));
+        assert!(is_existing_object_error(
+            "Execution Error: 1060 (42S21): Duplicate column name 'dedup_key'"
+        ));
+        assert!(is_existing_object_error("duplicate column name: dedup_key"));
+        assert!(is_existing_object_error(
+            "column \"dedup_key\" of relation \"logs\" already exists"
+        ));
+        assert!(!is_existing_object_error(
+            "1064 (42000): You have an error in your SQL syntax near 'IF NOT EXISTS'"
+        ));
+        assert!(!is_existing_object_error("no such table: logs"));
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+    #[test]
+    fn test_create_index_sql_prefixes_mysql_text_columns() {
+        use crate::DatabaseDriver;
+
+        let mysql = Some(&DatabaseDriver::MySQL);
+        assert_eq!(
+            create_index_sql(
+                mysql,
+                "logs",
+                "idx_logs_request_id",
+                "request_id",
+                false,
+                true
+            ),
+            "CREATE INDEX idx_logs_request_id ON logs (request_id(191))"
+        );
+        assert_eq!(
+            create_index_sql(mysql, "logs", "idx_logs_level", "level", false, false),
+            "CREATE INDEX idx_logs_level ON logs (level)"
+        );
+        // 前缀长度只用于 MySQL
+        assert_eq!(
+            create_index_sql(
+                Some(&DatabaseDriver::PostgreSQL),
+                "logs",
+                "idx_logs_request_id",
+                "request_id",
+                false,
+                true,
+            ),
+            "CREATE INDEX IF NOT EXISTS idx_logs_request_id ON logs (request_id)"
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    async fn count_logs(conn: &sea_orm::DatabaseConnection) -> i64 {
+        use sea_orm::{ConnectionTrait, DbBackend, Statement};
+
+        conn.query_one_raw(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT COUNT(*) AS n FROM logs",
+        ))
+        .await
+        .expect("count query")
+        .expect("count row")
+        .try_get::<i64>("", "n")
+        .expect("n")
     }
 
     #[cfg(feature = "sqlite")]
     #[tokio::test]
-    async fn test_dbnexus_adapter_insert_batch() {
-        // 创建临时权限配置文件
-        let temp_dir = std::env::temp_dir();
-        let perm_path = temp_dir.join("inklog_batch_perm.yaml");
-        let perm_content = r#"roles:
-  admin:
-    tables:
-      - name: "*"
-        operations: ["select", "insert", "update", "delete"]
-"#;
-        std::fs::write(&perm_path, perm_content).expect("Failed to write permissions file");
+    async fn test_dbnexus_adapter_bulk_insert_is_atomic() {
+        // CHECK 约束用于在第二个分块中制造失败
+        let (dir, db) = sqlite_adapter(&LOGS_TABLE_DDL.replace(
+            "message TEXT NOT NULL,",
+            "message TEXT NOT NULL CHECK (message <> 'poison'),",
+        ))
+        .await;
+        let conn = sea_orm::Database::connect(&sqlite_url(&dir))
+            .await
+            .expect("Failed to connect to database");
 
-        // 创建 DbConfig（使用不同的数据库文件）
-        let db_path = temp_dir.join("inklog_batch.db");
-        let db_url = format!("sqlite:{}?mode=rwc", db_path.to_string_lossy());
+        let records: Vec<LogRecord> = (0..10_000)
+            .map(|i| LogRecord::new(Level::INFO, "bulk".to_string(), format!("record {}", i)))
+            .collect();
+        let written = db.insert_batch(&records).await.expect("bulk insert");
+        assert_eq!(written, 10_000);
+        assert_eq!(count_logs(&conn).await, 10_000);
 
-        let config = DbConfig {
-            url: db_url,
-            max_connections: 2,
-            min_connections: 1,
-            idle_timeout: 300,
-            acquire_timeout: 30000,
-            permissions_path: Some(perm_path.to_string_lossy().to_string()),
-            migrations_dir: None,
-            auto_migrate: false,
-            migration_timeout: 60,
-            admin_role: "admin".to_string(),
-            warmup_timeout: 60,
-            warmup_retries: 5,
-            cache_config: dbnexus::foundation::config::CacheConfig::default(),
-        };
+        // 失败行位于第二个分块：整批回滚，第一个分块也不应保留
+        let mut poisoned: Vec<LogRecord> = records[..MAX_ROWS_PER_INSERT + 10].to_vec();
+        poisoned.push(LogRecord::new(
+            Level::INFO,
+            "bulk".to_string(),
+            "poison".to_string(),
+        ));
+        assert!(db.insert_batch(&poisoned).await.is_err());
+        assert_eq!(count_logs(&conn).await, 10_000);
+    }
 
-        let pool = DbPool::with_config(config)
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_dbnexus_adapter_dedup_skips_replayed_batch() {
+        let (dir, db) = sqlite_adapter(LOGS_TABLE_DDL).await;
+        let db = db.with_dedup(crate::DatabaseDriver::SQLite);
+
+        let records = vec![
+            LogRecord::new(Level::INFO, "app".to_string(), "first".to_string()),
+            LogRecord::new(Level::WARN, "app".to_string(), "second".to_string()),
+        ];
+        db.insert_batch(&records).await.expect("insert");
+        // 模拟部分提交后的重试：整批重放，外加一条新记录
+        let mut replay = records.clone();
+        replay.push(LogRecord::new(
+            Level::INFO,
+            "app".to_string(),
+            "third".to_string(),
+        ));
+        db.insert_batch(&replay).await.expect("replayed insert");
+
+        let conn = sea_orm::Database::connect(&sqlite_url(&dir))
             .await
-            .expect("Failed to create pool");
-        let db = DbNexusAdapter::from_pool(pool, "logs");
+            .expect("Failed to connect to database");
+        assert_eq!(count_logs(&conn).await, 3);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_dbnexus_adapter_promoted_fields_are_queryable() {
+        let (_dir, db) = sqlite_adapter(LOGS_TABLE_DDL).await;
+        let db = db.with_promoted_fields(vec!["request_id".to_string()], false);
+
+        let mut record = LogRecord::new(Level::INFO, "app".to_string(), "handled".to_string());
+        record
+            .fields
+            .insert("request_id".to_string(), serde_json::json!("req-1"));
+        let records = vec![
+            record,
+            LogRecord::new(Level::INFO, "app".to_string(), "no id".to_string()),
+        ];
+        let count = db.insert_batch(&records).await.expect("Failed to insert");
+        assert_eq!(count, 2);
 
-        // 创建 logs 表
+        // 提升列已补齐，可直接用于过滤
         let session = db
             .pool
             .get_session("admin")
             .await
             .expect("Failed to get session");
-        let create_result = session
-            .execute_raw_ddl(
-                "CREATE TABLE IF NOT EXISTS logs (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                timestamp TEXT NOT NULL,
-                level TEXT NOT NULL,
-                target TEXT NOT NULL,
-                message TEXT NOT NULL,
-                fields TEXT,
-                file TEXT,
-                line INTEGER,
-                thread_id TEXT NOT NULL
-            )",
-            )
+        let result = session
+            .execute_raw("SELECT message FROM logs WHERE request_id = 'req-1'")
             .await;
         assert!(
-            create_result.is_ok(),
-            "Failed to create table: {:?}",
-            create_result.err()
+            result.is_ok(),
+            "promoted column query failed: {:?}",
+            result.err()
         );
         drop(session);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_log_reader_decompresses_compressed_fields() {
+        use crate::sink::{LogQuery, LogReader};
+
+        // LogReader 需要 Model 的全部列
+        let (dir, db) = sqlite_adapter(&LOGS_TABLE_DDL.replace(
+            "thread_id TEXT NOT NULL",
+            "thread_id TEXT NOT NULL, module_path TEXT, metadata TEXT",
+        ))
+        .await;
+        let db = db.with_fields_compression(true);
+
+        let mut record = LogRecord::new(Level::INFO, "app".to_string(), "compressed".to_string());
+        record
+            .fields
+            .insert("user_id".to_string(), serde_json::json!(42));
+        assert_eq!(db.insert_batch(&[record]).await.expect("insert"), 1);
+
+        let conn = sea_orm::Database::connect(&sqlite_url(&dir))
+            .await
+            .expect("Failed to connect to database");
+        let logs = LogReader::new(conn, "logs")
+            .query(&LogQuery::new())
+            .await
+            .expect("query");
+        assert_eq!(logs.len(), 1);
+        let fields: serde_json::Value =
+            serde_json::from_str(logs[0].fields.as_deref().expect("fields column"))
+                .expect("fields should be plain JSON");
+        assert_eq!(fields["user_id"], 42);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_log_reader_filters_by_promoted_field() {
+        use crate::sink::{LogQuery, LogReader};
+
+        let (dir, db) = sqlite_adapter(&LOGS_TABLE_DDL.replace(
+            "thread_id TEXT NOT NULL",
+            "thread_id TEXT NOT NULL, module_path TEXT, metadata TEXT",
+        ))
+        .await;
+        let db = db.with_promoted_fields(vec!["request_id".to_string()], false);
+
+        let records: Vec<LogRecord> = ["req-1", "req-2", "req-1"]
+            .iter()
+            .enumerate()
+            .map(|(i, request_id)| {
+                let mut record = LogRecord::new(Level::INFO, "app".to_string(), format!("r{}", i));
+                record
+                    .fields
+                    .insert("request_id".to_string(), serde_json::json!(request_id));
+                record
+            })
+            .collect();
+        assert_eq!(db.insert_batch(&records).await.expect("insert"), 3);
+
+        let conn = sea_orm::Database::connect(&sqlite_url(&dir))
+            .await
+            .expect("Failed to connect to database");
+        let logs = LogReader::new(conn, "logs")
+            .query(&LogQuery::new().field_eq("request_id", "req-1"))
+            .await
+            .expect("query");
+        let mut messages: Vec<&str> = logs.iter().map(|m| m.message.as_str()).collect();
+        messages.sort_unstable();
+        assert_eq!(messages, vec!["r0", "r2"]);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_dbnexus_adapter_creates_configured_indexes() {
+        use sea_orm::{ConnectionTrait, DbBackend, Statement};
+
+        let (dir, db) = sqlite_adapter(LOGS_TABLE_DDL).await;
+        let db = db.with_indexes(crate::DatabaseSinkConfig::default().indexes);
 
         let records = vec![LogRecord::new(
-            tracing::Level::INFO,
-            "test::module".to_string(),
-            "Test message".to_string(),
+            Level::INFO,
+            "app".to_string(),
+            "indexed".to_string(),
         )];
+        assert_eq!(db.insert_batch(&records).await.expect("insert"), 1);
+        // 索引已存在时再次写入不应失败
+        assert_eq!(db.insert_batch(&records).await.expect("insert"), 1);
 
-        let count = db.insert_batch(&records).await.expect("Failed to insert");
-        assert_eq!(count, 1);
+        let conn = sea_orm::Database::connect(&sqlite_url(&dir))
+            .await
+            .expect("Failed to connect to database");
+        let rows = conn
+            .query_all_raw(Statement::from_string(
+                DbBackend::Sqlite,
+                "PRAGMA index_list(logs)",
+            ))
+            .await
+            .expect("PRAGMA index_list should succeed");
+        let names: Vec<String> = rows
+            .iter()
+            .map(|row| row.try_get::<String>("", "name").expect("name column"))
+            .collect();
+        for index in ["idx_logs_timestamp", "idx_logs_level", "idx_logs_target"] {
+            assert!(
+                names.iter().any(|name| name == index),
+                "missing {index}: {names:?}"
+            );
+        }
+    }
 
-        drop(db);
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_dbnexus_adapter_binds_metrics_snapshot_values() {
+        use sea_orm::{ConnectionTrait, DbBackend, Statement};
 
-        let _ = std::fs::remove_file(&perm_path);
-        let _ = std::fs::remove_file(&db_path);
+        let (dir, db) = sqlite_adapter(LOGS_TABLE_DDL).await;
+        let timestamp = Utc::now();
+        let snapshot = r#"{"note":"it's quoted'); DROP TABLE logs; --"}"#;
+        db.insert_metrics_snapshot("metrics_snapshot", timestamp, snapshot)
+            .await
+            .expect("snapshot insert");
+
+        let conn = sea_orm::Database::connect(&sqlite_url(&dir))
+            .await
+            .expect("Failed to connect to database");
+        let row = conn
+            .query_one_raw(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT timestamp, snapshot FROM metrics_snapshot",
+            ))
+            .await
+            .expect("query")
+            .expect("snapshot row");
+        assert_eq!(row.try_get::<String>("", "snapshot").unwrap(), snapshot);
+        let stored: DateTime<Utc> = row.try_get("", "timestamp").unwrap();
+        assert_eq!(stored, timestamp);
+        assert!(
+            conn.query_one_raw(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT COUNT(*) AS n FROM logs",
+            ))
+            .await
+            .is_ok()
+        );
     }
 
-    #[cfg(not(any(feature = "sqlite", feature = "postgres", feature = "mysql")))]
-    #[allow(deprecated)]
+    #[cfg(feature = "sqlite")]
     #[tokio::test]
-    async fn test_dbnexus_adapter_not_available_without_feature() {
-        let result = DbNexusAdapter::new("test", 1).await;
-        assert!(result.is_err());
-        if let Err(InklogError::DatabaseError(_)) = result {
-            // Expected
-        } else {
-            panic!("Expected DatabaseError");
-        }
+    async fn test_dbnexus_adapter_reports_index_ddl_failure() {
+        let (_dir, db) = sqlite_adapter(LOGS_TABLE_DDL).await;
+        let db = db.with_indexes(vec!["missing_column".to_string()]);
+
+        let records = vec![LogRecord::new(
+            Level::INFO,
+            "app".to_string(),
+            "indexed".to_string(),
+        )];
+        // DDL 失败时返回错误，且不标记为就绪，下次写入会重试
+        let err = db.insert_batch(&records).await.unwrap_err();
+        assert!(err.to_string().contains("missing_column"), "{err}");
+        assert!(db.insert_batch(&records).await.is_err());
     }
 
     // ============================================================================
@@ -154,11 +154,17 @@ pub mod integrations;
 
 // Re-export types from domain layer for backwards compatibility
 pub use domain::config::{
-    ChannelStrategy, ConsoleSinkConfig, DatabaseDriver, DatabaseSinkConfig, FileSinkConfig,
-    GlobalConfig, HttpAuthConfig, HttpErrorMode, HttpServerConfig, InklogConfig, ParquetConfig,
-    PartitionStrategy, PerformanceConfig,
+    AlertRuleConfig, ChannelStrategy, ColorMode, ConfigFormat, ConsoleSinkConfig, ControlCharMode,
+    DatabaseDriver, DatabaseSinkConfig, DedupConfig, ElasticsearchSinkConfig,
+    FileCompressionFormat, FileFormat, FileSinkConfig, GlobalConfig, HttpAuthConfig, HttpErrorMode,
+    HttpServerConfig, InklogConfig, LineDelimiter, LokiSinkConfig, MemoryRingSinkConfig,
+    MissingFeatureMode, NatsSinkConfig, OtlpProtocol, OtlpSinkConfig, OverflowPolicy,
+    ParquetCompression, ParquetConfig, ParquetFieldType, ParquetPromotedField, PartitionStrategy,
+    PerformanceConfig, SamplingConfig, SamplingMode, SamplingRuleConfig, SyslogFacility,
+    SyslogSinkConfig, SyslogTransport, TeeOutput,
 };
 pub use domain::db_provider::LogDbProvider;
+pub use domain::types::error_chain::{LogResultExt, error_chain};
 pub use domain::types::log_record::LogRecord;
 pub use error::InklogError;
 pub use error::InklogResult;
@@ -174,17 +180,23 @@ pub use integrations::DbNexusLogDbAdapter;
 pub use integrations::InklogModule;
 
 pub use domain::core::{
-    InklogContainer, InklogContainerBuilder, LoggerBuilder, LoggerDependencies, LoggerManager,
+    InklogContainer, InklogContainerBuilder, InklogLayer, LevelFilterLayer, LoggerBuilder,
+    LoggerDependencies, LoggerManager,
 };
 
 pub use log_level::{LogLevel, LogLevelParseError};
-pub use support::io::{LogAdapter, LogLogger};
+pub use support::io::{ConsoleCapture, LogAdapter, LogLogger};
+#[cfg(feature = "http")]
+pub use support::observability::access_log_middleware;
 pub use support::observability::{
-    FallbackConfig, FallbackState, GaugeF64, HealthStatus, Metrics, SinkHealthMonitor, SinkStatus,
+    ACCESS_LOG_TARGET, AccessLogEntry, AlertEvaluator, AlertEvent, FallbackConfig, FallbackState,
+    GaugeF64, HealthStatus, Metrics, MetricsSnapshot, ProcessStats, SinkHealthMonitor,
+    SinkLatencySnapshot, SinkStatus,
 };
 pub use support::processing::{
-    DataMasker, LogTemplate, ObjectPool, ObjectPoolConfig, get_log_record, get_string_buffer,
-    put_log_record, put_string_buffer,
+    DataMasker, DedupOutcome, LogDeduplicator, LogSampler, LogTemplate, ObjectPool,
+    ObjectPoolConfig, SAMPLING_SUMMARY_TARGET, get_log_record, get_string_buffer, put_log_record,
+    put_string_buffer,
 };
 pub use validation::{
     EscapeMode, LogSanitizer, PathValidator, PathValidatorConfig, SanitizerConfig, ValidationResult,
@@ -7,6 +7,7 @@
 
 use crate::LogRecord;
 use crate::Metrics;
+use crate::domain::core::ByteBudget;
 use chrono::Utc;
 use crossbeam_channel::Sender;
 use log::{Level, LevelFilter, Metadata, Record};
@@ -26,6 +27,8 @@ pub struct LogAdapter {
     async_sender: Sender<Arc<LogRecord>>,
     /// Metrics for monitoring
     metrics: Arc<Metrics>,
+    /// Optional byte budget for the async channel
+    byte_budget: Option<Arc<ByteBudget>>,
 }
 
 impl LogAdapter {
@@ -44,9 +47,16 @@ impl LogAdapter {
             console_sender,
             async_sender,
             metrics,
+            byte_budget: None,
         }
     }
 
+    /// 与 subscriber 共享异步 channel 的字节预算
+    pub fn with_byte_budget(mut self, budget: Arc<ByteBudget>) -> Self {
+        self.byte_budget = Some(budget);
+        self
+    }
+
     /// 将 `log::Level` 转换为字符串
     fn level_to_string(level: Level) -> &'static str {
         match level {
@@ -103,8 +113,24 @@ impl log::Log for LogAdapter {
             }
         }
 
+        // Byte budget: drop once the estimated queued bytes are exhausted
+        let record_bytes = log_record.estimated_size();
+        if let Some(budget) = &self.byte_budget
+            && !budget.try_acquire(record_bytes)
+        {
+            self.metrics.inc_channel_blocked();
+            self.metrics.inc_logs_dropped();
+            return;
+        }
+
         // Slow path: Async sinks (file, database, etc.) - drop on full to avoid blocking
-        match self.async_sender.try_send(log_record) {
+        let result = self.async_sender.try_send(log_record);
+        if result.is_err()
+            && let Some(budget) = &self.byte_budget
+        {
+            budget.release(record_bytes);
+        }
+        match result {
             Ok(_) => {}
             Err(crossbeam_channel::TrySendError::Full(_)) => {
                 self.metrics.inc_channel_blocked();
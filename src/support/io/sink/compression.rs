@@ -169,43 +169,48 @@ impl CompressionStrategy for GzipCompression {
     }
 
     fn compress_file(&self, path: &Path, level: i32) -> Result<PathBuf, InklogError> {
-        use flate2::Compression;
-        use flate2::write::GzEncoder;
+        let compressed_path = gzip_file(path, level)?;
+        let _ = std::fs::remove_file(path);
+        Ok(compressed_path)
+    }
+}
 
-        let compressed_path = path.with_extension("gz");
+/// Gzip-compresses `path` into `<path>.gz`, leaving the source file in place.
+pub(crate) fn gzip_file(path: &Path, level: i32) -> Result<PathBuf, InklogError> {
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
 
-        let input_file = File::open(path).map_err(|e| {
-            error!("Failed to open file for compression: {}", e);
-            InklogError::IoError(e)
-        })?;
+    let compressed_path = path.with_extension("gz");
 
-        let mut reader = BufReader::new(input_file);
-        let output_file = File::create(&compressed_path).map_err(|e| {
-            error!("Failed to create compressed file: {}", e);
-            InklogError::IoError(e)
-        })?;
+    let input_file = File::open(path).map_err(|e| {
+        error!("Failed to open file for compression: {}", e);
+        InklogError::IoError(e)
+    })?;
+
+    let mut reader = BufReader::new(input_file);
+    let output_file = File::create(&compressed_path).map_err(|e| {
+        error!("Failed to create compressed file: {}", e);
+        InklogError::IoError(e)
+    })?;
 
-        let level = level.clamp(0, 9) as u32;
-        let mut encoder = GzEncoder::new(output_file, Compression::new(level));
+    let level = level.clamp(0, 9) as u32;
+    let mut encoder = GzEncoder::new(output_file, Compression::new(level));
 
-        let mut buffer = [0u8; 8192];
-        loop {
-            let bytes_read = Read::read(&mut reader, &mut buffer)?;
-            if bytes_read == 0 {
-                break;
-            }
-            std::io::Write::write_all(&mut encoder, &buffer[..bytes_read])?;
+    let mut buffer = [0u8; 8192];
+    loop {
+        let bytes_read = Read::read(&mut reader, &mut buffer)?;
+        if bytes_read == 0 {
+            break;
         }
+        std::io::Write::write_all(&mut encoder, &buffer[..bytes_read])?;
+    }
 
-        encoder.finish().map_err(|e| {
-            error!("Failed to finish compression: {}", e);
-            InklogError::CompressionError(e.to_string())
-        })?;
-
-        let _ = std::fs::remove_file(path);
+    encoder.finish().map_err(|e| {
+        error!("Failed to finish compression: {}", e);
+        InklogError::CompressionError(e.to_string())
+    })?;
 
-        Ok(compressed_path)
-    }
+    Ok(compressed_path)
 }
 
 /// Internal function to compress a file using Zstd.
@@ -271,6 +276,70 @@ pub fn compress_string(data: &str, compression_level: i32) -> Result<Vec<u8>, In
     compress_data(data.as_bytes(), compression_level)
 }
 
+/// Marker prefix for zstd-compressed `fields` column values.
+pub const ZSTD_FIELDS_MARKER: &str = "zstd:";
+/// Marker prefix for gzip-compressed `fields` column values.
+pub const GZIP_FIELDS_MARKER: &str = "gzip:";
+
+/// Compress a serialized `fields` JSON string for storage in a database column.
+///
+/// The result is `<marker><base64 payload>`. Zstd is used when the `compression`
+/// feature is enabled, gzip otherwise.
+pub fn compress_fields_column(json: &str) -> Result<String, InklogError> {
+    use base64::{Engine as _, engine::general_purpose};
+
+    #[cfg(feature = "compression")]
+    let (marker, compressed) = (
+        ZSTD_FIELDS_MARKER,
+        ZstdCompression::default().compress(json.as_bytes())?,
+    );
+    #[cfg(not(feature = "compression"))]
+    let (marker, compressed) = (
+        GZIP_FIELDS_MARKER,
+        GzipCompression::default().compress(json.as_bytes())?,
+    );
+
+    Ok(format!(
+        "{}{}",
+        marker,
+        general_purpose::STANDARD.encode(compressed)
+    ))
+}
+
+/// Decode a `fields` column value written by [`compress_fields_column`].
+///
+/// Values without a compression marker are plain JSON and returned unchanged.
+pub fn decompress_fields_column(value: &str) -> Result<String, InklogError> {
+    use base64::{Engine as _, engine::general_purpose};
+
+    let decode = |payload: &str| {
+        general_purpose::STANDARD
+            .decode(payload)
+            .map_err(|e| InklogError::CompressionError(format!("Invalid fields payload: {}", e)))
+    };
+
+    let bytes = if let Some(payload) = value.strip_prefix(ZSTD_FIELDS_MARKER) {
+        #[cfg(feature = "compression")]
+        {
+            ZstdCompression::default().decompress(&decode(payload)?)?
+        }
+        #[cfg(not(feature = "compression"))]
+        {
+            let _ = decode(payload)?;
+            return Err(InklogError::CompressionError(
+                "zstd-compressed fields require the `compression` feature".to_string(),
+            ));
+        }
+    } else if let Some(payload) = value.strip_prefix(GZIP_FIELDS_MARKER) {
+        GzipCompression::default().decompress(&decode(payload)?)?
+    } else {
+        return Ok(value.to_string());
+    };
+
+    String::from_utf8(bytes)
+        .map_err(|e| InklogError::CompressionError(format!("Invalid UTF-8 in fields: {}", e)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -630,4 +699,51 @@ mod tests {
         assert!(compressed_path.exists());
         assert!(compressed_path.extension().is_some_and(|ext| ext == "zst"));
     }
+
+    #[test]
+    fn test_fields_column_round_trip() {
+        let json = serde_json::json!({
+            "user_id": 42,
+            "stack": "frame\n".repeat(200),
+            "nested": {"ok": true}
+        })
+        .to_string();
+
+        let encoded = compress_fields_column(&json).unwrap();
+        assert!(encoded.starts_with(ZSTD_FIELDS_MARKER) || encoded.starts_with(GZIP_FIELDS_MARKER));
+        assert!(encoded.len() < json.len());
+
+        let decoded = decompress_fields_column(&encoded).unwrap();
+        let original: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let round_trip: serde_json::Value = serde_json::from_str(&decoded).unwrap();
+        assert_eq!(original, round_trip);
+    }
+
+    #[test]
+    fn test_fields_column_plain_json_passthrough() {
+        let json = r#"{"key":"value"}"#;
+        assert_eq!(decompress_fields_column(json).unwrap(), json);
+    }
+
+    #[test]
+    fn test_fields_column_gzip_marker_decodes_without_feature() {
+        use base64::{Engine as _, engine::general_purpose};
+
+        let json = r#"{"a":1}"#;
+        let compressed = GzipCompression::default()
+            .compress(json.as_bytes())
+            .unwrap();
+        let encoded = format!(
+            "{}{}",
+            GZIP_FIELDS_MARKER,
+            general_purpose::STANDARD.encode(compressed)
+        );
+        assert_eq!(decompress_fields_column(&encoded).unwrap(), json);
+    }
+
+    #[test]
+    fn test_fields_column_invalid_payload_errors() {
+        let result = decompress_fields_column("gzip:not-base64!!");
+        assert!(matches!(result, Err(InklogError::CompressionError(_))));
+    }
 }
@@ -1,11 +1,14 @@
 // Copyright (c) 2026 Kirky.X
 // SPDX-License-Identifier: MIT
 use super::LogSink;
+use crate::ColorMode;
 use crate::ConsoleSinkConfig;
 use crate::DataMasker;
+use crate::FileFormat;
 use crate::InklogError;
 use crate::LogRecord;
 use crate::LogTemplate;
+use crate::TeeOutput;
 use async_trait::async_trait;
 use is_terminal::IsTerminal;
 use owo_colors::OwoColorize;
@@ -16,10 +19,77 @@ use std::sync::{Arc, Mutex};
 pub struct ConsoleSink {
     config: ConsoleSinkConfig,
     writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    /// stderr 级别的输出目标；`None` 表示直接写入进程 stderr
+    stderr_writer: Option<Arc<Mutex<Box<dyn Write + Send>>>>,
+    /// `tee_error_to` 的输出目标；`tee_levels` 中的记录会额外写入一份
+    tee_writer: Option<Arc<Mutex<Box<dyn Write + Send>>>>,
     template: LogTemplate,
     masker: DataMasker,
 }
 
+/// 控制台输出捕获句柄
+///
+/// 持有共享的 `Arc<Mutex<Vec<u8>>>` 缓冲区，配合 [`ConsoleSink::with_buffer`] 或
+/// `LoggerBuilder::capture_console()` 使用，便于测试中对渲染后的日志内容做确定性断言。
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleCapture {
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl ConsoleCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 基于调用方提供的缓冲区创建捕获句柄
+    pub fn from_buffer(buffer: Arc<Mutex<Vec<u8>>>) -> Self {
+        Self { buffer }
+    }
+
+    /// 返回底层共享缓冲区
+    pub fn buffer(&self) -> Arc<Mutex<Vec<u8>>> {
+        Arc::clone(&self.buffer)
+    }
+
+    /// 以 UTF-8（有损）字符串形式读取当前已捕获的输出
+    pub fn contents(&self) -> String {
+        match self.buffer.lock() {
+            Ok(buf) => String::from_utf8_lossy(&buf).into_owned(),
+            Err(poisoned) => String::from_utf8_lossy(&poisoned.into_inner()).into_owned(),
+        }
+    }
+
+    /// 按行读取当前已捕获的输出
+    pub fn lines(&self) -> Vec<String> {
+        self.contents().lines().map(str::to_string).collect()
+    }
+
+    /// 清空已捕获的输出
+    pub fn clear(&self) {
+        match self.buffer.lock() {
+            Ok(mut buf) => buf.clear(),
+            Err(poisoned) => poisoned.into_inner().clear(),
+        }
+    }
+}
+
+/// 将写入追加到共享缓冲区的 `Write` 实现
+struct SharedBufferWriter(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBufferWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .map_err(|_| io::Error::other("Lock poisoned"))?
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 impl fmt::Debug for ConsoleSink {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ConsoleSink")
@@ -31,9 +101,74 @@ impl fmt::Debug for ConsoleSink {
 
 impl ConsoleSink {
     pub fn new(config: ConsoleSinkConfig, template: LogTemplate) -> Self {
+        let tee_writer = Self::open_tee(&config);
         Self {
             config,
             writer: Arc::new(Mutex::new(Box::new(io::stdout()))),
+            stderr_writer: None,
+            tee_writer,
+            template,
+            masker: DataMasker::new(),
+        }
+    }
+
+    /// 使用自定义脱敏器（如 `global.masked_fields` 配置的拒绝列表），仅在 `masking_enabled` 时生效
+    pub fn with_masker(mut self, masker: DataMasker) -> Self {
+        self.masker = masker;
+        self
+    }
+
+    /// 打开 `tee_error_to` 指定的目标；文件无法打开时不启用 tee
+    fn open_tee(config: &ConsoleSinkConfig) -> Option<Arc<Mutex<Box<dyn Write + Send>>>> {
+        let writer: Box<dyn Write + Send> = match config.tee_error_to.as_ref()? {
+            TeeOutput::Stdout => Box::new(io::stdout()),
+            TeeOutput::Stderr => Box::new(io::stderr()),
+            TeeOutput::File(path) => {
+                if let Some(parent) = path.parent()
+                    && !parent.as_os_str().is_empty()
+                {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                match std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                {
+                    Ok(file) => Box::new(file),
+                    Err(e) => {
+                        eprintln!(
+                            "inklog: failed to open console tee file {}: {}",
+                            path.display(),
+                            e
+                        );
+                        return None;
+                    }
+                }
+            }
+        };
+        Some(Arc::new(Mutex::new(writer)))
+    }
+
+    /// 创建写入共享缓冲区的 ConsoleSink（主要用于测试捕获）
+    ///
+    /// stdout 与 stderr 级别的记录都会写入同一缓冲区，以保持输出顺序；
+    /// 捕获模式下不输出 ANSI 颜色码（`color = "always"` 除外），便于对内容做断言。
+    pub fn with_buffer(
+        mut config: ConsoleSinkConfig,
+        template: LogTemplate,
+        buffer: Arc<Mutex<Vec<u8>>>,
+    ) -> Self {
+        if config.color != ColorMode::Always {
+            config.colored = false;
+        }
+        let writer: Arc<Mutex<Box<dyn Write + Send>>> =
+            Arc::new(Mutex::new(Box::new(SharedBufferWriter(buffer))));
+        let tee_writer = Self::open_tee(&config);
+        Self {
+            config,
+            writer: Arc::clone(&writer),
+            stderr_writer: Some(writer),
+            tee_writer,
             template,
             masker: DataMasker::new(),
         }
@@ -45,24 +180,21 @@ impl ConsoleSink {
         record: &LogRecord,
         use_color: bool,
     ) -> io::Result<()> {
-        let formatted_message = self.template.render(record);
-
+        if self.config.output_format == FileFormat::Json {
+            // JSON 行供采集器解析，不着色
+            let line = serde_json::to_string(record).map_err(io::Error::other)?;
+            return writeln!(writer, "{}", line);
+        }
         if use_color {
-            let level_colored = match record.level.as_str() {
-                "ERROR" | "error" => record.level.red().to_string(),
-                "WARN" | "warn" => record.level.yellow().to_string(),
-                "INFO" | "info" => record.level.green().to_string(),
-                "DEBUG" | "debug" => record.level.blue().to_string(),
-                "TRACE" | "trace" => record.level.magenta().to_string(),
-                _ => record.level.clone(),
-            };
+            // 仅为级别标记着色
+            let level = self.apply_color(&record.level, &record.level);
             writeln!(
                 writer,
                 "{}",
-                self.apply_color(&formatted_message, &level_colored)
+                self.template.render_with_level(record, &level)
             )
         } else {
-            writeln!(writer, "{}", formatted_message)
+            writeln!(writer, "{}", self.template.render(record))
         }
     }
 
@@ -73,7 +205,7 @@ impl ConsoleSink {
             "INFO" | "info" => message.green().to_string(),
             "DEBUG" | "debug" => message.blue().to_string(),
             "TRACE" | "trace" => message.magenta().to_string(),
-            _ => message.green().to_string(),
+            _ => message.to_string(),
         }
     }
 
@@ -81,6 +213,11 @@ impl ConsoleSink {
         if !self.config.colored {
             return false;
         }
+        match self.config.color {
+            ColorMode::Always => return true,
+            ColorMode::Never => return false,
+            ColorMode::Auto => {}
+        }
 
         // NO_COLOR standard (https://no-color.org/)
         if std::env::var("NO_COLOR").is_ok() {
@@ -107,11 +244,9 @@ impl ConsoleSink {
             io::stdout().is_terminal()
         }
     }
-}
 
-#[async_trait]
-impl LogSink for ConsoleSink {
-    async fn write(&self, record: &LogRecord) -> Result<(), InklogError> {
+    /// 在调用线程上同步写入一条记录（同步控制台模式使用）
+    pub fn write_sync(&self, record: &LogRecord) -> Result<(), InklogError> {
         // 应用数据脱敏（如果启用）
         let masked_record = if self.config.masking_enabled {
             let mut masked = record.clone();
@@ -131,9 +266,17 @@ impl LogSink for ConsoleSink {
         let use_color = self.should_colorize(is_stderr);
 
         if is_stderr {
-            let mut stderr = io::stderr();
-            self.write_record(&mut stderr, &masked_record, use_color)
-                .map_err(InklogError::IoError)?;
+            if let Some(ref stderr_writer) = self.stderr_writer {
+                let mut writer = stderr_writer
+                    .lock()
+                    .map_err(|_| InklogError::IoError(io::Error::other("Lock poisoned")))?;
+                self.write_record(&mut *writer, &masked_record, use_color)
+                    .map_err(InklogError::IoError)?;
+            } else {
+                let mut stderr = io::stderr();
+                self.write_record(&mut stderr, &masked_record, use_color)
+                    .map_err(InklogError::IoError)?;
+            }
         } else {
             let mut writer = self
                 .writer
@@ -143,15 +286,50 @@ impl LogSink for ConsoleSink {
                 .map_err(InklogError::IoError)?;
         }
 
+        // Tee: 主输出之后再复制一份，不单独计入指标
+        if let Some(ref tee_writer) = self.tee_writer
+            && self
+                .config
+                .tee_levels
+                .contains(&masked_record.level.to_lowercase())
+        {
+            let mut writer = tee_writer
+                .lock()
+                .map_err(|_| InklogError::IoError(io::Error::other("Lock poisoned")))?;
+            self.write_record(&mut *writer, &masked_record, false)
+                .map_err(InklogError::IoError)?;
+        }
+
         Ok(())
     }
 
-    async fn flush(&self) -> Result<(), InklogError> {
+    /// 在调用线程上同步刷新输出
+    pub fn flush_sync(&self) -> Result<(), InklogError> {
         let mut writer = self
             .writer
             .lock()
             .map_err(|_| InklogError::IoError(io::Error::other("Lock poisoned")))?;
-        writer.flush().map_err(InklogError::IoError)
+        writer.flush().map_err(InklogError::IoError)?;
+        drop(writer);
+
+        if let Some(ref tee_writer) = self.tee_writer {
+            let mut writer = tee_writer
+                .lock()
+                .map_err(|_| InklogError::IoError(io::Error::other("Lock poisoned")))?;
+            writer.flush().map_err(InklogError::IoError)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LogSink for ConsoleSink {
+    async fn write(&self, record: &LogRecord) -> Result<(), InklogError> {
+        self.write_sync(record)
+    }
+
+    async fn flush(&self) -> Result<(), InklogError> {
+        self.flush_sync()
     }
 
     fn is_healthy(&self) -> bool {
@@ -169,6 +347,8 @@ impl Clone for ConsoleSink {
             config: self.config.clone(),
             // Clone shares the same writer (Arc ensures reference counting)
             writer: Arc::clone(&self.writer),
+            stderr_writer: self.stderr_writer.clone(),
+            tee_writer: self.tee_writer.clone(),
             template: self.template.clone(),
             masker: DataMasker::new(),
         }
@@ -507,7 +687,7 @@ mod tests {
         sink.write_record(&mut buf, &record, true).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("boom"));
-        assert!(output.contains("[ERROR]"));
+        assert!(output.contains(&format!("[{}]", "ERROR".red())));
         assert!(output.ends_with('\n'));
     }
 
@@ -519,7 +699,7 @@ mod tests {
         sink.write_record(&mut buf, &record, true).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("careful"));
-        assert!(output.contains("[WARN]"));
+        assert!(output.contains(&format!("[{}]", "WARN".yellow())));
     }
 
     #[test]
@@ -530,7 +710,7 @@ mod tests {
         sink.write_record(&mut buf, &record, true).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("details"));
-        assert!(output.contains("[DEBUG]"));
+        assert!(output.contains(&format!("[{}]", "DEBUG".blue())));
     }
 
     #[test]
@@ -541,13 +721,12 @@ mod tests {
         sink.write_record(&mut buf, &record, true).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("verbose"));
-        assert!(output.contains("[TRACE]"));
+        assert!(output.contains(&format!("[{}]", "TRACE".magenta())));
     }
 
     #[test]
     fn test_write_record_with_color_unknown_level() {
-        // Covers the `_ => record.level.clone()` fallback arm in write_record's
-        // level_colored match.
+        // Unknown levels fall through apply_color's `_` arm and stay uncolored.
         let sink = get_sink();
         let mut buf: Vec<u8> = Vec::new();
         let record = make_record("FATAL", "critical");
@@ -567,7 +746,7 @@ mod tests {
         sink.write_record(&mut buf, &record, true).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("lowercase boom"));
-        assert!(output.contains("[error]"));
+        assert!(output.contains(&format!("[{}]", "error".red())));
     }
 
     #[test]
@@ -800,6 +979,39 @@ mod tests {
     // Clone impl: config preserved, writer shared via Arc
     // ========================================================================
 
+    #[tokio::test]
+    async fn test_tee_error_to_file_duplicates_high_level_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let tee_path = dir.path().join("errors").join("console-errors.log");
+        let capture = crate::ConsoleCapture::new();
+        let sink = ConsoleSink::with_buffer(
+            ConsoleSinkConfig {
+                tee_error_to: Some(TeeOutput::File(tee_path.clone())),
+                ..Default::default()
+            },
+            LogTemplate::new("{level} {message}"),
+            capture.buffer(),
+        );
+
+        sink.write(&make_record("INFO", "routine")).await.unwrap();
+        sink.write(&make_record("ERROR", "disk failure"))
+            .await
+            .unwrap();
+        sink.write(&make_record("WARN", "slow query"))
+            .await
+            .unwrap();
+        sink.flush().await.unwrap();
+
+        // 主输出包含全部记录
+        assert_eq!(
+            capture.lines(),
+            vec!["INFO routine", "ERROR disk failure", "WARN slow query"]
+        );
+        // tee 目标只包含 tee_levels 中的记录
+        let teed = std::fs::read_to_string(&tee_path).unwrap();
+        assert_eq!(teed, "ERROR disk failure\nWARN slow query\n");
+    }
+
     #[test]
     fn test_console_sink_clone_preserves_config() {
         let config = ConsoleSinkConfig {
@@ -807,6 +1019,8 @@ mod tests {
             colored: true,
             stderr_levels: vec!["error".to_string(), "warn".to_string()],
             masking_enabled: true,
+            synchronous: false,
+            ..Default::default()
         };
         let sink = ConsoleSink::new(config, LogTemplate::default());
         let cloned = sink.clone();
@@ -870,7 +1084,9 @@ mod tests {
         sink.write_record(&mut buf, &record, true).unwrap();
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("info colored output"));
-        assert!(output.contains("[INFO]"));
+        assert!(output.contains(&format!("[{}]", "INFO".green())));
+        // 只有级别标记着色，消息保持原样
+        assert!(output.contains(" - info colored output\n"));
         assert!(
             output.ends_with('\n'),
             "writeln should append newline, got: {:?}",
@@ -918,4 +1134,135 @@ mod tests {
             );
         }
     }
+
+    // ========================================================================
+    // with_buffer / ConsoleCapture: 测试捕获
+    // ========================================================================
+
+    #[tokio::test]
+    async fn test_with_buffer_captures_rendered_output() {
+        let capture = ConsoleCapture::new();
+        let sink = ConsoleSink::with_buffer(
+            ConsoleSinkConfig {
+                enabled: true,
+                colored: true,
+                ..Default::default()
+            },
+            LogTemplate::default(),
+            capture.buffer(),
+        );
+        sink.write(&make_record("INFO", "captured info"))
+            .await
+            .unwrap();
+        sink.write(&make_record("ERROR", "captured error"))
+            .await
+            .unwrap();
+
+        let lines = capture.lines();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("captured info"));
+        // stderr 级别的记录同样进入捕获缓冲区，且顺序保持
+        assert!(lines[1].contains("captured error"));
+        // 捕获模式不输出 ANSI 颜色码
+        assert!(!capture.contents().contains("\x1b["));
+    }
+
+    #[tokio::test]
+    async fn test_color_mode_never_and_always() {
+        let render = |color: ColorMode| async move {
+            let capture = ConsoleCapture::new();
+            let sink = ConsoleSink::with_buffer(
+                ConsoleSinkConfig {
+                    color,
+                    ..Default::default()
+                },
+                LogTemplate::default(),
+                capture.buffer(),
+            );
+            sink.write(&make_record("WARN", "disk almost full"))
+                .await
+                .unwrap();
+            capture.contents()
+        };
+
+        let plain = render(ColorMode::Never).await;
+        assert!(plain.contains("[WARN] test::module - disk almost full"));
+        assert!(!plain.contains('\x1b'));
+
+        // Always 即使输出不是终端也着色，且只包裹级别标记
+        let colored = render(ColorMode::Always).await;
+        assert!(colored.contains(&format!("[{}]", "WARN".yellow())));
+        assert!(colored.contains(" test::module - disk almost full\n"));
+    }
+
+    #[tokio::test]
+    async fn test_json_output_format_nests_fields() {
+        let capture = ConsoleCapture::new();
+        let sink = ConsoleSink::with_buffer(
+            ConsoleSinkConfig {
+                output_format: FileFormat::Json,
+                color: ColorMode::Always,
+                ..Default::default()
+            },
+            LogTemplate::default(),
+            capture.buffer(),
+        );
+        let mut record = make_record("ERROR", "payment failed");
+        record
+            .fields
+            .insert("message".to_string(), serde_json::json!("from field"));
+        record
+            .fields
+            .insert("order_id".to_string(), serde_json::json!(42));
+        sink.write(&record).await.unwrap();
+
+        let lines = capture.lines();
+        assert_eq!(lines.len(), 1);
+        assert!(!lines[0].contains('\x1b'));
+        let value: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(value["level"], "ERROR");
+        assert_eq!(value["target"], "test::module");
+        assert_eq!(value["message"], "payment failed");
+        assert_eq!(value["fields"]["message"], "from field");
+        assert_eq!(value["fields"]["order_id"], 42);
+        assert!(value["timestamp"].is_string());
+        assert!(value.get("order_id").is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_color_mode_overrides_environment() {
+        unsafe {
+            env::set_var("NO_COLOR", "1");
+        }
+        let mut sink = get_sink();
+        sink.config.color = ColorMode::Always;
+        assert!(sink.should_colorize(false));
+        unsafe {
+            env::remove_var("NO_COLOR");
+            env::set_var("CLICOLOR_FORCE", "1");
+        }
+        sink.config.color = ColorMode::Never;
+        assert!(!sink.should_colorize(false));
+        unsafe {
+            env::remove_var("CLICOLOR_FORCE");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_console_capture_clear() {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let capture = ConsoleCapture::from_buffer(Arc::clone(&buffer));
+        let sink =
+            ConsoleSink::with_buffer(ConsoleSinkConfig::default(), LogTemplate::default(), buffer);
+        sink.write(&make_record("INFO", "first")).await.unwrap();
+        assert!(capture.contents().contains("first"));
+
+        capture.clear();
+        assert!(capture.contents().is_empty());
+
+        sink.write(&make_record("INFO", "second")).await.unwrap();
+        assert_eq!(capture.lines().len(), 1);
+        assert!(capture.contents().contains("second"));
+    }
 }
@@ -3,7 +3,6 @@
 //! DatabaseSink implementation details.
 
 use std::fmt;
-use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use std::time::{Duration, Instant};
@@ -66,12 +65,26 @@ impl DatabaseSink {
         database: Arc<dyn crate::integrations::infra::Database>,
         config: Option<crate::DatabaseSinkConfig>,
     ) -> Result<Self, InklogError> {
+        let fallback_path = config
+            .as_ref()
+            .map(|c| c.fallback_log_path.clone())
+            .unwrap_or_else(|| crate::DatabaseSinkConfig::default().fallback_log_path);
         let fallback_config = FileSinkConfig {
             enabled: true,
-            path: PathBuf::from("logs/db_fallback.log"),
+            path: fallback_path.clone(),
             ..Default::default()
         };
-        let fallback_sink = FileSink::new(fallback_config).ok();
+        let (fallback_sink, fallback_error) = match FileSink::new(fallback_config) {
+            Ok(sink) => (Some(sink), None),
+            Err(e) => (
+                None,
+                Some(format!(
+                    "Failed to create database fallback log {}: {}",
+                    fallback_path.display(),
+                    e
+                )),
+            ),
+        };
 
         // 使用配置参数或默认值
         let batch_size = config
@@ -96,9 +109,21 @@ impl DatabaseSink {
             database,
             masker: Arc::new(crate::DataMasker::new()),
             stop: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            fallback_error,
         })
     }
 
+    /// 降级文件无法创建时返回原因；此时数据库故障期间的记录将被丢弃
+    pub fn fallback_error(&self) -> Option<&str> {
+        self.fallback_error.as_deref()
+    }
+
+    /// 使用自定义脱敏器（如 `global.masked_fields` 配置的拒绝列表）
+    pub fn with_masker(mut self, masker: crate::DataMasker) -> Self {
+        self.masker = Arc::new(masker);
+        self
+    }
+
     pub async fn set_metrics(&self, metrics: Arc<Metrics>) {
         let mut inner = self.inner.lock().await;
         inner.metrics = Some(metrics);
@@ -148,10 +173,11 @@ impl crate::support::io::sink::LogSink for DatabaseSink {
             return Ok(());
         }
 
-        let masked_record = LogRecord {
+        let mut masked_record = LogRecord {
             message: self.masker.mask(&record.message),
             ..record.clone()
         };
+        self.masker.mask_hashmap(&mut masked_record.fields);
 
         inner.buffer.push(masked_record);
 
@@ -227,17 +253,105 @@ impl DatabaseSink {
     }
 }
 
+/// 按 `format`（json、ndjson、csv 或 parquet）将记录序列化为归档内容
+///
+/// `parquet_config` 仅在 `format` 为 parquet 时使用。
+pub fn convert_logs_to_archive(
+    logs: &[crate::LogRecord],
+    format: &str,
+    parquet_config: &crate::ParquetConfig,
+) -> Result<Vec<u8>, String> {
+    match format {
+        "json" => serde_json::to_vec(logs).map_err(|e| e.to_string()),
+        "ndjson" => convert_logs_to_ndjson(logs),
+        "csv" => convert_logs_to_csv(logs),
+        "parquet" => convert_logs_to_parquet(logs, parquet_config),
+        other => Err(format!("unsupported archive format '{}'", other)),
+    }
+}
+
+/// 归档格式对应的文件扩展名（不含压缩后缀）
+pub fn archive_file_extension(format: &str) -> Option<&'static str> {
+    match format {
+        "json" => Some("json"),
+        "ndjson" => Some("ndjson"),
+        "csv" => Some("csv"),
+        "parquet" => Some("parquet"),
+        _ => None,
+    }
+}
+
+/// Convert LogRecord to newline-delimited JSON, one object per line
+pub fn convert_logs_to_ndjson(logs: &[crate::LogRecord]) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    for log in logs {
+        serde_json::to_writer(&mut bytes, log).map_err(|e| e.to_string())?;
+        bytes.push(b'\n');
+    }
+    Ok(bytes)
+}
+
+/// CSV 的列，与 Parquet 的固定列一致；`fields` 以 JSON 文本写入单列
+const CSV_COLUMNS: &[&str] = &[
+    "id",
+    "timestamp",
+    "level",
+    "target",
+    "message",
+    "fields",
+    "file",
+    "line",
+    "thread_id",
+];
+
+/// Convert LogRecord to CSV (RFC 4180) with a header row
+pub fn convert_logs_to_csv(logs: &[crate::LogRecord]) -> Result<Vec<u8>, String> {
+    let mut out = String::new();
+    out.push_str(&CSV_COLUMNS.join(","));
+    out.push_str("\r\n");
+    for (index, log) in logs.iter().enumerate() {
+        let fields = serde_json::to_string(&log.fields).map_err(|e| e.to_string())?;
+        let row = [
+            (index + 1).to_string(),
+            log.timestamp.to_rfc3339(),
+            log.level.clone(),
+            log.target.clone(),
+            log.message.clone(),
+            fields,
+            log.file.clone().unwrap_or_default(),
+            log.line.map(|line| line.to_string()).unwrap_or_default(),
+            log.thread_id.clone(),
+        ];
+        let row: Vec<String> = row.iter().map(|value| csv_escape(value)).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    Ok(out.into_bytes())
+}
+
+/// 含逗号、引号或换行的值加双引号，内部引号加倍
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 /// Convert LogRecord to Parquet format
+///
+/// 压缩编码、字典编码、row group 行数、页大小与写入批大小取自 `config`。
+/// `config.promoted_fields` 中的字段各写入一个类型化列，并从 JSON `fields` 列中移除。
 #[cfg(feature = "parquet")]
 pub fn convert_logs_to_parquet(
     logs: &[crate::LogRecord],
-    _config: &crate::ParquetConfig,
+    config: &crate::ParquetConfig,
 ) -> Result<Vec<u8>, String> {
-    use arrow_array::{Date64Array, Int32Array, Int64Array, RecordBatch, StringArray};
+    use arrow_array::{Array, Date64Array, Int32Array, Int64Array, RecordBatch, StringArray};
     use arrow_schema::{DataType, Field, Schema};
     use std::sync::Arc;
 
-    let schema = Schema::new(vec![
+    let mut schema_fields = vec![
         Field::new("id", DataType::Int64, false),
         Field::new("timestamp", DataType::Date64, false),
         Field::new("level", DataType::Utf8, false),
@@ -247,7 +361,15 @@ pub fn convert_logs_to_parquet(
         Field::new("file", DataType::Utf8, true),
         Field::new("line", DataType::Int32, true),
         Field::new("thread_id", DataType::Utf8, true),
-    ]);
+    ];
+    schema_fields.extend(config.promoted_fields.iter().map(|promoted| {
+        Field::new(
+            promoted.name.as_str(),
+            parquet_field_data_type(promoted.data_type),
+            true,
+        )
+    }));
+    let schema = Schema::new(schema_fields);
 
     let ids: Vec<i64> = (1..=logs.len() as i64).collect();
     let timestamps: Vec<i64> = logs
@@ -259,7 +381,20 @@ pub fn convert_logs_to_parquet(
     let messages: Vec<&str> = logs.iter().map(|l| l.message.as_str()).collect();
     let fields: Vec<Option<String>> = logs
         .iter()
-        .map(|l| Some(serde_json::to_string(&l.fields).unwrap_or_default()))
+        .map(|l| {
+            let json = if config.promoted_fields.is_empty() {
+                serde_json::to_string(&l.fields)
+            } else {
+                let remaining: serde_json::Map<String, serde_json::Value> = l
+                    .fields
+                    .iter()
+                    .filter(|(key, _)| !config.promoted_fields.iter().any(|p| &p.name == *key))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                serde_json::to_string(&remaining)
+            };
+            Some(json.unwrap_or_default())
+        })
         .collect();
     let files: Vec<Option<&str>> = logs.iter().map(|l| l.file.as_deref()).collect();
     let lines: Vec<Option<i32>> = logs
@@ -268,31 +403,122 @@ pub fn convert_logs_to_parquet(
         .collect();
     let thread_ids: Vec<Option<&str>> = logs.iter().map(|l| Some(l.thread_id.as_str())).collect();
 
-    let batch = RecordBatch::try_new(
-        Arc::new(schema),
-        vec![
-            Arc::new(Int64Array::from(ids)) as Arc<dyn arrow_array::Array>,
-            Arc::new(Date64Array::from(timestamps)) as Arc<dyn arrow_array::Array>,
-            Arc::new(StringArray::from(levels)) as Arc<dyn arrow_array::Array>,
-            Arc::new(StringArray::from(targets)) as Arc<dyn arrow_array::Array>,
-            Arc::new(StringArray::from(messages)) as Arc<dyn arrow_array::Array>,
-            Arc::new(StringArray::from(fields)) as Arc<dyn arrow_array::Array>,
-            Arc::new(StringArray::from(files)) as Arc<dyn arrow_array::Array>,
-            Arc::new(Int32Array::from(lines)) as Arc<dyn arrow_array::Array>,
-            Arc::new(StringArray::from(thread_ids)) as Arc<dyn arrow_array::Array>,
-        ],
-    )
-    .map_err(|e| e.to_string())?;
+    let mut columns = vec![
+        Arc::new(Int64Array::from(ids)) as Arc<dyn Array>,
+        Arc::new(Date64Array::from(timestamps)) as Arc<dyn Array>,
+        Arc::new(StringArray::from(levels)) as Arc<dyn Array>,
+        Arc::new(StringArray::from(targets)) as Arc<dyn Array>,
+        Arc::new(StringArray::from(messages)) as Arc<dyn Array>,
+        Arc::new(StringArray::from(fields)) as Arc<dyn Array>,
+        Arc::new(StringArray::from(files)) as Arc<dyn Array>,
+        Arc::new(Int32Array::from(lines)) as Arc<dyn Array>,
+        Arc::new(StringArray::from(thread_ids)) as Arc<dyn Array>,
+    ];
+    columns.extend(
+        config
+            .promoted_fields
+            .iter()
+            .map(|promoted| promoted_field_column(logs, promoted)),
+    );
+
+    let batch = RecordBatch::try_new(Arc::new(schema), columns).map_err(|e| e.to_string())?;
 
     let mut bytes = Vec::new();
-    let mut writer = parquet::arrow::ArrowWriter::try_new(&mut bytes, batch.schema(), None)
-        .map_err(|e| e.to_string())?;
+    let mut writer = parquet::arrow::ArrowWriter::try_new(
+        &mut bytes,
+        batch.schema(),
+        Some(parquet_writer_properties(config)?),
+    )
+    .map_err(|e| e.to_string())?;
     writer.write(&batch).map_err(|e| e.to_string())?;
     writer.close().map_err(|e| e.to_string())?;
 
     Ok(bytes)
 }
 
+#[cfg(feature = "parquet")]
+fn parquet_field_data_type(field_type: crate::ParquetFieldType) -> arrow_schema::DataType {
+    use crate::ParquetFieldType;
+    use arrow_schema::DataType;
+
+    match field_type {
+        ParquetFieldType::Int64 => DataType::Int64,
+        ParquetFieldType::Float64 => DataType::Float64,
+        ParquetFieldType::Bool => DataType::Boolean,
+        ParquetFieldType::Utf8 => DataType::Utf8,
+    }
+}
+
+/// 取出每条记录中的提升字段构成一列；缺失或类型不符的值写为 null
+#[cfg(feature = "parquet")]
+fn promoted_field_column(
+    logs: &[crate::LogRecord],
+    promoted: &crate::ParquetPromotedField,
+) -> std::sync::Arc<dyn arrow_array::Array> {
+    use crate::ParquetFieldType;
+    use arrow_array::{BooleanArray, Float64Array, Int64Array, StringArray};
+    use std::sync::Arc;
+
+    let values = logs.iter().map(|l| l.fields.get(&promoted.name));
+    match promoted.data_type {
+        ParquetFieldType::Int64 => Arc::new(
+            values
+                .map(|v| v.and_then(serde_json::Value::as_i64))
+                .collect::<Int64Array>(),
+        ),
+        ParquetFieldType::Float64 => Arc::new(
+            values
+                .map(|v| v.and_then(serde_json::Value::as_f64))
+                .collect::<Float64Array>(),
+        ),
+        ParquetFieldType::Bool => Arc::new(
+            values
+                .map(|v| v.and_then(serde_json::Value::as_bool))
+                .collect::<BooleanArray>(),
+        ),
+        // 非字符串值按其 JSON 文本写入
+        ParquetFieldType::Utf8 => Arc::new(
+            values
+                .map(|v| match v {
+                    None | Some(serde_json::Value::Null) => None,
+                    Some(serde_json::Value::String(s)) => Some(s.clone()),
+                    Some(other) => Some(other.to_string()),
+                })
+                .collect::<StringArray>(),
+        ),
+    }
+}
+
+/// 按 `ParquetConfig` 构造 Parquet writer 参数
+#[cfg(feature = "parquet")]
+fn parquet_writer_properties(
+    config: &crate::ParquetConfig,
+) -> Result<parquet::file::properties::WriterProperties, String> {
+    use crate::ParquetCompression;
+    use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+
+    let compression = match config.effective_compression() {
+        ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+        ParquetCompression::Snappy => Compression::SNAPPY,
+        ParquetCompression::Zstd => Compression::ZSTD(
+            ZstdLevel::try_new(config.compression_level).map_err(|e| e.to_string())?,
+        ),
+        ParquetCompression::Gzip => {
+            let level = u32::try_from(config.compression_level)
+                .map_err(|_| format!("invalid gzip level {}", config.compression_level))?;
+            Compression::GZIP(GzipLevel::try_new(level).map_err(|e| e.to_string())?)
+        }
+    };
+
+    Ok(parquet::file::properties::WriterProperties::builder()
+        .set_compression(compression)
+        .set_dictionary_enabled(config.enable_dictionary)
+        .set_max_row_group_size(config.max_row_group_size.max(1))
+        .set_data_page_size_limit(config.max_page_size)
+        .set_write_batch_size(config.write_batch_size.max(1))
+        .build())
+}
+
 /// Convert LogRecord to Parquet format fallback (parquet feature not enabled).
 ///
 /// Returns an explicit error when the `parquet` feature is disabled, rather than
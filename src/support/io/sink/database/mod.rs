@@ -29,7 +29,14 @@ use crate::Metrics;
 #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
 mod database_impl;
 #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
-pub use database_impl::convert_logs_to_parquet;
+pub use database_impl::{
+    archive_file_extension, convert_logs_to_archive, convert_logs_to_csv, convert_logs_to_ndjson,
+    convert_logs_to_parquet,
+};
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+mod query;
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+pub use query::{LogQuery, LogReader};
 // Import constants for test access (tests use `use super::*;`)
 #[cfg(all(any(feature = "sqlite", feature = "postgres", feature = "mysql"), test))]
 use database_impl::{ADAPTIVE_WINDOW_SIZE, MAX_BATCH_SIZE, MIN_BATCH_SIZE};
@@ -59,6 +66,8 @@ pub struct DatabaseSink {
     masker: Arc<DataMasker>,
     /// 停止标志
     stop: Arc<AtomicBool>,
+    /// 降级文件创建失败的原因
+    fallback_error: Option<String>,
 }
 
 #[cfg(test)]
@@ -99,6 +108,102 @@ mod tests {
         assert!(!result.unwrap().is_empty());
     }
 
+    fn archive_logs() -> Vec<LogRecord> {
+        let mut tricky = LogRecord::new(
+            tracing::Level::WARN,
+            "app::db".to_string(),
+            "slow query, \"orders\"\nretrying".to_string(),
+        );
+        tricky
+            .fields
+            .insert("duration_ms".to_string(), serde_json::json!(1200));
+        tricky.line = Some(42);
+        vec![LogRecord::default(), tricky]
+    }
+
+    /// 最小的 RFC 4180 解析，仅用于校验输出
+    fn parse_csv(text: &str) -> Vec<Vec<String>> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut cell = String::new();
+        let mut quoted = false;
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (quoted, c) {
+                (true, '"') if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                }
+                (true, '"') => quoted = false,
+                (true, c) => cell.push(c),
+                (false, '"') => quoted = true,
+                (false, ',') => row.push(std::mem::take(&mut cell)),
+                (false, '\r') => {}
+                (false, '\n') => {
+                    row.push(std::mem::take(&mut cell));
+                    rows.push(std::mem::take(&mut row));
+                }
+                (false, c) => cell.push(c),
+            }
+        }
+        rows
+    }
+
+    #[test]
+    fn test_convert_logs_to_ndjson_one_object_per_line() {
+        let logs = archive_logs();
+        let bytes = convert_logs_to_archive(&logs, "ndjson", &Default::default()).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+
+        let parsed: Vec<LogRecord> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].message, logs[1].message);
+        assert_eq!(parsed[1].fields["duration_ms"], serde_json::json!(1200));
+    }
+
+    #[test]
+    fn test_convert_logs_to_csv_quotes_and_flattens() {
+        let logs = archive_logs();
+        let bytes = convert_logs_to_archive(&logs, "csv", &Default::default()).unwrap();
+        let rows = parse_csv(&String::from_utf8(bytes).unwrap());
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(
+            rows[0],
+            vec![
+                "id",
+                "timestamp",
+                "level",
+                "target",
+                "message",
+                "fields",
+                "file",
+                "line",
+                "thread_id"
+            ]
+        );
+        assert!(rows.iter().all(|row| row.len() == 9));
+        let row = &rows[2];
+        assert_eq!(row[0], "2");
+        assert_eq!(row[2], "WARN");
+        assert_eq!(row[4], "slow query, \"orders\"\nretrying");
+        let fields: serde_json::Value = serde_json::from_str(&row[5]).unwrap();
+        assert_eq!(fields, serde_json::json!({ "duration_ms": 1200 }));
+        assert_eq!(row[7], "42");
+        assert!(chrono::DateTime::parse_from_rfc3339(&row[1]).is_ok());
+    }
+
+    #[test]
+    fn test_archive_format_extensions() {
+        assert_eq!(archive_file_extension("ndjson"), Some("ndjson"));
+        assert_eq!(archive_file_extension("csv"), Some("csv"));
+        assert_eq!(archive_file_extension("xml"), None);
+        assert!(convert_logs_to_archive(&[], "xml", &Default::default()).is_err());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_database_sink_write_with_mock_db() {
         let mock_db = Arc::new(MockDatabaseAdapter::new());
@@ -120,6 +225,64 @@ mod tests {
         assert_eq!(mock_db.stored_count(), 1);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_database_sink_masks_nested_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let mock_db = Arc::new(MockDatabaseAdapter::new());
+        let config = DatabaseSinkConfig {
+            fallback_log_path: dir.path().join("db_down.log"),
+            ..Default::default()
+        };
+        let sink = DatabaseSink::new_with_config(mock_db.clone(), Some(config))
+            .unwrap()
+            .with_masker(crate::DataMasker::new().with_masked_fields(&["ssn", "token"]));
+
+        let mut record = LogRecord::default();
+        record.fields.insert(
+            "user".to_string(),
+            serde_json::json!({"ssn": "123-45-6789", "sessions": [{"Token": "t0k"}]}),
+        );
+        record
+            .fields
+            .insert("email".to_string(), serde_json::json!("alice@example.com"));
+        sink.write(&record).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let stored = mock_db.get_records();
+        assert_eq!(stored.len(), 1);
+        let fields = &stored[0].fields;
+        assert_eq!(fields["user"]["ssn"], "***MASKED***");
+        assert_eq!(fields["user"]["sessions"][0]["Token"], "***MASKED***");
+        assert_eq!(fields["email"], "**@**.***");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_database_sink_uses_configured_fallback_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let fallback_path = dir.path().join("db_down.log");
+        let config = DatabaseSinkConfig {
+            fallback_log_path: fallback_path.clone(),
+            ..Default::default()
+        };
+        let sink =
+            DatabaseSink::new_with_config(Arc::new(MockDatabaseAdapter::new()), Some(config))
+                .unwrap();
+        assert!(sink.fallback_error().is_none());
+        assert!(fallback_path.exists());
+
+        // 父路径是普通文件，降级文件无法创建
+        let blocker = dir.path().join("not_a_dir");
+        std::fs::write(&blocker, b"").unwrap();
+        let config = DatabaseSinkConfig {
+            fallback_log_path: blocker.join("db_down.log"),
+            ..Default::default()
+        };
+        let sink =
+            DatabaseSink::new_with_config(Arc::new(MockDatabaseAdapter::new()), Some(config))
+                .unwrap();
+        assert!(sink.fallback_error().unwrap().contains("not_a_dir"));
+    }
+
     /// 测试 new() 方法（不传 config，使用默认配置）
     #[tokio::test(flavor = "multi_thread")]
     async fn test_database_sink_new_without_config() {
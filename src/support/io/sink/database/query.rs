@@ -0,0 +1,228 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! Read path for logs written by the database sink.
+//!
+//! [`LogQuery`] describes a filter and [`LogReader`] runs it against the log
+//! table through the SeaORM [`Entity`](crate::sink::entity::Entity).
+
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::{Alias, Expr, ExprTrait, Order, Query};
+use sea_orm::{DatabaseConnection, EntityTrait, Iterable};
+use tracing::Level;
+
+use crate::InklogError;
+use crate::sink::compression::decompress_fields_column;
+use crate::sink::entity::{Column, Entity, Model};
+
+/// 日志查询条件
+///
+/// 所有条件可选，未设置的条件不参与过滤；结果按时间倒序返回。
+///
+/// # 示例
+///
+/// ```ignore
+/// let query = LogQuery::new()
+///     .min_level(Level::WARN)
+///     .target_contains("http")
+///     .field_eq("request_id", "req-1")
+///     .since(Utc::now() - chrono::Duration::hours(1))
+///     .limit(100);
+/// let logs = reader.query(&query).await?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LogQuery {
+    min_level: Option<Level>,
+    target_contains: Option<String>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    field_equals: Vec<(String, String)>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+impl LogQuery {
+    /// 创建不带任何条件的查询
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 仅返回不低于 `level` 的记录（如 `WARN` 包含 `WARN` 与 `ERROR`）
+    pub fn min_level(mut self, level: Level) -> Self {
+        self.min_level = Some(level);
+        self
+    }
+
+    /// 仅返回 target 包含 `pattern` 的记录（SQL `LIKE`，`%` 与 `_` 按通配符处理）
+    pub fn target_contains(mut self, pattern: impl Into<String>) -> Self {
+        self.target_contains = Some(pattern.into());
+        self
+    }
+
+    /// 仅返回时间戳不早于 `since` 的记录
+    pub fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// 仅返回时间戳早于 `until` 的记录
+    pub fn until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// 仅返回提升列 `column` 等于 `value` 的记录
+    ///
+    /// `column` 须为 `database_sink.promoted_fields` 中的字段；可多次调用，
+    /// 各条件同时满足。
+    pub fn field_eq(mut self, column: impl Into<String>, value: impl Into<String>) -> Self {
+        self.field_equals.push((column.into(), value.into()));
+        self
+    }
+
+    /// 最多返回 `limit` 条记录
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// 跳过前 `offset` 条记录
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// `min_level` 及更严重的级别名称
+    fn levels(&self) -> Option<Vec<&'static str>> {
+        self.min_level.map(|min| {
+            [
+                Level::TRACE,
+                Level::DEBUG,
+                Level::INFO,
+                Level::WARN,
+                Level::ERROR,
+            ]
+            .into_iter()
+            .filter(|level| *level <= min)
+            .map(|level| level.as_str())
+            .collect()
+        })
+    }
+}
+
+/// 日志表读取器
+///
+/// 与 [`DatabaseSink`](crate::DatabaseSink) 写入同一张表；表须包含
+/// [`Model`] 的全部列。
+pub struct LogReader {
+    conn: DatabaseConnection,
+    table_name: String,
+}
+
+impl LogReader {
+    /// 使用现有连接创建读取器
+    pub fn new(conn: DatabaseConnection, table_name: &str) -> Self {
+        Self {
+            conn,
+            table_name: table_name.to_string(),
+        }
+    }
+
+    /// 连接 `url` 并读取 `table_name`
+    ///
+    /// # 错误
+    ///
+    /// - `InklogError::DatabaseError` - 连接失败
+    pub async fn connect(url: &str, table_name: &str) -> Result<Self, InklogError> {
+        let conn = sea_orm::Database::connect(url)
+            .await
+            .map_err(|e| InklogError::DatabaseError(format!("Failed to connect: {}", e)))?;
+        Ok(Self::new(conn, table_name))
+    }
+
+    /// 按 `database_sink` 配置的 `url` 与 `table_name` 创建读取器
+    pub async fn from_config(config: &crate::DatabaseSinkConfig) -> Result<Self, InklogError> {
+        Self::connect(&config.url, &config.table_name).await
+    }
+
+    /// 获取表名
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    /// 执行查询，按时间倒序返回匹配的记录
+    ///
+    /// # 错误
+    ///
+    /// - `InklogError::DatabaseError` - 查询或结果解码失败
+    /// - `InklogError::CompressionError` - 压缩的 fields 列无法还原
+    pub async fn query(&self, query: &LogQuery) -> Result<Vec<Model>, InklogError> {
+        let mut select = Query::select();
+        select
+            .columns(Column::iter())
+            .from(Alias::new(self.table_name.as_str()));
+        if let Some(levels) = query.levels() {
+            select.and_where(Expr::col(Column::Level).is_in(levels));
+        }
+        if let Some(pattern) = &query.target_contains {
+            select.and_where(Expr::col(Column::Target).like(format!("%{}%", pattern)));
+        }
+        // 时间戳以 UTC 写入，绑定 DateTime<Utc> 时各后端均按同一格式比较
+        if let Some(since) = query.since {
+            select.and_where(Expr::col(Column::Timestamp).gte(since));
+        }
+        if let Some(until) = query.until {
+            select.and_where(Expr::col(Column::Timestamp).lt(until));
+        }
+        // 提升列为 TEXT，列名经标识符转义
+        for (column, value) in &query.field_equals {
+            select.and_where(Expr::col(Alias::new(column.as_str())).eq(value.as_str()));
+        }
+        select
+            .order_by(Column::Timestamp, Order::Desc)
+            .order_by(Column::Id, Order::Desc);
+        if let Some(limit) = query.limit {
+            select.limit(limit);
+        }
+        if let Some(offset) = query.offset {
+            select.offset(offset);
+        }
+
+        let statement = self.conn.get_database_backend().build(&select);
+        let rows = Entity::find()
+            .from_raw_sql(statement)
+            .all(&self.conn)
+            .await
+            .map_err(|e| InklogError::DatabaseError(format!("Log query failed: {}", e)))?;
+
+        // `compress_fields` 写入的 fields 列带压缩标记，返回前还原为 JSON
+        rows.into_iter()
+            .map(|mut model| {
+                if let Some(fields) = model.fields.take() {
+                    model.fields = Some(decompress_fields_column(&fields)?);
+                }
+                Ok(model)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_level_includes_more_severe_levels() {
+        assert_eq!(LogQuery::new().levels(), None);
+        assert_eq!(
+            LogQuery::new().min_level(Level::WARN).levels(),
+            Some(vec!["WARN", "ERROR"])
+        );
+        assert_eq!(
+            LogQuery::new()
+                .min_level(Level::TRACE)
+                .levels()
+                .map(|l| l.len()),
+            Some(5)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! 磁盘空间查询
+//!
+//! Unix 上使用 `statfs`，Windows 上使用 `GetDiskFreeSpaceExW`，
+//! 统一返回 `(total, available)` 字节数；低空间判定逻辑由各平台共享。
+
+use std::io;
+use std::path::Path;
+
+#[cfg(windows)]
+unsafe extern "system" {
+    fn GetDiskFreeSpaceExW(
+        directory_name: *const u16,
+        free_bytes_available: *mut u64,
+        total_bytes: *mut u64,
+        total_free_bytes: *mut u64,
+    ) -> i32;
+}
+
+/// 始终保留的最小可用空间
+const MIN_RESERVED_BYTES: u64 = 50 * 1024 * 1024;
+
+/// 查询 `dir` 所在文件系统的 `(total, available)` 字节数
+///
+/// `available` 为当前进程可用的字节数（不含仅 root 可用的保留块）。
+#[cfg(unix)]
+pub(crate) fn disk_space(dir: &Path) -> io::Result<(u64, u64)> {
+    let stat = nix::sys::statfs::statfs(dir).map_err(io::Error::from)?;
+    let block_size = stat.block_size() as u64;
+    Ok((
+        stat.blocks() * block_size,
+        stat.blocks_available() * block_size,
+    ))
+}
+
+/// 查询 `dir` 所在文件系统的 `(total, available)` 字节数
+///
+/// `available` 为调用者可用的字节数（考虑磁盘配额）。
+#[cfg(windows)]
+pub(crate) fn disk_space(dir: &Path) -> io::Result<(u64, u64)> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let mut wide_path: Vec<u16> = dir.as_os_str().encode_wide().collect();
+    wide_path.push(0);
+    let mut free_bytes_available: u64 = 0;
+    let mut total_bytes: u64 = 0;
+    let mut total_free_bytes: u64 = 0;
+    let result = unsafe {
+        GetDiskFreeSpaceExW(
+            wide_path.as_ptr(),
+            &mut free_bytes_available,
+            &mut total_bytes,
+            &mut total_free_bytes,
+        )
+    };
+    if result == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok((total_bytes, free_bytes_available))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn disk_space(_dir: &Path) -> io::Result<(u64, u64)> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "disk space query is not supported on this platform",
+    ))
+}
+
+/// 可用空间是否充足：保留 50MB 或 10% 的可用空间，以较大者为准
+pub(crate) fn has_sufficient_space(available: u64) -> bool {
+    available > MIN_RESERVED_BYTES.max(available / 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_disk_space_reports_totals_on_unix() {
+        let dir = tempfile::tempdir().unwrap();
+        let (total, available) = disk_space(dir.path()).unwrap();
+        assert!(total > 0);
+        assert!(available <= total);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_disk_space_reports_totals_on_windows() {
+        let dir = tempfile::tempdir().unwrap();
+        let (total, available) = disk_space(dir.path()).unwrap();
+        assert!(total > 0);
+        assert!(available <= total);
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn test_disk_space_missing_directory_is_error() {
+        assert!(disk_space(Path::new("/nonexistent_root_path_xyz/logs")).is_err());
+    }
+
+    #[test]
+    fn test_has_sufficient_space_keeps_minimum_reserve() {
+        assert!(!has_sufficient_space(0));
+        assert!(!has_sufficient_space(MIN_RESERVED_BYTES));
+        assert!(has_sufficient_space(MIN_RESERVED_BYTES + 1));
+        assert!(has_sufficient_space(10 * 1024 * 1024 * 1024));
+    }
+}
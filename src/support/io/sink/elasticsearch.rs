@@ -0,0 +1,536 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! Elasticsearch sink implementation.
+//!
+//! Batches log records and indexes them through the `_bulk` API, one index per
+//! `index_pattern` expansion of the record timestamp. Items the bulk response
+//! reports as failed with a retryable status (429 / 5xx) are resent on their
+//! own; everything that cannot be indexed goes to the console fallback.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use super::{CircuitBreaker, ConsoleSink, LogSink, masked_record};
+use crate::DataMasker;
+use crate::ElasticsearchSinkConfig;
+use crate::InklogError;
+use crate::LogRecord;
+
+/// 单次 `_bulk` 请求的超时
+const BULK_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Format `pattern` with `timestamp` to get the target index name.
+///
+/// # 错误
+///
+/// - `InklogError::ConfigError` - `pattern` 含无效的 strftime 说明符
+pub fn elasticsearch_index(
+    pattern: &str,
+    timestamp: &DateTime<Utc>,
+) -> Result<String, InklogError> {
+    use std::fmt::Write;
+    let mut index = String::new();
+    write!(index, "{}", timestamp.format(pattern)).map_err(|_| {
+        InklogError::ConfigError(format!("Invalid Elasticsearch index pattern: {}", pattern))
+    })?;
+    Ok(index)
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkResponse {
+    #[serde(default)]
+    errors: bool,
+    #[serde(default)]
+    items: Vec<HashMap<String, BulkItem>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkItem {
+    status: u16,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+/// bulk 响应中失败的条目
+struct FailedItem {
+    /// 在请求中的位置
+    position: usize,
+    status: u16,
+    reason: String,
+}
+
+impl FailedItem {
+    fn is_retryable(&self) -> bool {
+        self.status == 429 || self.status >= 500
+    }
+}
+
+enum Auth {
+    None,
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    ApiKey(String),
+}
+
+fn read_secret(env: &str) -> Result<String, InklogError> {
+    std::env::var(env).map_err(|_| {
+        InklogError::ConfigError(format!(
+            "Elasticsearch credential not found in environment variable: {}",
+            env
+        ))
+    })
+}
+
+struct ElasticsearchSinkInner {
+    buffer: Vec<LogRecord>,
+    last_flush: Instant,
+    circuit_breaker: CircuitBreaker,
+}
+
+/// Sink that indexes log records into Elasticsearch.
+pub struct ElasticsearchSink {
+    inner: Mutex<ElasticsearchSinkInner>,
+    client: reqwest::Client,
+    bulk_url: String,
+    auth: Auth,
+    config: ElasticsearchSinkConfig,
+    fallback: Option<ConsoleSink>,
+    masker: Option<DataMasker>,
+}
+
+impl ElasticsearchSink {
+    /// 创建 ElasticsearchSink；认证凭据从配置的环境变量中读取
+    ///
+    /// # 错误
+    ///
+    /// - `InklogError::ConfigError` - 配置无效或凭据环境变量不存在
+    pub fn new(config: ElasticsearchSinkConfig) -> Result<Self, InklogError> {
+        config.validate()?;
+
+        let auth = if let Some(ref env) = config.api_key_env {
+            Auth::ApiKey(read_secret(env)?)
+        } else if let Some(ref username) = config.username {
+            Auth::Basic {
+                username: username.clone(),
+                password: config
+                    .password_env
+                    .as_deref()
+                    .map(read_secret)
+                    .transpose()?,
+            }
+        } else {
+            Auth::None
+        };
+
+        let client = reqwest::Client::builder()
+            .timeout(BULK_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| {
+                InklogError::ConfigError(format!("Failed to build Elasticsearch client: {}", e))
+            })?;
+
+        Ok(Self {
+            inner: Mutex::new(ElasticsearchSinkInner {
+                buffer: Vec::with_capacity(config.batch_size),
+                last_flush: Instant::now(),
+                circuit_breaker: CircuitBreaker::new(3, Duration::from_secs(30), 3),
+            }),
+            client,
+            bulk_url: format!("{}/_bulk", config.url.trim_end_matches('/')),
+            auth,
+            config,
+            fallback: None,
+            masker: None,
+        })
+    }
+
+    /// 索引失败时将记录写入控制台
+    pub fn with_console_fallback(mut self, sink: ConsoleSink) -> Self {
+        self.fallback = Some(sink);
+        self
+    }
+
+    /// 索引前用 `masker` 脱敏 message 与 fields，回退输出同样使用脱敏后的记录
+    pub fn with_masker(mut self, masker: DataMasker) -> Self {
+        self.masker = Some(masker);
+        self
+    }
+
+    async fn write_fallback<'a>(&self, records: impl IntoIterator<Item = &'a LogRecord>) {
+        if let Some(ref sink) = self.fallback {
+            for record in records {
+                let _ = sink.write(record).await;
+            }
+        }
+    }
+
+    /// 构建 NDJSON 格式的 bulk 请求体：每条记录一行 action、一行文档
+    fn bulk_body(&self, records: &[LogRecord]) -> Result<Vec<u8>, InklogError> {
+        let mut body = Vec::new();
+        for record in records {
+            let index = elasticsearch_index(&self.config.index_pattern, &record.timestamp)?;
+            serde_json::to_writer(
+                &mut body,
+                &serde_json::json!({ "index": { "_index": index } }),
+            )?;
+            body.push(b'\n');
+            serde_json::to_writer(&mut body, record)?;
+            body.push(b'\n');
+        }
+        Ok(body)
+    }
+
+    /// 发送一次 bulk 请求，返回响应中失败的条目
+    async fn send_bulk(&self, records: &[LogRecord]) -> Result<Vec<FailedItem>, InklogError> {
+        let to_err = |e: String| {
+            InklogError::IoError(std::io::Error::other(format!(
+                "Elasticsearch bulk request failed: {}",
+                e
+            )))
+        };
+
+        let mut request = self
+            .client
+            .post(&self.bulk_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+            .body(self.bulk_body(records)?);
+        request = match self.auth {
+            Auth::None => request,
+            Auth::Basic {
+                ref username,
+                ref password,
+            } => request.basic_auth(username, password.as_deref()),
+            Auth::ApiKey(ref key) => {
+                request.header(reqwest::header::AUTHORIZATION, format!("ApiKey {}", key))
+            }
+        };
+
+        let response = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| to_err(e.to_string()))?;
+        let bulk: BulkResponse = response.json().await.map_err(|e| to_err(e.to_string()))?;
+        if !bulk.errors {
+            return Ok(Vec::new());
+        }
+
+        Ok(bulk
+            .items
+            .into_iter()
+            .enumerate()
+            .filter_map(|(position, item)| {
+                let item = item.into_values().next()?;
+                (item.status >= 300).then(|| FailedItem {
+                    position,
+                    status: item.status,
+                    reason: item.error.map(|e| e.to_string()).unwrap_or_default(),
+                })
+            })
+            .collect())
+    }
+
+    async fn flush_inner(&self, inner: &mut ElasticsearchSinkInner) -> Result<(), InklogError> {
+        if inner.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let mut pending = std::mem::take(&mut inner.buffer);
+        inner.last_flush = Instant::now();
+        let mut rejected = 0usize;
+        let mut attempt = 0u32;
+
+        loop {
+            let failed = match self.send_bulk(&pending).await {
+                Ok(failed) => failed,
+                Err(e) => {
+                    inner.circuit_breaker.record_failure();
+                    self.write_fallback(&pending).await;
+                    return Err(e);
+                }
+            };
+            if failed.is_empty() {
+                break;
+            }
+
+            // 不可重试的条目（如 mapping 错误）直接回退
+            let (retryable, permanent): (Vec<_>, Vec<_>) =
+                failed.into_iter().partition(FailedItem::is_retryable);
+            if let Some(item) = permanent.first() {
+                eprintln!(
+                    "Elasticsearch rejected {} bulk items (status {}): {}",
+                    permanent.len(),
+                    item.status,
+                    item.reason
+                );
+            }
+            rejected += permanent.len();
+            let rejected_records: Vec<&LogRecord> = permanent
+                .iter()
+                .map(|item| &pending[item.position])
+                .collect();
+            self.write_fallback(rejected_records).await;
+
+            if retryable.is_empty() {
+                break;
+            }
+            if attempt >= self.config.max_retries {
+                inner.circuit_breaker.record_failure();
+                let exhausted: Vec<&LogRecord> = retryable
+                    .iter()
+                    .map(|item| &pending[item.position])
+                    .collect();
+                self.write_fallback(exhausted).await;
+                return Err(InklogError::IoError(std::io::Error::other(format!(
+                    "{} Elasticsearch bulk items still failing after {} retries",
+                    retryable.len(),
+                    attempt
+                ))));
+            }
+
+            attempt += 1;
+            pending = retryable
+                .iter()
+                .map(|item| pending[item.position].clone())
+                .collect();
+            tokio::time::sleep(Duration::from_millis(100 << attempt.min(6))).await;
+        }
+
+        inner.circuit_breaker.record_success();
+        if rejected > 0 {
+            return Err(InklogError::IoError(std::io::Error::other(format!(
+                "Elasticsearch rejected {} bulk items",
+                rejected
+            ))));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LogSink for ElasticsearchSink {
+    async fn write(&self, record: &LogRecord) -> Result<(), InklogError> {
+        let record = masked_record(record, self.masker.as_ref());
+        let mut inner = self.inner.lock().await;
+
+        if !inner.circuit_breaker.can_execute() {
+            self.write_fallback([record.as_ref()]).await;
+            return Ok(());
+        }
+
+        inner.buffer.push(record.into_owned());
+
+        if inner.buffer.len() >= self.config.batch_size.max(1)
+            || inner.last_flush.elapsed() > Duration::from_millis(self.config.flush_interval_ms)
+        {
+            self.flush_inner(&mut inner).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<(), InklogError> {
+        let mut inner = self.inner.lock().await;
+        self.flush_inner(&mut inner).await
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.inner
+            .try_lock()
+            .map(|inner| inner.circuit_breaker.can_execute())
+            .unwrap_or(true)
+    }
+
+    async fn shutdown(&self) -> Result<(), InklogError> {
+        let mut inner = self.inner.lock().await;
+        self.flush_inner(&mut inner).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConsoleCapture;
+    use crate::ConsoleSinkConfig;
+    use crate::template::LogTemplate;
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    fn record(message: &str) -> LogRecord {
+        let mut record = LogRecord::new(
+            tracing::Level::INFO,
+            "app::http".to_string(),
+            message.to_string(),
+        );
+        record.timestamp = Utc.with_ymd_and_hms(2026, 3, 7, 12, 0, 0).unwrap();
+        record
+    }
+
+    /// 启动模拟 `_bulk` 端点：记录每次请求体，并按 `responder` 返回的状态码逐条响应
+    async fn mock_bulk_server(
+        responder: impl Fn(usize, usize) -> u16 + Send + Sync + 'static,
+    ) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        let requests = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        let requests_clone = requests.clone();
+        let responder = Arc::new(responder);
+        let app = axum::Router::new().route(
+            "/_bulk",
+            axum::routing::post(move |body: String| {
+                let requests = requests_clone.clone();
+                let responder = responder.clone();
+                async move {
+                    let call = {
+                        let mut requests = requests.lock().unwrap();
+                        requests.push(body.clone());
+                        requests.len() - 1
+                    };
+                    let statuses: Vec<u16> = (0..body.lines().count() / 2)
+                        .map(|item| responder(call, item))
+                        .collect();
+                    let items: Vec<serde_json::Value> = statuses
+                        .iter()
+                        .map(|&status| {
+                            let mut item = serde_json::json!({ "status": status });
+                            if status >= 300 {
+                                item["error"] = serde_json::json!({ "type": "rejected" });
+                            }
+                            serde_json::json!({ "index": item })
+                        })
+                        .collect();
+                    axum::Json(serde_json::json!({
+                        "errors": statuses.iter().any(|&status| status >= 300),
+                        "items": items,
+                    }))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock bulk server");
+        let addr = listener.local_addr().expect("local addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        (format!("http://{}", addr), requests)
+    }
+
+    fn sink_config(url: String) -> ElasticsearchSinkConfig {
+        ElasticsearchSinkConfig {
+            enabled: true,
+            url,
+            batch_size: 10,
+            flush_interval_ms: 60_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_elasticsearch_index_uses_record_date() {
+        let timestamp = Utc.with_ymd_and_hms(2026, 3, 7, 23, 59, 59).unwrap();
+        assert_eq!(
+            elasticsearch_index("logs-%Y.%m.%d", &timestamp).unwrap(),
+            "logs-2026.03.07"
+        );
+        assert!(elasticsearch_index("logs-%Q", &timestamp).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bulk_payload_shape() {
+        let (url, requests) = mock_bulk_server(|_, _| 201).await;
+        let sink = ElasticsearchSink::new(sink_config(url)).unwrap();
+
+        sink.write(&record("first")).await.unwrap();
+        sink.write(&record("second")).await.unwrap();
+        assert!(requests.lock().unwrap().is_empty());
+        sink.flush().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let lines: Vec<serde_json::Value> = requests[0]
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert!(requests[0].ends_with('\n'));
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["index"]["_index"], "logs-2026.03.07");
+        assert_eq!(lines[1]["message"], "first");
+        assert_eq!(lines[1]["target"], "app::http");
+        assert_eq!(lines[2]["index"]["_index"], "logs-2026.03.07");
+        assert_eq!(lines[3]["message"], "second");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_documents_are_masked() {
+        let (url, requests) = mock_bulk_server(|_, _| 201).await;
+        let sink = ElasticsearchSink::new(sink_config(url))
+            .unwrap()
+            .with_masker(DataMasker::new());
+
+        let mut login = record("login for alice@example.com");
+        login
+            .fields
+            .insert("password".to_string(), serde_json::json!("hunter2"));
+        sink.write(&login).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        let document: serde_json::Value =
+            serde_json::from_str(requests[0].lines().nth(1).unwrap()).unwrap();
+        assert_eq!(document["fields"]["password"], "***MASKED***");
+        assert!(!requests[0].contains("hunter2"));
+        assert!(!requests[0].contains("alice@example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_only_failed_items_are_retried() {
+        // 第一次请求中第 2 条返回 429，重试时全部成功
+        let (url, requests) =
+            mock_bulk_server(|call, item| if call == 0 && item == 1 { 429 } else { 201 }).await;
+        let sink = ElasticsearchSink::new(sink_config(url)).unwrap();
+
+        for message in ["a", "b", "c"] {
+            sink.write(&record(message)).await.unwrap();
+        }
+        sink.flush().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].lines().count(), 6);
+        let retried: Vec<serde_json::Value> = requests[1]
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(retried.len(), 2);
+        assert_eq!(retried[1]["message"], "b");
+    }
+
+    #[tokio::test]
+    async fn test_rejected_items_fall_back_to_console() {
+        let (url, requests) = mock_bulk_server(|_, item| if item == 0 { 400 } else { 201 }).await;
+        let capture = ConsoleCapture::new();
+        let console = ConsoleSink::with_buffer(
+            ConsoleSinkConfig::default(),
+            LogTemplate::default(),
+            capture.buffer(),
+        );
+        let sink = ElasticsearchSink::new(sink_config(url))
+            .unwrap()
+            .with_console_fallback(console);
+
+        sink.write(&record("unmappable")).await.unwrap();
+        sink.write(&record("indexed")).await.unwrap();
+        assert!(sink.flush().await.is_err());
+
+        // 400 不重试
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert!(capture.contents().contains("unmappable"));
+        assert!(!capture.contents().contains("indexed"));
+        assert!(sink.is_healthy());
+    }
+}
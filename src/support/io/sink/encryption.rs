@@ -3,14 +3,375 @@
 //! 加密相关工具模块
 //!
 //! 提供文件加密所需的密钥派生和加密功能
+//!
+//! ## 分块加密格式（版本 2）
+//!
+//! ```text
+//! "ENCLOG1\0" | version: u16 LE = 2 | algorithm: u16 LE = 1 | chunk_size: u32 LE
+//!     | key_id_len: u8 | key_id: [u8; key_id_len] | base_nonce: [u8; 12]
+//! frame 0 | frame 1 | ... | frame n
+//! ```
+//!
+//! 明文按 `chunk_size` 切分，每块单独以 AES-256-GCM 加密为一帧（密文 + 16 字节标签），
+//! 只有最后一帧可以短于 `chunk_size + 16`。第 `i` 帧的 nonce 为 `base_nonce`
+//! 低 8 字节与 `i`（大端）异或；附加数据为文件头加一个“是否最后一帧”字节，
+//! 因此帧被重排、截断或头部被篡改都会导致解密失败。
+//! 加解密都只需保留一帧的数据，适合 GB 级的轮转文件。
+//!
+//! `key_id` 标识加密所用的密钥（不含密钥本身），密钥轮换后解密方据此从多个密钥中选择。
 
 use crate::InklogError;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
 use base64::{Engine as _, engine::general_purpose};
 use pbkdf2::pbkdf2_hmac;
 use rand::Rng;
 use sha2::Sha256;
+use std::io::{BufRead, BufReader, Read, Write};
 use zeroize::Zeroizing;
 
+/// 加密文件头部魔数
+pub const ENCRYPTED_FILE_MAGIC: &[u8; 8] = b"ENCLOG1\0";
+
+/// 分块加密格式的版本号；版本 1 为整体加密的单块格式
+pub const CHUNKED_FORMAT_VERSION: u16 = 2;
+
+/// AES-256-GCM 的算法标识
+pub const ALGORITHM_AES_256_GCM: u16 = 1;
+
+/// 默认的明文块大小
+pub const DEFAULT_ENCRYPTION_CHUNK_SIZE: u32 = 64 * 1024;
+
+/// 密钥标识的最大字节数
+pub const MAX_KEY_ID_LEN: usize = u8::MAX as usize;
+
+/// 头部中 `key_id` 之前的定长部分：魔数 8 + 版本 2 + 算法 2 + 块大小 4 + 标识长度 1
+const FIXED_HEADER_LEN: usize = 17;
+
+/// 解密时接受的最大块大小，防止伪造的头部导致超大内存分配
+const MAX_ENCRYPTION_CHUNK_SIZE: u32 = 64 * 1024 * 1024;
+
+/// AES-GCM 认证标签长度
+const TAG_LEN: usize = 16;
+
+/// 带标识的 256 位加密密钥
+///
+/// 标识写入加密文件头部，解密时据此在多个密钥中选择；密钥内容在释放时清零。
+#[derive(Clone)]
+pub struct EncryptionKey {
+    id: String,
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl EncryptionKey {
+    /// 使用给定标识与密钥创建，标识超过 [`MAX_KEY_ID_LEN`] 字节时返回错误
+    pub fn new(id: impl Into<String>, key: [u8; 32]) -> Result<Self, InklogError> {
+        let id = id.into();
+        if id.len() > MAX_KEY_ID_LEN {
+            return Err(InklogError::ConfigError(format!(
+                "Encryption key id must be at most {} bytes, got {}",
+                MAX_KEY_ID_LEN,
+                id.len()
+            )));
+        }
+        Ok(Self {
+            id,
+            key: Zeroizing::new(key),
+        })
+    }
+
+    /// 密钥标识
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 密钥内容
+    pub fn bytes(&self) -> &[u8; 32] {
+        &self.key
+    }
+}
+
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptionKey")
+            .field("id", &self.id)
+            .field("key", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// 第 `index` 帧的 nonce：基础 nonce 的低 8 字节与帧序号异或
+fn frame_nonce(base_nonce: &[u8; 12], index: u64) -> [u8; 12] {
+    let mut nonce = *base_nonce;
+    for (byte, counter) in nonce[4..].iter_mut().zip(index.to_be_bytes()) {
+        *byte ^= counter;
+    }
+    nonce
+}
+
+/// 帧的附加数据：文件头 + 是否最后一帧
+fn frame_aad(header: &[u8], last: bool) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(header.len() + 1);
+    aad.extend_from_slice(header);
+    aad.push(u8::from(last));
+    aad
+}
+
+/// 读取直到填满 `buf` 或遇到 EOF，返回读取的字节数
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn new_cipher(key: &EncryptionKey) -> Result<Aes256Gcm, InklogError> {
+    Aes256Gcm::new_from_slice(key.bytes())
+        .map_err(|e| InklogError::EncryptionError(format!("Invalid key: {}", e)))
+}
+
+fn invalid_header() -> InklogError {
+    InklogError::EncryptionError(
+        "Invalid file header: not a chunked inklog encrypted file".to_string(),
+    )
+}
+
+/// 以分块格式（版本 2）加密 `input` 并写入 `output`，头部记录 `key` 的标识
+///
+/// 每次只在内存中保留一个 `chunk_size` 大小的明文块。
+pub fn encrypt_chunked(
+    key: &EncryptionKey,
+    input: impl Read,
+    output: &mut impl Write,
+    chunk_size: u32,
+) -> Result<(), InklogError> {
+    if chunk_size == 0 || chunk_size > MAX_ENCRYPTION_CHUNK_SIZE {
+        return Err(InklogError::EncryptionError(format!(
+            "Invalid encryption chunk size: {}",
+            chunk_size
+        )));
+    }
+    let cipher = new_cipher(key)?;
+
+    let mut base_nonce = [0u8; 12];
+    rand::rng().fill_bytes(&mut base_nonce);
+    let mut header = Vec::with_capacity(FIXED_HEADER_LEN + key.id.len() + 12);
+    header.extend_from_slice(ENCRYPTED_FILE_MAGIC);
+    header.extend_from_slice(&CHUNKED_FORMAT_VERSION.to_le_bytes());
+    header.extend_from_slice(&ALGORITHM_AES_256_GCM.to_le_bytes());
+    header.extend_from_slice(&chunk_size.to_le_bytes());
+    header.push(key.id.len() as u8);
+    header.extend_from_slice(key.id.as_bytes());
+    header.extend_from_slice(&base_nonce);
+    output.write_all(&header)?;
+
+    let mut input = BufReader::new(input);
+    let mut chunk = Zeroizing::new(vec![0u8; chunk_size as usize]);
+    for index in 0u64.. {
+        let len = read_full(&mut input, &mut chunk)?;
+        let last = len < chunk.len() || input.fill_buf()?.is_empty();
+        let aad = frame_aad(&header, last);
+        let frame = cipher
+            .encrypt(
+                &Nonce::from(frame_nonce(&base_nonce, index)),
+                Payload {
+                    msg: &chunk[..len],
+                    aad: &aad,
+                },
+            )
+            .map_err(|e| InklogError::EncryptionError(e.to_string()))?;
+        output.write_all(&frame)?;
+        if last {
+            break;
+        }
+    }
+    output.flush()?;
+    Ok(())
+}
+
+/// 解密分块格式（版本 2）的 `input`，逐帧将明文写入 `output`
+///
+/// 按头部中的密钥标识从 `keys` 中选择密钥；标识为空或没有同名密钥时依次尝试
+/// 全部密钥，使用第一个能通过首帧认证的密钥。每一帧通过认证后才写出；文件被截断
+/// 或篡改时返回错误，此前已写出的帧仍然是可信的明文。
+pub fn decrypt_chunked(
+    keys: &[EncryptionKey],
+    input: impl Read,
+    output: &mut impl Write,
+) -> Result<(), InklogError> {
+    let mut input = BufReader::new(input);
+    let mut header = vec![0u8; FIXED_HEADER_LEN];
+    if read_full(&mut input, &mut header)? < FIXED_HEADER_LEN
+        || &header[..8] != ENCRYPTED_FILE_MAGIC
+    {
+        return Err(invalid_header());
+    }
+    let version = u16::from_le_bytes([header[8], header[9]]);
+    if version != CHUNKED_FORMAT_VERSION {
+        return Err(InklogError::EncryptionError(format!(
+            "Unsupported file version: {}",
+            version
+        )));
+    }
+    let algorithm = u16::from_le_bytes([header[10], header[11]]);
+    if algorithm != ALGORITHM_AES_256_GCM {
+        return Err(InklogError::EncryptionError(format!(
+            "Unsupported encryption algorithm: {}",
+            algorithm
+        )));
+    }
+    let chunk_size = u32::from_le_bytes([header[12], header[13], header[14], header[15]]);
+    if chunk_size == 0 || chunk_size > MAX_ENCRYPTION_CHUNK_SIZE {
+        return Err(InklogError::EncryptionError(format!(
+            "Invalid encryption chunk size: {}",
+            chunk_size
+        )));
+    }
+    let key_id_len = header[16] as usize;
+    header.resize(FIXED_HEADER_LEN + key_id_len + 12, 0);
+    if read_full(&mut input, &mut header[FIXED_HEADER_LEN..])? < key_id_len + 12 {
+        return Err(invalid_header());
+    }
+    let key_id = String::from_utf8_lossy(&header[FIXED_HEADER_LEN..FIXED_HEADER_LEN + key_id_len])
+        .into_owned();
+    let base_nonce: [u8; 12] = header[FIXED_HEADER_LEN + key_id_len..]
+        .try_into()
+        .expect("nonce slice must be 12 bytes");
+
+    let mut candidates: Vec<&EncryptionKey> = keys.iter().filter(|key| key.id == key_id).collect();
+    if candidates.is_empty() {
+        candidates = keys.iter().collect();
+    }
+    if candidates.is_empty() {
+        return Err(InklogError::EncryptionError(
+            "No decryption key provided".to_string(),
+        ));
+    }
+
+    let mut cipher = None;
+    let mut frame = vec![0u8; chunk_size as usize + TAG_LEN];
+    for index in 0u64.. {
+        let len = read_full(&mut input, &mut frame)?;
+        if len < TAG_LEN {
+            return Err(InklogError::EncryptionError(
+                "Encrypted file is truncated".to_string(),
+            ));
+        }
+        let last = len < frame.len() || input.fill_buf()?.is_empty();
+        let aad = frame_aad(&header, last);
+        let nonce = Nonce::from(frame_nonce(&base_nonce, index));
+        let payload = || Payload {
+            msg: &frame[..len],
+            aad: &aad,
+        };
+        let decrypted = match &cipher {
+            Some(cipher) => Aead::decrypt(cipher, &nonce, payload()).ok(),
+            // 首帧：选出能通过认证的密钥
+            None => candidates.iter().find_map(|key| {
+                let candidate = new_cipher(key).ok()?;
+                let plaintext = candidate.decrypt(&nonce, payload()).ok()?;
+                cipher = Some(candidate);
+                Some(plaintext)
+            }),
+        };
+        let plaintext = Zeroizing::new(decrypted.ok_or_else(|| {
+            InklogError::EncryptionError(format!(
+                "Decryption failed at frame {} (key id '{}'): wrong key or corrupted file",
+                index, key_id
+            ))
+        })?);
+        output.write_all(&plaintext)?;
+        if last {
+            break;
+        }
+    }
+    output.flush()?;
+    Ok(())
+}
+
+/// 密钥熵的最低要求（Shannon 熵，比特/字节）
+///
+/// 32 字节随机密钥的熵通常在 4.5 以上；单字节重复或短周期模式远低于此值。
+const MIN_KEY_ENTROPY: f64 = 4.0;
+
+/// 验证密钥熵（Shannon entropy），熵不足 4.0 时返回错误
+pub fn validate_key_entropy(key: &[u8]) -> Result<(), InklogError> {
+    if key.is_empty() {
+        return Err(InklogError::EncryptionError(
+            "Encryption key cannot be empty".to_string(),
+        ));
+    }
+
+    let mut freq = [0u32; 256];
+    for &b in key {
+        freq[b as usize] += 1;
+    }
+
+    let len = key.len() as f64;
+    let entropy: f64 = freq
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum();
+
+    if entropy < MIN_KEY_ENTROPY {
+        return Err(InklogError::EncryptionError(format!(
+            "Encryption key has insufficient entropy ({} < {}). \
+             Please use a cryptographically random key.",
+            entropy, MIN_KEY_ENTROPY
+        )));
+    }
+
+    Ok(())
+}
+
+/// 解码 64 个十六进制字符表示的 32 字节密钥
+fn decode_hex_key(value: &str) -> Option<[u8; 32]> {
+    let value = value.as_bytes();
+    if value.len() != 64 {
+        return None;
+    }
+    let nibble = |c: u8| (c as char).to_digit(16).map(|d| d as u8);
+    let mut key = [0u8; 32];
+    for (byte, pair) in key.iter_mut().zip(value.chunks_exact(2)) {
+        *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+    Some(key)
+}
+
+/// 严格模式下解析密钥：只接受 Base64 或十六进制编码的 32 字节密钥，并验证密钥熵
+///
+/// 原始字符串与密码派生均被拒绝。
+pub fn decode_strict_key(value: &str) -> Result<[u8; 32], InklogError> {
+    let value = value.trim();
+    let key = if let Some(key) = decode_hex_key(value) {
+        key
+    } else {
+        let decoded = Zeroizing::new(general_purpose::STANDARD.decode(value).map_err(|_| {
+            InklogError::ConfigError(
+                "Strict key mode requires a Base64 or hex encoded 32-byte key".to_string(),
+            )
+        })?);
+        decoded.as_slice().try_into().map_err(|_| {
+            InklogError::ConfigError(format!(
+                "Encryption key must be exactly 32 bytes (256 bits), got {} bytes",
+                decoded.len()
+            ))
+        })?
+    };
+    validate_key_entropy(&key)?;
+    Ok(key)
+}
+
 /// 从环境变量获取加密密钥
 ///
 /// 支持以下格式：
@@ -30,6 +391,16 @@ use zeroize::Zeroizing;
 ///
 /// 如果环境变量未设置、密钥格式无效或长度不正确，返回错误
 pub fn get_encryption_key(env_var: &str) -> Result<[u8; 32], InklogError> {
+    get_encryption_key_with_policy(env_var, false)
+}
+
+/// 从环境变量获取加密密钥，`strict` 为 `true` 时使用 [`decode_strict_key`] 的规则
+///
+/// 宽松模式与 [`get_encryption_key`] 相同，但原始 32 字节密钥熵不足时会输出告警。
+pub fn get_encryption_key_with_policy(
+    env_var: &str,
+    strict: bool,
+) -> Result<[u8; 32], InklogError> {
     // 使用 Zeroizing 安全读取环境变量，防止密钥驻留内存
     let env_value = Zeroizing::new(std::env::var(env_var).map_err(|_| {
         InklogError::ConfigError(
@@ -38,12 +409,23 @@ pub fn get_encryption_key(env_var: &str) -> Result<[u8; 32], InklogError> {
         )
     })?);
 
+    if strict {
+        return decode_strict_key(&env_value);
+    }
+
     let raw_bytes = env_value.as_bytes();
 
     // 如果长度是32字节，尝试直接使用原始字节
     if raw_bytes.len() == 32 {
         let mut result = [0u8; 32];
         result.copy_from_slice(raw_bytes);
+        if validate_key_entropy(&result).is_err() {
+            tracing::warn!(
+                "Raw encryption key in {} looks weak (low entropy); \
+                 use a random Base64 key or enable strict_keys",
+                env_var
+            );
+        }
         return Ok(result);
     }
 
@@ -147,6 +529,50 @@ mod tests {
         assert_eq!(key.len(), 32);
     }
 
+    #[test]
+    fn test_strict_keys_accept_strong_encoded_key() {
+        let key: [u8; 32] = std::array::from_fn(|i| (i as u8).wrapping_mul(97).wrapping_add(13));
+        let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(
+            decode_strict_key(&general_purpose::STANDARD.encode(key)).unwrap(),
+            key
+        );
+        assert_eq!(decode_strict_key(&hex).unwrap(), key);
+        assert_eq!(decode_strict_key(&hex.to_uppercase()).unwrap(), key);
+
+        unsafe {
+            std::env::set_var("INKLOG_TEST_STRICT_STRONG", &hex);
+        }
+        let result = get_encryption_key_with_policy("INKLOG_TEST_STRICT_STRONG", true);
+        unsafe {
+            std::env::remove_var("INKLOG_TEST_STRICT_STRONG");
+        }
+        assert_eq!(result.unwrap(), key);
+    }
+
+    #[test]
+    fn test_strict_keys_reject_weak_key() {
+        let weak_raw = "a".repeat(32);
+        unsafe {
+            std::env::set_var("INKLOG_TEST_STRICT_WEAK", &weak_raw);
+        }
+        // 宽松模式保持兼容，只告警
+        let permissive = get_encryption_key_with_policy("INKLOG_TEST_STRICT_WEAK", false);
+        let strict = get_encryption_key_with_policy("INKLOG_TEST_STRICT_WEAK", true);
+        unsafe {
+            std::env::remove_var("INKLOG_TEST_STRICT_WEAK");
+        }
+        assert_eq!(permissive.unwrap(), [b'a'; 32]);
+        assert!(strict.is_err());
+
+        // 编码正确但熵不足
+        assert!(decode_strict_key(&general_purpose::STANDARD.encode([0xAAu8; 32])).is_err());
+        assert!(decode_strict_key(&"01".repeat(32)).is_err());
+        // 密码与长度不符的密钥
+        assert!(decode_strict_key("correct horse battery staple").is_err());
+        assert!(decode_strict_key(&general_purpose::STANDARD.encode([7u8; 16])).is_err());
+    }
+
     #[test]
     fn test_get_encryption_key_missing() {
         unsafe {
@@ -306,4 +732,146 @@ mod tests {
             err_msg
         );
     }
+
+    fn test_key(id: &str, byte: u8) -> EncryptionKey {
+        EncryptionKey::new(id, [byte; 32]).unwrap()
+    }
+
+    /// 版本 2 头部长度：定长部分 + 标识 + nonce
+    fn header_len(key: &EncryptionKey) -> usize {
+        FIXED_HEADER_LEN + key.id().len() + 12
+    }
+
+    #[test]
+    fn test_chunked_encryption_roundtrip_multi_megabyte_file() {
+        let key = test_key("primary", 7);
+        let dir = tempfile::tempdir().unwrap();
+        let plain_path = dir.path().join("rotated.log");
+        let enc_path = dir.path().join("rotated.log.enc");
+        // 3 MiB 加半块，覆盖多帧与较短的最后一帧
+        let plaintext: Vec<u8> = (0..(3 * 1024 * 1024 + 32 * 1024) as u32)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        std::fs::write(&plain_path, &plaintext).unwrap();
+
+        let mut output = std::fs::File::create(&enc_path).unwrap();
+        encrypt_chunked(
+            &key,
+            std::fs::File::open(&plain_path).unwrap(),
+            &mut output,
+            DEFAULT_ENCRYPTION_CHUNK_SIZE,
+        )
+        .unwrap();
+        drop(output);
+
+        let encrypted = std::fs::read(&enc_path).unwrap();
+        assert_eq!(&encrypted[..8], ENCRYPTED_FILE_MAGIC);
+        assert_eq!(
+            u16::from_le_bytes([encrypted[8], encrypted[9]]),
+            CHUNKED_FORMAT_VERSION
+        );
+        let frames = plaintext
+            .len()
+            .div_ceil(DEFAULT_ENCRYPTION_CHUNK_SIZE as usize);
+        assert_eq!(
+            encrypted.len(),
+            header_len(&key) + plaintext.len() + frames * TAG_LEN
+        );
+
+        let mut decrypted = Vec::new();
+        decrypt_chunked(
+            std::slice::from_ref(&key),
+            std::fs::File::open(&enc_path).unwrap(),
+            &mut decrypted,
+        )
+        .unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_chunked_encryption_empty_and_exact_multiple() {
+        let key = test_key("", 9);
+        for len in [0usize, 64, 128] {
+            let plaintext = vec![0xAB; len];
+            let mut encrypted = Vec::new();
+            encrypt_chunked(&key, &plaintext[..], &mut encrypted, 64).unwrap();
+            let mut decrypted = Vec::new();
+            decrypt_chunked(std::slice::from_ref(&key), &encrypted[..], &mut decrypted).unwrap();
+            assert_eq!(decrypted, plaintext, "len {len}");
+        }
+    }
+
+    #[test]
+    fn test_chunked_decryption_rejects_tampering() {
+        let key = test_key("k1", 3);
+        let keys = std::slice::from_ref(&key);
+        let plaintext = vec![0x5A; 1000];
+        let mut encrypted = Vec::new();
+        encrypt_chunked(&key, &plaintext[..], &mut encrypted, 100).unwrap();
+        let frame = 100 + TAG_LEN;
+
+        // 截断到帧边界
+        let truncated = &encrypted[..header_len(&key) + 5 * frame];
+        assert!(decrypt_chunked(keys, truncated, &mut Vec::new()).is_err());
+
+        // 交换两帧
+        let mut swapped = encrypted.clone();
+        let (first, second) = (header_len(&key), header_len(&key) + frame);
+        let frame_one = swapped[first..second].to_vec();
+        swapped.copy_within(second..second + frame, first);
+        swapped[second..second + frame].copy_from_slice(&frame_one);
+        assert!(decrypt_chunked(keys, &swapped[..], &mut Vec::new()).is_err());
+
+        // 篡改头部中的块大小
+        let mut header_changed = encrypted.clone();
+        header_changed[12] ^= 1;
+        assert!(decrypt_chunked(keys, &header_changed[..], &mut Vec::new()).is_err());
+
+        // 篡改头部中的密钥标识
+        let mut key_id_changed = encrypted.clone();
+        key_id_changed[FIXED_HEADER_LEN] ^= 1;
+        assert!(decrypt_chunked(keys, &key_id_changed[..], &mut Vec::new()).is_err());
+
+        // 错误的密钥
+        let wrong = test_key("k1", 4);
+        assert!(decrypt_chunked(&[wrong], &encrypted[..], &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_chunked_decryption_selects_key_by_id() {
+        let old = test_key("2026-09", 1);
+        let new = test_key("2026-10", 2);
+        let keys = [new.clone(), old.clone()];
+
+        for (key, plaintext) in [
+            (&old, b"written before rotation"),
+            (&new, b"written after rotation!"),
+        ] {
+            let mut encrypted = Vec::new();
+            encrypt_chunked(key, &plaintext[..], &mut encrypted, 8).unwrap();
+            let mut decrypted = Vec::new();
+            decrypt_chunked(&keys, &encrypted[..], &mut decrypted).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
+
+        // 标识不匹配时回退为逐个尝试
+        let mut encrypted = Vec::new();
+        encrypt_chunked(&test_key("renamed", 1), &b"fallback"[..], &mut encrypted, 8).unwrap();
+        let mut decrypted = Vec::new();
+        decrypt_chunked(&keys, &encrypted[..], &mut decrypted).unwrap();
+        assert_eq!(decrypted, b"fallback");
+
+        // 同名但密钥错误时失败
+        let mut encrypted = Vec::new();
+        encrypt_chunked(&test_key("2026-10", 9), &b"forged"[..], &mut encrypted, 8).unwrap();
+        assert!(decrypt_chunked(&keys, &encrypted[..], &mut Vec::new()).is_err());
+        assert!(decrypt_chunked(&[], &encrypted[..], &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_encryption_key_rejects_long_id() {
+        assert!(EncryptionKey::new("x".repeat(MAX_KEY_ID_LEN), [0u8; 32]).is_ok());
+        assert!(EncryptionKey::new("x".repeat(MAX_KEY_ID_LEN + 1), [0u8; 32]).is_err());
+        assert!(!format!("{:?}", test_key("id", 5)).contains("5"));
+    }
 }
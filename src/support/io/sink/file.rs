@@ -7,24 +7,27 @@
 
 use super::CircuitBreaker;
 use super::LogSink;
+use super::disk_space::{disk_space, has_sufficient_space};
 use super::{RotationStrategy, SizeBasedRotation, TimeBasedRotation};
 use crate::DataMasker;
+use crate::FileFormat;
 use crate::FileSinkConfig;
 use crate::InklogError;
+use crate::LineDelimiter;
 use crate::LogRecord;
+use crate::LogTemplate;
 use crate::validation::PathValidatorConfig;
-use aes_gcm::KeyInit;
-use aes_gcm::aead::Aead;
 use async_trait::async_trait;
 use bytes::BytesMut;
 use chrono::{DateTime, Datelike, Utc};
 use parking_lot::RwLock;
+use std::collections::BTreeMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::thread;
 use std::time::{Duration as StdDuration, Instant};
 use tracing::{debug, error, info, warn};
@@ -32,14 +35,207 @@ use tracing::{debug, error, info, warn};
 // 类型别名，保持向后兼容
 pub use super::circuit_breaker::{CircuitBreakerConfig, CircuitState};
 
-#[cfg(windows)]
-unsafe extern "system" {
-    fn GetDiskFreeSpaceExW(
-        directory_name: *const u16,
-        free_bytes_available: *mut u64,
-        total_bytes: *mut u64,
-        total_free_bytes: *mut u64,
-    ) -> i32;
+/// 按 `delimiter` 对单条记录的文本格式进行分帧
+///
+/// 文本格式为 `"{ts} [{level}] {target} - {message}"`。
+/// - `Newline`：转义内嵌的 `\n`/`\r`，以 `\n` 结尾
+/// - `Nul`：转义内嵌的 NUL 字节，以 `\0` 结尾
+/// - `LengthPrefixed`：4 字节大端长度前缀 + 原始文本
+pub fn frame_record(record: &LogRecord, delimiter: LineDelimiter) -> Vec<u8> {
+    frame_record_as(record, FileFormat::Text, delimiter)
+}
+
+/// 按 `format` 渲染单条记录后按 `delimiter` 分帧
+///
+/// `Json` 格式序列化完整的 `LogRecord`；JSON 字符串中的换行与 NUL 已被转义，
+/// 因此每条记录仍占一行。
+pub fn frame_record_as(
+    record: &LogRecord,
+    format: FileFormat,
+    delimiter: LineDelimiter,
+) -> Vec<u8> {
+    let text = || {
+        format!(
+            "{} [{}] {} - {}",
+            record.timestamp.to_rfc3339(),
+            record.level,
+            record.target,
+            record.message
+        )
+    };
+    let line = match format {
+        FileFormat::Text => text(),
+        FileFormat::Json => serde_json::to_string(record).unwrap_or_else(|e| {
+            error!("Failed to serialize record as JSON: {}", e);
+            text()
+        }),
+    };
+    frame_line(line, delimiter)
+}
+
+/// 按 `delimiter` 对已渲染的单行文本分帧
+fn frame_line(line: String, delimiter: LineDelimiter) -> Vec<u8> {
+    match delimiter {
+        LineDelimiter::Newline => {
+            let mut framed = line.replace('\n', "\\n").replace('\r', "\\r");
+            framed.push('\n');
+            framed.into_bytes()
+        }
+        LineDelimiter::Nul => {
+            let mut framed = line.replace('\0', "\\0").into_bytes();
+            framed.push(0);
+            framed
+        }
+        LineDelimiter::LengthPrefixed => {
+            let bytes = line.into_bytes();
+            let mut framed = Vec::with_capacity(4 + bytes.len());
+            framed.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            framed.extend_from_slice(&bytes);
+            framed
+        }
+    }
+}
+
+/// 日志文件所在目录；相对路径无父目录时为当前目录
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// 目录中是否已有同名（忽略扩展名）的轮转文件，包括其压缩或加密后的副本
+fn rotated_name_taken(path: &Path) -> bool {
+    let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+        return path.exists();
+    };
+    let prefix = format!("{}.", stem);
+    fs::read_dir(parent_dir(path))
+        .map(|entries| {
+            entries.flatten().any(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| name == stem || name.starts_with(&prefix))
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// 解析 `{stem}_{%Y%m%d_%H%M%S}[_{n}].*` 形式的轮转文件名，返回按时间排序的键
+fn rotated_file_key(stem: &str, file_name: &str) -> Option<(String, u64)> {
+    let rest = file_name.strip_prefix(stem)?.strip_prefix('_')?;
+    let name = rest.split('.').next()?;
+    let timestamp = name.get(..15)?;
+    chrono::NaiveDateTime::parse_from_str(timestamp, "%Y%m%d_%H%M%S").ok()?;
+    let collision = match &name[15..] {
+        "" => 0,
+        suffix => suffix.strip_prefix('_')?.parse().ok()?,
+    };
+    Some((timestamp.to_string(), collision))
+}
+
+/// 压缩后等待宽限期删除的源文件及其到期时间，由清理定时器删除
+static PENDING_SOURCE_REMOVALS: std::sync::Mutex<Vec<(PathBuf, Instant)>> =
+    std::sync::Mutex::new(Vec::new());
+
+/// 压缩源文件已完成归档时，归档产物相对源文件的扩展名
+const ARCHIVED_SOURCE_EXTENSIONS: [&str; 4] = ["zst", "gz", "gz.enc", "gz.unencrypted"];
+
+/// 删除宽限期已过的压缩源文件
+fn remove_expired_sources() {
+    let now = Instant::now();
+    let mut pending = PENDING_SOURCE_REMOVALS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    pending.retain(|(path, due)| {
+        if *due > now {
+            return true;
+        }
+        if let Err(e) = fs::remove_file(path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            error!("Failed to remove {}: {}", path.display(), e);
+        }
+        false
+    });
+}
+
+/// 未配置 `encryption_key_env` 时读取密钥的环境变量
+const DEFAULT_KEY_ENV: &str = "INKLOG_ENCRYPTION_KEY";
+
+/// 旧版默认密钥环境变量，[`DEFAULT_KEY_ENV`] 未设置时回退读取
+const LEGACY_KEY_ENV: &str = "LOG_ENCRYPTION_KEY";
+
+/// 检查日志文件是否已被外部移走的最短间隔
+const PATH_CHECK_INTERVAL: StdDuration = StdDuration::from_secs(1);
+
+/// 重新打开请求计数：每次 SIGHUP 或 [`request_reopen`] 加一
+static REOPEN_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// SIGHUP 处理线程只安装一次
+#[cfg(unix)]
+static SIGHUP_HANDLER: std::sync::OnceLock<Result<(), String>> = std::sync::OnceLock::new();
+
+fn reopen_generation() -> u64 {
+    REOPEN_GENERATION.load(Ordering::Acquire)
+}
+
+/// 请求进程内所有 FileSink 关闭并重新打开当前日志文件
+///
+/// 各 sink 在下一次 `write` 或 `flush` 时处理该请求；缓冲区中的记录
+/// 先写入旧文件，之后的记录写入重新打开的文件。
+pub fn request_reopen() {
+    REOPEN_GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// 安装 SIGHUP 处理器，收到信号时调用 [`request_reopen`]
+///
+/// 配合 logrotate 的 `create` + `postrotate`（`kill -HUP <pid>`）使用：
+/// logrotate 移走文件后发送信号，各 sink 随后在原路径上重新打开文件。
+/// 重复调用只安装一次；处理线程随进程存活，与各 sink 的 `shutdown_flag`
+/// 无关，已关闭的 sink 不再写入，也就不会处理请求。
+#[cfg(unix)]
+pub fn install_sighup_handler() -> Result<(), InklogError> {
+    SIGHUP_HANDLER
+        .get_or_init(|| {
+            let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+            thread::Builder::new()
+                .name("inklog-sighup".to_string())
+                .spawn(move || {
+                    let runtime = match tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                    {
+                        Ok(runtime) => runtime,
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(e.to_string()));
+                            return;
+                        }
+                    };
+                    runtime.block_on(async move {
+                        use tokio::signal::unix::{SignalKind, signal};
+                        let mut hangup = match signal(SignalKind::hangup()) {
+                            Ok(hangup) => hangup,
+                            Err(e) => {
+                                let _ = ready_tx.send(Err(e.to_string()));
+                                return;
+                            }
+                        };
+                        let _ = ready_tx.send(Ok(()));
+                        while hangup.recv().await.is_some() {
+                            info!("SIGHUP received, reopening log files");
+                            request_reopen();
+                        }
+                    });
+                })
+                .map_err(|e| e.to_string())?;
+            ready_rx
+                .recv()
+                .map_err(|_| "SIGHUP handler thread exited".to_string())?
+        })
+        .clone()
+        .map_err(|e| InklogError::ConfigError(format!("Failed to install SIGHUP handler: {e}")))
 }
 
 /// FileSink 的可变内部状态
@@ -59,8 +255,14 @@ struct FileSinkInner {
     last_rotation_date: Option<i32>,
     /// 序列号（用于区分同名轮转文件）
     sequence: u32,
-    /// 批量写入缓冲区
-    batch_buffer: Vec<LogRecord>,
+    /// 批量写入缓冲区（已分帧的记录）
+    batch_buffer: Vec<Vec<u8>>,
+    /// 批量缓冲区中的字节数
+    batch_bytes: u64,
+    /// 最近一次处理的重新打开请求，落后于全局计数时需要重新打开文件
+    reopen_generation: u64,
+    /// 上次检查日志文件是否仍在原路径的时间
+    last_path_check: Instant,
     /// 最后一次刷新时间
     last_flush_time: Instant,
     /// 断路器
@@ -103,6 +305,10 @@ pub struct FileSink {
     shutdown_flag: Arc<AtomicBool>,
     /// 数据脱敏器（只读）
     masker: DataMasker,
+    /// 文本格式的自定义模板（只读），为 `None` 时使用内置格式
+    template: Option<LogTemplate>,
+    /// `buffer_size` 解析后的字节数（只读）
+    buffer_size_bytes: Option<u64>,
     /// 可变内部状态
     inner: RwLock<FileSinkInner>,
 }
@@ -147,6 +353,9 @@ impl FileSink {
             fallback_sink: None,
             circuit_breaker: CircuitBreaker::new(5, StdDuration::from_secs(30), 3),
             batch_buffer: Vec::with_capacity(config.batch_size),
+            batch_bytes: 0,
+            reopen_generation: reopen_generation(),
+            last_path_check: Instant::now(),
             last_flush_time: Instant::now(),
             timer_handle: None,
             rotation_timer: Some(rotation_timer.clone()),
@@ -160,6 +369,8 @@ impl FileSink {
             last_cleanup_time: Arc::new(parking_lot::Mutex::new(None)),
             shutdown_flag: Arc::new(AtomicBool::new(false)),
             masker: DataMasker::new(),
+            template: config.template.as_deref().map(LogTemplate::new),
+            buffer_size_bytes: config.buffer_size.as_deref().and_then(Self::parse_size),
             inner: RwLock::new(inner),
         };
 
@@ -178,6 +389,9 @@ impl FileSink {
             }
         }
 
+        // 清理上次运行中未到期删除的压缩源文件
+        sink.sweep_archived_sources();
+
         // 启动轮转定时器
         sink.start_rotation_timer();
 
@@ -187,6 +401,28 @@ impl FileSink {
         Ok(sink)
     }
 
+    /// 使用自定义模板渲染文本格式的记录，覆盖 `config.template`
+    pub fn with_template(mut self, template: LogTemplate) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    /// 使用自定义脱敏器（如 `global.masked_fields` 配置的拒绝列表），仅在 `masking_enabled` 时生效
+    pub fn with_masker(mut self, masker: DataMasker) -> Self {
+        self.masker = masker;
+        self
+    }
+
+    /// 按配置的格式、模板与分隔符对单条记录分帧
+    fn frame(&self, record: &LogRecord) -> Vec<u8> {
+        match (self.config.format, &self.template) {
+            (FileFormat::Text, Some(template)) => {
+                frame_line(template.render(record), self.config.line_delimiter)
+            }
+            (format, _) => frame_record_as(record, format, self.config.line_delimiter),
+        }
+    }
+
     /// 解析文件大小字符串
     pub fn parse_size(size_str: &str) -> Option<u64> {
         let size_str = size_str.trim();
@@ -223,14 +459,24 @@ impl FileSink {
         }
     }
 
+    /// 密钥所在的环境变量名
+    ///
+    /// 未配置 `encryption_key_env` 时使用 [`DEFAULT_KEY_ENV`]；
+    /// 该变量未设置而旧版 [`LEGACY_KEY_ENV`] 已设置时回退到旧名。
+    fn key_env_name(&self) -> &str {
+        if let Some(name) = &self.config.encryption_key_env {
+            return name;
+        }
+        if std::env::var_os(DEFAULT_KEY_ENV).is_none() && std::env::var_os(LEGACY_KEY_ENV).is_some()
+        {
+            return LEGACY_KEY_ENV;
+        }
+        DEFAULT_KEY_ENV
+    }
+
     /// 获取加密密钥
     fn get_encryption_key(&self) -> Result<BytesMut, InklogError> {
-        let default_key = "LOG_ENCRYPTION_KEY".to_string();
-        let key_str = self
-            .config
-            .encryption_key_env
-            .as_ref()
-            .unwrap_or(&default_key);
+        let key_str = self.key_env_name();
 
         let key = std::env::var(key_str).map_err(|_| {
             InklogError::EncryptionError(format!(
@@ -239,6 +485,12 @@ impl FileSink {
             ))
         })?;
 
+        // 严格模式：只接受 Base64 或十六进制编码的 32 字节随机密钥
+        if self.config.strict_keys {
+            let key = super::encryption::decode_strict_key(&key)?;
+            return Ok(BytesMut::from(&key[..]));
+        }
+
         // 验证密钥长度（Base64 编码前至少 16 字符）
         if key.len() < 16 {
             return Err(InklogError::EncryptionError(
@@ -260,49 +512,13 @@ impl FileSink {
         }
 
         // 验证密钥熵（确保不是弱密钥）
-        Self::validate_key_entropy(&decoded)?;
+        super::encryption::validate_key_entropy(&decoded)?;
 
         let key_bytes = BytesMut::from(&decoded[..]);
 
         Ok(key_bytes)
     }
 
-    /// 验证密钥熵（Shannon entropy）
-    /// 返回 Ok(()) 如果密钥有足够的熵（>= 4.0）
-    fn validate_key_entropy(key: &[u8]) -> Result<(), InklogError> {
-        if key.is_empty() {
-            return Err(InklogError::EncryptionError(
-                "Encryption key cannot be empty".to_string(),
-            ));
-        }
-
-        let mut freq = [0u32; 256];
-        for &b in key {
-            freq[b as usize] += 1;
-        }
-
-        let len = key.len() as f64;
-        let entropy: f64 = freq
-            .iter()
-            .filter(|&&count| count > 0)
-            .map(|&count| {
-                let p = count as f64 / len;
-                -p * p.log2()
-            })
-            .sum();
-
-        const MIN_ENTROPY_THRESHOLD: f64 = 4.0;
-        if entropy < MIN_ENTROPY_THRESHOLD {
-            return Err(InklogError::EncryptionError(format!(
-                "Encryption key has insufficient entropy ({} < {}). \
-                 Please use a cryptographically random key.",
-                entropy, MIN_ENTROPY_THRESHOLD
-            )));
-        }
-
-        Ok(())
-    }
-
     fn open_file_inner(&self, inner: &mut FileSinkInner) -> Result<(), InklogError> {
         // vuln-0002: 验证路径安全性，防止路径遍历和敏感文件访问。
         // 必须在 `create_dir_all` 之前执行，避免恶意路径创建目录。
@@ -357,6 +573,7 @@ impl FileSink {
             .open(&self.config.path)
         {
             Ok(file) => {
+                self.apply_file_mode(&self.config.path);
                 inner.current_file = Some(file);
                 inner.current_size = self.config.path.metadata().map(|m| m.len()).unwrap_or(0);
                 debug!(
@@ -411,6 +628,8 @@ impl FileSink {
                     break;
                 }
 
+                remove_expired_sources();
+
                 // 检查是否到达清理时间（使用实例级别的清理时间）
                 let mut last_cleanup = last_cleanup_time.lock();
                 let now = Instant::now();
@@ -496,59 +715,18 @@ impl FileSink {
 
     /// Returns disk space information for the log file's filesystem.
     pub fn get_disk_space_info(&self) -> Result<(u64, u64), InklogError> {
-        #[cfg(unix)]
-        {
-            if let Some(parent) = self.config.path.parent()
-                && let Ok(_metadata) = fs::metadata(parent)
-                && let Ok(stat) = nix::sys::statfs::statfs(parent)
-            {
-                let total_blocks = stat.blocks();
-                let available_blocks = stat.blocks_available();
-
-                // 获取块大小
-                let block_size = stat.block_size() as u64;
-                let total_bytes = total_blocks * block_size;
-                let available_bytes = available_blocks * block_size;
-
-                return Ok((total_bytes, available_bytes));
-            }
-        }
-
-        #[cfg(windows)]
-        {
-            use std::os::windows::ffi::OsStrExt;
-            if let Some(parent) = self.config.path.parent() {
-                let mut wide_path: Vec<u16> = parent.as_os_str().encode_wide().collect();
-                wide_path.push(0);
-                let mut free_bytes_available: u64 = 0;
-                let mut total_bytes: u64 = 0;
-                let mut total_free_bytes: u64 = 0;
-                let result = unsafe {
-                    GetDiskFreeSpaceExW(
-                        wide_path.as_ptr(),
-                        &mut free_bytes_available,
-                        &mut total_bytes,
-                        &mut total_free_bytes,
-                    )
-                };
-                if result != 0 {
-                    return Ok((total_bytes, free_bytes_available));
-                }
-            }
-        }
-
-        Err(InklogError::IoError(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "Unable to get disk space info",
-        )))
+        disk_space(parent_dir(&self.config.path)).map_err(|e| {
+            InklogError::IoError(std::io::Error::new(
+                e.kind(),
+                format!("Unable to get disk space info: {}", e),
+            ))
+        })
     }
 
     /// 检查磁盘空间是否充足
     fn check_disk_space(&self) -> Result<bool, InklogError> {
         let (_total, available) = self.get_disk_space_info()?;
-        // 保留 50MB 或 10% 的可用空间，以较大者为准
-        let reserved = (50 * 1024 * 1024u64).max(available / 10);
-        Ok(available > reserved)
+        Ok(has_sufficient_space(available))
     }
 
     /// 计算下次轮转时间
@@ -657,32 +835,41 @@ impl FileSink {
         self.inner.write().timer_handle = Some(timer_handle);
     }
 
+    /// 批量缓冲区是否需要刷新：条数达到 `batch_size`、超过 `flush_interval_ms`，
+    /// 或已缓冲字节数达到 `buffer_size`
+    fn batch_due(&self, inner: &FileSinkInner) -> bool {
+        let flush_interval = StdDuration::from_millis(self.config.flush_interval_ms);
+        inner.batch_buffer.len() >= self.config.batch_size
+            || inner.last_flush_time.elapsed() >= flush_interval
+            || self
+                .buffer_size_bytes
+                .is_some_and(|max| inner.batch_bytes >= max)
+    }
+
     /// 批量刷新缓冲区到文件
     fn flush_batch_inner(&self, inner: &mut FileSinkInner) -> Result<(), InklogError> {
         if inner.batch_buffer.is_empty() {
             return Ok(());
         }
 
-        let records = std::mem::take(&mut inner.batch_buffer);
+        // 日志文件被外部轮转（如 `inklog rotate`）移走时，重新打开原路径。
+        // 每 PATH_CHECK_INTERVAL 最多检查一次；写入出错时下方会立即重新打开
+        if inner.current_file.is_some() && inner.last_path_check.elapsed() >= PATH_CHECK_INTERVAL {
+            inner.last_path_check = Instant::now();
+            if !self.config.path.exists() {
+                let _ = inner.current_file.take();
+                self.open_file_inner(inner)?;
+            }
+        }
+
+        let frames = std::mem::take(&mut inner.batch_buffer);
+        inner.batch_bytes = 0;
 
         if let Some(file) = &mut inner.current_file {
-            for record in &records {
-                match writeln!(
-                    file,
-                    "{} [{}] {} - {}",
-                    record.timestamp.to_rfc3339(),
-                    record.level,
-                    record.target,
-                    record.message
-                ) {
+            for framed in &frames {
+                match file.write_all(framed) {
                     Ok(_) => {
-                        let len = record.timestamp.to_rfc3339().len()
-                            + record.level.len()
-                            + record.target.len()
-                            + record.message.len()
-                            + 7; // " []  - \n"
-
-                        inner.current_size += len as u64;
+                        inner.current_size += framed.len() as u64;
                     }
                     Err(e) => {
                         error!("Batch write error: {}", e);
@@ -703,9 +890,40 @@ impl FileSink {
         Ok(())
     }
 
-    /// 同步压缩文件（可在后台线程调用）
-    #[cfg(feature = "compression")]
+    /// 处理 SIGHUP 或 [`request_reopen`] 发起的重新打开请求
+    ///
+    /// 缓冲区中的记录先写入旧文件，再关闭句柄并在原路径上重新打开。
+    fn reopen_if_requested_inner(&self, inner: &mut FileSinkInner) -> Result<(), InklogError> {
+        let generation = reopen_generation();
+        if inner.reopen_generation == generation {
+            return Ok(());
+        }
+        inner.reopen_generation = generation;
+        if inner.current_file.is_none() {
+            return Ok(());
+        }
+
+        self.flush_batch_inner(inner)?;
+        if let Some(mut file) = inner.current_file.take() {
+            let _ = file.flush();
+        }
+        debug!("Reopening log file: {}", self.config.path.display());
+        self.open_file_inner(inner)
+    }
+
+    /// 同步压缩文件（可在后台线程调用），按 `compression_format` 选择编码器
     fn compress_file(&self, path: &Path) -> Result<PathBuf, InklogError> {
+        match self.config.compression_format {
+            #[cfg(feature = "compression")]
+            crate::FileCompressionFormat::Zstd => self.compress_file_zstd(path),
+            // 未启用 compression feature 时 zstd 回退到 gzip
+            _ => self.compress_file_gzip(path),
+        }
+    }
+
+    /// zstd 压缩，输出 `.zst`
+    #[cfg(feature = "compression")]
+    fn compress_file_zstd(&self, path: &Path) -> Result<PathBuf, InklogError> {
         let compressed_path = path.with_extension("zst");
 
         let input_file = fs::File::open(path).map_err(|e| {
@@ -734,6 +952,7 @@ impl FileSink {
 
         // Encoder is automatically finished when dropped due to auto_finish()
         drop(encoder);
+        self.apply_file_mode(&compressed_path);
 
         // 如果需要加密
         if self.config.encrypt {
@@ -751,25 +970,23 @@ impl FileSink {
             Ok(encrypted_path)
         } else {
             // 删除原始文件
-            let _ = fs::remove_file(path);
+            self.remove_uncompressed(path);
             Ok(compressed_path)
         }
     }
 
-    /// 同步压缩文件 fallback（compression feature 未启用时）。
+    /// gzip 压缩，输出 `.gz`；`compression_level` 截断到 gzip 的 0-9。
     ///
-    /// 当 `compression` feature 未启用但用户配置了 `compress = true` 时，
-    /// 使用 gzip（flate2，始终可用的非 optional 依赖）进行压缩，而非返回错误。
+    /// 配置 `compression_format = "gzip"` 时使用；`compression` feature 未启用时
+    /// 也作为 zstd 的 fallback（flate2 是始终可用的非 optional 依赖），
     /// 这样下游项目无需引入 zstd-sys 即可获得日志压缩能力。
     ///
-    /// 当 `encrypt = true` 时，与 compression feature 启用时的 zstd 路径行为对齐：
+    /// 当 `encrypt = true` 时，与 zstd 路径行为对齐：
     /// 对压缩产物加密生成 `.gz.enc`，加密失败时保留压缩文件为 `.gz.unencrypted`。
-    #[cfg(not(feature = "compression"))]
-    fn compress_file(&self, path: &Path) -> Result<PathBuf, InklogError> {
-        use super::CompressionStrategy;
-        use super::GzipCompression;
-        let strategy = GzipCompression::default();
-        let compressed_path = strategy.compress_file(path, self.config.compression_level)?;
+    fn compress_file_gzip(&self, path: &Path) -> Result<PathBuf, InklogError> {
+        let compressed_path = super::compression::gzip_file(path, self.config.compression_level)?;
+        self.apply_file_mode(&compressed_path);
+        self.remove_uncompressed(path);
 
         // 如果需要加密（与 compression feature 启用时的 zstd 路径行为对齐）
         if self.config.encrypt {
@@ -790,70 +1007,181 @@ impl FileSink {
         }
     }
 
+    /// 删除压缩前的源文件；配置了 `compression_grace_secs` 时登记到待删除列表，
+    /// 宽限期过后由清理定时器删除
+    fn remove_uncompressed(&self, path: &Path) {
+        let grace = self.config.compression_grace_secs;
+        if grace == 0 {
+            let _ = fs::remove_file(path);
+            return;
+        }
+        PENDING_SOURCE_REMOVALS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((
+                path.to_path_buf(),
+                Instant::now() + StdDuration::from_secs(grace),
+            ));
+    }
+
+    /// 删除之前运行遗留的压缩源文件：已有归档产物、且产物生成已超过宽限期的
+    /// 未压缩轮转文件
+    fn sweep_archived_sources(&self) {
+        let grace = StdDuration::from_secs(self.config.compression_grace_secs);
+        let Some(stem) = self.config.path.file_stem() else {
+            return;
+        };
+        let stem = stem.to_string_lossy();
+        let Ok(entries) = fs::read_dir(parent_dir(&self.config.path)) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path == self.config.path
+                || path.extension() != self.config.path.extension()
+                || path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_none_or(|name| rotated_file_key(&stem, name).is_none())
+            {
+                continue;
+            }
+            let archived_long_enough = ARCHIVED_SOURCE_EXTENSIONS.iter().any(|ext| {
+                fs::metadata(path.with_extension(ext))
+                    .and_then(|m| m.modified())
+                    .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age >= grace))
+            });
+            if archived_long_enough {
+                match fs::remove_file(&path) {
+                    Ok(()) => debug!("Removed archived log source: {}", path.display()),
+                    Err(e) => error!("Failed to remove {}: {}", path.display(), e),
+                }
+            }
+        }
+    }
+
     /// 同步加密文件（可在后台线程调用）
+    ///
+    /// 使用分块格式（见 [`super::encryption`]），逐块读取与写出，不会把整个文件读入内存。
     fn encrypt_file(&self, input_path: &Path, output_path: &Path) -> Result<(), InklogError> {
-        use aes_gcm::{Aes256Gcm, Nonce};
-        use rand::Rng;
-
-        // 获取密钥
+        // 获取密钥；未配置标识时以环境变量名作为密钥标识
         let key_bytes = self.get_encryption_key()?;
-        let cipher = Aes256Gcm::new_from_slice(&key_bytes)
-            .map_err(|e| InklogError::EncryptionError(format!("Invalid key: {}", e)))?;
-
-        // 生成加密安全的随机 nonce
-        // 使用 rand::rng() 获取线程本地 RNG，该 RNG 从 SysRng 定期种子化
-        // rand::rng() 返回 ThreadRng，它是密码学安全的
-        let mut nonce_bytes = [0u8; 12];
-        rand::rng().fill_bytes(&mut nonce_bytes);
-        let nonce = Nonce::from(nonce_bytes);
-
-        // 读取输入文件
-        let input_data = fs::read(input_path).map_err(|e| {
+        let key_id = self
+            .config
+            .encryption_key_id
+            .clone()
+            .unwrap_or_else(|| self.key_env_name().to_string());
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&key_bytes);
+        let key = super::encryption::EncryptionKey::new(key_id, key)?;
+
+        let input = fs::File::open(input_path).map_err(|e| {
             error!("Failed to read file for encryption: {}", e);
             InklogError::IoError(e)
         })?;
 
-        // 加密
-        let ciphertext = cipher.encrypt(&nonce, input_data.as_slice()).map_err(|e| {
-            error!("Encryption failed: {}", e);
-            InklogError::EncryptionError(e.to_string())
-        })?;
-
-        // 写入加密文件
-        let mut output = fs::File::create(output_path).map_err(|e| {
+        let output = fs::File::create(output_path).map_err(|e| {
             error!("Failed to create encrypted file: {}", e);
             InklogError::IoError(e)
         })?;
-
-        // 写入格式：nonce (12 bytes) + ciphertext
-        output.write_all(&nonce_bytes)?;
-        output.write_all(&ciphertext)?;
-
+        let mut output = std::io::BufWriter::new(output);
+
+        super::encryption::encrypt_chunked(
+            &key,
+            input,
+            &mut output,
+            super::encryption::DEFAULT_ENCRYPTION_CHUNK_SIZE,
+        )
+        .inspect_err(|e| error!("Encryption failed: {}", e))?;
+        output
+            .into_inner()
+            .map_err(|e| InklogError::IoError(e.into_error()))?;
+
+        self.apply_file_mode(output_path);
         debug!("Encrypted log file: {}", output_path.display());
         Ok(())
     }
 
+    /// 按 `file_mode` 设置文件权限（仅 Unix）；失败时仅告警
+    fn apply_file_mode(&self, path: &Path) {
+        #[cfg(unix)]
+        if let Some(mode) = self.config.file_mode {
+            use std::os::unix::fs::PermissionsExt;
+            if let Err(e) = fs::set_permissions(path, fs::Permissions::from_mode(mode)) {
+                warn!("Failed to set mode {:o} on {}: {}", mode, path.display(), e);
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = path;
+    }
+
     /// 执行文件轮转
     fn rotate_inner(&self, inner: &mut FileSinkInner) -> Result<(), InklogError> {
+        let new_path = self.rename_current_inner(inner)?;
+
+        // 如果启用压缩或加密，在后台线程处理
+        if self.config.compress || self.config.encrypt {
+            let config = self.config.clone();
+            let _ = thread::spawn(move || {
+                let _ = Self::archive_rotated(config, &new_path);
+            });
+        }
+
+        // 重新打开文件
+        self.open_file_inner(inner)?;
+
+        self.prune_rotated_files();
+        Ok(())
+    }
+
+    /// 立即轮转当前日志文件，在当前线程内完成重命名、压缩与加密
+    ///
+    /// 与自动轮转使用相同的命名与归档逻辑，返回最终的归档文件路径；
+    /// 当前文件为空时不轮转并返回 `None`。
+    ///
+    /// 轮转会重置本实例的按时间轮转计时，因此不会紧接着再触发一次自动轮转。
+    /// 由另一个进程（如 `inklog rotate`）轮转时，正在写入的进程会在约一秒后的
+    /// 批量刷新中发现文件已被移走并重新打开，但其按时间轮转的计时不受影响：
+    /// 由 logrotate 等外部工具驱动轮转时，应将 `rotation_time` 设为不短于外部
+    /// 轮转周期，避免同一时段被轮转两次。
+    pub fn rotate_now(&self) -> Result<Option<PathBuf>, InklogError> {
+        let mut inner = self.inner.write();
+        self.flush_batch_inner(&mut inner)?;
+        if let Some(file) = &mut inner.current_file {
+            file.flush()?;
+        }
+        if !fs::metadata(&self.config.path).is_ok_and(|m| m.len() > 0) {
+            return Ok(None);
+        }
+
+        let new_path = self.rename_current_inner(&mut inner)?;
+        self.open_file_inner(&mut inner)?;
+        let archived = if self.config.compress || self.config.encrypt {
+            Self::archive_rotated(self.config.clone(), &new_path)?
+        } else {
+            new_path
+        };
+
+        self.prune_rotated_files();
+        Ok(Some(archived))
+    }
+
+    /// 关闭并重命名当前日志文件，更新轮转状态，返回重命名后的路径
+    fn rename_current_inner(&self, inner: &mut FileSinkInner) -> Result<PathBuf, InklogError> {
         debug!("Rotating log file: {}", self.config.path.display());
 
         // 关闭当前文件
         let _ = inner.current_file.take();
 
-        // 重命名当前日志文件
-        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-        let new_path = if let Some(parent) = self.config.path.parent() {
-            let stem = self.config.path.file_stem().unwrap_or_default();
-            let ext = self.config.path.extension().unwrap_or_default();
-            parent.join(format!(
-                "{}_{}.{}",
-                stem.to_string_lossy(),
-                timestamp,
-                ext.to_string_lossy()
-            ))
-        } else {
-            PathBuf::from(format!("{}_{}", self.config.path.display(), timestamp))
-        };
+        // 重命名当前日志文件；同一秒内多次轮转时追加序号，避免覆盖上一次的轮转文件
+        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string();
+        let mut new_path = self.rotated_path(&timestamp);
+        let mut collision = 0;
+        while rotated_name_taken(&new_path) {
+            collision += 1;
+            new_path = self.rotated_path(&format!("{}_{}", timestamp, collision));
+        }
 
         // 尝试重命名
         if self.config.path.exists()
@@ -873,90 +1201,126 @@ impl FileSink {
 
         // 更新轮转时间
         inner.last_rotation = Instant::now();
+        if let Some(timer) = &inner.rotation_timer {
+            *timer.lock() = inner.last_rotation;
+        }
         self.update_next_rotation_time_inner(inner);
         inner.current_size = 0;
 
         info!("Log rotated to: {}", new_path.display());
+        Ok(new_path)
+    }
 
-        // 如果启用压缩，在后台线程处理
-        if self.config.compress {
-            let config = self.config.clone();
-            let path = new_path.clone();
-            let _ = thread::spawn(move || {
-                // 为后台线程创建一个最小化的 FileSink 实例用于压缩
-                let inner = FileSinkInner {
-                    current_file: None,
-                    current_size: 0,
-                    last_rotation: Instant::now(),
-                    next_rotation_time: None,
-                    last_rotation_date: None,
-                    sequence: 0,
-                    fallback_sink: None,
-                    circuit_breaker: CircuitBreaker::new(5, StdDuration::from_secs(30), 3),
-                    batch_buffer: Vec::new(),
-                    last_flush_time: Instant::now(),
-                    timer_handle: None,
-                    rotation_timer: None,
-                    cleanup_timer_handle: None,
-                    rotation_strategy: Box::new(crate::support::io::sink::CompositeRotation::new(
-                        vec![],
-                    )),
-                };
-                let sink = FileSink {
-                    config,
-                    rotation_interval: StdDuration::from_secs(86400),
-                    last_cleanup_time: Arc::new(parking_lot::Mutex::new(None)),
-                    shutdown_flag: Arc::new(AtomicBool::new(false)),
-                    masker: DataMasker::new(),
-                    inner: RwLock::new(inner),
-                };
-                if let Err(e) = sink.compress_file(&path) {
-                    error!("Failed to compress rotated log: {}", e);
+    /// 按配置压缩和/或加密已轮转的文件，返回最终文件路径
+    ///
+    /// 使用不启动定时器的最小化 FileSink 实例，可在后台线程调用。
+    fn archive_rotated(config: FileSinkConfig, path: &Path) -> Result<PathBuf, InklogError> {
+        let compress = config.compress;
+        let inner = FileSinkInner {
+            current_file: None,
+            current_size: 0,
+            last_rotation: Instant::now(),
+            next_rotation_time: None,
+            last_rotation_date: None,
+            sequence: 0,
+            fallback_sink: None,
+            circuit_breaker: CircuitBreaker::new(5, StdDuration::from_secs(30), 3),
+            batch_buffer: Vec::new(),
+            batch_bytes: 0,
+            reopen_generation: reopen_generation(),
+            last_path_check: Instant::now(),
+            last_flush_time: Instant::now(),
+            timer_handle: None,
+            rotation_timer: None,
+            cleanup_timer_handle: None,
+            rotation_strategy: Box::new(crate::support::io::sink::CompositeRotation::new(vec![])),
+        };
+        let sink = FileSink {
+            config,
+            rotation_interval: StdDuration::from_secs(86400),
+            last_cleanup_time: Arc::new(parking_lot::Mutex::new(None)),
+            shutdown_flag: Arc::new(AtomicBool::new(false)),
+            masker: DataMasker::new(),
+            template: None,
+            buffer_size_bytes: None,
+            inner: RwLock::new(inner),
+        };
+
+        if compress {
+            sink.compress_file(path).inspect_err(|e| {
+                error!("Failed to compress rotated log: {}", e);
+            })
+        } else {
+            // 只启用加密（不压缩）时直接加密
+            let encrypted_path = path.with_extension("enc");
+            match sink.encrypt_file(path, &encrypted_path) {
+                Ok(()) => {
+                    let _ = fs::remove_file(path);
+                    Ok(encrypted_path)
                 }
-            });
-        } else if self.config.encrypt {
-            // 如果只启用加密（不压缩），直接在后台线程加密
-            let config = self.config.clone();
-            let path = new_path.clone();
-            let _ = thread::spawn(move || {
-                // 为后台线程创建一个最小化的 FileSink 实例用于加密
-                let inner = FileSinkInner {
-                    current_file: None,
-                    current_size: 0,
-                    last_rotation: Instant::now(),
-                    next_rotation_time: None,
-                    last_rotation_date: None,
-                    sequence: 0,
-                    fallback_sink: None,
-                    circuit_breaker: CircuitBreaker::new(5, StdDuration::from_secs(30), 3),
-                    batch_buffer: Vec::new(),
-                    last_flush_time: Instant::now(),
-                    timer_handle: None,
-                    rotation_timer: None,
-                    cleanup_timer_handle: None,
-                    rotation_strategy: Box::new(crate::support::io::sink::CompositeRotation::new(
-                        vec![],
-                    )),
-                };
-                let sink = FileSink {
-                    config,
-                    rotation_interval: StdDuration::from_secs(86400),
-                    last_cleanup_time: Arc::new(parking_lot::Mutex::new(None)),
-                    shutdown_flag: Arc::new(AtomicBool::new(false)),
-                    masker: DataMasker::new(),
-                    inner: RwLock::new(inner),
-                };
-                let encrypted_path = path.with_extension("enc");
-                if let Err(e) = sink.encrypt_file(&path, &encrypted_path) {
+                Err(e) => {
                     error!("Failed to encrypt rotated log: {}", e);
-                } else {
-                    let _ = fs::remove_file(&path);
+                    Err(e)
                 }
-            });
+            }
         }
+    }
 
-        // 重新打开文件
-        self.open_file_inner(inner)
+    /// 轮转文件路径：`{stem}_{suffix}.{ext}`
+    fn rotated_path(&self, suffix: &str) -> PathBuf {
+        if let Some(parent) = self.config.path.parent() {
+            let stem = self.config.path.file_stem().unwrap_or_default();
+            let ext = self.config.path.extension().unwrap_or_default();
+            parent.join(format!(
+                "{}_{}.{}",
+                stem.to_string_lossy(),
+                suffix,
+                ext.to_string_lossy()
+            ))
+        } else {
+            PathBuf::from(format!("{}_{}", self.config.path.display(), suffix))
+        }
+    }
+
+    /// 按 `keep_files` 只保留最新的轮转文件，`0` 表示不限制
+    ///
+    /// 只处理与当前日志同名前缀的轮转文件，不会触及正在写入的文件；
+    /// 同一次轮转的未压缩、压缩与加密副本算作一个。
+    fn prune_rotated_files(&self) {
+        let keep = self.config.keep_files as usize;
+        if keep == 0 {
+            return;
+        }
+        let Some(stem) = self.config.path.file_stem() else {
+            return;
+        };
+        let stem = stem.to_string_lossy();
+        let Ok(entries) = fs::read_dir(parent_dir(&self.config.path)) else {
+            return;
+        };
+
+        let mut rotations: BTreeMap<(String, u64), Vec<PathBuf>> = BTreeMap::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path == self.config.path {
+                continue;
+            }
+            if let Some(key) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| rotated_file_key(&stem, name))
+            {
+                rotations.entry(key).or_default().push(path);
+            }
+        }
+
+        let excess = rotations.len().saturating_sub(keep);
+        for path in rotations.into_values().take(excess).flatten() {
+            match fs::remove_file(&path) {
+                Ok(()) => debug!("Removed old rotated log: {}", path.display()),
+                Err(e) => error!("Failed to remove {}: {}", path.display(), e),
+            }
+        }
     }
 
     /// 检查是否需要轮转
@@ -1005,6 +1369,7 @@ impl LogSink for FileSink {
         // parking_lot::RwLockWriteGuard 非 Send，不能跨 await 持有，故用 block scope 隔离。
         let rotation_failed_fallback: Option<Arc<dyn LogSink + Send + Sync>> = {
             let mut inner = self.inner.write();
+            self.reopen_if_requested_inner(&mut inner)?;
 
             // 应用数据脱敏（如果启用）
             let masked_record = if self.config.masking_enabled {
@@ -1016,14 +1381,11 @@ impl LogSink for FileSink {
                 record.clone()
             };
 
-            // 添加到批量缓冲区
-            let record_len = masked_record.timestamp.to_rfc3339().len()
-                + masked_record.level.len()
-                + masked_record.target.len()
-                + masked_record.message.len()
-                + 7;
-            inner.current_size += record_len as u64;
-            inner.batch_buffer.push(masked_record);
+            // 分帧后加入批量缓冲区，按实际写出的字节数计入大小
+            let framed = self.frame(&masked_record);
+            inner.current_size += framed.len() as u64;
+            inner.batch_bytes += framed.len() as u64;
+            inner.batch_buffer.push(framed);
 
             // 检查轮转条件（在更新 current_size 之后）
             let should_rotate = Self::parse_size(&self.config.max_size)
@@ -1040,22 +1402,14 @@ impl LogSink for FileSink {
                     inner.fallback_sink.clone()
                 } else {
                     // 轮转成功，继续 batch flush
-                    let now = Instant::now();
-                    let flush_interval = StdDuration::from_millis(self.config.flush_interval_ms);
-                    if inner.batch_buffer.len() >= self.config.batch_size
-                        || now.duration_since(inner.last_flush_time) >= flush_interval
-                    {
+                    if self.batch_due(&inner) {
                         self.flush_batch_inner(&mut inner)?;
                     }
                     None
                 }
             } else {
                 // 无需轮转，batch flush
-                let now = Instant::now();
-                let flush_interval = StdDuration::from_millis(self.config.flush_interval_ms);
-                if inner.batch_buffer.len() >= self.config.batch_size
-                    || now.duration_since(inner.last_flush_time) >= flush_interval
-                {
+                if self.batch_due(&inner) {
                     self.flush_batch_inner(&mut inner)?;
                 }
                 None
@@ -1072,6 +1426,7 @@ impl LogSink for FileSink {
 
     async fn flush(&self) -> Result<(), InklogError> {
         let mut inner = self.inner.write();
+        self.reopen_if_requested_inner(&mut inner)?;
         // 先刷新批量缓冲区
         self.flush_batch_inner(&mut inner)?;
 
@@ -1213,6 +1568,9 @@ impl Clone for FileSink {
             fallback_sink: None,
             circuit_breaker: CircuitBreaker::new(5, StdDuration::from_secs(30), 3),
             batch_buffer: Vec::with_capacity(self.config.batch_size),
+            batch_bytes: 0,
+            reopen_generation: reopen_generation(),
+            last_path_check: Instant::now(),
             last_flush_time: Instant::now(),
             timer_handle: None,
             rotation_timer: None,
@@ -1226,6 +1584,8 @@ impl Clone for FileSink {
             last_cleanup_time: Arc::new(parking_lot::Mutex::new(None)),
             shutdown_flag: Arc::new(AtomicBool::new(false)),
             masker: DataMasker::new(),
+            template: self.template.clone(),
+            buffer_size_bytes: self.buffer_size_bytes,
             inner: RwLock::new(inner),
         }
     }
@@ -1269,6 +1629,9 @@ mod tests {
             fallback_sink: None,
             circuit_breaker: CircuitBreaker::new(5, StdDuration::from_secs(30), 3),
             batch_buffer: Vec::new(),
+            batch_bytes: 0,
+            reopen_generation: reopen_generation(),
+            last_path_check: Instant::now(),
             last_flush_time: Instant::now(),
             timer_handle: None,
             rotation_timer: None,
@@ -1277,6 +1640,8 @@ mod tests {
         };
 
         FileSink {
+            template: config.template.as_deref().map(LogTemplate::new),
+            buffer_size_bytes: config.buffer_size.as_deref().and_then(FileSink::parse_size),
             config,
             rotation_interval: StdDuration::from_secs(86400),
             last_cleanup_time: Arc::new(parking_lot::Mutex::new(None)),
@@ -1317,6 +1682,7 @@ mod tests {
             batch_size: 100,
             flush_interval_ms: 100,
             masking_enabled: true,
+            ..Default::default()
         };
 
         // Create test files
@@ -1355,22 +1721,55 @@ mod tests {
     }
 
     #[test]
-    fn test_disk_space_info() {
-        let temp_dir = tempdir().unwrap();
+    #[serial]
+    fn test_get_encryption_key_strict_keys() {
         let config = FileSinkConfig {
             enabled: true,
-            path: temp_dir.path().join("test.log"),
+            path: PathBuf::from("test.log"),
+            encryption_key_env: Some("TEST_STRICT_KEY".to_string()),
+            strict_keys: true,
             ..Default::default()
         };
-
         let sink = create_test_file_sink(config);
+        let (key_bytes, key_b64) = make_test_key();
 
-        let result = sink.get_disk_space_info();
-        assert!(result.is_ok());
+        // 十六进制与 Base64 编码的随机密钥均可使用
+        let key_hex: String = key_bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        for encoded in [key_hex.as_str(), key_b64.as_str()] {
+            unsafe {
+                std::env::set_var("TEST_STRICT_KEY", encoded);
+            }
+            assert_eq!(&sink.get_encryption_key().unwrap()[..], &key_bytes[..]);
+        }
 
-        let (total, available) = result.unwrap();
-        assert!(total > 0);
-        assert!(available > 0);
+        // 低熵密钥被拒绝
+        unsafe {
+            std::env::set_var("TEST_STRICT_KEY", "61".repeat(32));
+        }
+        assert!(sink.get_encryption_key().is_err());
+
+        unsafe {
+            std::env::remove_var("TEST_STRICT_KEY");
+        }
+    }
+
+    #[test]
+    fn test_disk_space_info() {
+        let temp_dir = tempdir().unwrap();
+        let config = FileSinkConfig {
+            enabled: true,
+            path: temp_dir.path().join("test.log"),
+            ..Default::default()
+        };
+
+        let sink = create_test_file_sink(config);
+
+        let result = sink.get_disk_space_info();
+        assert!(result.is_ok());
+
+        let (total, available) = result.unwrap();
+        assert!(total > 0);
+        assert!(available > 0);
     }
 
     #[test]
@@ -1480,6 +1879,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_get_encryption_key_no_env_var() {
         let config = FileSinkConfig {
             enabled: true,
@@ -1491,13 +1891,44 @@ mod tests {
         let sink = create_test_file_sink(config);
 
         let result = sink.get_encryption_key();
-        // When encryption_key_env is None, it tries to use LOG_ENCRYPTION_KEY env var
-        // This test expects the env var to be set or the test to handle missing env
-        // Let's check if we get an error and skip if env var is not set
+        // When encryption_key_env is None, it reads INKLOG_ENCRYPTION_KEY,
+        // falling back to the legacy LOG_ENCRYPTION_KEY
         if result.is_err() {
-            // This is expected if LOG_ENCRYPTION_KEY is not set
-            assert!(std::env::var("LOG_ENCRYPTION_KEY").is_err());
+            assert!(std::env::var(DEFAULT_KEY_ENV).is_err());
+            assert!(std::env::var(LEGACY_KEY_ENV).is_err());
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_default_key_env_falls_back_to_legacy_name() {
+        let config = FileSinkConfig {
+            enabled: true,
+            path: PathBuf::from("test.log"),
+            encryption_key_env: None,
+            ..Default::default()
+        };
+        let sink = create_test_file_sink(config);
+        let key = "YWJjZGVmZ2hpamtsbW5vcHFyc3R1dnd4eXoxMjM0NTY=";
+
+        unsafe {
+            std::env::remove_var(DEFAULT_KEY_ENV);
+            std::env::set_var(LEGACY_KEY_ENV, key);
+        }
+        assert_eq!(sink.key_env_name(), LEGACY_KEY_ENV);
+        assert_eq!(sink.get_encryption_key().unwrap().len(), 32);
+
+        // 新旧变量都设置时以新名为准
+        unsafe {
+            std::env::set_var(DEFAULT_KEY_ENV, key);
         }
+        assert_eq!(sink.key_env_name(), DEFAULT_KEY_ENV);
+
+        unsafe {
+            std::env::remove_var(DEFAULT_KEY_ENV);
+            std::env::remove_var(LEGACY_KEY_ENV);
+        }
+        assert_eq!(sink.key_env_name(), DEFAULT_KEY_ENV);
     }
 
     #[test]
@@ -1535,9 +1966,10 @@ mod tests {
 
     #[test]
     fn test_file_sink_disabled() {
+        let temp_dir = tempdir().unwrap();
         let config = FileSinkConfig {
             enabled: false,
-            path: PathBuf::from("test.log"),
+            path: temp_dir.path().join("test.log"),
             ..Default::default()
         };
         let result = FileSink::new(config);
@@ -1552,21 +1984,21 @@ mod tests {
             0x2e, 0x6f, 0x9b, 0x3c, 0x8d, 0x1e, 0x4b, 0x6a, 0x2b, 0x6c, 0x9f, 0x3a, 0x8b, 0x1c,
             0x4d, 0x7e, 0x2f, 0x6a,
         ];
-        assert!(FileSink::validate_key_entropy(&strong_key).is_ok());
+        assert!(crate::sink::encryption::validate_key_entropy(&strong_key).is_ok());
     }
 
     #[test]
     fn test_validate_key_entropy_weak() {
         // 使用弱密钥（全相同字节）
         let weak_key = [0xaa; 32];
-        assert!(FileSink::validate_key_entropy(&weak_key).is_err());
+        assert!(crate::sink::encryption::validate_key_entropy(&weak_key).is_err());
     }
 
     #[test]
     fn test_validate_key_entropy_empty() {
         // 空密钥应该返回错误
         let empty_key: [u8; 0] = [];
-        assert!(FileSink::validate_key_entropy(&empty_key).is_err());
+        assert!(crate::sink::encryption::validate_key_entropy(&empty_key).is_err());
     }
 
     #[test]
@@ -1631,6 +2063,11 @@ mod tests {
         (key_bytes, key_b64)
     }
 
+    /// 将测试密钥包装为无标识的解密密钥
+    fn test_encryption_key(key_bytes: &[u8]) -> crate::sink::encryption::EncryptionKey {
+        crate::sink::encryption::EncryptionKey::new("", key_bytes.try_into().unwrap()).unwrap()
+    }
+
     // ==================== parse_size 边界测试 ====================
 
     #[test]
@@ -1874,8 +2311,12 @@ mod tests {
         let mut inner = sink.inner.write();
         sink.open_file_inner(&mut inner).unwrap();
 
-        inner.batch_buffer.push(create_test_record("Message 1"));
-        inner.batch_buffer.push(create_test_record("Message 2"));
+        inner
+            .batch_buffer
+            .push(sink.frame(&create_test_record("Message 1")));
+        inner
+            .batch_buffer
+            .push(sink.frame(&create_test_record("Message 2")));
 
         let result = sink.flush_batch_inner(&mut inner);
         assert!(result.is_ok());
@@ -1900,7 +2341,9 @@ mod tests {
         sink.open_file_inner(&mut inner).unwrap();
 
         let initial_size = inner.current_size;
-        inner.batch_buffer.push(create_test_record("Test message"));
+        inner
+            .batch_buffer
+            .push(sink.frame(&create_test_record("Test message")));
         sink.flush_batch_inner(&mut inner).unwrap();
         assert!(inner.current_size > initial_size);
     }
@@ -2016,6 +2459,174 @@ mod tests {
         assert_eq!(inner.current_size, 0);
     }
 
+    #[test]
+    fn test_rotate_now_compresses_synchronously() {
+        let temp_dir = tempdir().unwrap();
+        let log_path = temp_dir.path().join("app.log");
+        let config = FileSinkConfig {
+            enabled: true,
+            path: log_path.clone(),
+            compress: true,
+            compression_format: crate::FileCompressionFormat::Gzip,
+            ..Default::default()
+        };
+        let sink = create_test_file_sink(config);
+        assert!(
+            sink.rotate_now().unwrap().is_none(),
+            "empty file is not rotated"
+        );
+
+        std::fs::write(&log_path, "line one\nline two\n").unwrap();
+        let archived = sink.rotate_now().unwrap().expect("rotated");
+        assert_eq!(archived.extension().unwrap(), "gz");
+        let mut decoder = flate2::read::GzDecoder::new(std::fs::File::open(&archived).unwrap());
+        let mut decompressed = Vec::new();
+        std::io::Read::read_to_end(&mut decoder, &mut decompressed).unwrap();
+        assert_eq!(decompressed, b"line one\nline two\n");
+        assert_eq!(std::fs::metadata(&log_path).unwrap().len(), 0);
+        let entries: Vec<_> = std::fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 2, "uncompressed copy should be removed");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_reopen_request_reopens_file_handle() {
+        let temp_dir = tempdir().unwrap();
+        let log_path = temp_dir.path().join("app.log");
+        let rotated_path = temp_dir.path().join("app.log.1");
+        let config = FileSinkConfig {
+            enabled: true,
+            path: log_path.clone(),
+            batch_size: 1,
+            ..Default::default()
+        };
+        let sink = create_test_file_sink(config);
+        sink.open_file_inner(&mut sink.inner.write()).unwrap();
+        sink.write(&create_test_record("before rotate"))
+            .await
+            .unwrap();
+
+        // 模拟 logrotate 的 create：移走文件并在原路径创建新文件
+        std::fs::rename(&log_path, &rotated_path).unwrap();
+        std::fs::write(&log_path, b"").unwrap();
+        sink.write(&create_test_record("old handle")).await.unwrap();
+
+        request_reopen();
+        sink.write(&create_test_record("new handle")).await.unwrap();
+
+        let rotated = std::fs::read_to_string(&rotated_path).unwrap();
+        assert!(rotated.contains("before rotate") && rotated.contains("old handle"));
+        let current = std::fs::read_to_string(&log_path).unwrap();
+        assert!(current.contains("new handle"));
+        assert!(!current.contains("old handle"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    fn test_sighup_requests_reopen() {
+        install_sighup_handler().unwrap();
+        // 重复安装是幂等的
+        install_sighup_handler().unwrap();
+
+        let before = reopen_generation();
+        let status = std::process::Command::new("kill")
+            .args(["-HUP", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let deadline = Instant::now() + StdDuration::from_secs(5);
+        while reopen_generation() == before {
+            assert!(Instant::now() < deadline, "SIGHUP was not handled");
+            thread::sleep(StdDuration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_flush_reopens_externally_rotated_file() {
+        let temp_dir = tempdir().unwrap();
+        let log_path = temp_dir.path().join("app.log");
+        let config = FileSinkConfig {
+            enabled: true,
+            path: log_path.clone(),
+            ..Default::default()
+        };
+        let sink = create_test_file_sink(config);
+        let mut inner = sink.inner.write();
+        sink.open_file_inner(&mut inner).unwrap();
+        std::fs::rename(&log_path, temp_dir.path().join("app_rotated.log")).unwrap();
+
+        // 检查间隔内不访问路径，记录仍写入被移走的文件
+        inner
+            .batch_buffer
+            .push(sink.frame(&create_test_record("before check")));
+        sink.flush_batch_inner(&mut inner).unwrap();
+        assert!(!log_path.exists());
+
+        inner.last_path_check = Instant::now() - PATH_CHECK_INTERVAL;
+        inner
+            .batch_buffer
+            .push(sink.frame(&create_test_record("after rotation")));
+        sink.flush_batch_inner(&mut inner).unwrap();
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("after rotation"));
+    }
+
+    #[tokio::test]
+    async fn test_size_rotation_keeps_only_keep_files_rotations() {
+        let temp_dir = tempdir().unwrap();
+        let log_path = temp_dir.path().join("burst.log");
+        // 不相关的文件不应被清理
+        std::fs::write(temp_dir.path().join("other_20200101_000000.log"), b"x").unwrap();
+        std::fs::write(temp_dir.path().join("burst_notes.txt"), b"x").unwrap();
+
+        let keep_files = 3;
+        let config = FileSinkConfig {
+            enabled: true,
+            path: log_path.clone(),
+            max_size: "64".to_string(),
+            keep_files,
+            compress: false,
+            encrypt: false,
+            masking_enabled: false,
+            batch_size: 1,
+            ..Default::default()
+        };
+        let sink = FileSink::new(config).unwrap();
+        for i in 0..(keep_files + 3) {
+            sink.write(&create_test_record(&format!("burst record {:0>64}", i)))
+                .await
+                .unwrap();
+        }
+        sink.flush().await.unwrap();
+        assert!(sink.inner.read().sequence >= keep_files + 3);
+        sink.shutdown().await.unwrap();
+
+        let mut rotated: Vec<String> = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .flatten()
+            .filter_map(|e| e.file_name().into_string().ok())
+            .filter(|name| rotated_file_key("burst", name).is_some())
+            .collect();
+        rotated.sort();
+        assert_eq!(rotated.len(), keep_files as usize, "{:?}", rotated);
+        assert!(log_path.exists());
+        assert!(temp_dir.path().join("other_20200101_000000.log").exists());
+        assert!(temp_dir.path().join("burst_notes.txt").exists());
+    }
+
+    #[test]
+    fn test_rotated_file_key_orders_same_second_rotations() {
+        let base = rotated_file_key("app", "app_20260316_101500.log").unwrap();
+        let second = rotated_file_key("app", "app_20260316_101500_2.zst").unwrap();
+        let tenth = rotated_file_key("app", "app_20260316_101500_10.gz.enc").unwrap();
+        assert!(base < second && second < tenth);
+        assert!(rotated_file_key("app", "app.log").is_none());
+        assert!(rotated_file_key("app", "app_notes.log").is_none());
+        assert!(rotated_file_key("app", "application_20260316_101500.log").is_none());
+    }
+
     #[test]
     fn test_rotate_inner_updates_next_rotation_time() {
         let temp_dir = tempdir().unwrap();
@@ -2038,6 +2649,75 @@ mod tests {
 
     // ==================== compress_file 测试 ====================
 
+    #[test]
+    fn test_compress_file_keeps_source_for_grace_period() {
+        let temp_dir = tempdir().unwrap();
+        let original_path = temp_dir.path().join("grace_20260101_000000.log");
+        std::fs::write(&original_path, b"grep me before it is gone").unwrap();
+
+        let config = FileSinkConfig {
+            enabled: true,
+            path: temp_dir.path().join("grace.log"),
+            compress: true,
+            compression_grace_secs: 1,
+            encrypt: false,
+            ..Default::default()
+        };
+        let sink = create_test_file_sink(config);
+
+        let compressed_path = sink.compress_file(&original_path).unwrap();
+        assert!(compressed_path.exists());
+        // 宽限期内未压缩文件仍可读取
+        assert_eq!(
+            std::fs::read(&original_path).unwrap(),
+            b"grep me before it is gone"
+        );
+
+        remove_expired_sources();
+        assert!(original_path.exists());
+
+        // 宽限期过后由清理定时器删除
+        thread::sleep(StdDuration::from_millis(1100));
+        remove_expired_sources();
+        assert!(
+            !original_path.exists(),
+            "source should be removed after the grace period"
+        );
+        assert!(compressed_path.exists());
+    }
+
+    #[test]
+    fn test_open_sweeps_archived_sources_past_grace_period() {
+        let temp_dir = tempdir().unwrap();
+        let archived_source = temp_dir.path().join("app_20260101_000000.log");
+        let archive = temp_dir.path().join("app_20260101_000000.gz");
+        let unarchived = temp_dir.path().join("app_20260101_000001.log");
+        for path in [&archived_source, &archive, &unarchived] {
+            std::fs::write(path, b"rotated").unwrap();
+        }
+
+        let config = FileSinkConfig {
+            enabled: true,
+            path: temp_dir.path().join("app.log"),
+            compression_grace_secs: 60,
+            ..Default::default()
+        };
+        // 归档产物刚生成，仍在宽限期内
+        drop(FileSink::new(config.clone()).unwrap());
+        assert!(archived_source.exists());
+
+        let sink = FileSink::new(FileSinkConfig {
+            compression_grace_secs: 0,
+            ..config
+        })
+        .unwrap();
+        assert!(!archived_source.exists());
+        assert!(archive.exists());
+        // 没有归档产物的轮转文件不受影响
+        assert!(unarchived.exists());
+        drop(sink);
+    }
+
     #[test]
     #[cfg(feature = "compression")]
     fn test_compress_file_roundtrip() {
@@ -2072,6 +2752,36 @@ mod tests {
         assert_eq!(decompressed, original_content);
     }
 
+    #[test]
+    fn test_compress_file_gzip_format_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let original_path = temp_dir.path().join("test.log");
+        let original_content = b"This is test content for gzip compression. Hello World!";
+        std::fs::write(&original_path, original_content).unwrap();
+
+        let config = FileSinkConfig {
+            enabled: true,
+            path: temp_dir.path().join("dummy.log"),
+            compress: true,
+            compression_format: crate::FileCompressionFormat::Gzip,
+            // 超出 gzip 的 0-9 范围，应被截断而非报错
+            compression_level: 19,
+            encrypt: false,
+            ..Default::default()
+        };
+        let sink = create_test_file_sink(config);
+
+        let compressed_path = sink.compress_file(&original_path).unwrap();
+        assert_eq!(compressed_path.extension().unwrap(), "gz");
+        assert!(!original_path.exists());
+
+        let compressed_file = std::fs::File::open(&compressed_path).unwrap();
+        let mut decoder = flate2::read::GzDecoder::new(compressed_file);
+        let mut decompressed = Vec::new();
+        std::io::Read::read_to_end(&mut decoder, &mut decompressed).unwrap();
+        assert_eq!(decompressed, original_content);
+    }
+
     #[test]
     #[cfg(feature = "compression")]
     fn test_compress_file_nonexistent_input_returns_error() {
@@ -2119,15 +2829,14 @@ mod tests {
         assert_eq!(encrypted_path.extension().unwrap(), "enc");
         assert!(encrypted_path.exists());
 
-        // 解密：前 12 字节是 nonce，其余是 ciphertext
-        let encrypted_data = std::fs::read(&encrypted_path).unwrap();
-        assert!(encrypted_data.len() > 12);
-        use aes_gcm::{Aes256Gcm, Nonce};
-        let cipher = Aes256Gcm::new_from_slice(&key_bytes).unwrap();
-        let nonce_arr: [u8; 12] = encrypted_data[..12].try_into().unwrap();
-        let nonce = Nonce::from(nonce_arr);
-        let ciphertext = &encrypted_data[12..];
-        let decrypted_compressed = cipher.decrypt(&nonce, ciphertext).unwrap();
+        // 解密：分块格式，逐帧校验
+        let mut decrypted_compressed = Vec::new();
+        crate::sink::encryption::decrypt_chunked(
+            &[test_encryption_key(&key_bytes)],
+            std::fs::File::open(&encrypted_path).unwrap(),
+            &mut decrypted_compressed,
+        )
+        .unwrap();
 
         // 解压
         let mut decoder = zstd::stream::Decoder::new(&decrypted_compressed[..]).unwrap();
@@ -2178,15 +2887,14 @@ mod tests {
         assert_eq!(encrypted_path.extension().unwrap(), "enc");
         assert!(encrypted_path.exists());
 
-        // 解密：前 12 字节是 nonce，其余是 ciphertext
-        let encrypted_data = std::fs::read(&encrypted_path).unwrap();
-        assert!(encrypted_data.len() > 12);
-        use aes_gcm::{Aes256Gcm, Nonce};
-        let cipher = Aes256Gcm::new_from_slice(&key_bytes).unwrap();
-        let nonce_arr: [u8; 12] = encrypted_data[..12].try_into().unwrap();
-        let nonce = Nonce::from(nonce_arr);
-        let ciphertext = &encrypted_data[12..];
-        let decrypted_compressed = cipher.decrypt(&nonce, ciphertext).unwrap();
+        // 解密：分块格式，逐帧校验
+        let mut decrypted_compressed = Vec::new();
+        crate::sink::encryption::decrypt_chunked(
+            &[test_encryption_key(&key_bytes)],
+            std::fs::File::open(&encrypted_path).unwrap(),
+            &mut decrypted_compressed,
+        )
+        .unwrap();
 
         // gzip 解压
         use std::io::Read;
@@ -2235,15 +2943,14 @@ mod tests {
         assert!(result.is_ok(), "encrypt_file failed: {:?}", result.err());
         assert!(output_path.exists());
 
-        // 解密验证
-        let encrypted_data = std::fs::read(&output_path).unwrap();
-        assert!(encrypted_data.len() > 12);
-        use aes_gcm::{Aes256Gcm, Nonce};
-        let cipher = Aes256Gcm::new_from_slice(&key_bytes).unwrap();
-        let nonce_arr: [u8; 12] = encrypted_data[..12].try_into().unwrap();
-        let nonce = Nonce::from(nonce_arr);
-        let ciphertext = &encrypted_data[12..];
-        let decrypted = cipher.decrypt(&nonce, ciphertext).unwrap();
+        // 解密：分块格式，逐帧校验
+        let mut decrypted = Vec::new();
+        crate::sink::encryption::decrypt_chunked(
+            &[test_encryption_key(&key_bytes)],
+            std::fs::File::open(&output_path).unwrap(),
+            &mut decrypted,
+        )
+        .unwrap();
         assert_eq!(decrypted, original_content);
 
         unsafe {
@@ -2251,6 +2958,43 @@ mod tests {
         }
     }
 
+    #[test]
+    #[serial]
+    fn test_encrypt_file_writes_key_id_header() {
+        let temp_dir = tempdir().unwrap();
+        let input_path = temp_dir.path().join("test.log");
+        std::fs::write(&input_path, b"key id header").unwrap();
+
+        let (_key_bytes, key_b64) = make_test_key();
+        unsafe {
+            std::env::set_var("TEST_ENC_KEY_ID", &key_b64);
+        }
+
+        // 头部：魔数 8 + 版本 2 + 算法 2 + 块大小 4，随后是标识长度与标识
+        let key_id_of = |key_id: Option<&str>, name: &str| {
+            let output_path = temp_dir.path().join(name);
+            let sink = create_test_file_sink(FileSinkConfig {
+                enabled: true,
+                path: temp_dir.path().join("dummy.log"),
+                encrypt: true,
+                encryption_key_env: Some("TEST_ENC_KEY_ID".to_string()),
+                encryption_key_id: key_id.map(str::to_string),
+                ..Default::default()
+            });
+            sink.encrypt_file(&input_path, &output_path).unwrap();
+            let encrypted = std::fs::read(&output_path).unwrap();
+            let len = encrypted[16] as usize;
+            String::from_utf8(encrypted[17..17 + len].to_vec()).unwrap()
+        };
+
+        assert_eq!(key_id_of(None, "default.enc"), "TEST_ENC_KEY_ID");
+        assert_eq!(key_id_of(Some("2026-10"), "custom.enc"), "2026-10");
+
+        unsafe {
+            std::env::remove_var("TEST_ENC_KEY_ID");
+        }
+    }
+
     #[test]
     #[serial]
     fn test_encrypt_file_missing_key_returns_error() {
@@ -2386,6 +3130,7 @@ mod tests {
             batch_size: 100,
             flush_interval_ms: 100,
             masking_enabled: true,
+            ..Default::default()
         };
 
         let result = FileSink::perform_cleanup(&config, &dir.path().join("test.log"));
@@ -2508,7 +3253,9 @@ mod tests {
         {
             let mut inner = sink.inner.write();
             sink.open_file_inner(&mut inner).unwrap();
-            inner.batch_buffer.push(create_test_record("Flush test"));
+            inner
+                .batch_buffer
+                .push(sink.frame(&create_test_record("Flush test")));
         }
         let result = sink.flush().await;
         assert!(result.is_ok());
@@ -2543,7 +3290,9 @@ mod tests {
         {
             let mut inner = sink.inner.write();
             sink.open_file_inner(&mut inner).unwrap();
-            inner.batch_buffer.push(create_test_record("Shutdown test"));
+            inner
+                .batch_buffer
+                .push(sink.frame(&create_test_record("Shutdown test")));
         }
         let result = sink.shutdown().await;
         assert!(result.is_ok());
@@ -2577,6 +3326,183 @@ mod tests {
         sink.shutdown().await.unwrap();
     }
 
+    async fn write_framed(delimiter: LineDelimiter, message: &str) -> Vec<u8> {
+        let temp_dir = tempdir().unwrap();
+        let log_path = temp_dir.path().join("framed.log");
+        let config = FileSinkConfig {
+            enabled: true,
+            path: log_path.clone(),
+            masking_enabled: false,
+            batch_size: 1,
+            line_delimiter: delimiter,
+            ..Default::default()
+        };
+        let sink = FileSink::new(config).unwrap();
+        sink.write(&create_test_record(message)).await.unwrap();
+        sink.flush().await.unwrap();
+        sink.shutdown().await.unwrap();
+        std::fs::read(&log_path).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_write_newline_delimiter_escapes_embedded_newlines() {
+        let content = write_framed(LineDelimiter::Newline, "first\nsecond\r\nthird").await;
+        let text = String::from_utf8(content).unwrap();
+        assert_eq!(text.matches('\n').count(), 1);
+        assert!(text.ends_with("first\\nsecond\\r\\nthird\n"));
+    }
+
+    #[tokio::test]
+    async fn test_write_nul_delimiter_keeps_newlines() {
+        let content = write_framed(LineDelimiter::Nul, "first\nsecond").await;
+        assert_eq!(content.last(), Some(&0));
+        assert_eq!(content.iter().filter(|&&b| b == 0).count(), 1);
+        let text = String::from_utf8(content[..content.len() - 1].to_vec()).unwrap();
+        assert!(text.ends_with("first\nsecond"));
+    }
+
+    #[tokio::test]
+    async fn test_write_length_prefixed_delimiter_keeps_newlines() {
+        let content = write_framed(LineDelimiter::LengthPrefixed, "first\nsecond").await;
+        let len = u32::from_be_bytes(content[..4].try_into().unwrap()) as usize;
+        assert_eq!(len, content.len() - 4);
+        let text = String::from_utf8(content[4..].to_vec()).unwrap();
+        assert!(text.ends_with("first\nsecond"));
+    }
+
+    #[tokio::test]
+    async fn test_write_json_format_keeps_all_fields() {
+        let temp_dir = tempdir().unwrap();
+        let log_path = temp_dir.path().join("records.jsonl");
+        let config = FileSinkConfig {
+            enabled: true,
+            path: log_path.clone(),
+            masking_enabled: false,
+            batch_size: 1,
+            format: FileFormat::Json,
+            ..Default::default()
+        };
+        let sink = FileSink::new(config).unwrap();
+
+        let mut record = create_test_record("first\nsecond");
+        record
+            .fields
+            .insert("user_id".to_string(), serde_json::json!(42));
+        record.fields.insert(
+            "request".to_string(),
+            serde_json::json!({ "path": "/orders", "ok": true }),
+        );
+        sink.write(&record).await.unwrap();
+        sink.write(&create_test_record("plain")).await.unwrap();
+        sink.flush().await.unwrap();
+        sink.shutdown().await.unwrap();
+
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        // 大小统计按写出的 JSON 行计算
+        assert_eq!(
+            frame_record_as(&record, FileFormat::Json, LineDelimiter::Newline).len(),
+            content.lines().next().unwrap().len() + 1
+        );
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        let first = &lines[0];
+        assert_eq!(first["message"], "first\nsecond");
+        assert_eq!(first["level"], "INFO");
+        assert_eq!(first["target"], "test_module");
+        assert_eq!(first["file"], "/path/to/test.rs");
+        assert_eq!(first["line"], 42);
+        assert_eq!(first["thread_id"], "test-thread");
+        assert_eq!(first["fields"]["user_id"], 42);
+        assert_eq!(first["fields"]["request"]["path"], "/orders");
+        assert_eq!(
+            first["timestamp"],
+            serde_json::to_value(record.timestamp).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_buffer_size_flushes_before_interval() {
+        let temp_dir = tempdir().unwrap();
+        let log_path = temp_dir.path().join("buffered.log");
+        let config = FileSinkConfig {
+            enabled: true,
+            path: log_path.clone(),
+            masking_enabled: false,
+            batch_size: 10_000,
+            flush_interval_ms: 60_000,
+            buffer_size: Some("1KB".to_string()),
+            ..Default::default()
+        };
+        let sink = FileSink::new(config).unwrap();
+        let record = create_test_record(&"x".repeat(200));
+        let record_len = sink.frame(&record).len() as u64;
+
+        // 未达到阈值：仅在内存中缓冲
+        sink.write(&record).await.unwrap();
+        assert_eq!(std::fs::metadata(&log_path).unwrap().len(), 0);
+
+        // 越过阈值的那次写入立即落盘
+        let count = 1024 / record_len + 1;
+        for _ in 1..count {
+            sink.write(&record).await.unwrap();
+        }
+        assert_eq!(
+            std::fs::metadata(&log_path).unwrap().len(),
+            count * record_len
+        );
+        assert!(sink.inner.read().batch_buffer.is_empty());
+        sink.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_with_custom_template() {
+        let temp_dir = tempdir().unwrap();
+        let log_path = temp_dir.path().join("templated.log");
+        let config = FileSinkConfig {
+            enabled: true,
+            path: log_path.clone(),
+            masking_enabled: false,
+            batch_size: 1,
+            template: Some("[{level}] {message} ({file}:{line})".to_string()),
+            ..Default::default()
+        };
+        let sink = FileSink::new(config).unwrap();
+        sink.write(&create_test_record("from config"))
+            .await
+            .unwrap();
+        sink.flush().await.unwrap();
+        sink.shutdown().await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            "[INFO] from config (/path/to/test.rs:42)\n"
+        );
+
+        // 显式传入的模板覆盖配置
+        let other_path = temp_dir.path().join("builder.log");
+        let config = FileSinkConfig {
+            enabled: true,
+            path: other_path.clone(),
+            masking_enabled: false,
+            batch_size: 1,
+            ..Default::default()
+        };
+        let sink = FileSink::new(config)
+            .unwrap()
+            .with_template(LogTemplate::new("{thread_id} {target}: {message}"));
+        sink.write(&create_test_record("from builder"))
+            .await
+            .unwrap();
+        sink.flush().await.unwrap();
+        sink.shutdown().await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&other_path).unwrap(),
+            "test-thread test_module: from builder\n"
+        );
+    }
+
     #[tokio::test]
     async fn test_write_with_masking_disabled_preserves_sensitive_value() {
         let temp_dir = tempdir().unwrap();
@@ -2806,6 +3732,47 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_file_mode_applies_to_log_and_compressed_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let log_path = temp_dir.path().join("shared.log");
+        let config = FileSinkConfig {
+            enabled: true,
+            path: log_path.clone(),
+            compress: true,
+            compression_format: crate::FileCompressionFormat::Gzip,
+            encrypt: false,
+            file_mode: Some(0o640),
+            ..Default::default()
+        };
+        let sink = create_test_file_sink(config);
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+        sink.open_file_inner(&mut sink.inner.write()).unwrap();
+        assert_eq!(mode(&log_path), 0o640);
+
+        let rotated = temp_dir.path().join("shared_20260101_000000.log");
+        std::fs::write(&rotated, b"rotated").unwrap();
+        let compressed = sink.compress_file(&rotated).unwrap();
+        assert_eq!(mode(&compressed), 0o640);
+    }
+
+    #[test]
+    fn test_get_disk_space_info_relative_path_uses_current_dir() {
+        // 无目录部分的相对路径，父目录为空字符串时应回退到当前目录
+        let config = FileSinkConfig {
+            enabled: true,
+            path: PathBuf::from("relative.log"),
+            ..Default::default()
+        };
+        let sink = create_test_file_sink(config);
+        let (total, _available) = sink.get_disk_space_info().unwrap();
+        assert!(total > 0);
+    }
+
     // ==================== perform_cleanup 边界测试 ====================
 
     #[test]
@@ -3370,7 +4337,9 @@ mod tests {
         let _ = inner.current_file.take(); // 移除文件句柄
 
         // 此时 batch_buffer 有记录但无文件句柄
-        inner.batch_buffer.push(create_test_record("Will fail"));
+        inner
+            .batch_buffer
+            .push(sink.frame(&create_test_record("Will fail")));
         let initial_failures = inner.circuit_breaker.failure_count();
         let result = sink.flush_batch_inner(&mut inner);
 
@@ -3646,7 +4615,7 @@ mod tests {
     fn test_validate_key_entropy_single_byte_repeated() {
         // 单字节重复 32 次：熵为 0，应被拒绝
         let weak_key = [0x42; 32];
-        let result = FileSink::validate_key_entropy(&weak_key);
+        let result = crate::sink::encryption::validate_key_entropy(&weak_key);
         assert!(
             result.is_err(),
             "single-byte repeated key should be rejected"
@@ -3660,7 +4629,7 @@ mod tests {
         for (i, byte) in pattern_key.iter_mut().enumerate() {
             *byte = if i % 2 == 0 { 0xAA } else { 0x55 };
         }
-        let result = FileSink::validate_key_entropy(&pattern_key);
+        let result = crate::sink::encryption::validate_key_entropy(&pattern_key);
         assert!(
             result.is_err(),
             "two-byte pattern key should be rejected (entropy < 4.0)"
@@ -3675,7 +4644,7 @@ mod tests {
         for (i, byte) in pattern_key.iter_mut().enumerate() {
             *byte = pattern[i % 4];
         }
-        let result = FileSink::validate_key_entropy(&pattern_key);
+        let result = crate::sink::encryption::validate_key_entropy(&pattern_key);
         assert!(
             result.is_err(),
             "four-byte pattern key should be rejected (entropy = 2.0 < 4.0)"
@@ -0,0 +1,471 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! Grafana Loki sink implementation.
+//!
+//! Batches log records and pushes them as JSON to `/loki/api/v1/push`, grouped
+//! into streams by their label values. Pushes failing with 429 or a 5xx status
+//! are retried with backoff; everything that cannot be pushed goes to the
+//! console fallback.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use super::{CircuitBreaker, ConsoleSink, LogSink, masked_record};
+use crate::DataMasker;
+use crate::InklogError;
+use crate::LogRecord;
+use crate::LokiSinkConfig;
+use crate::template::LogTemplate;
+
+/// Loki push API 路径
+const LOKI_PUSH_PATH: &str = "/loki/api/v1/push";
+
+/// 超出 `max_label_values` 的标签值统一替换为该值
+const OVERFLOW_LABEL_VALUE: &str = "_other";
+
+/// 单次 push 请求的超时
+const PUSH_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 按标签集分组的 push 条目，每项为 `[纳秒时间戳, 日志行]`
+type StreamBatches = Vec<(BTreeMap<String, String>, Vec<[String; 2]>)>;
+
+fn read_secret(env: &str) -> Result<String, InklogError> {
+    std::env::var(env).map_err(|_| {
+        InklogError::ConfigError(format!(
+            "Loki credential not found in environment variable: {}",
+            env
+        ))
+    })
+}
+
+struct LokiSinkInner {
+    buffer: Vec<LogRecord>,
+    last_flush: Instant,
+    circuit_breaker: CircuitBreaker,
+    /// 每个标签键已出现过的值，用于限制 cardinality
+    label_values: HashMap<String, HashSet<String>>,
+}
+
+/// Sink that pushes log records to Grafana Loki.
+pub struct LokiSink {
+    inner: Mutex<LokiSinkInner>,
+    client: reqwest::Client,
+    push_url: String,
+    password: Option<String>,
+    template: LogTemplate,
+    config: LokiSinkConfig,
+    fallback: Option<ConsoleSink>,
+    masker: Option<DataMasker>,
+}
+
+impl LokiSink {
+    /// 创建 LokiSink；basic auth 密码从配置的环境变量中读取
+    ///
+    /// # 错误
+    ///
+    /// - `InklogError::ConfigError` - 配置无效或凭据环境变量不存在
+    pub fn new(config: LokiSinkConfig) -> Result<Self, InklogError> {
+        config.validate()?;
+
+        let password = config
+            .password_env
+            .as_deref()
+            .map(read_secret)
+            .transpose()?;
+        let client = reqwest::Client::builder()
+            .timeout(PUSH_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| InklogError::ConfigError(format!("Failed to build Loki client: {}", e)))?;
+        let base = config.url.trim().trim_end_matches('/');
+        let push_url = if base.ends_with(LOKI_PUSH_PATH) {
+            base.to_string()
+        } else {
+            format!("{}{}", base, LOKI_PUSH_PATH)
+        };
+
+        Ok(Self {
+            inner: Mutex::new(LokiSinkInner {
+                buffer: Vec::with_capacity(config.batch_size),
+                last_flush: Instant::now(),
+                circuit_breaker: CircuitBreaker::new(3, Duration::from_secs(30), 3),
+                label_values: HashMap::new(),
+            }),
+            client,
+            push_url,
+            password,
+            template: LogTemplate::new(&config.line_format),
+            config,
+            fallback: None,
+            masker: None,
+        })
+    }
+
+    /// push 失败时将记录写入控制台
+    pub fn with_console_fallback(mut self, sink: ConsoleSink) -> Self {
+        self.fallback = Some(sink);
+        self
+    }
+
+    /// 构建 push 请求前用 `masker` 脱敏 message 与 fields
+    pub fn with_masker(mut self, masker: DataMasker) -> Self {
+        self.masker = Some(masker);
+        self
+    }
+
+    async fn write_fallback(&self, records: &[LogRecord]) {
+        if let Some(ref sink) = self.fallback {
+            for record in records {
+                let _ = sink.write(record).await;
+            }
+        }
+    }
+
+    /// 计算记录所属 stream 的标签集；字段缺失的标签键不出现在标签集中
+    fn stream_labels(
+        &self,
+        label_values: &mut HashMap<String, HashSet<String>>,
+        record: &LogRecord,
+    ) -> BTreeMap<String, String> {
+        let mut labels: BTreeMap<String, String> = self
+            .config
+            .labels
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        for key in &self.config.label_keys {
+            let value = match key.as_str() {
+                "level" => record.level.to_lowercase(),
+                "target" => record.target.clone(),
+                field => match record.fields.get(field) {
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(serde_json::Value::Null) | None => continue,
+                    Some(other) => other.to_string(),
+                },
+            };
+            let seen = label_values.entry(key.clone()).or_default();
+            let value = if seen.contains(&value) || seen.len() < self.config.max_label_values {
+                seen.insert(value.clone());
+                value
+            } else {
+                OVERFLOW_LABEL_VALUE.to_string()
+            };
+            labels.insert(key.clone(), value);
+        }
+        labels
+    }
+
+    /// 构建 push 请求体：按标签集分组，条目为 `[纳秒时间戳, 日志行]`
+    fn push_body(
+        &self,
+        label_values: &mut HashMap<String, HashSet<String>>,
+        records: &[LogRecord],
+    ) -> serde_json::Value {
+        let mut streams: StreamBatches = Vec::new();
+        for record in records {
+            let labels = self.stream_labels(label_values, record);
+            let entry = [
+                record
+                    .timestamp
+                    .timestamp_nanos_opt()
+                    .unwrap_or_default()
+                    .to_string(),
+                self.template.render(record),
+            ];
+            match streams.iter_mut().find(|(stream, _)| *stream == labels) {
+                Some((_, values)) => values.push(entry),
+                None => streams.push((labels, vec![entry])),
+            }
+        }
+
+        serde_json::json!({
+            "streams": streams
+                .into_iter()
+                .map(|(stream, values)| serde_json::json!({ "stream": stream, "values": values }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// 发送一次 push 请求；错误中的布尔值表示是否可重试
+    async fn send_push(&self, body: &serde_json::Value) -> Result<(), (bool, String)> {
+        let mut request = self.client.post(&self.push_url).json(body);
+        if let Some(ref tenant) = self.config.tenant_id {
+            request = request.header("X-Scope-OrgID", tenant);
+        }
+        if let Some(ref username) = self.config.username {
+            request = request.basic_auth(username, self.password.as_deref());
+        }
+
+        let response = request.send().await.map_err(|e| (true, e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let detail = response.text().await.unwrap_or_default();
+        Err((
+            status.as_u16() == 429 || status.is_server_error(),
+            format!("Loki returned {}: {}", status, detail.trim()),
+        ))
+    }
+
+    async fn flush_inner(&self, inner: &mut LokiSinkInner) -> Result<(), InklogError> {
+        if inner.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let pending = std::mem::take(&mut inner.buffer);
+        inner.last_flush = Instant::now();
+        let body = self.push_body(&mut inner.label_values, &pending);
+        let mut attempt = 0u32;
+
+        loop {
+            match self.send_push(&body).await {
+                Ok(()) => {
+                    inner.circuit_breaker.record_success();
+                    return Ok(());
+                }
+                Err((true, _)) if attempt < self.config.max_retries => {
+                    attempt += 1;
+                    tokio::time::sleep(Duration::from_millis(100 << attempt.min(6))).await;
+                }
+                Err((_, message)) => {
+                    inner.circuit_breaker.record_failure();
+                    self.write_fallback(&pending).await;
+                    return Err(InklogError::IoError(std::io::Error::other(format!(
+                        "Loki push of {} records failed after {} retries: {}",
+                        pending.len(),
+                        attempt,
+                        message
+                    ))));
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl LogSink for LokiSink {
+    async fn write(&self, record: &LogRecord) -> Result<(), InklogError> {
+        let record = masked_record(record, self.masker.as_ref());
+        let mut inner = self.inner.lock().await;
+
+        if !inner.circuit_breaker.can_execute() {
+            self.write_fallback(std::slice::from_ref(record.as_ref()))
+                .await;
+            return Ok(());
+        }
+
+        inner.buffer.push(record.into_owned());
+
+        if inner.buffer.len() >= self.config.batch_size.max(1)
+            || inner.last_flush.elapsed() > Duration::from_millis(self.config.flush_interval_ms)
+        {
+            self.flush_inner(&mut inner).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<(), InklogError> {
+        let mut inner = self.inner.lock().await;
+        self.flush_inner(&mut inner).await
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.inner
+            .try_lock()
+            .map(|inner| inner.circuit_breaker.can_execute())
+            .unwrap_or(true)
+    }
+
+    async fn shutdown(&self) -> Result<(), InklogError> {
+        let mut inner = self.inner.lock().await;
+        self.flush_inner(&mut inner).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConsoleCapture;
+    use crate::ConsoleSinkConfig;
+    use chrono::{TimeZone, Utc};
+    use std::sync::Arc;
+
+    fn record(level: tracing::Level, target: &str, message: &str) -> LogRecord {
+        let mut record = LogRecord::new(level, target.to_string(), message.to_string());
+        record.timestamp = Utc.with_ymd_and_hms(2026, 3, 7, 12, 0, 0).unwrap();
+        record
+    }
+
+    /// 启动模拟 push 端点：记录每次请求的 JSON 与租户头，返回固定状态码
+    async fn mock_loki(
+        status: u16,
+    ) -> (
+        String,
+        Arc<std::sync::Mutex<Vec<(Option<String>, serde_json::Value)>>>,
+    ) {
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let requests_clone = requests.clone();
+        let app = axum::Router::new().route(
+            LOKI_PUSH_PATH,
+            axum::routing::post(
+                move |headers: axum::http::HeaderMap,
+                      axum::Json(body): axum::Json<serde_json::Value>| {
+                    let requests = requests_clone.clone();
+                    async move {
+                        let tenant = headers
+                            .get("X-Scope-OrgID")
+                            .and_then(|v| v.to_str().ok())
+                            .map(str::to_string);
+                        requests.lock().unwrap().push((tenant, body));
+                        axum::http::StatusCode::from_u16(status).unwrap()
+                    }
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock loki");
+        let addr = listener.local_addr().expect("local addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        (format!("http://{}", addr), requests)
+    }
+
+    fn sink_config(url: String) -> LokiSinkConfig {
+        LokiSinkConfig {
+            enabled: true,
+            url,
+            labels: HashMap::from([("job".to_string(), "checkout".to_string())]),
+            tenant_id: Some("team-a".to_string()),
+            batch_size: 10,
+            flush_interval_ms: 60_000,
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_body_shape() {
+        let (url, requests) = mock_loki(204).await;
+        let sink = LokiSink::new(sink_config(url)).unwrap();
+
+        let mut with_field = record(tracing::Level::INFO, "app::http", "served");
+        with_field
+            .fields
+            .insert("status".to_string(), serde_json::json!(200));
+        sink.write(&with_field).await.unwrap();
+        sink.write(&record(tracing::Level::ERROR, "app::db", "timeout"))
+            .await
+            .unwrap();
+        sink.write(&record(tracing::Level::INFO, "app::http", "served again"))
+            .await
+            .unwrap();
+        assert!(requests.lock().unwrap().is_empty());
+        sink.flush().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let (tenant, body) = &requests[0];
+        assert_eq!(tenant.as_deref(), Some("team-a"));
+        let streams = body["streams"].as_array().unwrap();
+        assert_eq!(streams.len(), 2);
+        assert_eq!(
+            streams[0]["stream"],
+            serde_json::json!({ "job": "checkout", "level": "info", "target": "app::http" })
+        );
+        let nanos = Utc
+            .with_ymd_and_hms(2026, 3, 7, 12, 0, 0)
+            .unwrap()
+            .timestamp_nanos_opt()
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            streams[0]["values"],
+            serde_json::json!([[nanos, "served status=200"], [nanos, "served again"]])
+        );
+        assert_eq!(streams[1]["stream"]["level"], "error");
+        assert_eq!(streams[1]["values"][0][1], "timeout");
+    }
+
+    #[tokio::test]
+    async fn test_pushed_lines_are_masked() {
+        let (url, requests) = mock_loki(204).await;
+        let sink = LokiSink::new(sink_config(url))
+            .unwrap()
+            .with_masker(DataMasker::new());
+
+        let mut login = record(
+            tracing::Level::INFO,
+            "app::auth",
+            "login for alice@example.com",
+        );
+        login
+            .fields
+            .insert("password".to_string(), serde_json::json!("hunter2"));
+        sink.write(&login).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        let line = requests[0].1["streams"][0]["values"][0][1]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(line.contains("password=***MASKED***"), "{line}");
+        assert!(!line.contains("hunter2"), "{line}");
+        assert!(!line.contains("alice@example.com"), "{line}");
+    }
+
+    #[tokio::test]
+    async fn test_label_values_are_capped() {
+        let (url, requests) = mock_loki(204).await;
+        let config = LokiSinkConfig {
+            label_keys: vec!["target".to_string()],
+            max_label_values: 1,
+            ..sink_config(url)
+        };
+        let sink = LokiSink::new(config).unwrap();
+
+        for target in ["app::a", "app::b", "app::c"] {
+            sink.write(&record(tracing::Level::INFO, target, "m"))
+                .await
+                .unwrap();
+        }
+        sink.flush().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        let streams = requests[0].1["streams"].as_array().unwrap();
+        let targets: Vec<&str> = streams
+            .iter()
+            .map(|s| s["stream"]["target"].as_str().unwrap())
+            .collect();
+        assert_eq!(targets, ["app::a", OVERFLOW_LABEL_VALUE]);
+        assert_eq!(streams[1]["values"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_push_falls_back_to_console() {
+        let (url, requests) = mock_loki(400).await;
+        let capture = ConsoleCapture::new();
+        let console = ConsoleSink::with_buffer(
+            ConsoleSinkConfig::default(),
+            LogTemplate::default(),
+            capture.buffer(),
+        );
+        let sink = LokiSink::new(sink_config(url))
+            .unwrap()
+            .with_console_fallback(console);
+
+        sink.write(&record(tracing::Level::WARN, "app", "rejected line"))
+            .await
+            .unwrap();
+        assert!(sink.flush().await.is_err());
+
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert!(capture.contents().contains("rejected line"));
+    }
+}
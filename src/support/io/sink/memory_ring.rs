@@ -0,0 +1,197 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! In-memory ring buffer of the most recent log records.
+
+use super::LogSink;
+use crate::DataMasker;
+use crate::InklogError;
+use crate::LogRecord;
+use crate::LogTemplate;
+use crate::MemoryRingSinkConfig;
+use arc_swap::ArcSwapOption;
+use async_trait::async_trait;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+
+/// 环形缓冲区中的一个槽位，`seq` 为写入序号，用于识别被覆盖的槽位
+struct Slot {
+    seq: u64,
+    record: Arc<LogRecord>,
+}
+
+/// 保存最近 `capacity` 条记录的内存 sink
+///
+/// 写入只做一次 `fetch_add` 和一次原子指针替换，不持有锁；超出容量后最旧的
+/// 记录被覆盖。通过 [`dump_recent`](Self::dump_recent) 按写入顺序读取。
+pub struct MemoryRingSink {
+    slots: Box<[ArcSwapOption<Slot>]>,
+    /// 已写入的记录总数，同时是下一条记录的序号
+    next_seq: AtomicU64,
+    template: LogTemplate,
+    masker: DataMasker,
+}
+
+impl MemoryRingSink {
+    /// 创建容量为 `capacity` 的环形缓冲区（至少为 1）
+    pub fn new(capacity: usize) -> Self {
+        let slots = (0..capacity.max(1))
+            .map(|_| ArcSwapOption::empty())
+            .collect();
+        Self {
+            slots,
+            next_seq: AtomicU64::new(0),
+            template: LogTemplate::default(),
+            masker: DataMasker::new(),
+        }
+    }
+
+    /// 按配置创建，`template` 用于渲染 panic dump 文件中的每一行
+    pub fn from_config(config: &MemoryRingSinkConfig, template: LogTemplate) -> Self {
+        Self::new(config.capacity).with_template(template)
+    }
+
+    pub fn with_template(mut self, template: LogTemplate) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// 写入 dump 文件前对记录做脱敏
+    pub fn with_masker(mut self, masker: DataMasker) -> Self {
+        self.masker = masker;
+        self
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// 追加一条记录，必要时覆盖最旧的记录
+    pub fn push(&self, record: Arc<LogRecord>) {
+        let seq = self.next_seq.fetch_add(1, Ordering::AcqRel);
+        let index = (seq % self.slots.len() as u64) as usize;
+        self.slots[index].store(Some(Arc::new(Slot { seq, record })));
+    }
+
+    /// 返回最近的至多 `n` 条记录，按写入顺序从旧到新排列
+    ///
+    /// 与并发写入同时进行时，已被更新记录覆盖的槽位会被跳过。
+    pub fn dump_recent(&self, n: usize) -> Vec<LogRecord> {
+        let end = self.next_seq.load(Ordering::Acquire);
+        let count = n.min(self.slots.len()) as u64;
+        let start = end.saturating_sub(count);
+        (start..end)
+            .filter_map(|seq| {
+                let index = (seq % self.slots.len() as u64) as usize;
+                self.slots[index]
+                    .load_full()
+                    .filter(|slot| slot.seq == seq)
+                    .map(|slot| LogRecord::clone(&slot.record))
+            })
+            .collect()
+    }
+
+    /// 将缓冲区中的全部记录追加写入 `path`
+    ///
+    /// # 错误
+    ///
+    /// 无法创建目录或写入文件时返回 `IoError`。
+    pub fn dump_to_file(&self, path: &Path) -> Result<usize, InklogError> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut writer = BufWriter::new(file);
+        let records = self.dump_recent(self.slots.len());
+        for record in &records {
+            let mut masked = record.clone();
+            masked.message = self.masker.mask(&record.message);
+            self.masker.mask_hashmap(&mut masked.fields);
+            writeln!(writer, "{}", self.template.render(&masked))?;
+        }
+        writer.flush()?;
+        Ok(records.len())
+    }
+
+    /// 安装 panic hook：panic 时先把缓冲区写入 `path`，再调用之前的 hook
+    ///
+    /// hook 只持有弱引用，sink 释放后 dump 步骤自动跳过。
+    pub fn install_panic_hook(self: &Arc<Self>, path: impl AsRef<Path>) {
+        let ring: Weak<Self> = Arc::downgrade(self);
+        let path = path.as_ref().to_path_buf();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if let Some(ring) = ring.upgrade()
+                && let Err(e) = ring.dump_to_file(&path)
+            {
+                eprintln!(
+                    "inklog: failed to dump recent logs to {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+            previous(info);
+        }));
+    }
+}
+
+#[async_trait]
+impl LogSink for MemoryRingSink {
+    async fn write(&self, record: &LogRecord) -> Result<(), InklogError> {
+        self.push(Arc::new(record.clone()));
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<(), InklogError> {
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<(), InklogError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::Level;
+
+    fn record(message: &str) -> LogRecord {
+        LogRecord::new(Level::INFO, "ring".to_string(), message.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_overwrites_past_capacity_and_keeps_order() {
+        let sink = MemoryRingSink::new(3);
+        for i in 0..5 {
+            sink.write(&record(&format!("msg-{i}"))).await.unwrap();
+        }
+
+        let messages: Vec<String> = sink
+            .dump_recent(10)
+            .into_iter()
+            .map(|r| r.message)
+            .collect();
+        assert_eq!(messages, vec!["msg-2", "msg-3", "msg-4"]);
+
+        let last_two: Vec<String> = sink.dump_recent(2).into_iter().map(|r| r.message).collect();
+        assert_eq!(last_two, vec!["msg-3", "msg-4"]);
+    }
+
+    #[test]
+    fn test_dump_to_file_appends_rendered_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("crash").join("recent.log");
+        let sink = MemoryRingSink::new(4).with_template(LogTemplate::new("{level} {message}"));
+        sink.push(Arc::new(record("first")));
+        sink.push(Arc::new(record("second")));
+
+        assert_eq!(sink.dump_to_file(&path).unwrap(), 2);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "INFO first\nINFO second\n");
+    }
+}
@@ -5,27 +5,55 @@ pub mod compression;
 pub mod console;
 #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
 pub mod database;
+mod disk_space;
+#[cfg(feature = "elasticsearch")]
+pub mod elasticsearch;
 pub mod encryption;
 #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
 pub mod entity;
 pub mod file;
+#[cfg(feature = "loki")]
+pub mod loki;
+#[cfg(feature = "memory-ring")]
+pub mod memory_ring;
+#[cfg(feature = "nats")]
+pub mod nats;
+#[cfg(feature = "otlp")]
+pub mod otlp;
 pub mod registry;
 pub mod ring_buffered_file;
 pub mod rotation;
+#[cfg(feature = "syslog")]
+pub mod syslog;
 
 pub use circuit_breaker::CircuitBreaker;
 #[cfg(feature = "compression")]
 pub use compression::ZstdCompression;
 pub use compression::{CompressionStrategy, GzipCompression, NoCompression};
-pub use console::ConsoleSink;
+pub use console::{ConsoleCapture, ConsoleSink};
 #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
-pub use database::DatabaseSink;
+pub use database::{DatabaseSink, LogQuery, LogReader};
+#[cfg(feature = "elasticsearch")]
+pub use elasticsearch::ElasticsearchSink;
 pub use file::FileSink;
+#[cfg(unix)]
+pub use file::install_sighup_handler;
+pub use file::request_reopen;
+#[cfg(feature = "loki")]
+pub use loki::LokiSink;
+#[cfg(feature = "memory-ring")]
+pub use memory_ring::MemoryRingSink;
+#[cfg(feature = "nats")]
+pub use nats::{AsyncNatsPublisher, LazyNatsPublisher, NatsPublisher, NatsSink};
+#[cfg(feature = "otlp")]
+pub use otlp::OtlpSink;
 pub use registry::{FileSinkFactory, SinkFactory, SinkMetadata, SinkRegistry};
 pub use rotation::{
     CompositeRotation, RotationContext, RotationResult, RotationStrategy, SizeBasedRotation,
     TimeBasedRotation,
 };
+#[cfg(feature = "syslog")]
+pub use syslog::{SyslogSink, syslog_message};
 
 use crate::InklogError;
 use crate::LogRecord;
@@ -68,6 +96,29 @@ pub trait LogSink: Send + Sync {
     }
 }
 
+/// 远程 sink 发送前按 `masker` 脱敏记录的 message 与 fields；未设置 masker 时原样返回
+#[cfg(any(
+    feature = "nats",
+    feature = "elasticsearch",
+    feature = "otlp",
+    feature = "syslog",
+    feature = "loki"
+))]
+pub(crate) fn masked_record<'a>(
+    record: &'a LogRecord,
+    masker: Option<&crate::DataMasker>,
+) -> std::borrow::Cow<'a, LogRecord> {
+    match masker {
+        Some(masker) => {
+            let mut masked = record.clone();
+            masked.message = masker.mask(&record.message);
+            masker.mask_hashmap(&mut masked.fields);
+            std::borrow::Cow::Owned(masked)
+        }
+        None => std::borrow::Cow::Borrowed(record),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,454 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! NATS sink implementation.
+//!
+//! Publishes each log record as JSON to `<subject_prefix>.<target>`, optionally
+//! through JetStream. Publishing goes through the [`NatsPublisher`] trait so the
+//! transport can be replaced in tests.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use super::{CircuitBreaker, ConsoleSink, LogSink, masked_record};
+use crate::DataMasker;
+use crate::InklogError;
+use crate::LogRecord;
+use crate::NatsSinkConfig;
+
+/// Transport used by [`NatsSink`] to publish messages.
+#[async_trait]
+pub trait NatsPublisher: Send + Sync {
+    /// Publish a single payload to `subject`.
+    async fn publish(&self, subject: String, payload: Vec<u8>) -> Result<(), InklogError>;
+
+    /// Flush any messages buffered by the client.
+    async fn flush(&self) -> Result<(), InklogError>;
+}
+
+/// [`NatsPublisher`] backed by an `async_nats` client.
+pub struct AsyncNatsPublisher {
+    client: async_nats::Client,
+    jetstream: Option<async_nats::jetstream::Context>,
+}
+
+impl AsyncNatsPublisher {
+    /// Connect to the servers listed in `config`.
+    ///
+    /// # 错误
+    ///
+    /// - `InklogError::ConfigError` - credentials 文件无法加载
+    /// - `InklogError::IoError` - 无法连接到 NATS 服务器
+    pub async fn connect(config: &NatsSinkConfig) -> Result<Self, InklogError> {
+        let mut options = async_nats::ConnectOptions::new();
+        if let Some(ref path) = config.credentials_file {
+            options = options.credentials_file(path).await.map_err(|e| {
+                InklogError::ConfigError(format!("Failed to load NATS credentials: {}", e))
+            })?;
+        }
+
+        let client = options
+            .connect(config.servers.join(","))
+            .await
+            .map_err(|e| {
+                InklogError::IoError(std::io::Error::other(format!(
+                    "Failed to connect to NATS: {}",
+                    e
+                )))
+            })?;
+
+        let jetstream = config
+            .jetstream
+            .then(|| async_nats::jetstream::new(client.clone()));
+
+        Ok(Self { client, jetstream })
+    }
+}
+
+#[async_trait]
+impl NatsPublisher for AsyncNatsPublisher {
+    async fn publish(&self, subject: String, payload: Vec<u8>) -> Result<(), InklogError> {
+        let to_err = |e: String| {
+            InklogError::IoError(std::io::Error::other(format!("NATS publish failed: {}", e)))
+        };
+        match self.jetstream {
+            Some(ref js) => {
+                js.publish(subject, payload.into())
+                    .await
+                    .map_err(|e| to_err(e.to_string()))?
+                    .await
+                    .map_err(|e| to_err(e.to_string()))?;
+            }
+            None => {
+                self.client
+                    .publish(subject, payload.into())
+                    .await
+                    .map_err(|e| to_err(e.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<(), InklogError> {
+        self.client.flush().await.map_err(|e| {
+            InklogError::IoError(std::io::Error::other(format!("NATS flush failed: {}", e)))
+        })
+    }
+}
+
+/// [`NatsPublisher`] that connects on first use instead of at construction.
+///
+/// Failed connection attempts are reported as publish errors, so the sink's
+/// circuit breaker and console fallback apply until a connection succeeds.
+pub struct LazyNatsPublisher {
+    config: NatsSinkConfig,
+    connected: Mutex<Option<Arc<AsyncNatsPublisher>>>,
+}
+
+impl LazyNatsPublisher {
+    /// 创建尚未连接的 publisher
+    pub fn new(config: NatsSinkConfig) -> Self {
+        Self {
+            config,
+            connected: Mutex::new(None),
+        }
+    }
+
+    async fn publisher(&self) -> Result<Arc<AsyncNatsPublisher>, InklogError> {
+        let mut connected = self.connected.lock().await;
+        if let Some(ref publisher) = *connected {
+            return Ok(Arc::clone(publisher));
+        }
+        let publisher = Arc::new(AsyncNatsPublisher::connect(&self.config).await?);
+        *connected = Some(Arc::clone(&publisher));
+        Ok(publisher)
+    }
+}
+
+#[async_trait]
+impl NatsPublisher for LazyNatsPublisher {
+    async fn publish(&self, subject: String, payload: Vec<u8>) -> Result<(), InklogError> {
+        self.publisher().await?.publish(subject, payload).await
+    }
+
+    async fn flush(&self) -> Result<(), InklogError> {
+        self.publisher().await?.flush().await
+    }
+}
+
+/// Build the subject for `target` under `prefix`.
+///
+/// `::` separators become subject tokens; characters NATS reserves in subjects
+/// (whitespace, `*`, `>`, `.`) are replaced with `_`, and empty tokens become `_`.
+pub fn nats_subject(prefix: &str, target: &str) -> String {
+    let tokens: Vec<String> = target
+        .split("::")
+        .map(|token| {
+            let cleaned: String = token
+                .chars()
+                .map(|c| match c {
+                    '*' | '>' | '.' => '_',
+                    c if c.is_whitespace() => '_',
+                    c => c,
+                })
+                .collect();
+            if cleaned.is_empty() {
+                "_".to_string()
+            } else {
+                cleaned
+            }
+        })
+        .collect();
+    format!("{}.{}", prefix.trim_end_matches('.'), tokens.join("."))
+}
+
+struct NatsSinkInner {
+    buffer: Vec<LogRecord>,
+    last_flush: Instant,
+    circuit_breaker: CircuitBreaker,
+}
+
+/// Sink that publishes log records to NATS.
+pub struct NatsSink {
+    inner: Mutex<NatsSinkInner>,
+    publisher: Arc<dyn NatsPublisher>,
+    config: NatsSinkConfig,
+    fallback: Option<ConsoleSink>,
+    masker: Option<DataMasker>,
+}
+
+impl NatsSink {
+    /// 使用给定的 publisher 创建 NatsSink（DI 模式）
+    pub fn new(publisher: Arc<dyn NatsPublisher>, config: NatsSinkConfig) -> Self {
+        Self {
+            inner: Mutex::new(NatsSinkInner {
+                buffer: Vec::with_capacity(config.batch_size),
+                last_flush: Instant::now(),
+                circuit_breaker: CircuitBreaker::new(3, Duration::from_secs(30), 3),
+            }),
+            publisher,
+            config,
+            fallback: None,
+            masker: None,
+        }
+    }
+
+    /// 连接 NATS 并创建 NatsSink
+    pub async fn connect(config: NatsSinkConfig) -> Result<Self, InklogError> {
+        let publisher = AsyncNatsPublisher::connect(&config).await?;
+        Ok(Self::new(Arc::new(publisher), config))
+    }
+
+    /// 创建在首次发布时才连接 NATS 的 NatsSink
+    ///
+    /// 连接成功前，记录经由控制台回退输出。
+    pub fn connect_lazy(config: NatsSinkConfig) -> Self {
+        let publisher = LazyNatsPublisher::new(config.clone());
+        Self::new(Arc::new(publisher), config)
+    }
+
+    /// 发布失败时将记录写入控制台
+    pub fn with_console_fallback(mut self, sink: ConsoleSink) -> Self {
+        self.fallback = Some(sink);
+        self
+    }
+
+    /// 发布前用 `masker` 脱敏 message 与 fields
+    pub fn with_masker(mut self, masker: DataMasker) -> Self {
+        self.masker = Some(masker);
+        self
+    }
+
+    async fn write_fallback(&self, records: &[LogRecord]) {
+        if let Some(ref sink) = self.fallback {
+            for record in records {
+                let _ = sink.write(record).await;
+            }
+        }
+    }
+
+    async fn flush_inner(&self, inner: &mut NatsSinkInner) -> Result<(), InklogError> {
+        if inner.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let records = std::mem::take(&mut inner.buffer);
+        inner.last_flush = Instant::now();
+
+        for (idx, record) in records.iter().enumerate() {
+            let result = match serde_json::to_vec(record) {
+                Ok(payload) => {
+                    self.publisher
+                        .publish(
+                            nats_subject(&self.config.subject_prefix, &record.target),
+                            payload,
+                        )
+                        .await
+                }
+                Err(e) => Err(InklogError::SerializationError(e)),
+            };
+            if let Err(e) = result {
+                inner.circuit_breaker.record_failure();
+                self.write_fallback(&records[idx..]).await;
+                return Err(e);
+            }
+        }
+
+        if let Err(e) = self.publisher.flush().await {
+            inner.circuit_breaker.record_failure();
+            return Err(e);
+        }
+
+        inner.circuit_breaker.record_success();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LogSink for NatsSink {
+    async fn write(&self, record: &LogRecord) -> Result<(), InklogError> {
+        let mut inner = self.inner.lock().await;
+
+        if !inner.circuit_breaker.can_execute() {
+            self.write_fallback(std::slice::from_ref(record)).await;
+            return Ok(());
+        }
+
+        inner
+            .buffer
+            .push(masked_record(record, self.masker.as_ref()).into_owned());
+
+        if inner.buffer.len() >= self.config.batch_size.max(1)
+            || inner.last_flush.elapsed() > Duration::from_millis(self.config.flush_interval_ms)
+        {
+            self.flush_inner(&mut inner).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<(), InklogError> {
+        let mut inner = self.inner.lock().await;
+        self.flush_inner(&mut inner).await
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.inner
+            .try_lock()
+            .map(|inner| inner.circuit_breaker.can_execute())
+            .unwrap_or(true)
+    }
+
+    async fn shutdown(&self) -> Result<(), InklogError> {
+        let mut inner = self.inner.lock().await;
+        self.flush_inner(&mut inner).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConsoleCapture;
+    use crate::ConsoleSinkConfig;
+    use crate::template::LogTemplate;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// In-memory publisher that records every delivered message.
+    #[derive(Default)]
+    struct MockPublisher {
+        messages: std::sync::Mutex<Vec<(String, Vec<u8>)>>,
+        fail: AtomicBool,
+    }
+
+    #[async_trait]
+    impl NatsPublisher for MockPublisher {
+        async fn publish(&self, subject: String, payload: Vec<u8>) -> Result<(), InklogError> {
+            if self.fail.load(Ordering::Relaxed) {
+                return Err(InklogError::IoError(std::io::Error::other("unavailable")));
+            }
+            self.messages.lock().unwrap().push((subject, payload));
+            Ok(())
+        }
+
+        async fn flush(&self) -> Result<(), InklogError> {
+            Ok(())
+        }
+    }
+
+    fn record(target: &str, message: &str) -> LogRecord {
+        LogRecord::new(
+            tracing::Level::INFO,
+            target.to_string(),
+            message.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_nats_subject_uses_target_hierarchy() {
+        assert_eq!(nats_subject("logs", "my_app::db"), "logs.my_app.db");
+        assert_eq!(nats_subject("logs.", "app"), "logs.app");
+        assert_eq!(nats_subject("logs", "a b::*::>"), "logs.a_b._._");
+        assert_eq!(nats_subject("logs", ""), "logs._");
+    }
+
+    #[tokio::test]
+    async fn test_nats_sink_delivers_batch_on_flush() {
+        let publisher = Arc::new(MockPublisher::default());
+        let sink = NatsSink::new(
+            publisher.clone(),
+            NatsSinkConfig {
+                enabled: true,
+                batch_size: 10,
+                flush_interval_ms: 60_000,
+                ..Default::default()
+            },
+        );
+
+        sink.write(&record("app::http", "first")).await.unwrap();
+        sink.write(&record("app::db", "second")).await.unwrap();
+        assert!(publisher.messages.lock().unwrap().is_empty());
+
+        sink.flush().await.unwrap();
+
+        let messages = publisher.messages.lock().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].0, "logs.app.http");
+        assert_eq!(messages[1].0, "logs.app.db");
+        let decoded: LogRecord = serde_json::from_slice(&messages[1].1).unwrap();
+        assert_eq!(decoded.message, "second");
+    }
+
+    #[tokio::test]
+    async fn test_nats_sink_masks_sensitive_fields() {
+        let publisher = Arc::new(MockPublisher::default());
+        let sink = NatsSink::new(
+            publisher.clone(),
+            NatsSinkConfig {
+                enabled: true,
+                batch_size: 1,
+                ..Default::default()
+            },
+        )
+        .with_masker(DataMasker::new());
+
+        let mut login = record("app::auth", "login for alice@example.com");
+        login
+            .fields
+            .insert("password".to_string(), serde_json::json!("hunter2"));
+        sink.write(&login).await.unwrap();
+
+        let messages = publisher.messages.lock().unwrap();
+        let decoded: LogRecord = serde_json::from_slice(&messages[0].1).unwrap();
+        assert_eq!(decoded.fields["password"], "***MASKED***");
+        assert!(!decoded.message.contains("alice@example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_nats_sink_falls_back_to_console_on_failure() {
+        let publisher = Arc::new(MockPublisher::default());
+        publisher.fail.store(true, Ordering::Relaxed);
+        let capture = ConsoleCapture::new();
+        let console = ConsoleSink::with_buffer(
+            ConsoleSinkConfig::default(),
+            LogTemplate::default(),
+            capture.buffer(),
+        );
+        let sink = NatsSink::new(
+            publisher,
+            NatsSinkConfig {
+                enabled: true,
+                batch_size: 1,
+                ..Default::default()
+            },
+        )
+        .with_console_fallback(console);
+
+        assert!(sink.write(&record("app", "undeliverable")).await.is_err());
+        assert!(capture.contents().contains("undeliverable"));
+    }
+
+    #[tokio::test]
+    async fn test_lazy_nats_sink_falls_back_until_connected() {
+        let capture = ConsoleCapture::new();
+        let console = ConsoleSink::with_buffer(
+            ConsoleSinkConfig::default(),
+            LogTemplate::default(),
+            capture.buffer(),
+        );
+        let sink = NatsSink::connect_lazy(NatsSinkConfig {
+            enabled: true,
+            servers: vec!["nats://127.0.0.1:1".to_string()],
+            batch_size: 1,
+            ..Default::default()
+        })
+        .with_console_fallback(console);
+
+        assert!(
+            sink.write(&record("app", "not_yet_connected"))
+                .await
+                .is_err()
+        );
+        assert!(capture.contents().contains("not_yet_connected"));
+    }
+}
@@ -0,0 +1,631 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! OpenTelemetry (OTLP) sink implementation.
+//!
+//! Batches log records and exports them as OTLP `LogRecord`s, either as
+//! protobuf over HTTP (`POST {endpoint}/v1/logs`) or through the gRPC
+//! `LogsService/Export` call. Exports rejected with a retryable status are
+//! resent with backoff; everything that cannot be exported goes to the console
+//! fallback.
+
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use opentelemetry_proto::tonic::collector::logs::v1::logs_service_client::LogsServiceClient;
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
+use opentelemetry_proto::tonic::common::v1::{
+    AnyValue, ArrayValue, InstrumentationScope, KeyValue, KeyValueList, any_value,
+};
+use opentelemetry_proto::tonic::logs::v1::{
+    LogRecord as OtlpLogRecord, ResourceLogs, ScopeLogs, SeverityNumber,
+};
+use opentelemetry_proto::tonic::resource::v1::Resource;
+use prost::Message;
+use tokio::sync::Mutex;
+
+use super::{CircuitBreaker, ConsoleSink, LogSink, masked_record};
+use crate::DataMasker;
+use crate::InklogError;
+use crate::LogRecord;
+use crate::{OtlpProtocol, OtlpSinkConfig};
+
+/// OTLP/HTTP 日志导出路径
+const OTLP_LOGS_PATH: &str = "/v1/logs";
+
+/// 一次导出失败的分类
+enum ExportError {
+    /// 429 / 502-504 或等价的 gRPC 状态，可重试
+    Retryable(String),
+    Permanent(String),
+}
+
+enum Transport {
+    Http {
+        client: reqwest::Client,
+        url: String,
+        headers: reqwest::header::HeaderMap,
+    },
+    Grpc {
+        client: LogsServiceClient<tonic::transport::Channel>,
+        metadata: tonic::metadata::MetadataMap,
+    },
+}
+
+impl Transport {
+    fn new(config: &OtlpSinkConfig) -> Result<Self, InklogError> {
+        let invalid_header =
+            |name: &str| InklogError::ConfigError(format!("Invalid otlp_sink header '{}'", name));
+        let timeout = Duration::from_millis(config.timeout_ms);
+        let endpoint = config.endpoint.trim().trim_end_matches('/');
+
+        match config.protocol {
+            OtlpProtocol::Http => {
+                let mut headers = reqwest::header::HeaderMap::new();
+                for (name, value) in &config.headers {
+                    headers.insert(
+                        reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                            .map_err(|_| invalid_header(name))?,
+                        reqwest::header::HeaderValue::from_str(value)
+                            .map_err(|_| invalid_header(name))?,
+                    );
+                }
+                let client = reqwest::Client::builder()
+                    .timeout(timeout)
+                    .build()
+                    .map_err(|e| {
+                        InklogError::ConfigError(format!("Failed to build OTLP client: {}", e))
+                    })?;
+                let url = if endpoint.ends_with(OTLP_LOGS_PATH) {
+                    endpoint.to_string()
+                } else {
+                    format!("{}{}", endpoint, OTLP_LOGS_PATH)
+                };
+                Ok(Self::Http {
+                    client,
+                    url,
+                    headers,
+                })
+            }
+            OtlpProtocol::Grpc => {
+                let mut metadata = tonic::metadata::MetadataMap::new();
+                for (name, value) in &config.headers {
+                    metadata.insert(
+                        tonic::metadata::MetadataKey::from_bytes(
+                            name.to_ascii_lowercase().as_bytes(),
+                        )
+                        .map_err(|_| invalid_header(name))?,
+                        value.parse().map_err(|_| invalid_header(name))?,
+                    );
+                }
+                // 惰性连接：collector 暂不可用时首次导出才会失败并进入重试
+                let channel = tonic::transport::Endpoint::from_shared(endpoint.to_string())
+                    .map_err(|e| {
+                        InklogError::ConfigError(format!(
+                            "Invalid otlp_sink.endpoint '{}': {}",
+                            config.endpoint, e
+                        ))
+                    })?
+                    .timeout(timeout)
+                    .connect_lazy();
+                Ok(Self::Grpc {
+                    client: LogsServiceClient::new(channel),
+                    metadata,
+                })
+            }
+        }
+    }
+
+    async fn export(&self, request: ExportLogsServiceRequest) -> Result<(), ExportError> {
+        let response = match self {
+            Self::Http {
+                client,
+                url,
+                headers,
+            } => {
+                let response = client
+                    .post(url)
+                    .headers(headers.clone())
+                    .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
+                    .body(request.encode_to_vec())
+                    .send()
+                    .await
+                    .map_err(|e| ExportError::Retryable(e.to_string()))?;
+                let status = response.status();
+                if !status.is_success() {
+                    let message = format!("collector returned {}", status);
+                    return Err(match status.as_u16() {
+                        429 | 502..=504 => ExportError::Retryable(message),
+                        _ => ExportError::Permanent(message),
+                    });
+                }
+                let body = response
+                    .bytes()
+                    .await
+                    .map_err(|e| ExportError::Retryable(e.to_string()))?;
+                ExportLogsServiceResponse::decode(body).map_err(|e| {
+                    ExportError::Permanent(format!("invalid collector response: {}", e))
+                })?
+            }
+            Self::Grpc { client, metadata } => {
+                let mut grpc_request = tonic::Request::new(request);
+                *grpc_request.metadata_mut() = metadata.clone();
+                client
+                    .clone()
+                    .export(grpc_request)
+                    .await
+                    .map_err(|status| {
+                        let message = format!("collector returned {}", status);
+                        match status.code() {
+                            tonic::Code::Unavailable
+                            | tonic::Code::ResourceExhausted
+                            | tonic::Code::DeadlineExceeded
+                            | tonic::Code::Aborted => ExportError::Retryable(message),
+                            _ => ExportError::Permanent(message),
+                        }
+                    })?
+                    .into_inner()
+            }
+        };
+
+        // partial_success 表示 collector 丢弃了部分记录，重发同一批次无济于事
+        match response.partial_success {
+            Some(partial) if partial.rejected_log_records > 0 => {
+                Err(ExportError::Permanent(format!(
+                    "collector rejected {} log records: {}",
+                    partial.rejected_log_records, partial.error_message
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn severity_number(level: &str) -> SeverityNumber {
+    match level.to_ascii_uppercase().as_str() {
+        "TRACE" => SeverityNumber::Trace,
+        "DEBUG" => SeverityNumber::Debug,
+        "INFO" => SeverityNumber::Info,
+        "WARN" | "WARNING" => SeverityNumber::Warn,
+        "ERROR" => SeverityNumber::Error,
+        _ => SeverityNumber::Unspecified,
+    }
+}
+
+fn string_value(value: impl Into<String>) -> AnyValue {
+    AnyValue {
+        value: Some(any_value::Value::StringValue(value.into())),
+    }
+}
+
+fn key_value(key: impl Into<String>, value: AnyValue) -> KeyValue {
+    KeyValue {
+        key: key.into(),
+        value: Some(value),
+    }
+}
+
+/// 将 JSON 字段值转换为 OTLP `AnyValue`；`null` 映射为空值
+fn any_value(value: &serde_json::Value) -> AnyValue {
+    let value = match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::Bool(b) => Some(any_value::Value::BoolValue(*b)),
+        serde_json::Value::Number(n) => Some(match n.as_i64() {
+            Some(i) => any_value::Value::IntValue(i),
+            None => any_value::Value::DoubleValue(n.as_f64().unwrap_or_default()),
+        }),
+        serde_json::Value::String(s) => Some(any_value::Value::StringValue(s.clone())),
+        serde_json::Value::Array(items) => Some(any_value::Value::ArrayValue(ArrayValue {
+            values: items.iter().map(any_value).collect(),
+        })),
+        serde_json::Value::Object(map) => Some(any_value::Value::KvlistValue(KeyValueList {
+            values: map
+                .iter()
+                .map(|(key, item)| key_value(key.clone(), any_value(item)))
+                .collect(),
+        })),
+    };
+    AnyValue { value }
+}
+
+/// 将 inklog 记录映射为 OTLP `LogRecord`
+///
+/// `target`、源码位置与线程名写入对应的语义约定属性，`fields` 按原键名写入。
+fn otlp_log_record(record: &LogRecord) -> OtlpLogRecord {
+    let time_unix_nano = record
+        .timestamp
+        .timestamp_nanos_opt()
+        .map_or(0, |nanos| nanos.max(0) as u64);
+
+    let mut attributes = Vec::with_capacity(record.fields.len() + 4);
+    attributes.push(key_value("target", string_value(&record.target)));
+    if let Some(ref file) = record.file {
+        attributes.push(key_value("code.filepath", string_value(file)));
+    }
+    if let Some(line) = record.line {
+        attributes.push(key_value(
+            "code.lineno",
+            AnyValue {
+                value: Some(any_value::Value::IntValue(i64::from(line))),
+            },
+        ));
+    }
+    attributes.push(key_value("thread.name", string_value(&record.thread_id)));
+    let mut fields: Vec<_> = record.fields.iter().collect();
+    fields.sort_by(|a, b| a.0.cmp(b.0));
+    attributes.extend(
+        fields
+            .into_iter()
+            .map(|(key, value)| key_value(key.clone(), any_value(value))),
+    );
+
+    OtlpLogRecord {
+        time_unix_nano,
+        observed_time_unix_nano: time_unix_nano,
+        severity_number: severity_number(&record.level) as i32,
+        severity_text: record.level.clone(),
+        body: Some(string_value(&record.message)),
+        attributes,
+        ..Default::default()
+    }
+}
+
+struct OtlpSinkInner {
+    buffer: Vec<LogRecord>,
+    last_flush: Instant,
+    circuit_breaker: CircuitBreaker,
+}
+
+/// Sink that exports log records to an OpenTelemetry collector.
+pub struct OtlpSink {
+    inner: Mutex<OtlpSinkInner>,
+    transport: Transport,
+    resource: Resource,
+    config: OtlpSinkConfig,
+    fallback: Option<ConsoleSink>,
+    masker: Option<DataMasker>,
+}
+
+impl OtlpSink {
+    /// 创建 OtlpSink；gRPC 连接在首次导出时建立
+    ///
+    /// # 错误
+    ///
+    /// - `InklogError::ConfigError` - 配置无效或 header 不是合法的 HTTP header
+    pub fn new(config: OtlpSinkConfig) -> Result<Self, InklogError> {
+        config.validate()?;
+        let transport = Transport::new(&config)?;
+        let resource = Resource {
+            attributes: vec![key_value(
+                "service.name",
+                string_value(&config.service_name),
+            )],
+            ..Default::default()
+        };
+
+        Ok(Self {
+            inner: Mutex::new(OtlpSinkInner {
+                buffer: Vec::with_capacity(config.batch_size),
+                last_flush: Instant::now(),
+                circuit_breaker: CircuitBreaker::new(3, Duration::from_secs(30), 3),
+            }),
+            transport,
+            resource,
+            config,
+            fallback: None,
+            masker: None,
+        })
+    }
+
+    /// 导出失败时将记录写入控制台
+    pub fn with_console_fallback(mut self, sink: ConsoleSink) -> Self {
+        self.fallback = Some(sink);
+        self
+    }
+
+    /// 映射为 OTLP 记录前用 `masker` 脱敏 body 与 attributes 的来源（message 与 fields）
+    pub fn with_masker(mut self, masker: DataMasker) -> Self {
+        self.masker = Some(masker);
+        self
+    }
+
+    async fn write_fallback(&self, records: &[LogRecord]) {
+        if let Some(ref sink) = self.fallback {
+            for record in records {
+                let _ = sink.write(record).await;
+            }
+        }
+    }
+
+    fn export_request(&self, records: &[LogRecord]) -> ExportLogsServiceRequest {
+        ExportLogsServiceRequest {
+            resource_logs: vec![ResourceLogs {
+                resource: Some(self.resource.clone()),
+                scope_logs: vec![ScopeLogs {
+                    scope: Some(InstrumentationScope {
+                        name: "inklog".to_string(),
+                        version: env!("CARGO_PKG_VERSION").to_string(),
+                        ..Default::default()
+                    }),
+                    log_records: records.iter().map(otlp_log_record).collect(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+    }
+
+    async fn flush_inner(&self, inner: &mut OtlpSinkInner) -> Result<(), InklogError> {
+        if inner.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let pending = std::mem::take(&mut inner.buffer);
+        inner.last_flush = Instant::now();
+        let request = self.export_request(&pending);
+        let mut attempt = 0u32;
+
+        loop {
+            let message = match self.transport.export(request.clone()).await {
+                Ok(()) => {
+                    inner.circuit_breaker.record_success();
+                    return Ok(());
+                }
+                Err(ExportError::Retryable(_)) if attempt < self.config.max_retries => {
+                    attempt += 1;
+                    tokio::time::sleep(Duration::from_millis(100 << attempt.min(6))).await;
+                    continue;
+                }
+                Err(ExportError::Retryable(message)) | Err(ExportError::Permanent(message)) => {
+                    message
+                }
+            };
+
+            inner.circuit_breaker.record_failure();
+            self.write_fallback(&pending).await;
+            return Err(InklogError::IoError(std::io::Error::other(format!(
+                "OTLP export of {} records failed after {} retries: {}",
+                pending.len(),
+                attempt,
+                message
+            ))));
+        }
+    }
+}
+
+#[async_trait]
+impl LogSink for OtlpSink {
+    async fn write(&self, record: &LogRecord) -> Result<(), InklogError> {
+        let record = masked_record(record, self.masker.as_ref());
+        let mut inner = self.inner.lock().await;
+
+        if !inner.circuit_breaker.can_execute() {
+            self.write_fallback(std::slice::from_ref(record.as_ref()))
+                .await;
+            return Ok(());
+        }
+
+        inner.buffer.push(record.into_owned());
+
+        if inner.buffer.len() >= self.config.batch_size.max(1)
+            || inner.last_flush.elapsed() > Duration::from_millis(self.config.flush_interval_ms)
+        {
+            self.flush_inner(&mut inner).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<(), InklogError> {
+        let mut inner = self.inner.lock().await;
+        self.flush_inner(&mut inner).await
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.inner
+            .try_lock()
+            .map(|inner| inner.circuit_breaker.can_execute())
+            .unwrap_or(true)
+    }
+
+    async fn shutdown(&self) -> Result<(), InklogError> {
+        let mut inner = self.inner.lock().await;
+        self.flush_inner(&mut inner).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConsoleCapture;
+    use crate::ConsoleSinkConfig;
+    use crate::template::LogTemplate;
+    use chrono::{TimeZone, Utc};
+    use std::sync::Arc;
+
+    fn record(message: &str) -> LogRecord {
+        let mut record = LogRecord::new(
+            tracing::Level::WARN,
+            "app::checkout".to_string(),
+            message.to_string(),
+        );
+        record.timestamp = Utc.with_ymd_and_hms(2026, 3, 7, 12, 0, 0).unwrap();
+        record
+            .fields
+            .insert("order_id".to_string(), serde_json::json!(42));
+        record
+    }
+
+    /// 启动模拟 OTLP/HTTP collector：解码并记录每次导出请求，按 `responder` 返回状态码
+    async fn mock_collector(
+        responder: impl Fn(usize) -> u16 + Send + Sync + 'static,
+    ) -> (String, Arc<std::sync::Mutex<Vec<ExportLogsServiceRequest>>>) {
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let requests_clone = requests.clone();
+        let responder = Arc::new(responder);
+        let app = axum::Router::new().route(
+            OTLP_LOGS_PATH,
+            axum::routing::post(move |body: bytes::Bytes| {
+                let requests = requests_clone.clone();
+                let responder = responder.clone();
+                async move {
+                    let request =
+                        ExportLogsServiceRequest::decode(body).expect("valid OTLP protobuf");
+                    let call = {
+                        let mut requests = requests.lock().unwrap();
+                        requests.push(request);
+                        requests.len() - 1
+                    };
+                    let status = axum::http::StatusCode::from_u16(responder(call)).unwrap();
+                    (status, ExportLogsServiceResponse::default().encode_to_vec())
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock collector");
+        let addr = listener.local_addr().expect("local addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        (format!("http://{}", addr), requests)
+    }
+
+    fn sink_config(endpoint: String) -> OtlpSinkConfig {
+        OtlpSinkConfig {
+            enabled: true,
+            endpoint,
+            service_name: "checkout".to_string(),
+            batch_size: 10,
+            flush_interval_ms: 60_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_otlp_log_record_mapping() {
+        let otlp = otlp_log_record(&record("payment declined"));
+        assert_eq!(otlp.severity_number, SeverityNumber::Warn as i32);
+        assert_eq!(otlp.severity_text, "WARN");
+        assert_eq!(
+            otlp.time_unix_nano,
+            Utc.with_ymd_and_hms(2026, 3, 7, 12, 0, 0)
+                .unwrap()
+                .timestamp_nanos_opt()
+                .unwrap() as u64
+        );
+        assert_eq!(otlp.body, Some(string_value("payment declined")));
+        let attribute = |key: &str| {
+            otlp.attributes
+                .iter()
+                .find(|kv| kv.key == key)
+                .and_then(|kv| kv.value.clone())
+        };
+        assert_eq!(attribute("target"), Some(string_value("app::checkout")));
+        assert_eq!(
+            attribute("order_id"),
+            Some(AnyValue {
+                value: Some(any_value::Value::IntValue(42))
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_records_are_exported_to_collector() {
+        let (endpoint, requests) = mock_collector(|_| 200).await;
+        let sink = OtlpSink::new(sink_config(endpoint)).unwrap();
+
+        sink.write(&record("first")).await.unwrap();
+        sink.write(&record("second")).await.unwrap();
+        assert!(requests.lock().unwrap().is_empty());
+        sink.flush().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let resource_logs = &requests[0].resource_logs[0];
+        assert_eq!(
+            resource_logs.resource.as_ref().unwrap().attributes[0],
+            key_value("service.name", string_value("checkout"))
+        );
+        let records = &resource_logs.scope_logs[0].log_records;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].body, Some(string_value("first")));
+        assert_eq!(records[1].body, Some(string_value("second")));
+    }
+
+    #[tokio::test]
+    async fn test_exported_records_are_masked() {
+        let (endpoint, requests) = mock_collector(|_| 200).await;
+        let sink = OtlpSink::new(sink_config(endpoint))
+            .unwrap()
+            .with_masker(DataMasker::new());
+
+        let mut login = record("login for alice@example.com");
+        login
+            .fields
+            .insert("password".to_string(), serde_json::json!("hunter2"));
+        sink.write(&login).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        let exported = &requests[0].resource_logs[0].scope_logs[0].log_records[0];
+        let password = exported
+            .attributes
+            .iter()
+            .find(|kv| kv.key == "password")
+            .and_then(|kv| kv.value.clone());
+        assert_eq!(password, Some(string_value("***MASKED***")));
+        let Some(AnyValue {
+            value: Some(any_value::Value::StringValue(body)),
+        }) = &exported.body
+        else {
+            panic!("body should be a string: {:?}", exported.body);
+        };
+        assert!(!body.contains("alice@example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_retryable_status_is_retried() {
+        let (endpoint, requests) = mock_collector(|call| if call == 0 { 503 } else { 200 }).await;
+        let sink = OtlpSink::new(sink_config(endpoint)).unwrap();
+
+        sink.write(&record("retried")).await.unwrap();
+        sink.flush().await.unwrap();
+
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert!(sink.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_export_falls_back_to_console() {
+        let (endpoint, requests) = mock_collector(|_| 400).await;
+        let capture = ConsoleCapture::new();
+        let console = ConsoleSink::with_buffer(
+            ConsoleSinkConfig::default(),
+            LogTemplate::default(),
+            capture.buffer(),
+        );
+        let sink = OtlpSink::new(sink_config(endpoint))
+            .unwrap()
+            .with_console_fallback(console);
+
+        sink.write(&record("undeliverable")).await.unwrap();
+        assert!(sink.flush().await.is_err());
+
+        // 400 不重试
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert!(capture.contents().contains("undeliverable"));
+    }
+
+    #[test]
+    fn test_invalid_header_is_config_error() {
+        let mut config = sink_config("http://127.0.0.1:4318".to_string());
+        config
+            .headers
+            .insert("bad header".to_string(), "x".to_string());
+        assert!(matches!(
+            OtlpSink::new(config),
+            Err(InklogError::ConfigError(_))
+        ));
+    }
+}
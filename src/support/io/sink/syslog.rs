@@ -0,0 +1,541 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! Syslog sink implementation.
+//!
+//! Formats each log record as an RFC 5424 message and sends it to a remote
+//! syslog server over UDP, TCP or TLS. Stream transports use octet-counting
+//! framing (RFC 6587). A dropped connection is re-established on the next
+//! write; repeated failures open the circuit breaker and records go to the
+//! console fallback.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::Mutex;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::rustls;
+
+use super::{CircuitBreaker, ConsoleSink, LogSink, masked_record};
+use crate::DataMasker;
+use crate::InklogError;
+use crate::LogRecord;
+use crate::{SyslogSinkConfig, SyslogTransport};
+
+/// RFC 5424 中 NILVALUE
+const NIL: &str = "-";
+
+/// 日志级别对应的 syslog severity；未知级别按 notice (5) 处理
+fn severity(level: &str) -> u8 {
+    match level.to_ascii_uppercase().as_str() {
+        "ERROR" => 3,
+        "WARN" | "WARNING" => 4,
+        "INFO" => 6,
+        "DEBUG" | "TRACE" => 7,
+        _ => 5,
+    }
+}
+
+/// 仅保留可打印 ASCII 并截断到 `max` 字节，结果为空时返回 NILVALUE
+fn header_field(value: &str, max: usize) -> String {
+    let field: String = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max)
+        .collect();
+    if field.is_empty() {
+        NIL.to_string()
+    } else {
+        field
+    }
+}
+
+/// PARAM-NAME 不允许 `=`、空格、`]`、`"`，替换为 `_`
+fn param_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| match c {
+            '=' | ']' | '"' => '_',
+            c if c.is_ascii_graphic() => c,
+            _ => '_',
+        })
+        .take(32)
+        .collect();
+    if name.is_empty() {
+        "_".to_string()
+    } else {
+        name
+    }
+}
+
+/// PARAM-VALUE 中的 `"`、`\`、`]` 需要转义
+fn param_value(value: &serde_json::Value) -> String {
+    let raw = match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Format `record` as an RFC 5424 syslog message.
+///
+/// `fields` become parameters of one SD-ELEMENT named `config.sd_id`, sorted
+/// by key; the target becomes the MSGID and the process id the PROCID.
+pub fn syslog_message(config: &SyslogSinkConfig, hostname: &str, record: &LogRecord) -> String {
+    let priority = u16::from(config.facility.code()) * 8 + u16::from(severity(&record.level));
+
+    let structured_data = if record.fields.is_empty() {
+        NIL.to_string()
+    } else {
+        let mut fields: Vec<_> = record.fields.iter().collect();
+        fields.sort_by(|a, b| a.0.cmp(b.0));
+        let params: String = fields
+            .into_iter()
+            .map(|(key, value)| format!(" {}=\"{}\"", param_name(key), param_value(value)))
+            .collect();
+        format!("[{}{}]", config.sd_id, params)
+    };
+
+    format!(
+        "<{}>1 {} {} {} {} {} {} {}",
+        priority,
+        record.timestamp.format("%Y-%m-%dT%H:%M:%S%.6fZ"),
+        header_field(hostname, 255),
+        header_field(&config.app_name, 48),
+        std::process::id(),
+        header_field(&record.target, 32),
+        structured_data,
+        record.message
+    )
+}
+
+/// HOSTNAME 默认值：`HOSTNAME` 环境变量或 `/etc/hostname`
+fn local_hostname() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| NIL.to_string())
+}
+
+fn tls_connector(config: &SyslogSinkConfig) -> Result<TlsConnector, InklogError> {
+    let tls_err = |e: String| InklogError::ConfigError(format!("Syslog TLS setup failed: {}", e));
+
+    let mut roots = rustls::RootCertStore::empty();
+    match config.tls_ca_file {
+        Some(ref path) => {
+            use rustls::pki_types::CertificateDer;
+            use rustls::pki_types::pem::PemObject;
+            for cert in CertificateDer::pem_file_iter(path).map_err(|e| tls_err(e.to_string()))? {
+                roots
+                    .add(cert.map_err(|e| tls_err(e.to_string()))?)
+                    .map_err(|e| tls_err(e.to_string()))?;
+            }
+        }
+        None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+    }
+
+    let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+    let tls = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| tls_err(e.to_string()))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(tls)))
+}
+
+enum Connection {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+    Tls(Box<tokio_rustls::client::TlsStream<TcpStream>>),
+}
+
+impl Connection {
+    async fn send(&mut self, message: &str) -> std::io::Result<()> {
+        match self {
+            Self::Udp(socket) => socket.send(message.as_bytes()).await.map(|_| ()),
+            Self::Tcp(stream) => {
+                let frame = format!("{} {}", message.len(), message);
+                stream.write_all(frame.as_bytes()).await
+            }
+            Self::Tls(stream) => {
+                let frame = format!("{} {}", message.len(), message);
+                stream.write_all(frame.as_bytes()).await?;
+                stream.flush().await
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Udp(_) => Ok(()),
+            Self::Tcp(stream) => stream.flush().await,
+            Self::Tls(stream) => stream.flush().await,
+        }
+    }
+}
+
+struct SyslogSinkInner {
+    connection: Option<Connection>,
+    circuit_breaker: CircuitBreaker,
+}
+
+/// Sink that forwards log records to a syslog server.
+pub struct SyslogSink {
+    inner: Mutex<SyslogSinkInner>,
+    address: String,
+    tls: Option<TlsConnector>,
+    hostname: String,
+    config: SyslogSinkConfig,
+    fallback: Option<ConsoleSink>,
+    masker: Option<DataMasker>,
+}
+
+impl SyslogSink {
+    /// 创建 SyslogSink；连接在首次写入时建立
+    ///
+    /// # 错误
+    ///
+    /// - `InklogError::ConfigError` - 配置无效或 TLS CA 文件无法加载
+    pub fn new(config: SyslogSinkConfig) -> Result<Self, InklogError> {
+        config.validate()?;
+        let tls = match config.transport {
+            SyslogTransport::Tls => Some(tls_connector(&config)?),
+            SyslogTransport::Udp | SyslogTransport::Tcp => None,
+        };
+        let hostname = config.hostname.clone().unwrap_or_else(local_hostname);
+
+        Ok(Self {
+            inner: Mutex::new(SyslogSinkInner {
+                connection: None,
+                circuit_breaker: CircuitBreaker::new(3, Duration::from_secs(30), 3),
+            }),
+            address: format!("{}:{}", config.host, config.port),
+            tls,
+            hostname,
+            config,
+            fallback: None,
+            masker: None,
+        })
+    }
+
+    /// 发送失败时将记录写入控制台
+    pub fn with_console_fallback(mut self, sink: ConsoleSink) -> Self {
+        self.fallback = Some(sink);
+        self
+    }
+
+    /// 格式化前用 `masker` 脱敏 MSG 与 SD 参数（message 与 fields）
+    pub fn with_masker(mut self, masker: DataMasker) -> Self {
+        self.masker = Some(masker);
+        self
+    }
+
+    async fn write_fallback(&self, record: &LogRecord) {
+        if let Some(ref sink) = self.fallback {
+            let _ = sink.write(record).await;
+        }
+    }
+
+    async fn connect(&self) -> std::io::Result<Connection> {
+        let target = tokio::net::lookup_host(&self.address)
+            .await?
+            .next()
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("no address found for {}", self.address),
+                )
+            })?;
+
+        match self.config.transport {
+            SyslogTransport::Udp => {
+                let local = if target.is_ipv6() {
+                    "[::]:0"
+                } else {
+                    "0.0.0.0:0"
+                };
+                let socket = UdpSocket::bind(local).await?;
+                socket.connect(target).await?;
+                Ok(Connection::Udp(socket))
+            }
+            SyslogTransport::Tcp => Ok(Connection::Tcp(TcpStream::connect(target).await?)),
+            SyslogTransport::Tls => {
+                let server_name = rustls::pki_types::ServerName::try_from(self.config.host.clone())
+                    .map_err(|e| {
+                        std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string())
+                    })?;
+                let stream = TcpStream::connect(target).await?;
+                let connector = self.tls.as_ref().ok_or_else(|| {
+                    std::io::Error::other("TLS transport without a TLS connector")
+                })?;
+                Ok(Connection::Tls(Box::new(
+                    connector.connect(server_name, stream).await?,
+                )))
+            }
+        }
+    }
+
+    /// 通过现有连接发送；连接已断开（如 TCP 被对端关闭）时重连并重发一次
+    async fn send(&self, inner: &mut SyslogSinkInner, message: &str) -> std::io::Result<()> {
+        let timeout = Duration::from_millis(self.config.timeout_ms);
+        let timed_out = || std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+
+        if let Some(connection) = inner.connection.as_mut() {
+            match tokio::time::timeout(timeout, connection.send(message)).await {
+                Ok(Ok(())) => return Ok(()),
+                Ok(Err(_)) | Err(_) => inner.connection = None,
+            }
+        }
+
+        let mut connection = tokio::time::timeout(timeout, self.connect())
+            .await
+            .map_err(|_| timed_out())??;
+        tokio::time::timeout(timeout, connection.send(message))
+            .await
+            .map_err(|_| timed_out())??;
+        inner.connection = Some(connection);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LogSink for SyslogSink {
+    async fn write(&self, record: &LogRecord) -> Result<(), InklogError> {
+        let record = masked_record(record, self.masker.as_ref());
+        let record = record.as_ref();
+        let mut inner = self.inner.lock().await;
+
+        if !inner.circuit_breaker.can_execute() {
+            self.write_fallback(record).await;
+            return Ok(());
+        }
+
+        let message = syslog_message(&self.config, &self.hostname, record);
+        match self.send(&mut inner, &message).await {
+            Ok(()) => {
+                inner.circuit_breaker.record_success();
+                Ok(())
+            }
+            Err(e) => {
+                inner.connection = None;
+                inner.circuit_breaker.record_failure();
+                self.write_fallback(record).await;
+                Err(InklogError::IoError(std::io::Error::other(format!(
+                    "Syslog send to {} failed: {}",
+                    self.address, e
+                ))))
+            }
+        }
+    }
+
+    async fn flush(&self) -> Result<(), InklogError> {
+        let mut inner = self.inner.lock().await;
+        if let Some(connection) = inner.connection.as_mut()
+            && let Err(e) = connection.flush().await
+        {
+            inner.connection = None;
+            return Err(InklogError::IoError(e));
+        }
+        Ok(())
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.inner
+            .try_lock()
+            .map(|inner| inner.circuit_breaker.can_execute())
+            .unwrap_or(true)
+    }
+
+    async fn shutdown(&self) -> Result<(), InklogError> {
+        let mut inner = self.inner.lock().await;
+        if let Some(mut connection) = inner.connection.take() {
+            connection.flush().await?;
+            match connection {
+                Connection::Udp(_) => {}
+                Connection::Tcp(mut stream) => stream.shutdown().await?,
+                Connection::Tls(mut stream) => stream.shutdown().await?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SyslogFacility;
+    use chrono::{TimeZone, Utc};
+    use tokio::io::AsyncReadExt;
+
+    fn record(message: &str) -> LogRecord {
+        let mut record = LogRecord::new(
+            tracing::Level::INFO,
+            "app::checkout".to_string(),
+            message.to_string(),
+        );
+        record.timestamp = Utc.with_ymd_and_hms(2026, 3, 7, 12, 0, 0).unwrap();
+        record
+    }
+
+    fn sink_config(port: u16, transport: SyslogTransport) -> SyslogSinkConfig {
+        SyslogSinkConfig {
+            enabled: true,
+            port,
+            transport,
+            facility: SyslogFacility::Local0,
+            hostname: Some("web-1".to_string()),
+            app_name: "checkout".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_syslog_message_format() {
+        let config = sink_config(514, SyslogTransport::Udp);
+        let mut record = record("payment accepted");
+        record
+            .fields
+            .insert("order_id".to_string(), serde_json::json!(42));
+        record
+            .fields
+            .insert("note".to_string(), serde_json::json!("a \"quoted\" ]"));
+
+        let message = syslog_message(&config, "web-1", &record);
+        assert_eq!(
+            message,
+            format!(
+                "<134>1 2026-03-07T12:00:00.000000Z web-1 checkout {} app::checkout \
+                 [fields@32473 note=\"a \\\"quoted\\\" \\]\" order_id=\"42\"] payment accepted",
+                std::process::id()
+            )
+        );
+
+        let plain = syslog_message(&config, "", &self::record("no fields"));
+        assert!(plain.contains(" - checkout "), "{plain}");
+        assert!(plain.ends_with(" app::checkout - no fields"), "{plain}");
+    }
+
+    #[test]
+    fn test_priority_uses_facility_and_level() {
+        let config = SyslogSinkConfig::default();
+        let mut error = record("failed");
+        error.level = "ERROR".to_string();
+        // user (1) * 8 + err (3)
+        assert!(syslog_message(&config, "h", &error).starts_with("<11>1 "));
+    }
+
+    #[tokio::test]
+    async fn test_udp_message_arrives() {
+        let listener = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let sink = SyslogSink::new(sink_config(port, SyslogTransport::Udp)).unwrap();
+
+        sink.write(&record("over udp")).await.unwrap();
+
+        let mut buf = [0u8; 2048];
+        let len = tokio::time::timeout(Duration::from_secs(5), listener.recv(&mut buf))
+            .await
+            .expect("datagram should arrive")
+            .unwrap();
+        let message = std::str::from_utf8(&buf[..len]).unwrap();
+        assert!(
+            message.starts_with("<134>1 2026-03-07T12:00:00.000000Z web-1 checkout "),
+            "{message}"
+        );
+        assert!(message.ends_with(" app::checkout - over udp"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_sent_message_is_masked() {
+        let listener = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let sink = SyslogSink::new(sink_config(port, SyslogTransport::Udp))
+            .unwrap()
+            .with_masker(DataMasker::new());
+
+        let mut login = record("login for alice@example.com");
+        login
+            .fields
+            .insert("password".to_string(), serde_json::json!("hunter2"));
+        sink.write(&login).await.unwrap();
+
+        let mut buf = [0u8; 2048];
+        let len = tokio::time::timeout(Duration::from_secs(5), listener.recv(&mut buf))
+            .await
+            .expect("datagram should arrive")
+            .unwrap();
+        let message = std::str::from_utf8(&buf[..len]).unwrap();
+        assert!(
+            message.contains("[fields@32473 password=\"***MASKED***\"]"),
+            "{message}"
+        );
+        assert!(!message.contains("hunter2"), "{message}");
+        assert!(!message.contains("alice@example.com"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_tcp_uses_octet_counting_and_reconnects() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let sink = SyslogSink::new(sink_config(port, SyslogTransport::Tcp)).unwrap();
+
+        sink.write(&record("first")).await.unwrap();
+        let (mut first, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 2048];
+        let len = first.read(&mut buf).await.unwrap();
+        let frame = std::str::from_utf8(&buf[..len]).unwrap();
+        let (count, message) = frame.split_once(' ').unwrap();
+        assert_eq!(count.parse::<usize>().unwrap(), message.len());
+        assert!(message.ends_with("first"));
+
+        // 服务端断开后，后续写入应重新建立连接
+        drop(first);
+        let reconnected = tokio::spawn(async move { listener.accept().await.map(|(s, _)| s) });
+        for _ in 0..5 {
+            let _ = sink.write(&record("after drop")).await;
+            if reconnected.is_finished() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let mut second = tokio::time::timeout(Duration::from_secs(5), reconnected)
+            .await
+            .expect("sink should reconnect")
+            .unwrap()
+            .unwrap();
+        let len = second.read(&mut buf).await.unwrap();
+        assert!(
+            std::str::from_utf8(&buf[..len])
+                .unwrap()
+                .contains("after drop")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_opens_circuit_breaker() {
+        // 先占用再释放端口，确保无服务监听
+        let port = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let sink = SyslogSink::new(sink_config(port, SyslogTransport::Tcp)).unwrap();
+
+        for _ in 0..3 {
+            assert!(sink.write(&record("lost")).await.is_err());
+        }
+        assert!(!sink.is_healthy());
+        // 断路器打开后直接回退，不再报错
+        assert!(sink.write(&record("lost")).await.is_ok());
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! Structured HTTP access logs.
+//!
+//! Access records are emitted under the fixed [`ACCESS_LOG_TARGET`] with
+//! standardized field names, so they can be filtered or sent to a dedicated
+//! sink by target like any other record.
+
+use crate::LogRecord;
+use serde_json::Value;
+use std::time::Duration;
+
+/// Target used for every access-log record.
+pub const ACCESS_LOG_TARGET: &str = "inklog::access";
+
+/// Field name for the request method.
+pub const FIELD_HTTP_METHOD: &str = "http.method";
+/// Field name for the request path.
+pub const FIELD_HTTP_PATH: &str = "http.path";
+/// Field name for the response status code.
+pub const FIELD_HTTP_STATUS: &str = "http.status";
+/// Field name for the request duration in milliseconds.
+pub const FIELD_DURATION_MS: &str = "duration_ms";
+
+/// A single HTTP request/response pair.
+///
+/// # Example
+///
+/// ```
+/// use inklog::AccessLogEntry;
+/// use std::time::Duration;
+///
+/// let entry = AccessLogEntry::new("GET", "/health", 200, Duration::from_millis(3));
+/// let record = entry.to_record();
+/// assert_eq!(record.target, "inklog::access");
+/// assert_eq!(record.fields["http.status"], 200);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessLogEntry {
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub duration: Duration,
+}
+
+impl AccessLogEntry {
+    pub fn new(
+        method: impl Into<String>,
+        path: impl Into<String>,
+        status: u16,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            method: method.into(),
+            path: path.into(),
+            status,
+            duration,
+        }
+    }
+
+    /// Log level derived from the status code: 5xx → ERROR, 4xx → WARN, otherwise INFO.
+    pub fn level(&self) -> tracing::Level {
+        match self.status {
+            500..=599 => tracing::Level::ERROR,
+            400..=499 => tracing::Level::WARN,
+            _ => tracing::Level::INFO,
+        }
+    }
+
+    fn duration_ms(&self) -> u64 {
+        self.duration.as_millis().min(u64::MAX as u128) as u64
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "{} {} {} {}ms",
+            self.method,
+            self.path,
+            self.status,
+            self.duration_ms()
+        )
+    }
+
+    /// Builds a `LogRecord` carrying the standardized access-log fields.
+    pub fn to_record(&self) -> LogRecord {
+        let mut record =
+            LogRecord::new(self.level(), ACCESS_LOG_TARGET.to_string(), self.message());
+        record.fields.insert(
+            FIELD_HTTP_METHOD.to_string(),
+            Value::String(self.method.clone()),
+        );
+        record.fields.insert(
+            FIELD_HTTP_PATH.to_string(),
+            Value::String(self.path.clone()),
+        );
+        record
+            .fields
+            .insert(FIELD_HTTP_STATUS.to_string(), Value::from(self.status));
+        record.fields.insert(
+            FIELD_DURATION_MS.to_string(),
+            Value::from(self.duration_ms()),
+        );
+        record
+    }
+
+    /// Emits the entry through `tracing` so it flows through the installed pipeline.
+    pub fn emit(&self) {
+        let method = self.method.as_str();
+        let path = self.path.as_str();
+        let status = self.status;
+        let duration_ms = self.duration_ms();
+        let message = self.message();
+        match self.level() {
+            tracing::Level::ERROR => tracing::event!(
+                target: ACCESS_LOG_TARGET,
+                tracing::Level::ERROR,
+                { http.method = method, http.path = path, http.status = status, duration_ms = duration_ms },
+                "{}",
+                message
+            ),
+            tracing::Level::WARN => tracing::event!(
+                target: ACCESS_LOG_TARGET,
+                tracing::Level::WARN,
+                { http.method = method, http.path = path, http.status = status, duration_ms = duration_ms },
+                "{}",
+                message
+            ),
+            _ => tracing::event!(
+                target: ACCESS_LOG_TARGET,
+                tracing::Level::INFO,
+                { http.method = method, http.path = path, http.status = status, duration_ms = duration_ms },
+                "{}",
+                message
+            ),
+        }
+    }
+}
+
+/// Axum middleware that emits an [`AccessLogEntry`] for every request.
+///
+/// ```ignore
+/// let app = Router::new()
+///     .route("/", get(handler))
+///     .layer(axum::middleware::from_fn(inklog::access_log_middleware));
+/// ```
+#[cfg(feature = "http")]
+pub async fn access_log_middleware(
+    request: axum::http::Request<axum::body::Body>,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let start = std::time::Instant::now();
+    let response = next.run(request).await;
+    AccessLogEntry::new(method, path, response.status().as_u16(), start.elapsed()).emit();
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Metrics;
+    use crate::domain::core::LoggerSubscriber;
+    use crossbeam_channel::bounded;
+    use std::sync::Arc;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_to_record_has_standard_fields() {
+        let entry = AccessLogEntry::new("POST", "/api/users", 201, Duration::from_millis(42));
+        let record = entry.to_record();
+
+        assert_eq!(record.target, ACCESS_LOG_TARGET);
+        assert_eq!(record.level, "INFO");
+        assert_eq!(record.fields[FIELD_HTTP_METHOD], "POST");
+        assert_eq!(record.fields[FIELD_HTTP_PATH], "/api/users");
+        assert_eq!(record.fields[FIELD_HTTP_STATUS], 201);
+        assert_eq!(record.fields[FIELD_DURATION_MS], 42);
+        assert_eq!(record.message, "POST /api/users 201 42ms");
+    }
+
+    #[test]
+    fn test_level_follows_status_class() {
+        let level = |status| AccessLogEntry::new("GET", "/", status, Duration::ZERO).level();
+        assert_eq!(level(200), tracing::Level::INFO);
+        assert_eq!(level(304), tracing::Level::INFO);
+        assert_eq!(level(404), tracing::Level::WARN);
+        assert_eq!(level(503), tracing::Level::ERROR);
+    }
+
+    #[test]
+    fn test_emit_flows_through_subscriber() {
+        let (console_tx, _console_rx) = bounded(10);
+        let (async_tx, async_rx) = bounded(10);
+        let layer = LoggerSubscriber::new(console_tx, async_tx, Arc::new(Metrics::new()));
+        let registry = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(registry, || {
+            AccessLogEntry::new("GET", "/missing", 404, Duration::from_millis(7)).emit();
+        });
+
+        let record = async_rx.try_recv().expect("access record should be sent");
+        assert_eq!(record.target, ACCESS_LOG_TARGET);
+        assert_eq!(record.level, "WARN");
+        assert_eq!(record.fields[FIELD_HTTP_METHOD], "GET");
+        assert_eq!(record.fields[FIELD_HTTP_STATUS], 404);
+        assert_eq!(record.fields[FIELD_DURATION_MS], 7);
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! # 进程内告警规则
+//!
+//! 按 [`AlertRuleConfig`] 统计匹配的日志记录：在 `window_secs` 内匹配数达到
+//! `threshold` 时触发一次告警，并在 `cooldown_secs` 内抑制重复触发。每条规则
+//! 最多保留 `threshold` 个时间戳，内存占用有界。
+//!
+//! 告警的投递由调用方提供的通知函数负责；启用 `alerts` feature 时，
+//! [`LoggerManager`](crate::LoggerManager) 会以 JSON POST 到规则的 `webhook_url`。
+
+use crate::domain::core::subscriber::target_matches;
+use crate::{AlertRuleConfig, DataMasker, InklogError, LogRecord};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 一次规则触发产生的告警
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertEvent {
+    /// 规则名称
+    pub rule: String,
+    /// 窗口内的匹配记录数
+    pub count: usize,
+    /// 统计窗口（秒）
+    pub window_secs: u64,
+    /// 触发告警的最后一条记录；设置了 masker 时 `message` 已脱敏
+    pub target: String,
+    pub level: String,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+    /// 告警投递地址
+    #[serde(skip)]
+    pub webhook_url: String,
+}
+
+type Notifier = Box<dyn Fn(AlertEvent) + Send + Sync>;
+
+struct CompiledRule {
+    config: AlertRuleConfig,
+    level: tracing::Level,
+    pattern: Option<Regex>,
+    state: Mutex<RuleState>,
+}
+
+#[derive(Default)]
+struct RuleState {
+    /// 窗口内匹配记录的时间，长度不超过 `threshold`
+    hits: VecDeque<Instant>,
+    last_fired: Option<Instant>,
+}
+
+impl CompiledRule {
+    fn matches(&self, record: &LogRecord) -> bool {
+        // tracing::Level 中越严重的级别越小
+        let severe_enough = record
+            .level
+            .parse::<tracing::Level>()
+            .is_ok_and(|level| level <= self.level);
+        severe_enough
+            && self
+                .config
+                .target
+                .as_deref()
+                .is_none_or(|pattern| target_matches(pattern, &record.target))
+            && self
+                .pattern
+                .as_ref()
+                .is_none_or(|re| re.is_match(&record.message))
+    }
+
+    /// 记录一次匹配，达到阈值且不在冷却期内时返回窗口内的匹配数
+    fn record_hit(&self, now: Instant) -> Option<usize> {
+        let window = Duration::from_secs(self.config.window_secs);
+        let cooldown =
+            Duration::from_secs(self.config.cooldown_secs.unwrap_or(self.config.window_secs));
+        let threshold = self.config.threshold as usize;
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        while state
+            .hits
+            .front()
+            .is_some_and(|&hit| now.duration_since(hit) > window)
+        {
+            state.hits.pop_front();
+        }
+        state.hits.push_back(now);
+        if state.hits.len() > threshold {
+            state.hits.pop_front();
+        }
+
+        if state.hits.len() < threshold
+            || state
+                .last_fired
+                .is_some_and(|fired| now.duration_since(fired) < cooldown)
+        {
+            return None;
+        }
+        let count = state.hits.len();
+        state.hits.clear();
+        state.last_fired = Some(now);
+        Some(count)
+    }
+}
+
+/// 对日志记录求值告警规则
+pub struct AlertEvaluator {
+    rules: Vec<CompiledRule>,
+    notifier: Notifier,
+    masker: Option<DataMasker>,
+}
+
+impl AlertEvaluator {
+    /// 编译规则；`notifier` 在规则触发时于日志调用线程上执行，应避免阻塞
+    pub fn new(
+        rules: &[AlertRuleConfig],
+        notifier: impl Fn(AlertEvent) + Send + Sync + 'static,
+    ) -> Result<Self, InklogError> {
+        let rules = rules
+            .iter()
+            .map(|config| {
+                let level = config.level.parse::<tracing::Level>().map_err(|_| {
+                    InklogError::ConfigError(format!(
+                        "alert rule '{}': invalid level '{}'",
+                        config.name, config.level
+                    ))
+                })?;
+                let pattern = config
+                    .pattern
+                    .as_deref()
+                    .map(Regex::new)
+                    .transpose()
+                    .map_err(|e| {
+                        InklogError::ConfigError(format!(
+                            "alert rule '{}': invalid pattern: {}",
+                            config.name, e
+                        ))
+                    })?;
+                Ok(CompiledRule {
+                    config: config.clone(),
+                    level,
+                    pattern,
+                    state: Mutex::new(RuleState::default()),
+                })
+            })
+            .collect::<Result<Vec<_>, InklogError>>()?;
+
+        Ok(Self {
+            rules,
+            notifier: Box::new(notifier),
+            masker: None,
+        })
+    }
+
+    /// 构建告警前用 `masker` 脱敏触发记录的消息
+    pub fn with_masker(mut self, masker: DataMasker) -> Self {
+        self.masker = Some(masker);
+        self
+    }
+
+    /// 统计一条记录，触发的规则通过通知函数投递
+    pub fn observe(&self, record: &LogRecord) {
+        let now = Instant::now();
+        for rule in &self.rules {
+            if !rule.matches(record) {
+                continue;
+            }
+            if let Some(count) = rule.record_hit(now) {
+                (self.notifier)(AlertEvent {
+                    rule: rule.config.name.clone(),
+                    count,
+                    window_secs: rule.config.window_secs,
+                    target: record.target.clone(),
+                    level: record.level.clone(),
+                    message: self
+                        .masker
+                        .as_ref()
+                        .map_or_else(|| record.message.clone(), |m| m.mask(&record.message)),
+                    timestamp: record.timestamp,
+                    webhook_url: rule.config.webhook_url.clone(),
+                });
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for AlertEvaluator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlertEvaluator")
+            .field(
+                "rules",
+                &self
+                    .rules
+                    .iter()
+                    .map(|r| r.config.name.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn rule(threshold: u32) -> AlertRuleConfig {
+        AlertRuleConfig {
+            name: "db-errors".to_string(),
+            target: Some("app::db*".to_string()),
+            level: "error".to_string(),
+            pattern: Some("connection".to_string()),
+            threshold,
+            window_secs: 60,
+            cooldown_secs: None,
+            webhook_url: "http://127.0.0.1:9/alert".to_string(),
+        }
+    }
+
+    fn record(level: tracing::Level, target: &str, message: &str) -> LogRecord {
+        LogRecord::new(level, target.to_string(), message.to_string())
+    }
+
+    fn collecting_evaluator(
+        rules: &[AlertRuleConfig],
+    ) -> (AlertEvaluator, Arc<Mutex<Vec<AlertEvent>>>) {
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let sink = fired.clone();
+        let evaluator = AlertEvaluator::new(rules, move |event| sink.lock().unwrap().push(event))
+            .expect("rules should compile");
+        (evaluator, fired)
+    }
+
+    #[test]
+    fn test_rule_fires_once_when_threshold_reached() {
+        let (evaluator, fired) = collecting_evaluator(&[rule(3)]);
+
+        for _ in 0..10 {
+            evaluator.observe(&record(
+                tracing::Level::ERROR,
+                "app::db",
+                "connection refused",
+            ));
+        }
+
+        let fired = fired.lock().unwrap();
+        assert_eq!(fired.len(), 1, "debounced within the cooldown");
+        assert_eq!(fired[0].rule, "db-errors");
+        assert_eq!(fired[0].count, 3);
+        assert_eq!(fired[0].message, "connection refused");
+    }
+
+    #[test]
+    fn test_alert_message_is_masked() {
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let sink = fired.clone();
+        let evaluator =
+            AlertEvaluator::new(&[rule(1)], move |event| sink.lock().unwrap().push(event))
+                .unwrap()
+                .with_masker(DataMasker::new());
+
+        evaluator.observe(&record(
+            tracing::Level::ERROR,
+            "app::db",
+            "connection refused for alice@example.com",
+        ));
+
+        let fired = fired.lock().unwrap();
+        assert_eq!(fired.len(), 1);
+        assert!(fired[0].message.starts_with("connection refused for "));
+        assert!(!fired[0].message.contains("alice@example.com"));
+    }
+
+    #[test]
+    fn test_non_matching_records_are_ignored() {
+        let (evaluator, fired) = collecting_evaluator(&[rule(1)]);
+
+        evaluator.observe(&record(
+            tracing::Level::WARN,
+            "app::db",
+            "connection refused",
+        ));
+        evaluator.observe(&record(
+            tracing::Level::ERROR,
+            "app::http",
+            "connection refused",
+        ));
+        evaluator.observe(&record(tracing::Level::ERROR, "app::db", "query timeout"));
+        assert!(fired.lock().unwrap().is_empty());
+
+        evaluator.observe(&record(
+            tracing::Level::ERROR,
+            "app::db::pool",
+            "connection reset",
+        ));
+        assert_eq!(fired.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rule_state_is_bounded_by_threshold() {
+        let compiled = CompiledRule {
+            level: tracing::Level::ERROR,
+            pattern: None,
+            state: Mutex::new(RuleState::default()),
+            config: rule(5),
+        };
+
+        let now = Instant::now();
+        let fired = (0..1000).filter_map(|_| compiled.record_hit(now)).count();
+        assert_eq!(fired, 1);
+        // 冷却期内持续命中，保留的时间戳仍不超过阈值
+        assert_eq!(compiled.state.lock().unwrap().hits.len(), 5);
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        let mut config = rule(1);
+        config.pattern = Some("(".to_string());
+        assert!(AlertEvaluator::new(&[config], |_| {}).is_err());
+    }
+}
@@ -48,8 +48,9 @@
 //! | `inklog_sink_healthy` | Gauge | Sink 健康状态 |
 //! | `inklog_uptime_seconds` | Gauge | 运行时间（秒）|
 
+use super::process::ProcessStats;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
@@ -187,6 +188,88 @@ impl GaugeF64 {
     }
 }
 
+/// Bounds (microseconds) of the end-to-end latency histogram: 1ms, 5ms, 10ms, 50ms, 100ms, 500ms, 1s
+const DEFAULT_LATENCY_BOUNDS_US: [u64; 7] = [1000, 5000, 10000, 50000, 100000, 500000, 1000000];
+
+/// Default upper bounds (seconds) of the `inklog_sink_write_latency_seconds` buckets
+pub const DEFAULT_SINK_LATENCY_BUCKETS: &[f64] =
+    &[0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// 各 sink 的写入耗时直方图（Prometheus `le` 语义：耗时不超过上界即计入该桶）
+#[derive(Debug, Default)]
+pub(crate) struct SinkLatencyHistograms {
+    /// 桶上界（微秒），升序
+    bounds_us: Vec<u64>,
+    sinks: HashMap<String, SinkLatency>,
+}
+
+#[derive(Debug, Default)]
+struct SinkLatency {
+    /// 各桶的非累计计数，超过最大上界的样本只计入 `count`
+    buckets: Vec<u64>,
+    sum_us: u64,
+    count: u64,
+}
+
+impl SinkLatencyHistograms {
+    fn new(buckets_secs: &[f64]) -> Self {
+        let mut bounds_us: Vec<u64> = buckets_secs
+            .iter()
+            .filter(|secs| secs.is_finite() && **secs > 0.0)
+            .map(|secs| (secs * 1_000_000.0).round() as u64)
+            .collect();
+        bounds_us.sort_unstable();
+        bounds_us.dedup();
+        Self {
+            bounds_us,
+            sinks: HashMap::new(),
+        }
+    }
+
+    fn record(&mut self, sink: &str, duration: Duration) {
+        let micros = duration.as_micros().min(u64::MAX as u128) as u64;
+        let bucket_count = self.bounds_us.len();
+        let entry = self
+            .sinks
+            .entry(sink.to_string())
+            .or_insert_with(|| SinkLatency {
+                buckets: vec![0; bucket_count],
+                ..Default::default()
+            });
+        if let Some(index) = self.bounds_us.iter().position(|&bound| micros <= bound) {
+            entry.buckets[index] += 1;
+        }
+        entry.sum_us = entry.sum_us.saturating_add(micros);
+        entry.count += 1;
+    }
+
+    fn snapshot(&self) -> BTreeMap<String, SinkLatencySnapshot> {
+        self.sinks
+            .iter()
+            .map(|(name, latency)| {
+                let mut cumulative = 0;
+                let buckets = self
+                    .bounds_us
+                    .iter()
+                    .zip(&latency.buckets)
+                    .map(|(bound, count)| {
+                        cumulative += count;
+                        (*bound as f64 / 1_000_000.0, cumulative)
+                    })
+                    .collect();
+                (
+                    name.clone(),
+                    SinkLatencySnapshot {
+                        buckets,
+                        sum_seconds: latency.sum_us as f64 / 1_000_000.0,
+                        count: latency.count,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
 /// Histogram metric for latency distribution
 #[derive(Debug)]
 pub struct Histogram {
@@ -258,10 +341,15 @@ impl Histogram {
     }
 }
 
-#[derive(Debug, Serialize)]
+/// Point-in-time copy of all metrics, independent of the Prometheus text format.
+///
+/// Returned by [`Metrics::snapshot`] and embedded in [`HealthStatus`].
+#[derive(Debug, Clone, Serialize)]
 pub struct MetricsSnapshot {
     pub logs_written: u64,
     pub logs_dropped: u64,
+    pub records_sanitized: u64,
+    pub records_sampled: u64,
     pub channel_blocked: u64,
     pub sink_errors: u64,
     pub db_batch_size: i64,
@@ -273,6 +361,24 @@ pub struct MetricsSnapshot {
     pub latency_distribution: Vec<u64>,
     pub active_workers: i64,
     pub pool_hit_rate: f64,
+    pub uptime_seconds: u64,
+    /// Process gauges, `None` until sampled
+    pub process_open_fds: Option<u64>,
+    pub process_memory_bytes: Option<u64>,
+    pub process_threads: Option<u64>,
+    /// Whether each sink is operational (healthy or degraded)
+    pub sinks_operational: BTreeMap<String, bool>,
+    /// Per-sink write latency histograms, for sinks that have recorded a write
+    pub sink_write_latency: BTreeMap<String, SinkLatencySnapshot>,
+}
+
+/// Write latency histogram of one sink within a [`MetricsSnapshot`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SinkLatencySnapshot {
+    /// `(upper bound in seconds, cumulative count)` per bucket, ascending
+    pub buckets: Vec<(f64, u64)>,
+    pub sum_seconds: f64,
+    pub count: u64,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -319,6 +425,10 @@ pub struct Metrics {
     pub(crate) sink_errors_total: AtomicU64,
     pub(crate) lock_contention_total: AtomicU64,
     pub(crate) db_batch_records_total: AtomicU64,
+    pub(crate) records_sanitized_total: AtomicU64,
+    pub(crate) records_sampled_total: AtomicU64,
+    /// 丢弃数超过该值时整体状态降级（0 表示不检查）
+    pub(crate) dropped_degraded_threshold: AtomicU64,
     pub(crate) start_time: Instant,
 
     // Latency tracking
@@ -331,14 +441,24 @@ pub struct Metrics {
     pub(crate) db_batch_size: Gauge,
     pub(crate) pool_hit_rate: GaugeF64,
 
+    // Process resources (-1 = not sampled)
+    pub(crate) process_open_fds: Gauge,
+    pub(crate) process_memory_bytes: Gauge,
+    pub(crate) process_threads: Gauge,
+
     // Sink Health
     pub(crate) sink_health: Mutex<HashMap<String, SinkHealth>>,
+
+    // Per-sink write latency
+    pub(crate) sink_latency: Mutex<SinkLatencyHistograms>,
+
+    /// 预格式化的静态标签（`name="value",...`），为空时不追加
+    pub(crate) static_labels: Mutex<String>,
 }
 
 impl Default for Metrics {
     fn default() -> Self {
-        // Default buckets: 1ms, 5ms, 10ms, 50ms, 100ms, 500ms, 1s
-        let bounds = vec![1000, 5000, 10000, 50000, 100000, 500000, 1000000];
+        let bounds = DEFAULT_LATENCY_BOUNDS_US.to_vec();
         Self {
             logs_written_total: AtomicU64::new(0),
             logs_dropped_total: AtomicU64::new(0),
@@ -346,6 +466,9 @@ impl Default for Metrics {
             sink_errors_total: AtomicU64::new(0),
             lock_contention_total: AtomicU64::new(0),
             db_batch_records_total: AtomicU64::new(0),
+            records_sanitized_total: AtomicU64::new(0),
+            records_sampled_total: AtomicU64::new(0),
+            dropped_degraded_threshold: AtomicU64::new(0),
             start_time: Instant::now(),
             total_latency_us: AtomicU64::new(0),
             latency_count: AtomicU64::new(0),
@@ -353,7 +476,12 @@ impl Default for Metrics {
             active_workers: Gauge::new(0),
             db_batch_size: Gauge::new(0),
             pool_hit_rate: GaugeF64::new(0.0),
+            process_open_fds: Gauge::new(-1),
+            process_memory_bytes: Gauge::new(-1),
+            process_threads: Gauge::new(-1),
             sink_health: Mutex::new(HashMap::new()),
+            sink_latency: Mutex::new(SinkLatencyHistograms::new(DEFAULT_SINK_LATENCY_BUCKETS)),
+            static_labels: Mutex::new(String::new()),
         }
     }
 }
@@ -380,6 +508,16 @@ impl Metrics {
         self.logs_dropped_total.load(Ordering::Relaxed)
     }
 
+    /// Returns the total number of records whose control characters were sanitized.
+    pub fn records_sanitized(&self) -> u64 {
+        self.records_sanitized_total.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total number of records dropped by sampling rules.
+    pub fn records_sampled(&self) -> u64 {
+        self.records_sampled_total.load(Ordering::Relaxed)
+    }
+
     /// Returns the total number of times the channel was blocked.
     pub fn channel_blocked(&self) -> u64 {
         self.channel_send_blocked_total.load(Ordering::Relaxed)
@@ -429,6 +567,35 @@ impl Metrics {
         self.start_time.elapsed()
     }
 
+    /// 记录一次进程资源采样；未采样到的指标保持不变
+    pub fn record_process_stats(&self, stats: &ProcessStats) {
+        let to_gauge = |v: u64| i64::try_from(v).unwrap_or(i64::MAX);
+        if let Some(fds) = stats.open_fds {
+            self.process_open_fds.set(to_gauge(fds));
+        }
+        if let Some(bytes) = stats.memory_bytes {
+            self.process_memory_bytes.set(to_gauge(bytes));
+        }
+        if let Some(threads) = stats.threads {
+            self.process_threads.set(to_gauge(threads));
+        }
+    }
+
+    /// 最近一次采样的打开文件描述符数；尚未采样时为 `None`
+    pub fn process_open_fds(&self) -> Option<u64> {
+        u64::try_from(self.process_open_fds.get()).ok()
+    }
+
+    /// 最近一次采样的常驻内存字节数；尚未采样时为 `None`
+    pub fn process_memory_bytes(&self) -> Option<u64> {
+        u64::try_from(self.process_memory_bytes.get()).ok()
+    }
+
+    /// 最近一次采样的线程数；尚未采样时为 `None`
+    pub fn process_threads(&self) -> Option<u64> {
+        u64::try_from(self.process_threads.get()).ok()
+    }
+
     pub fn inc_logs_written(&self) {
         self.logs_written_total.fetch_add(1, Ordering::Relaxed);
     }
@@ -437,6 +604,57 @@ impl Metrics {
         self.logs_dropped_total.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// 设置降级阈值：累计丢弃数超过 `threshold` 时 [`get_status`](Self::get_status)
+    /// 将整体状态报告为 `Degraded`；`None` 关闭检查
+    pub fn set_dropped_degraded_threshold(&self, threshold: Option<u64>) {
+        self.dropped_degraded_threshold
+            .store(threshold.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// 设置 `inklog_sink_write_latency_seconds` 的桶上界（秒），并清空已记录的数据
+    ///
+    /// 非正数与非有限值被忽略，上界自动排序去重。
+    pub fn set_sink_latency_buckets(&self, buckets_secs: &[f64]) {
+        if let Ok(mut histograms) = self.sink_latency.lock() {
+            *histograms = SinkLatencyHistograms::new(buckets_secs);
+        }
+    }
+
+    /// 设置追加到每条 Prometheus 指标行的静态标签（按名称排序，值按 Prometheus 规则转义）
+    pub fn set_static_labels(&self, labels: &HashMap<String, String>) {
+        let mut pairs: Vec<(&String, &String)> = labels.iter().collect();
+        pairs.sort();
+        let formatted = pairs
+            .into_iter()
+            .map(|(name, value)| {
+                let escaped = value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n");
+                format!("{}=\"{}\"", name, escaped)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        if let Ok(mut static_labels) = self.static_labels.lock() {
+            *static_labels = formatted;
+        }
+    }
+
+    /// 记录一次 sink 写入耗时（含重试）
+    pub fn record_sink_write(&self, sink: &str, duration: Duration) {
+        if let Ok(mut histograms) = self.sink_latency.lock() {
+            histograms.record(sink, duration);
+        }
+    }
+
+    pub fn inc_records_sanitized(&self) {
+        self.records_sanitized_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_records_sampled(&self) {
+        self.records_sampled_total.fetch_add(1, Ordering::Relaxed);
+    }
+
     pub fn inc_channel_blocked(&self) {
         self.channel_send_blocked_total
             .fetch_add(1, Ordering::Relaxed);
@@ -589,9 +807,22 @@ impl Metrics {
             }
         };
 
-        let count = self.latency_count.load(Ordering::Relaxed);
-        let total = self.total_latency_us.load(Ordering::Relaxed);
-        let avg_latency = total.checked_div(count).unwrap_or(0);
+        // 丢弃过多时即使各 sink 正常也视为降级；Unhealthy/NotStarted 保持不变
+        let snapshot = self.snapshot();
+        let dropped = snapshot.logs_dropped;
+        let threshold = self.dropped_degraded_threshold.load(Ordering::Relaxed);
+        let overall_status = if threshold > 0 && dropped > threshold {
+            let reason = format!("{} logs dropped (threshold {})", dropped, threshold);
+            match overall_status {
+                SinkStatus::Healthy => SinkStatus::Degraded { reason },
+                SinkStatus::Degraded { reason: existing } => SinkStatus::Degraded {
+                    reason: format!("{}; {}", existing, reason),
+                },
+                other => other,
+            }
+        } else {
+            overall_status
+        };
 
         HealthStatus {
             overall_status,
@@ -601,161 +832,282 @@ impl Metrics {
             } else {
                 0.0
             },
-            uptime_seconds: self.uptime().as_secs(),
-            metrics: MetricsSnapshot {
-                logs_written: self.logs_written_total.load(Ordering::Relaxed),
-                logs_dropped: self.logs_dropped_total.load(Ordering::Relaxed),
-                channel_blocked: self.channel_send_blocked_total.load(Ordering::Relaxed),
-                sink_errors: self.sink_errors_total.load(Ordering::Relaxed),
-                db_batch_size: self.db_batch_size.get(),
-                db_batch_records_total: self.db_batch_records_total.load(Ordering::Relaxed),
-                avg_latency_us: avg_latency,
-                p50_latency_us: self.latency_histogram.p50(),
-                p95_latency_us: self.latency_histogram.p95(),
-                p99_latency_us: self.latency_histogram.p99(),
-                latency_distribution: self.latency_histogram.snapshot(),
-                active_workers: self.active_workers.get(),
-                pool_hit_rate: self.pool_hit_rate.get(),
-            },
+            uptime_seconds: snapshot.uptime_seconds,
+            metrics: snapshot,
             pool_stats: None,
             encryption_key_valid: true,
         }
     }
 
-    pub fn export_prometheus(&self) -> String {
-        let mut s = String::new();
-        s.push_str("# HELP inklog_logs_written_total Total logs successfully written\n");
-        s.push_str("# TYPE inklog_logs_written_total counter\n");
-        s.push_str(&format!(
-            "inklog_logs_written_total {}\n",
-            self.logs_written_total.load(Ordering::Relaxed)
-        ));
+    /// 当前指标的结构化快照，便于转发到 StatsD、OpenTelemetry 等后端
+    ///
+    /// [`export_prometheus`](Self::export_prometheus) 基于同一快照生成文本。
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let count = self.latency_count.load(Ordering::Relaxed);
+        let total = self.total_latency_us.load(Ordering::Relaxed);
+        let sinks_operational = match self.sink_health.lock() {
+            Ok(map) => map
+                .iter()
+                .map(|(name, health)| (name.clone(), health.status.is_operational()))
+                .collect(),
+            Err(_) => BTreeMap::new(),
+        };
+        let sink_write_latency = match self.sink_latency.lock() {
+            Ok(histograms) => histograms.snapshot(),
+            Err(_) => BTreeMap::new(),
+        };
 
-        s.push_str("# HELP inklog_logs_dropped_total Total logs dropped\n");
-        s.push_str("# TYPE inklog_logs_dropped_total counter\n");
-        s.push_str(&format!(
-            "inklog_logs_dropped_total {}\n",
-            self.logs_dropped_total.load(Ordering::Relaxed)
-        ));
+        MetricsSnapshot {
+            logs_written: self.logs_written_total.load(Ordering::Relaxed),
+            logs_dropped: self.logs_dropped_total.load(Ordering::Relaxed),
+            records_sanitized: self.records_sanitized_total.load(Ordering::Relaxed),
+            records_sampled: self.records_sampled_total.load(Ordering::Relaxed),
+            channel_blocked: self.channel_send_blocked_total.load(Ordering::Relaxed),
+            sink_errors: self.sink_errors_total.load(Ordering::Relaxed),
+            db_batch_size: self.db_batch_size.get(),
+            db_batch_records_total: self.db_batch_records_total.load(Ordering::Relaxed),
+            avg_latency_us: total.checked_div(count).unwrap_or(0),
+            p50_latency_us: self.latency_histogram.p50(),
+            p95_latency_us: self.latency_histogram.p95(),
+            p99_latency_us: self.latency_histogram.p99(),
+            latency_distribution: self.latency_histogram.snapshot(),
+            active_workers: self.active_workers.get(),
+            pool_hit_rate: self.pool_hit_rate.get(),
+            uptime_seconds: self.uptime().as_secs(),
+            process_open_fds: self.process_open_fds(),
+            process_memory_bytes: self.process_memory_bytes(),
+            process_threads: self.process_threads(),
+            sinks_operational,
+            sink_write_latency,
+        }
+    }
 
-        s.push_str("# HELP inklog_channel_blocked_total Total times channel was blocked\n");
-        s.push_str("# TYPE inklog_channel_blocked_total counter\n");
-        s.push_str(&format!(
-            "inklog_channel_blocked_total {}\n",
-            self.channel_send_blocked_total.load(Ordering::Relaxed)
-        ));
+    pub fn export_prometheus(&self) -> String {
+        let snapshot = self.snapshot();
+        let mut s = String::new();
 
-        s.push_str("# HELP inklog_sink_errors_total Total sink errors\n");
-        s.push_str("# TYPE inklog_sink_errors_total counter\n");
-        s.push_str(&format!(
-            "inklog_sink_errors_total {}\n",
-            self.sink_errors_total.load(Ordering::Relaxed)
-        ));
+        for (name, help, value) in [
+            (
+                "inklog_logs_written_total",
+                "Total logs successfully written",
+                snapshot.logs_written,
+            ),
+            (
+                "inklog_logs_dropped_total",
+                "Total logs dropped",
+                snapshot.logs_dropped,
+            ),
+            (
+                "inklog_records_sanitized_total",
+                "Records with control characters sanitized",
+                snapshot.records_sanitized,
+            ),
+            (
+                "inklog_records_sampled_total",
+                "Records dropped by sampling rules",
+                snapshot.records_sampled,
+            ),
+            (
+                "inklog_channel_blocked_total",
+                "Total times channel was blocked",
+                snapshot.channel_blocked,
+            ),
+            (
+                "inklog_sink_errors_total",
+                "Total sink errors",
+                snapshot.sink_errors,
+            ),
+        ] {
+            s.push_str(&format!("# HELP {} {}\n", name, help));
+            s.push_str(&format!("# TYPE {} counter\n", name));
+            s.push_str(&format!("{} {}\n", name, value));
+        }
 
         s.push_str("# HELP inklog_db_batch_size Database batch size in last flush\n");
         s.push_str("# TYPE inklog_db_batch_size gauge\n");
         s.push_str(&format!(
             "inklog_db_batch_size{{sink=\"database\"}} {}\n",
-            self.db_batch_size.get()
+            snapshot.db_batch_size
         ));
 
         s.push_str("# HELP inklog_db_batch_records_total Total records written by database batch flushes\n");
         s.push_str("# TYPE inklog_db_batch_records_total counter\n");
         s.push_str(&format!(
             "inklog_db_batch_records_total{{sink=\"database\"}} {}\n",
-            self.db_batch_records_total.load(Ordering::Relaxed)
+            snapshot.db_batch_records_total
         ));
 
         s.push_str("# HELP inklog_active_workers Current active worker threads\n");
         s.push_str("# TYPE inklog_active_workers gauge\n");
         s.push_str(&format!(
             "inklog_active_workers {}\n",
-            self.active_workers.get()
+            snapshot.active_workers
         ));
 
-        //
-        let count = self.latency_count.load(Ordering::Relaxed);
-        let total = self.total_latency_us.load(Ordering::Relaxed);
-        let avg_latency = total.checked_div(count).unwrap_or(0);
-
         s.push_str("# HELP inklog_avg_latency_us Average log processing latency in microseconds\n");
         s.push_str("# TYPE inklog_avg_latency_us gauge\n");
-        s.push_str(&format!("inklog_avg_latency_us {}\n", avg_latency));
-
-        // P50/P95/P99 latency percentiles
-        s.push_str("# HELP inklog_latency_p50_us P50 latency in microseconds\n");
-        s.push_str("# TYPE inklog_latency_p50_us gauge\n");
-        s.push_str(&format!(
-            "inklog_latency_p50_us {}\n",
-            self.latency_histogram.p50()
-        ));
-
-        s.push_str("# HELP inklog_latency_p95_us P95 latency in microseconds\n");
-        s.push_str("# TYPE inklog_latency_p95_us gauge\n");
         s.push_str(&format!(
-            "inklog_latency_p95_us {}\n",
-            self.latency_histogram.p95()
+            "inklog_avg_latency_us {}\n",
+            snapshot.avg_latency_us
         ));
 
-        s.push_str("# HELP inklog_latency_p99_us P99 latency in microseconds\n");
-        s.push_str("# TYPE inklog_latency_p99_us gauge\n");
-        s.push_str(&format!(
-            "inklog_latency_p99_us {}\n",
-            self.latency_histogram.p99()
-        ));
+        // P50/P95/P99 latency percentiles
+        for (name, help, value) in [
+            (
+                "inklog_latency_p50_us",
+                "P50 latency in microseconds",
+                snapshot.p50_latency_us,
+            ),
+            (
+                "inklog_latency_p95_us",
+                "P95 latency in microseconds",
+                snapshot.p95_latency_us,
+            ),
+            (
+                "inklog_latency_p99_us",
+                "P99 latency in microseconds",
+                snapshot.p99_latency_us,
+            ),
+        ] {
+            s.push_str(&format!("# HELP {} {}\n", name, help));
+            s.push_str(&format!("# TYPE {} gauge\n", name));
+            s.push_str(&format!("{} {}\n", name, value));
+        }
 
-        //
-        let uptime = self.uptime().as_secs();
-        if uptime > 0 {
+        if snapshot.uptime_seconds > 0 {
             s.push_str("# HELP inklog_uptime_seconds Uptime in seconds\n");
             s.push_str("# TYPE inklog_uptime_seconds gauge\n");
-            s.push_str(&format!("inklog_uptime_seconds {}\n", uptime));
+            s.push_str(&format!(
+                "inklog_uptime_seconds {}\n",
+                snapshot.uptime_seconds
+            ));
         }
 
         s.push_str("# HELP inklog_pool_hit_rate Pool hit rate percentage (0-100)\n");
         s.push_str("# TYPE inklog_pool_hit_rate gauge\n");
         s.push_str(&format!(
             "inklog_pool_hit_rate {}\n",
-            self.pool_hit_rate.get()
+            snapshot.pool_hit_rate
         ));
 
-        //
+        // Process resources, only once sampled
+        for (name, help, value) in [
+            (
+                "inklog_process_open_fds",
+                "Open file descriptors of the process",
+                snapshot.process_open_fds,
+            ),
+            (
+                "inklog_process_memory_bytes",
+                "Resident memory of the process in bytes",
+                snapshot.process_memory_bytes,
+            ),
+            (
+                "inklog_process_threads",
+                "Threads of the process",
+                snapshot.process_threads,
+            ),
+        ] {
+            if let Some(value) = value {
+                s.push_str(&format!("# HELP {} {}\n", name, help));
+                s.push_str(&format!("# TYPE {} gauge\n", name));
+                s.push_str(&format!("{} {}\n", name, value));
+            }
+        }
+
         s.push_str("# HELP inklog_sink_healthy Sink health status (1=healthy, 0=unhealthy)\n");
         s.push_str("# TYPE inklog_sink_healthy gauge\n");
-        if let Ok(health_map) = self.sink_health.lock() {
-            for (name, health) in health_map.iter() {
-                let value = if health.status.is_operational() { 1 } else { 0 };
-                s.push_str(&format!(
-                    "inklog_sink_healthy{{sink=\"{}\"}} {}\n",
-                    name, value
-                ));
-            }
+        for (name, operational) in &snapshot.sinks_operational {
+            s.push_str(&format!(
+                "inklog_sink_healthy{{sink=\"{}\"}} {}\n",
+                name,
+                u8::from(*operational)
+            ));
         }
 
-        //
         s.push_str("# HELP inklog_latency_bucket Latency histogram bucket\n");
         s.push_str("# TYPE inklog_latency_bucket counter\n");
-        let bounds = [1000, 5000, 10000, 50000, 100000, 500000, 1000000];
-        let buckets = self.latency_histogram.snapshot();
-        for (i, &bound) in bounds.iter().enumerate() {
-            if i < buckets.len() {
-                s.push_str(&format!(
-                    "inklog_latency_bucket{{le=\"{}\"}} {}\n",
-                    bound, buckets[i]
-                ));
-            }
+        for (bound, count) in DEFAULT_LATENCY_BOUNDS_US
+            .iter()
+            .zip(&snapshot.latency_distribution)
+        {
+            s.push_str(&format!(
+                "inklog_latency_bucket{{le=\"{}\"}} {}\n",
+                bound, count
+            ));
         }
-        //
-        let total_count: u64 = buckets.iter().sum();
+        let total_count: u64 = snapshot.latency_distribution.iter().sum();
         s.push_str(&format!(
             "inklog_latency_bucket{{le=\"+Inf\"}} {}\n",
             total_count
         ));
 
-        s
+        if !snapshot.sink_write_latency.is_empty() {
+            const NAME: &str = "inklog_sink_write_latency_seconds";
+            s.push_str(&format!("# HELP {} Sink write latency in seconds\n", NAME));
+            s.push_str(&format!("# TYPE {} histogram\n", NAME));
+            for (name, latency) in &snapshot.sink_write_latency {
+                for (bound, cumulative) in &latency.buckets {
+                    s.push_str(&format!(
+                        "{}_bucket{{sink=\"{}\",le=\"{}\"}} {}\n",
+                        NAME, name, bound, cumulative
+                    ));
+                }
+                s.push_str(&format!(
+                    "{}_bucket{{sink=\"{}\",le=\"+Inf\"}} {}\n",
+                    NAME, name, latency.count
+                ));
+                s.push_str(&format!(
+                    "{}_sum{{sink=\"{}\"}} {}\n",
+                    NAME, name, latency.sum_seconds
+                ));
+                s.push_str(&format!(
+                    "{}_count{{sink=\"{}\"}} {}\n",
+                    NAME, name, latency.count
+                ));
+            }
+        }
+
+        match self.static_labels.lock() {
+            Ok(labels) if !labels.is_empty() => with_static_labels(&s, &labels),
+            _ => s,
+        }
     }
 }
 
+/// 为每条指标行追加静态标签，与已有标签（如 `sink`、`le`）合并；注释行保持不变
+fn with_static_labels(text: &str, labels: &str) -> String {
+    let mut out = String::with_capacity(text.len() + text.lines().count() * labels.len());
+    for line in text.lines() {
+        if line.is_empty() || line.starts_with('#') {
+            out.push_str(line);
+        } else if let Some(close) = line
+            .find(['{', ' '])
+            .filter(|&i| line[i..].starts_with('{'))
+            .and_then(|_| line.rfind('}'))
+        {
+            let (head, rest) = line.split_at(close);
+            out.push_str(head);
+            if !head.ends_with('{') {
+                out.push(',');
+            }
+            out.push_str(labels);
+            out.push_str(rest);
+        } else if let Some(space) = line.find(' ') {
+            let (name, value) = line.split_at(space);
+            out.push_str(name);
+            out.push('{');
+            out.push_str(labels);
+            out.push('}');
+            out.push_str(value);
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    out
+}
+
 /// 降级状态追踪
 #[derive(Debug, Clone, PartialEq)]
 pub enum FallbackState {
@@ -1780,6 +2132,221 @@ mod metrics_tests {
         assert!(latency >= 100 + 200 + 300);
     }
 
+    #[test]
+    fn test_status_not_operational_before_first_write() {
+        let metrics = Metrics::new();
+        // 尚无 sink 成功写入：就绪探针据此返回 503
+        assert!(!metrics.get_status(0, 10).overall_status.is_operational());
+        metrics.update_sink_health("file", true, None);
+        assert!(metrics.get_status(0, 10).overall_status.is_operational());
+    }
+
+    #[test]
+    fn test_dropped_logs_above_threshold_degrade_status() {
+        let metrics = Metrics::new();
+        metrics.sink_started("file");
+        for _ in 0..3 {
+            metrics.inc_logs_dropped();
+        }
+        // 未设置阈值时不影响整体状态
+        assert_eq!(
+            metrics.get_status(0, 10).overall_status,
+            SinkStatus::Healthy
+        );
+
+        metrics.set_dropped_degraded_threshold(Some(3));
+        assert_eq!(
+            metrics.get_status(0, 10).overall_status,
+            SinkStatus::Healthy
+        );
+
+        metrics.inc_logs_dropped();
+        let status = metrics.get_status(0, 10);
+        assert_eq!(status.metrics.logs_dropped, 4);
+        assert_eq!(
+            status.overall_status,
+            SinkStatus::Degraded {
+                reason: "4 logs dropped (threshold 3)".to_string()
+            }
+        );
+
+        metrics.update_sink_health("file", false, Some("disk full".to_string()));
+        assert!(matches!(
+            metrics.get_status(0, 10).overall_status,
+            SinkStatus::Unhealthy { .. }
+        ));
+
+        metrics.set_dropped_degraded_threshold(None);
+        assert!(!matches!(
+            metrics.get_status(0, 10).overall_status,
+            SinkStatus::Degraded { .. }
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_matches_prometheus_export() {
+        let metrics = Metrics::new();
+        for _ in 0..3 {
+            metrics.inc_logs_written();
+        }
+        metrics.inc_logs_dropped();
+        metrics.inc_sink_error();
+        metrics.inc_sink_error();
+        metrics.set_db_batch_size(42);
+        metrics.record_latency(Duration::from_micros(2500));
+        metrics.update_sink_health("file", true, None);
+        metrics.update_sink_health("database", false, Some("down".to_string()));
+        metrics.record_sink_write("file", Duration::from_millis(2));
+
+        let snapshot = metrics.snapshot();
+        let output = metrics.export_prometheus();
+        let samples: HashMap<&str, f64> = output
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.rsplit_once(' '))
+            .map(|(name, value)| (name, value.parse().unwrap()))
+            .collect();
+
+        assert_eq!(snapshot.logs_written, 3);
+        assert_eq!(
+            samples["inklog_logs_written_total"],
+            snapshot.logs_written as f64
+        );
+        assert_eq!(
+            samples["inklog_logs_dropped_total"],
+            snapshot.logs_dropped as f64
+        );
+        assert_eq!(
+            samples["inklog_sink_errors_total"],
+            snapshot.sink_errors as f64
+        );
+        assert_eq!(
+            samples["inklog_db_batch_size{sink=\"database\"}"],
+            snapshot.db_batch_size as f64
+        );
+        assert_eq!(
+            samples["inklog_avg_latency_us"],
+            snapshot.avg_latency_us as f64
+        );
+        assert_eq!(
+            samples["inklog_latency_p99_us"],
+            snapshot.p99_latency_us as f64
+        );
+        assert_eq!(
+            snapshot.sinks_operational,
+            BTreeMap::from([("database".to_string(), false), ("file".to_string(), true)])
+        );
+        assert_eq!(samples["inklog_sink_healthy{sink=\"database\"}"], 0.0);
+        assert_eq!(samples["inklog_sink_healthy{sink=\"file\"}"], 1.0);
+
+        let file_latency = &snapshot.sink_write_latency["file"];
+        assert_eq!(file_latency.count, 1);
+        assert_eq!(
+            samples["inklog_sink_write_latency_seconds_count{sink=\"file\"}"],
+            file_latency.count as f64
+        );
+        assert_eq!(
+            samples["inklog_sink_write_latency_seconds_sum{sink=\"file\"}"],
+            file_latency.sum_seconds
+        );
+        for (bound, cumulative) in &file_latency.buckets {
+            let key = format!(
+                "inklog_sink_write_latency_seconds_bucket{{sink=\"file\",le=\"{}\"}}",
+                bound
+            );
+            assert_eq!(samples[key.as_str()], *cumulative as f64);
+        }
+
+        // 快照可直接序列化给其他后端
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["logs_written"], 3);
+        assert_eq!(json["sink_write_latency"]["file"]["count"], 1);
+    }
+
+    #[test]
+    fn test_export_prometheus_static_labels() {
+        let metrics = Metrics::new();
+        metrics.inc_logs_written();
+        metrics.update_sink_health("file", true, None);
+        metrics.record_sink_write("file", Duration::from_micros(50));
+        let labels = HashMap::from([
+            ("service".to_string(), "api".to_string()),
+            ("region".to_string(), "us-\"west\"".to_string()),
+        ]);
+        metrics.set_static_labels(&labels);
+
+        let output = metrics.export_prometheus();
+        let static_labels = "region=\"us-\\\"west\\\"\",service=\"api\"";
+        // counter / gauge 无自带标签
+        assert!(output.contains(&format!(
+            "inklog_logs_written_total{{{}}} 1\n",
+            static_labels
+        )));
+        assert!(output.contains(&format!("inklog_active_workers{{{}}} 0\n", static_labels)));
+        // 与自带标签合并
+        assert!(output.contains(&format!(
+            "inklog_sink_healthy{{sink=\"file\",{}}} 1\n",
+            static_labels
+        )));
+        assert!(output.contains(&format!(
+            "inklog_sink_write_latency_seconds_count{{sink=\"file\",{}}} 1\n",
+            static_labels
+        )));
+        // 注释行不变
+        assert!(output.contains("# TYPE inklog_logs_written_total counter\n"));
+        assert!(
+            output
+                .lines()
+                .filter(|line| !line.starts_with('#'))
+                .all(|line| line.contains(static_labels)),
+            "{}",
+            output
+        );
+
+        metrics.set_static_labels(&HashMap::new());
+        assert!(
+            metrics
+                .export_prometheus()
+                .contains("inklog_logs_written_total 1\n")
+        );
+    }
+
+    #[test]
+    fn test_export_prometheus_sink_write_latency_histogram() {
+        let metrics = Metrics::new();
+        assert!(
+            !metrics
+                .export_prometheus()
+                .contains("inklog_sink_write_latency_seconds")
+        );
+
+        metrics.set_sink_latency_buckets(&[0.01, 0.001, 0.1, -1.0]);
+        metrics.record_sink_write("file", Duration::from_micros(500));
+        metrics.record_sink_write("file", Duration::from_millis(1));
+        metrics.record_sink_write("file", Duration::from_millis(50));
+        metrics.record_sink_write("file", Duration::from_secs(2));
+        metrics.record_sink_write("database", Duration::from_millis(5));
+
+        let output = metrics.export_prometheus();
+        assert!(output.contains("# TYPE inklog_sink_write_latency_seconds histogram\n"));
+        for line in [
+            "inklog_sink_write_latency_seconds_bucket{sink=\"file\",le=\"0.001\"} 2\n",
+            "inklog_sink_write_latency_seconds_bucket{sink=\"file\",le=\"0.01\"} 2\n",
+            "inklog_sink_write_latency_seconds_bucket{sink=\"file\",le=\"0.1\"} 3\n",
+            "inklog_sink_write_latency_seconds_bucket{sink=\"file\",le=\"+Inf\"} 4\n",
+            "inklog_sink_write_latency_seconds_sum{sink=\"file\"} 2.0515\n",
+            "inklog_sink_write_latency_seconds_count{sink=\"file\"} 4\n",
+            "inklog_sink_write_latency_seconds_bucket{sink=\"database\",le=\"0.001\"} 0\n",
+            "inklog_sink_write_latency_seconds_bucket{sink=\"database\",le=\"0.01\"} 1\n",
+            "inklog_sink_write_latency_seconds_sum{sink=\"database\"} 0.005\n",
+            "inklog_sink_write_latency_seconds_count{sink=\"database\"} 1\n",
+        ] {
+            assert!(output.contains(line), "missing {:?} in:\n{}", line, output);
+        }
+        // 负数上界被忽略
+        assert!(!output.contains("le=\"-1\""));
+    }
+
     #[test]
     fn test_metrics_update_sink_health() {
         let metrics = Metrics::new();
@@ -2078,6 +2645,33 @@ mod metrics_tests {
         assert!(output.contains("inklog_sink_healthy{sink=\"file\"} 0"));
     }
 
+    #[test]
+    fn test_export_prometheus_process_gauges_only_after_sampling() {
+        let metrics = Metrics::new();
+        assert!(!metrics.export_prometheus().contains("inklog_process_"));
+        assert_eq!(metrics.process_open_fds(), None);
+
+        metrics.record_process_stats(&ProcessStats {
+            open_fds: Some(12),
+            memory_bytes: Some(4096),
+            threads: None,
+        });
+        let output = metrics.export_prometheus();
+        assert!(output.contains("inklog_process_open_fds 12"));
+        assert!(output.contains("inklog_process_memory_bytes 4096"));
+        assert!(!output.contains("inklog_process_threads"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_record_process_stats_from_linux_sample() {
+        let metrics = Metrics::new();
+        metrics.record_process_stats(&ProcessStats::sample());
+        assert!(metrics.process_open_fds().is_some_and(|fds| fds >= 1));
+        assert!(metrics.process_memory_bytes().is_some_and(|b| b > 0));
+        assert!(metrics.process_threads().is_some_and(|t| t >= 1));
+    }
+
     #[test]
     fn test_histogram_percentile_last_bucket() {
         let histogram = Histogram::new(vec![100, 500, 1000]);
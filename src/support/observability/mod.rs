@@ -2,9 +2,18 @@
 // SPDX-License-Identifier: MIT
 //! Observability module - monitoring and health.
 
+pub mod access_log;
+pub mod alerts;
 pub mod metrics;
+pub mod process;
+
+#[cfg(feature = "http")]
+pub use access_log::access_log_middleware;
+pub use access_log::{ACCESS_LOG_TARGET, AccessLogEntry};
+pub use alerts::{AlertEvaluator, AlertEvent};
 
 pub use metrics::{
-    FallbackConfig, FallbackState, GaugeF64, HealthStatus, Metrics, SinkHealth, SinkHealthMonitor,
-    SinkStatus,
+    FallbackConfig, FallbackState, GaugeF64, HealthStatus, Metrics, MetricsSnapshot, SinkHealth,
+    SinkHealthMonitor, SinkLatencySnapshot, SinkStatus,
 };
+pub use process::ProcessStats;
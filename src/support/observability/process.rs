@@ -0,0 +1,90 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! # 进程资源采样
+//!
+//! 采集当前进程的打开文件描述符数、常驻内存（RSS）与线程数，供长时间运行的
+//! 服务排查资源泄漏。Linux 下读取 `/proc/self`；其他平台尽力而为，无法获取的
+//! 指标返回 `None`。
+
+/// 一次进程资源采样结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessStats {
+    /// 打开的文件描述符数量
+    pub open_fds: Option<u64>,
+    /// 常驻内存大小（字节）
+    pub memory_bytes: Option<u64>,
+    /// 线程数量
+    pub threads: Option<u64>,
+}
+
+impl ProcessStats {
+    /// 采样当前进程的资源使用情况
+    pub fn sample() -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self::sample_linux()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Self::default()
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn sample_linux() -> Self {
+        let open_fds = std::fs::read_dir("/proc/self/fd")
+            .ok()
+            .map(|entries| entries.count() as u64);
+
+        let status = std::fs::read_to_string("/proc/self/status").unwrap_or_default();
+        let (memory_bytes, threads) = parse_proc_status(&status);
+
+        Self {
+            open_fds,
+            memory_bytes,
+            threads,
+        }
+    }
+}
+
+/// 从 `/proc/self/status` 内容中解析 `VmRSS`（转换为字节）与 `Threads`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_status(status: &str) -> (Option<u64>, Option<u64>) {
+    let mut memory_bytes = None;
+    let mut threads = None;
+    for line in status.lines() {
+        if let Some(rest) = line.strip_prefix("VmRSS:") {
+            memory_bytes = rest
+                .split_whitespace()
+                .next()
+                .and_then(|kb| kb.parse::<u64>().ok())
+                .map(|kb| kb * 1024);
+        } else if let Some(rest) = line.strip_prefix("Threads:") {
+            threads = rest.trim().parse().ok();
+        }
+    }
+    (memory_bytes, threads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_status_extracts_rss_and_threads() {
+        let status = "Name:\tinklog\nVmPeak:\t  20000 kB\nVmRSS:\t    1536 kB\nThreads:\t7\n";
+        assert_eq!(parse_proc_status(status), (Some(1536 * 1024), Some(7)));
+        assert_eq!(parse_proc_status(""), (None, None));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sample_reports_plausible_values_on_linux() {
+        let stats = ProcessStats::sample();
+        // 读取 /proc/self/fd 时该目录句柄本身也计入
+        assert!(stats.open_fds.expect("open fds should be sampled") >= 1);
+        let memory = stats.memory_bytes.expect("RSS should be sampled");
+        assert!(memory > 1024 * 1024 && memory < 64 * 1024 * 1024 * 1024);
+        assert!(stats.threads.expect("threads should be sampled") >= 1);
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! # 重复消息折叠
+//!
+//! 按 [`DedupConfig`] 折叠连续重复的记录：与上一条记录身份相同（级别、target、
+//! 消息，以及可选的字段）且距连续段开始不超过 `window_ms` 的记录被丢弃。
+//! 连续段因出现不同记录或超出窗口而结束时，生成一条
+//! "last message repeated N times" 记录；超出窗口后没有新记录时，由
+//! [`LogDeduplicator::expire`] 在后台定时结束连续段。
+
+use crate::{DedupConfig, LogRecord};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 当前的连续重复段
+struct Streak {
+    key: u64,
+    started: Instant,
+    /// 被丢弃的重复次数
+    repeats: u64,
+    /// 最近一条重复记录，用于生成汇总
+    last: Option<LogRecord>,
+}
+
+/// [`LogDeduplicator::observe`] 的结果
+#[derive(Debug, Default)]
+pub struct DedupOutcome {
+    /// 记录是否应继续发送
+    pub keep: bool,
+    /// 刚结束的连续段的汇总记录，应先于该记录发送
+    pub summary: Option<LogRecord>,
+}
+
+/// 折叠连续重复记录
+pub struct LogDeduplicator {
+    window: Duration,
+    include_fields: bool,
+    exclude_fields: Vec<String>,
+    streak: Mutex<Option<Streak>>,
+}
+
+impl LogDeduplicator {
+    pub fn new(config: &DedupConfig) -> Self {
+        Self {
+            window: Duration::from_millis(config.window_ms),
+            include_fields: config.include_fields,
+            exclude_fields: config.exclude_fields.clone(),
+            streak: Mutex::new(None),
+        }
+    }
+
+    /// 记录的身份哈希：级别、target、消息，以及按名称排序的字段
+    fn identity(&self, record: &LogRecord) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        record.level.hash(&mut hasher);
+        record.target.hash(&mut hasher);
+        record.message.hash(&mut hasher);
+        if self.include_fields {
+            let mut fields: Vec<_> = record
+                .fields
+                .iter()
+                .filter(|(name, _)| !self.exclude_fields.contains(name))
+                .collect();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            for (name, value) in fields {
+                name.hash(&mut hasher);
+                value.to_string().hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// 判断记录是否为重复；连续段结束时一并返回其汇总
+    pub fn observe(&self, record: &LogRecord) -> DedupOutcome {
+        let key = self.identity(record);
+        let now = Instant::now();
+        let mut streak = self.streak.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(current) = streak.as_mut()
+            && current.key == key
+            && now.duration_since(current.started) < self.window
+        {
+            current.repeats += 1;
+            current.last = Some(record.clone());
+            return DedupOutcome {
+                keep: false,
+                summary: None,
+            };
+        }
+
+        let summary = streak
+            .take()
+            .and_then(|ended| Self::summary(ended.repeats, ended.last?));
+        *streak = Some(Streak {
+            key,
+            started: now,
+            repeats: 0,
+            last: None,
+        });
+        DedupOutcome {
+            keep: true,
+            summary,
+        }
+    }
+
+    /// 连续段已超出窗口时结束它并返回其汇总，供后台定时器调用
+    pub fn expire(&self) -> Option<LogRecord> {
+        let mut streak = self.streak.lock().unwrap_or_else(|e| e.into_inner());
+        if streak
+            .as_ref()
+            .is_some_and(|current| current.started.elapsed() >= self.window)
+        {
+            streak
+                .take()
+                .and_then(|ended| Self::summary(ended.repeats, ended.last?))
+        } else {
+            None
+        }
+    }
+
+    /// 结束当前连续段并返回其汇总（无重复时为 `None`）
+    pub fn finish(&self) -> Option<LogRecord> {
+        let mut streak = self.streak.lock().unwrap_or_else(|e| e.into_inner());
+        streak
+            .take()
+            .and_then(|ended| Self::summary(ended.repeats, ended.last?))
+    }
+
+    /// 以最后一条重复记录为基础生成汇总，保留其级别、target 与时间
+    fn summary(repeats: u64, mut last: LogRecord) -> Option<LogRecord> {
+        if repeats == 0 {
+            return None;
+        }
+        last.message = if repeats == 1 {
+            "last message repeated 1 time".to_string()
+        } else {
+            format!("last message repeated {} times", repeats)
+        };
+        last.fields.clear();
+        last.fields.insert("repeated".to_string(), repeats.into());
+        Some(last)
+    }
+}
+
+impl std::fmt::Debug for LogDeduplicator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogDeduplicator")
+            .field("window", &self.window)
+            .field("include_fields", &self.include_fields)
+            .field("exclude_fields", &self.exclude_fields)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dedup(window_ms: u64) -> LogDeduplicator {
+        LogDeduplicator::new(&DedupConfig {
+            enabled: true,
+            window_ms,
+            include_fields: true,
+            exclude_fields: vec!["request_id".to_string()],
+        })
+    }
+
+    fn record(message: &str, request_id: u64, code: u64) -> LogRecord {
+        let mut record =
+            LogRecord::new(tracing::Level::WARN, "app".to_string(), message.to_string());
+        record
+            .fields
+            .insert("request_id".to_string(), request_id.into());
+        record.fields.insert("code".to_string(), code.into());
+        record
+    }
+
+    #[test]
+    fn test_excluded_fields_do_not_break_a_streak() {
+        let dedup = dedup(60_000);
+        assert!(dedup.observe(&record("retrying", 1, 7)).keep);
+        assert!(!dedup.observe(&record("retrying", 2, 7)).keep);
+        assert!(!dedup.observe(&record("retrying", 3, 7)).keep);
+
+        // code 参与身份判断，变化即结束连续段
+        let outcome = dedup.observe(&record("retrying", 4, 8));
+        assert!(outcome.keep);
+        let summary = outcome.summary.expect("streak of two repeats");
+        assert_eq!(summary.message, "last message repeated 2 times");
+        assert_eq!(summary.level, "WARN");
+        assert_eq!(summary.fields["repeated"], serde_json::json!(2));
+    }
+
+    #[test]
+    fn test_window_elapsed_starts_new_streak() {
+        let dedup = dedup(10);
+        assert!(dedup.observe(&record("tick", 1, 0)).keep);
+        assert!(!dedup.observe(&record("tick", 1, 0)).keep);
+        std::thread::sleep(Duration::from_millis(20));
+
+        let outcome = dedup.observe(&record("tick", 1, 0));
+        assert!(outcome.keep);
+        assert_eq!(
+            outcome.summary.map(|s| s.message).as_deref(),
+            Some("last message repeated 1 time")
+        );
+        assert!(dedup.finish().is_none());
+    }
+
+    #[test]
+    fn test_expire_ends_streak_without_later_records() {
+        let dedup = dedup(10);
+        assert!(dedup.observe(&record("tick", 1, 0)).keep);
+        assert!(!dedup.observe(&record("tick", 1, 0)).keep);
+        assert!(!dedup.observe(&record("tick", 1, 0)).keep);
+        assert!(dedup.expire().is_none());
+        std::thread::sleep(Duration::from_millis(20));
+
+        let summary = dedup.expire().expect("window elapsed");
+        assert_eq!(summary.message, "last message repeated 2 times");
+        assert!(dedup.expire().is_none());
+        // 过期后的相同记录开始新的连续段
+        assert!(dedup.observe(&record("tick", 1, 0)).keep);
+    }
+}
@@ -14,6 +14,10 @@
 //! - **基于模式的脱敏**：通过正则表达式模式检测敏感数据（邮箱、电话等）
 //! - **字段名检测**：通过字段名识别敏感字段（password、api_key 等）
 //! - **嵌套结构支持**：递归处理嵌套的 JSON 对象和数组
+//! - **字段名拒绝列表**：名称命中拒绝列表的字段（任意嵌套层级，大小写不敏感）整体替换为
+//!   `***MASKED***`，默认列表为 [`DEFAULT_MASKED_FIELDS`]
+//! - **内置检测器**：可选启用 [`DetectorKind`] 中的邮箱、信用卡号（Luhn 校验）与 IP 地址检测，
+//!   命中内容替换为固定标记（如 `[REDACTED_EMAIL]`）
 //! - **自定义规则**：支持多个脱敏规则，可配置模式
 //!
 //! ## 敏感字段检测
@@ -64,9 +68,105 @@
 //! - 支持禁用特定检测规则以减少开销
 
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+/// 默认的字段名拒绝列表，对应 `global.masked_fields` 的默认值
+pub const DEFAULT_MASKED_FIELDS: &[&str] = &["password", "token", "authorization"];
+
+/// 拒绝列表字段的替换值
+pub const MASKED_VALUE: &str = "***MASKED***";
+
+/// 可选启用的内置 PII 检测器，对应 `global.builtin_detectors`
+///
+/// 每种检测器将命中内容替换为固定标记，先于常规脱敏规则执行。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DetectorKind {
+    /// 邮箱地址，替换为 `[REDACTED_EMAIL]`
+    Email,
+    /// 13–19 位信用卡号（允许空格或 `-` 分隔），须通过 Luhn 校验，替换为
+    /// `[REDACTED_CREDIT_CARD]`
+    CreditCard,
+    /// IPv4 与 IPv6 地址，替换为 `[REDACTED_IP]`
+    Ip,
+}
+
+impl DetectorKind {
+    /// 命中内容的替换标记
+    pub fn token(self) -> &'static str {
+        match self {
+            DetectorKind::Email => "[REDACTED_EMAIL]",
+            DetectorKind::CreditCard => "[REDACTED_CREDIT_CARD]",
+            DetectorKind::Ip => "[REDACTED_IP]",
+        }
+    }
+
+    fn apply(self, text: &str) -> String {
+        match self {
+            DetectorKind::Email => DETECTOR_EMAIL_REGEX
+                .replace_all(text, self.token())
+                .into_owned(),
+            DetectorKind::CreditCard => DETECTOR_CARD_REGEX
+                .replace_all(text, |caps: &regex::Captures| {
+                    let candidate = &caps[0];
+                    if passes_luhn(candidate) {
+                        self.token().to_string()
+                    } else {
+                        candidate.to_string()
+                    }
+                })
+                .into_owned(),
+            DetectorKind::Ip => {
+                let text = DETECTOR_IPV6_REGEX.replace_all(text, |caps: &regex::Captures| {
+                    let candidate = &caps[0];
+                    let whole = caps.get(0).expect("group 0 always matches");
+                    // 前后紧邻标识符字符时（如 `std::net`）不是独立地址
+                    let is_boundary =
+                        |c: char| !(c.is_ascii_alphanumeric() || c == ':' || c == '.' || c == '_');
+                    let standalone = text[..whole.start()]
+                        .chars()
+                        .next_back()
+                        .is_none_or(is_boundary)
+                        && text[whole.end()..].chars().next().is_none_or(is_boundary);
+                    if standalone && candidate.parse::<std::net::Ipv6Addr>().is_ok() {
+                        self.token().to_string()
+                    } else {
+                        candidate.to_string()
+                    }
+                });
+                DETECTOR_IPV4_REGEX
+                    .replace_all(&text, self.token())
+                    .into_owned()
+            }
+        }
+    }
+}
+
+/// Luhn 校验：忽略分隔符，仅对 13–19 位数字生效
+fn passes_luhn(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
 /// Word-boundary regex patterns for sensitive field detection.
 /// Uses \b (word boundary) to avoid false positives like "cakey" matching "key".
 static SENSITIVE_FIELD_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
@@ -158,6 +258,10 @@ static SENSITIVE_FIELD_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
 #[derive(Debug, Clone, Default)]
 pub struct DataMasker {
     rules: Vec<MaskRule>,
+    /// 小写的字段名拒绝列表
+    masked_fields: Vec<String>,
+    /// 启用的内置检测器
+    detectors: Vec<DetectorKind>,
 }
 
 #[derive(Debug, Clone)]
@@ -182,7 +286,41 @@ impl DataMasker {
             MaskRule::new_generic_secret_rule(),
         ];
 
-        Self { rules }
+        Self {
+            rules,
+            masked_fields: DEFAULT_MASKED_FIELDS
+                .iter()
+                .map(|field| field.to_string())
+                .collect(),
+            detectors: Vec::new(),
+        }
+    }
+
+    /// 启用内置检测器（替换已启用的列表，重复项只执行一次）
+    pub fn with_builtin_detectors(mut self, detectors: &[DetectorKind]) -> Self {
+        self.detectors.clear();
+        for detector in detectors {
+            if !self.detectors.contains(detector) {
+                self.detectors.push(*detector);
+            }
+        }
+        self
+    }
+
+    /// 替换字段名拒绝列表（大小写不敏感的精确匹配）
+    pub fn with_masked_fields<S: AsRef<str>>(mut self, fields: &[S]) -> Self {
+        self.masked_fields = fields
+            .iter()
+            .map(|field| field.as_ref().to_lowercase())
+            .collect();
+        self
+    }
+
+    /// 字段名是否在拒绝列表中
+    pub fn is_masked_field(&self, field_name: &str) -> bool {
+        self.masked_fields
+            .iter()
+            .any(|field| field.eq_ignore_ascii_case(field_name))
     }
 
     /// 检查字段名是否为敏感字段（大小写不敏感，使用词边界正则避免误判）
@@ -199,6 +337,9 @@ impl DataMasker {
 
     pub fn mask(&self, text: &str) -> String {
         let mut result = text.to_string();
+        for detector in &self.detectors {
+            result = detector.apply(&result);
+        }
         for rule in &self.rules {
             result = rule.apply(&result);
         }
@@ -216,8 +357,8 @@ impl DataMasker {
                 }
             }
             Value::Object(map) => {
-                for (_, v) in map {
-                    self.mask_value(v);
+                for (k, v) in map {
+                    self.mask_entry(k, v);
                 }
             }
             _ => {}
@@ -225,8 +366,17 @@ impl DataMasker {
     }
 
     pub fn mask_hashmap(&self, map: &mut HashMap<String, Value>) {
-        for v in map.values_mut() {
-            self.mask_value(v);
+        for (k, v) in map.iter_mut() {
+            self.mask_entry(k, v);
+        }
+    }
+
+    /// 拒绝列表中的字段整体替换，其余字段递归按正则规则脱敏
+    fn mask_entry(&self, key: &str, value: &mut Value) {
+        if self.is_masked_field(key) {
+            *value = Value::String(MASKED_VALUE.to_string());
+        } else {
+            self.mask_value(value);
         }
     }
 }
@@ -246,6 +396,31 @@ static ID_CARD_REGEX: LazyLock<Regex> =
 static BANK_CARD_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(\d{4})(\d+)(\d{4})").expect("Invalid bank card regex"));
 
+/// 内置邮箱检测器 - 要求带顶级域名
+static DETECTOR_EMAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b[a-zA-Z0-9._%+-]+@[a-zA-Z0-9-]+(?:\.[a-zA-Z0-9-]+)*\.[a-zA-Z]{2,}\b")
+        .expect("Invalid email detector regex")
+});
+
+/// 内置信用卡检测器 - 13–19 位数字，允许空格或 `-` 分隔；命中后仍需 Luhn 校验
+static DETECTOR_CARD_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").expect("Invalid credit card detector regex")
+});
+
+/// 内置 IPv4 检测器 - 每段 0–255
+static DETECTOR_IPV4_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b",
+    )
+    .expect("Invalid IPv4 detector regex")
+});
+
+/// 内置 IPv6 候选 - 至少两个冒号的十六进制分组（可含尾部 IPv4），命中后由 `Ipv6Addr` 校验
+static DETECTOR_IPV6_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:[0-9a-fA-F]{0,4}:){2,7}(?:\d{1,3}(?:\.\d{1,3}){3}|[0-9a-fA-F]{0,4})")
+        .expect("Invalid IPv6 detector regex")
+});
+
 /// API Key 模式 - 匹配常见的 API key 格式
 static API_KEY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)(api[_-]?key[^\s:=]*\s*[=:]\s*[a-zA-Z0-9_-]{20,})")
@@ -692,6 +867,148 @@ mod tests {
         assert_eq!(map["name"], "John");
     }
 
+    #[test]
+    fn test_email_detector() {
+        let email = DetectorKind::Email;
+        assert_eq!(
+            email.apply("contact alice.smith+logs@mail.example.co.uk now"),
+            "contact [REDACTED_EMAIL] now"
+        );
+        assert_eq!(email.apply("user@localhost"), "user@localhost");
+        assert_eq!(email.apply("@handle and a@b"), "@handle and a@b");
+    }
+
+    #[test]
+    fn test_credit_card_detector_requires_luhn() {
+        let card = DetectorKind::CreditCard;
+        assert_eq!(
+            card.apply("card=4111111111111111"),
+            "card=[REDACTED_CREDIT_CARD]"
+        );
+        assert_eq!(
+            card.apply("paid with 5500 0000 0000 0004 today"),
+            "paid with [REDACTED_CREDIT_CARD] today"
+        );
+        assert_eq!(
+            card.apply("amex 378282246310005"),
+            "amex [REDACTED_CREDIT_CARD]"
+        );
+        // 16 位但未通过 Luhn 校验的 ID 保持原样
+        assert_eq!(
+            card.apply("order 1234567812345678"),
+            "order 1234567812345678"
+        );
+        assert_eq!(card.apply("4111111111111112"), "4111111111111112");
+        // 过短或过长的数字串不视为卡号
+        assert_eq!(card.apply("pin 123456"), "pin 123456");
+        assert_eq!(
+            card.apply("trace 41111111111111111111"),
+            "trace 41111111111111111111"
+        );
+    }
+
+    #[test]
+    fn test_ip_detector() {
+        let ip = DetectorKind::Ip;
+        assert_eq!(
+            ip.apply("from 192.168.1.20:8080"),
+            "from [REDACTED_IP]:8080"
+        );
+        assert_eq!(
+            ip.apply("peer 2001:db8::ff00:42:8329"),
+            "peer [REDACTED_IP]"
+        );
+        assert_eq!(ip.apply("loopback ::1 up"), "loopback [REDACTED_IP] up");
+        assert_eq!(ip.apply("mapped ::ffff:10.0.0.1"), "mapped [REDACTED_IP]");
+
+        assert_eq!(ip.apply("version 999.1.1.1"), "version 999.1.1.1");
+        assert_eq!(ip.apply("at 12:30:45"), "at 12:30:45");
+        assert_eq!(ip.apply("use std::net::Ipv6Addr"), "use std::net::Ipv6Addr");
+        assert_eq!(ip.apply("mac aa:bb:cc:dd:ee:ff"), "mac aa:bb:cc:dd:ee:ff");
+    }
+
+    #[test]
+    fn test_builtin_detectors_are_opt_in() {
+        let text = "alice@example.com paid 4111111111111111 from 10.1.2.3";
+
+        let default = DataMasker::new().mask(text);
+        assert!(!default.contains("[REDACTED_"));
+
+        let masker = DataMasker::new().with_builtin_detectors(&[
+            DetectorKind::Email,
+            DetectorKind::CreditCard,
+            DetectorKind::Ip,
+        ]);
+        assert_eq!(
+            masker.mask(text),
+            "[REDACTED_EMAIL] paid [REDACTED_CREDIT_CARD] from [REDACTED_IP]"
+        );
+
+        let mut value = serde_json::json!({"client": {"addr": "10.1.2.3"}});
+        masker.mask_value(&mut value);
+        assert_eq!(value["client"]["addr"], "[REDACTED_IP]");
+    }
+
+    #[test]
+    fn test_detector_kind_deserializes_snake_case() {
+        let kinds: Vec<DetectorKind> =
+            serde_json::from_str(r#"["email", "credit_card", "ip"]"#).unwrap();
+        assert_eq!(
+            kinds,
+            vec![
+                DetectorKind::Email,
+                DetectorKind::CreditCard,
+                DetectorKind::Ip
+            ]
+        );
+    }
+
+    #[test]
+    fn test_masked_fields_are_redacted_at_any_depth() {
+        let masker = DataMasker::new().with_masked_fields(&["password", "Authorization", "ssn"]);
+        let mut map: HashMap<String, Value> = HashMap::new();
+        map.insert(
+            "request".to_string(),
+            serde_json::json!({
+                "headers": {"AUTHORIZATION": "Bearer abc", "accept": "json"},
+                "users": [
+                    {"name": "A", "SSN": 123456789, "email": "a@b.com"},
+                    {"name": "B", "profile": {"Password": {"hash": "x"}}}
+                ]
+            }),
+        );
+        map.insert("password".to_string(), serde_json::json!(["p1", "p2"]));
+        map.insert("passwords_reset".to_string(), serde_json::json!(true));
+
+        masker.mask_hashmap(&mut map);
+
+        assert_eq!(map["password"], MASKED_VALUE);
+        // 只匹配完整字段名
+        assert_eq!(map["passwords_reset"], true);
+        let request = &map["request"];
+        assert_eq!(request["headers"]["AUTHORIZATION"], MASKED_VALUE);
+        assert_eq!(request["headers"]["accept"], "json");
+        assert_eq!(request["users"][0]["SSN"], MASKED_VALUE);
+        assert_eq!(request["users"][0]["name"], "A");
+        // 其余值仍按正则规则脱敏
+        assert_eq!(request["users"][0]["email"], "**@**.***");
+        assert_eq!(request["users"][1]["profile"]["Password"], MASKED_VALUE);
+    }
+
+    #[test]
+    fn test_default_masked_fields() {
+        let masker = DataMasker::new();
+        for field in ["password", "Token", "AUTHORIZATION"] {
+            assert!(masker.is_masked_field(field), "{field} should be masked");
+        }
+        assert!(!masker.is_masked_field("username"));
+        assert!(
+            !DataMasker::new()
+                .with_masked_fields::<&str>(&[])
+                .is_masked_field("password")
+        );
+    }
+
     #[test]
     fn test_mask_array_of_objects() {
         let masker = DataMasker::new();
@@ -2,13 +2,17 @@
 // SPDX-License-Identifier: MIT
 //! Processing module - log processing utilities.
 
+pub mod dedup;
 pub mod masking;
 pub mod object_pool;
+pub mod sampling;
 pub mod template;
 
+pub use dedup::{DedupOutcome, LogDeduplicator};
 pub use masking::DataMasker;
 pub use object_pool::{
     ObjectPool, ObjectPoolConfig, get_log_record, get_string_buffer, put_log_record,
     put_string_buffer,
 };
+pub use sampling::{LogSampler, SAMPLING_SUMMARY_TARGET};
 pub use template::LogTemplate;
@@ -0,0 +1,304 @@
+// Copyright (c) 2026 Kirky.X
+// SPDX-License-Identifier: MIT
+//! # 日志采样
+//!
+//! 按 [`SamplingConfig`] 在记录进入异步 channel 之前丢弃部分高频日志：
+//! `fixed` 模式每 `rate` 条保留一条，`token_bucket` 模式每秒最多保留 `rate` 条。
+//! 第一个匹配的规则决定记录是否保留，未匹配任何规则的记录始终保留。
+//! 被丢弃的数量按规则累计，每隔 `summary_interval_ms` 以一条汇总记录报告。
+
+use crate::domain::core::subscriber::target_matches;
+use crate::{InklogError, LogRecord, SamplingConfig, SamplingMode, SamplingRuleConfig};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// 汇总记录使用的 target，该 target 的记录不参与采样
+pub const SAMPLING_SUMMARY_TARGET: &str = "inklog::sampling";
+
+struct CompiledRule {
+    config: SamplingRuleConfig,
+    levels: Vec<tracing::Level>,
+    /// 匹配的记录数（`fixed` 模式）
+    seen: AtomicU64,
+    /// 自上次汇总以来被丢弃的记录数
+    suppressed: AtomicU64,
+    bucket: Mutex<TokenBucket>,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl CompiledRule {
+    fn matches(&self, record: &LogRecord) -> bool {
+        (self.levels.is_empty()
+            || record
+                .level
+                .parse::<tracing::Level>()
+                .is_ok_and(|level| self.levels.contains(&level)))
+            && self
+                .config
+                .target
+                .as_deref()
+                .is_none_or(|pattern| target_matches(pattern, &record.target))
+    }
+
+    fn admit(&self, now: Instant) -> bool {
+        let rate = self.config.rate.max(1);
+        match self.config.mode {
+            SamplingMode::Fixed => self
+                .seen
+                .fetch_add(1, Ordering::Relaxed)
+                .is_multiple_of(rate),
+            SamplingMode::TokenBucket => {
+                let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+                let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * rate as f64).min(rate as f64);
+                bucket.last_refill = now;
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        let target = self.config.target.as_deref().unwrap_or("*");
+        if self.config.levels.is_empty() {
+            target.to_string()
+        } else {
+            format!("{} [{}]", target, self.config.levels.join(","))
+        }
+    }
+}
+
+/// 按采样规则决定记录是否进入 channel
+pub struct LogSampler {
+    rules: Vec<CompiledRule>,
+    summary_interval: Duration,
+    started: Instant,
+    /// 上次汇总距 `started` 的毫秒数
+    last_summary_ms: AtomicU64,
+}
+
+impl LogSampler {
+    /// 编译规则
+    ///
+    /// # 错误
+    ///
+    /// 规则中的级别无法解析时返回 `ConfigError`。
+    pub fn new(config: &SamplingConfig) -> Result<Self, InklogError> {
+        let now = Instant::now();
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let levels = rule
+                    .levels
+                    .iter()
+                    .map(|level| {
+                        level.parse::<tracing::Level>().map_err(|_| {
+                            InklogError::ConfigError(format!(
+                                "sampling rule '{}': invalid level '{}'",
+                                rule.target.as_deref().unwrap_or("*"),
+                                level
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, InklogError>>()?;
+                Ok(CompiledRule {
+                    config: rule.clone(),
+                    levels,
+                    seen: AtomicU64::new(0),
+                    suppressed: AtomicU64::new(0),
+                    bucket: Mutex::new(TokenBucket {
+                        tokens: rule.rate as f64,
+                        last_refill: now,
+                    }),
+                })
+            })
+            .collect::<Result<Vec<_>, InklogError>>()?;
+
+        Ok(Self {
+            rules,
+            summary_interval: Duration::from_millis(config.summary_interval_ms),
+            started: now,
+            last_summary_ms: AtomicU64::new(0),
+        })
+    }
+
+    /// 返回记录是否保留；被丢弃时计入对应规则的汇总
+    pub fn sample(&self, record: &LogRecord) -> bool {
+        if record.target == SAMPLING_SUMMARY_TARGET {
+            return true;
+        }
+        let Some(rule) = self.rules.iter().find(|rule| rule.matches(record)) else {
+            return true;
+        };
+        if rule.admit(Instant::now()) {
+            return true;
+        }
+        rule.suppressed.fetch_add(1, Ordering::Relaxed);
+        false
+    }
+
+    /// 距上次汇总超过 `summary_interval` 时，为每个有丢弃的规则生成一条汇总记录
+    ///
+    /// 由记录路径与后台定时器共同调用，洪峰之后没有新记录时汇总也会按时发出。
+    pub fn take_summaries(&self) -> Vec<LogRecord> {
+        let now_ms = self.started.elapsed().as_millis() as u64;
+        let last = self.last_summary_ms.load(Ordering::Relaxed);
+        if now_ms.saturating_sub(last) < self.summary_interval.as_millis() as u64
+            || self
+                .last_summary_ms
+                .compare_exchange(last, now_ms, Ordering::AcqRel, Ordering::Relaxed)
+                .is_err()
+        {
+            return Vec::new();
+        }
+        self.summaries()
+    }
+
+    /// 不论间隔，立即为所有有丢弃的规则生成汇总（shutdown 时使用）
+    pub fn flush(&self) -> Vec<LogRecord> {
+        self.last_summary_ms
+            .store(self.started.elapsed().as_millis() as u64, Ordering::Relaxed);
+        self.summaries()
+    }
+
+    fn summaries(&self) -> Vec<LogRecord> {
+        self.rules
+            .iter()
+            .filter_map(|rule| {
+                let suppressed = rule.suppressed.swap(0, Ordering::Relaxed);
+                (suppressed > 0).then(|| {
+                    let rule_name = rule.describe();
+                    let mut record = LogRecord::new(
+                        tracing::Level::WARN,
+                        SAMPLING_SUMMARY_TARGET.to_string(),
+                        format!(
+                            "suppressed {} messages matching sampling rule {}",
+                            suppressed, rule_name
+                        ),
+                    );
+                    record
+                        .fields
+                        .insert("suppressed".to_string(), suppressed.into());
+                    record.fields.insert("rule".to_string(), rule_name.into());
+                    record
+                })
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for LogSampler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogSampler")
+            .field(
+                "rules",
+                &self
+                    .rules
+                    .iter()
+                    .map(CompiledRule::describe)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sampler(mode: SamplingMode, rate: u64) -> LogSampler {
+        LogSampler::new(&SamplingConfig {
+            rules: vec![SamplingRuleConfig {
+                target: Some("app::*".to_string()),
+                levels: vec!["warn".to_string()],
+                mode,
+                rate,
+            }],
+            summary_interval_ms: 1,
+        })
+        .unwrap()
+    }
+
+    fn record(level: tracing::Level, target: &str) -> LogRecord {
+        LogRecord::new(level, target.to_string(), "flood".to_string())
+    }
+
+    #[test]
+    fn test_fixed_mode_keeps_one_in_n() {
+        let sampler = sampler(SamplingMode::Fixed, 10);
+        let kept = (0..1000)
+            .filter(|_| sampler.sample(&record(tracing::Level::WARN, "app::loop")))
+            .count();
+        assert_eq!(kept, 100);
+    }
+
+    #[test]
+    fn test_token_bucket_caps_burst() {
+        let sampler = sampler(SamplingMode::TokenBucket, 50);
+        let kept = (0..1000)
+            .filter(|_| sampler.sample(&record(tracing::Level::WARN, "app::loop")))
+            .count();
+        // 一次突发最多消耗满桶的令牌，循环期间补充的令牌极少
+        assert!((50..60).contains(&kept), "kept {kept}");
+    }
+
+    #[test]
+    fn test_unmatched_records_are_kept() {
+        let sampler = sampler(SamplingMode::Fixed, 1000);
+        assert!(sampler.sample(&record(tracing::Level::WARN, "app::loop")));
+        assert!(!sampler.sample(&record(tracing::Level::WARN, "app::loop")));
+        assert!(sampler.sample(&record(tracing::Level::ERROR, "app::loop")));
+        assert!(sampler.sample(&record(tracing::Level::WARN, "other")));
+    }
+
+    #[test]
+    fn test_summary_reports_suppressed_count_once() {
+        let sampler = sampler(SamplingMode::Fixed, 4);
+        for _ in 0..8 {
+            sampler.sample(&record(tracing::Level::WARN, "app::loop"));
+        }
+        std::thread::sleep(Duration::from_millis(5));
+
+        let summaries = sampler.take_summaries();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].target, SAMPLING_SUMMARY_TARGET);
+        assert_eq!(summaries[0].fields["suppressed"], serde_json::json!(6));
+        assert!(sampler.sample(&summaries[0]));
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(sampler.take_summaries().is_empty());
+    }
+
+    #[test]
+    fn test_flush_ignores_summary_interval() {
+        let sampler = LogSampler::new(&SamplingConfig {
+            rules: vec![SamplingRuleConfig {
+                target: Some("app::*".to_string()),
+                levels: Vec::new(),
+                mode: SamplingMode::Fixed,
+                rate: 2,
+            }],
+            summary_interval_ms: 60_000,
+        })
+        .unwrap();
+        for _ in 0..4 {
+            sampler.sample(&record(tracing::Level::WARN, "app::loop"));
+        }
+
+        assert!(sampler.take_summaries().is_empty());
+        let summaries = sampler.flush();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].fields["suppressed"], serde_json::json!(2));
+        assert!(sampler.flush().is_empty());
+    }
+}
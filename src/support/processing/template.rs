@@ -269,6 +269,13 @@ impl LogTemplate {
     /// assert!(output.starts_with("[INFO] Task completed"));
     /// ```
     pub fn render(&self, record: &LogRecord) -> String {
+        self.render_with_level(record, &record.level)
+    }
+
+    /// 渲染日志记录，`{level}` 使用给定文本代替 `record.level`
+    ///
+    /// 控制台 sink 以此只为级别标记着色，其余内容保持原样。
+    pub fn render_with_level(&self, record: &LogRecord, level: &str) -> String {
         let mut result = String::new();
 
         for placeholder in &self.placeholders {
@@ -282,7 +289,7 @@ impl LogTemplate {
                     );
                 }
                 Placeholder::Level => {
-                    result.push_str(&record.level);
+                    result.push_str(level);
                 }
                 Placeholder::Target => {
                     result.push_str(&record.target);
@@ -3,7 +3,7 @@
 //! CLI 集成测试
 //!
 //! 使用 assert_cmd 测试 inklog-cli 二进制的端到端行为。
-//! 覆盖 validate/generate/decrypt 三个子命令的成功与错误路径。
+//! 覆盖 validate/generate/decrypt/rotate/tail 子命令的成功与错误路径。
 
 #![cfg(feature = "cli")]
 
@@ -66,6 +66,24 @@ level = "invalid_level"
         .stderr(predicate::str::contains("Invalid log level"));
 }
 
+#[test]
+fn test_cli_validate_strict_rejects_unknown_key() {
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = dir.path().join("typo.toml");
+    fs::write(&config_path, "[performance]\nchannel_capcity = 1000\n").expect("write config");
+
+    let mut cmd = Command::cargo_bin("inklog-cli").expect("inklog-cli binary not found");
+    cmd.args(["validate", "-c", config_path.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("inklog-cli").expect("inklog-cli binary not found");
+    cmd.args(["validate", "-c", config_path.to_str().unwrap(), "--strict"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("performance.channel_capcity"));
+}
+
 // ============================================================================
 // generate 子命令
 // ============================================================================
@@ -200,6 +218,243 @@ fn test_cli_decrypt_success() {
     );
 }
 
+#[test]
+fn test_cli_decrypt_to_stdout() {
+    use aes_gcm::aead::{Aead, KeyInit};
+    use aes_gcm::{Aes256Gcm, Nonce};
+    use base64::{Engine as _, engine::general_purpose};
+
+    let dir = TempDir::new().expect("tempdir");
+    let key = [0x42u8; 32];
+    let plaintext = "2026-01-01T00:00:00Z [ERROR] app - disk full\n".repeat(5000);
+
+    let nonce_bytes = [0xAAu8; 12];
+    let ciphertext = Aes256Gcm::new((&key).into())
+        .encrypt(&Nonce::from(nonce_bytes), plaintext.as_bytes())
+        .expect("encrypt");
+    let mut encrypted = b"ENCLOG1\0".to_vec();
+    encrypted.extend_from_slice(&1u16.to_le_bytes());
+    encrypted.extend_from_slice(&1u16.to_le_bytes());
+    encrypted.extend_from_slice(&nonce_bytes);
+    encrypted.extend_from_slice(&ciphertext);
+    let enc_path = dir.path().join("archive.enc");
+    fs::write(&enc_path, encrypted).expect("write enc file");
+
+    let mut cmd = Command::cargo_bin("inklog-cli").expect("inklog-cli binary not found");
+    cmd.args([
+        "decrypt",
+        "-i",
+        enc_path.to_str().unwrap(),
+        "--stdout",
+        "-k",
+        "INKLOG_CLI_TEST_KEY",
+    ])
+    .env("INKLOG_CLI_TEST_KEY", general_purpose::STANDARD.encode(key))
+    .assert()
+    .success()
+    .stdout(plaintext);
+
+    let entries = fs::read_dir(dir.path()).expect("read dir").count();
+    assert_eq!(entries, 1, "--stdout must not create an output file");
+}
+
+#[test]
+fn test_cli_decrypt_with_rotated_keys() {
+    use base64::{Engine as _, engine::general_purpose};
+    use inklog::sink::encryption::{EncryptionKey, encrypt_chunked};
+
+    let dir = TempDir::new().expect("tempdir");
+    let (old_key, new_key) = ([0x11u8; 32], [0x22u8; 32]);
+
+    for (id, key, plaintext) in [
+        ("2026-09", old_key, "written with the old key\n"),
+        ("2026-10", new_key, "written with the new key\n"),
+    ] {
+        let mut encrypted = Vec::new();
+        encrypt_chunked(
+            &EncryptionKey::new(id, key).expect("key"),
+            plaintext.as_bytes(),
+            &mut encrypted,
+            16,
+        )
+        .expect("encrypt");
+        let enc_path = dir.path().join(format!("{id}.enc"));
+        fs::write(&enc_path, encrypted).expect("write enc file");
+
+        let mut cmd = Command::cargo_bin("inklog-cli").expect("inklog-cli binary not found");
+        cmd.args([
+            "decrypt",
+            "-i",
+            enc_path.to_str().unwrap(),
+            "--stdout",
+            "-k",
+            "2026-10=INKLOG_CLI_NEW_KEY",
+            "-k",
+            "2026-09=INKLOG_CLI_OLD_KEY",
+        ])
+        .env(
+            "INKLOG_CLI_OLD_KEY",
+            general_purpose::STANDARD.encode(old_key),
+        )
+        .env(
+            "INKLOG_CLI_NEW_KEY",
+            general_purpose::STANDARD.encode(new_key),
+        )
+        .assert()
+        .success()
+        .stdout(plaintext);
+    }
+}
+
+// ============================================================================
+// rotate 子命令
+// ============================================================================
+
+#[test]
+fn test_cli_rotate_produces_compressed_file() {
+    let dir = TempDir::new().expect("tempdir");
+    let log_path = dir.path().join("app.log");
+    fs::write(&log_path, "first line\nsecond line\n").expect("write log");
+    let config_path = dir.path().join("inklog.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+[file_sink]
+enabled = true
+path = "{}"
+compress = true
+compression_format = "gzip"
+"#,
+            log_path.display()
+        ),
+    )
+    .expect("write config");
+
+    let mut cmd = Command::cargo_bin("inklog-cli").expect("inklog-cli binary not found");
+    cmd.args(["rotate", "-c", config_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Rotated:"));
+
+    let archives: Vec<_> = fs::read_dir(dir.path())
+        .expect("read dir")
+        .map(|entry| entry.expect("entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "gz"))
+        .collect();
+    assert_eq!(archives.len(), 1, "expected one rotated .gz file");
+    let mut decoder = flate2::read::GzDecoder::new(fs::File::open(&archives[0]).unwrap());
+    let mut content = String::new();
+    std::io::Read::read_to_string(&mut decoder, &mut content).expect("gunzip");
+    assert_eq!(content, "first line\nsecond line\n");
+    assert_eq!(fs::metadata(&log_path).expect("log file").len(), 0);
+}
+
+// ============================================================================
+// tail 子命令
+// ============================================================================
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn test_cli_tail_database_filters_by_level() {
+    use inklog::chrono::{Duration as ChronoDuration, Utc};
+    use inklog::sink::entity::sea_orm::{ConnectionTrait, Database};
+
+    let dir = TempDir::new().expect("tempdir");
+    let url = format!("sqlite:{}?mode=rwc", dir.path().join("logs.db").display());
+    let runtime = tokio::runtime::Runtime::new().expect("runtime");
+    runtime.block_on(async {
+        let conn = Database::connect(&url).await.expect("connect");
+        conn.execute_unprepared(
+            "CREATE TABLE logs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                level TEXT NOT NULL,
+                target TEXT NOT NULL,
+                message TEXT NOT NULL,
+                fields TEXT,
+                file TEXT,
+                line INTEGER,
+                thread_id TEXT NOT NULL,
+                module_path TEXT,
+                metadata TEXT
+            )",
+        )
+        .await
+        .expect("create table");
+        let now = Utc::now();
+        let rows = [
+            (
+                now - ChronoDuration::minutes(4),
+                "INFO",
+                "app::http",
+                "request served",
+            ),
+            (
+                now - ChronoDuration::minutes(3),
+                "WARN",
+                "app::http",
+                "slow request",
+            ),
+            (
+                now - ChronoDuration::minutes(2),
+                "DEBUG",
+                "app::db",
+                "query plan",
+            ),
+            (
+                now - ChronoDuration::minutes(1),
+                "ERROR",
+                "app::db",
+                "query failed",
+            ),
+        ];
+        for (timestamp, level, target, message) in rows {
+            conn.execute_unprepared(&format!(
+                "INSERT INTO logs (timestamp, level, target, message, fields, thread_id) \
+                 VALUES ('{}', '{}', '{}', '{}', '{{}}', 'main')",
+                timestamp.to_rfc3339(),
+                level,
+                target,
+                message
+            ))
+            .await
+            .expect("insert");
+        }
+    });
+
+    let config_path = dir.path().join("inklog.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+[global]
+format = "[{{level}}] {{target}} - {{message}}"
+
+[database_sink]
+enabled = true
+driver = "sqlite"
+url = "{}"
+table_name = "logs"
+"#,
+            url
+        ),
+    )
+    .expect("write config");
+
+    let mut cmd = Command::cargo_bin("inklog-cli").expect("inklog-cli binary not found");
+    cmd.args([
+        "tail",
+        "-c",
+        config_path.to_str().unwrap(),
+        "--level",
+        "warn",
+    ])
+    .assert()
+    .success()
+    .stdout("[WARN] app::http - slow request\n[ERROR] app::db - query failed\n");
+}
+
 // ============================================================================
 // 无参数 / 错误处理
 // ============================================================================
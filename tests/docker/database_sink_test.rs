@@ -51,6 +51,7 @@ async fn setup_sink(url: &str, batch_size: usize, flush_interval_ms: u64) -> Dat
         table_name: "logs".to_string(),
         archive_format: "json".to_string(),
         parquet_config: inklog::config::ParquetConfig::default(),
+        ..Default::default()
     };
     DatabaseSink::new_with_config(db, Some(config)).expect("Failed to create DatabaseSink")
 }
@@ -127,6 +127,8 @@ mod console_sink_e2e {
             colored: false,
             stderr_levels: vec!["error".to_string(), "warn".to_string()],
             masking_enabled: false,
+            synchronous: false,
+            ..Default::default()
         };
         let template = LogTemplate::default();
         let sink = ConsoleSink::new(config, template);
@@ -149,6 +151,8 @@ mod console_sink_e2e {
             colored: true,
             stderr_levels: vec!["error".to_string(), "warn".to_string()],
             masking_enabled: false,
+            synchronous: false,
+            ..Default::default()
         };
         let template = LogTemplate::default();
         let sink = ConsoleSink::new(config, template);
@@ -178,6 +182,8 @@ mod console_sink_e2e {
             colored: false,
             stderr_levels: vec![],
             masking_enabled: true,
+            synchronous: false,
+            ..Default::default()
         };
         let template = LogTemplate::default();
         let sink = ConsoleSink::new(config, template);
@@ -166,6 +166,7 @@ fn make_test_db_config(name: &str, enabled: bool) -> inklog::config::DatabaseSin
         table_name: "logs".to_string(),
         archive_format: "json".to_string(),
         parquet_config: inklog::config::ParquetConfig::default(),
+        ..Default::default()
     }
 }
 
@@ -544,6 +545,68 @@ async fn test_manager_block_strategy_high_load_sampling() {
     let _ = manager.shutdown();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_manager_routes_targets_to_separate_file_sinks() {
+    let temp_dir = tempdir().unwrap();
+    let access_path = temp_dir.path().join("access.log");
+    let app_path = temp_dir.path().join("app.log");
+
+    let mut config = inklog::InklogConfig::default();
+    config.global.level = "info".to_string();
+    config.console_sink = Some(inklog::ConsoleSinkConfig {
+        enabled: false,
+        ..Default::default()
+    });
+    config.file_sinks = vec![
+        inklog::FileSinkConfig {
+            name: "access".to_string(),
+            path: access_path.clone(),
+            targets: vec!["test::access*".to_string()],
+            ..Default::default()
+        },
+        inklog::FileSinkConfig {
+            name: "app".to_string(),
+            path: app_path.clone(),
+            targets: vec!["test::app*".to_string()],
+            ..Default::default()
+        },
+    ];
+    assert!(config.validate().is_ok());
+
+    let (manager, subscriber, filter) = LoggerManager::build_detached(
+        config,
+        #[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+        None,
+    )
+    .await
+    .unwrap();
+    let registry = tracing_subscriber::registry().with(subscriber).with(filter);
+
+    tracing::subscriber::with_default(registry, || {
+        for i in 0..20 {
+            tracing::info!(target: "test::access", message = format!("access-{i}"));
+            tracing::info!(target: "test::app::core", message = format!("app-{i}"));
+        }
+    });
+
+    manager.flush(Duration::from_secs(5)).unwrap();
+
+    let access = std::fs::read_to_string(&access_path).unwrap();
+    let app = std::fs::read_to_string(&app_path).unwrap();
+    assert_eq!(access.lines().count(), 20);
+    assert_eq!(app.lines().count(), 20);
+    assert!(access.lines().all(|line| line.contains("access-")));
+    assert!(app.lines().all(|line| line.contains("app-")));
+
+    let status = manager.get_health_status();
+    assert!(status.sinks.contains_key("access"));
+    assert!(status.sinks.contains_key("app"));
+    assert!(manager.flush_sink("access").is_ok());
+    assert!(manager.flush_sink("file").is_err());
+
+    let _ = manager.shutdown();
+}
+
 #[tokio::test]
 async fn test_manager_adaptive_channel_capacity_and_health_link() {
     let temp_dir = tempdir().unwrap();
@@ -1024,3 +1087,83 @@ fn test_error_display() {
     let error_str = format!("{}", error);
     assert!(error_str.contains("test error"));
 }
+
+// === LogReader 查询测试 ===
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_log_reader_filters_by_level_and_time() {
+    use inklog::chrono::{Duration as ChronoDuration, Utc};
+    use inklog::sink::entity::sea_orm::{ConnectionTrait, Database};
+    use inklog::sink::{LogQuery, LogReader};
+    use inklog::tracing::Level;
+
+    let dir = tempdir().unwrap();
+    let url = format!("sqlite:{}?mode=rwc", dir.path().join("logs.db").display());
+    let conn = Database::connect(&url).await.unwrap();
+    conn.execute_unprepared(
+        "CREATE TABLE logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp TEXT NOT NULL,
+            level TEXT NOT NULL,
+            target TEXT NOT NULL,
+            message TEXT NOT NULL,
+            fields TEXT,
+            file TEXT,
+            line INTEGER,
+            thread_id TEXT NOT NULL,
+            module_path TEXT,
+            metadata TEXT
+        )",
+    )
+    .await
+    .unwrap();
+
+    // 时间戳格式与 DbNexusAdapter 写入的一致（RFC 3339）
+    let now = Utc::now();
+    let rows = [
+        (now - ChronoDuration::hours(2), "ERROR", "app::db", "old error"),
+        (now - ChronoDuration::minutes(30), "INFO", "app::http", "recent info"),
+        (now - ChronoDuration::minutes(20), "WARN", "app::http", "recent warn"),
+        (now - ChronoDuration::minutes(10), "ERROR", "app::db", "recent error"),
+        (now - ChronoDuration::minutes(5), "DEBUG", "app::db", "recent debug"),
+    ];
+    for (timestamp, level, target, message) in rows {
+        conn.execute_unprepared(&format!(
+            "INSERT INTO logs (timestamp, level, target, message, fields, thread_id) \
+             VALUES ('{}', '{}', '{}', '{}', '{{}}', 'main')",
+            timestamp.to_rfc3339(),
+            level,
+            target,
+            message
+        ))
+        .await
+        .unwrap();
+    }
+
+    let reader = LogReader::new(conn, "logs");
+    let recent_problems = reader
+        .query(
+            &LogQuery::new()
+                .min_level(Level::WARN)
+                .since(now - ChronoDuration::hours(1)),
+        )
+        .await
+        .unwrap();
+    let messages: Vec<&str> = recent_problems.iter().map(|m| m.message.as_str()).collect();
+    assert_eq!(messages, vec!["recent error", "recent warn"]);
+
+    let db_logs = reader
+        .query(&LogQuery::new().target_contains("db").limit(2).offset(1))
+        .await
+        .unwrap();
+    let messages: Vec<&str> = db_logs.iter().map(|m| m.message.as_str()).collect();
+    assert_eq!(messages, vec!["recent error", "old error"]);
+
+    let older = reader
+        .query(&LogQuery::new().until(now - ChronoDuration::hours(1)))
+        .await
+        .unwrap();
+    assert_eq!(older.len(), 1);
+    assert_eq!(older[0].level, "ERROR");
+}
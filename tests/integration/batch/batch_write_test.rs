@@ -32,6 +32,7 @@ fn create_test_database_sink(
         table_name: "logs".to_string(),
         archive_format: "json".to_string(),
         parquet_config: inklog::config::ParquetConfig::default(),
+        ..Default::default()
     };
 
     let sink = DatabaseSink::new(&config).expect("Failed to create DatabaseSink");
@@ -147,6 +147,7 @@ fn verify_database_sink_sqlite() {
         table_name: "logs".to_string(),
         archive_format: "json".to_string(),
         parquet_config: inklog::config::ParquetConfig::default(),
+        ..Default::default()
     };
 
     let mut sink = DatabaseSink::new(&config).expect("Failed to create DatabaseSink");
@@ -244,6 +244,7 @@ async fn test_database_batch_write_dbnexus() {
         table_name: "logs".to_string(),
         archive_format: "json".to_string(),
         parquet_config: inklog::config::ParquetConfig::default(),
+        ..Default::default()
     };
 
     // 使用 MockDatabaseAdapter 进行测试
@@ -313,6 +314,7 @@ async fn test_database_timeout_flush_dbnexus() {
         table_name: "logs".to_string(),
         archive_format: "json".to_string(),
         parquet_config: inklog::config::ParquetConfig::default(),
+        ..Default::default()
     };
 
     // 使用 MockDatabaseAdapter 进行测试
@@ -481,9 +483,12 @@ async fn test_http_server_startup_with_default_config() {
         port,
         metrics_path: "/metrics".to_string(),
         health_path: "/health".to_string(),
+        ready_path: "/ready".to_string(),
+        ready_channel_watermark: 0.9,
         error_mode: HttpErrorMode::Strict,
         auth: None,
         ip_whitelist: None,
+        allow_unauthenticated_level_change: false,
     };
 
     let inklog_config = HttpInklogConfig {
@@ -508,9 +513,12 @@ async fn test_http_server_error_mode_panic() {
         port: 18081,
         metrics_path: "/metrics".to_string(),
         health_path: "/health".to_string(),
+        ready_path: "/ready".to_string(),
+        ready_channel_watermark: 0.9,
         error_mode: HttpErrorMode::Strict,
         auth: None,
         ip_whitelist: None,
+        allow_unauthenticated_level_change: false,
     };
 
     match config.error_mode {
@@ -530,9 +538,12 @@ async fn test_http_server_error_mode_warn() {
         port: 18082,
         metrics_path: "/metrics".to_string(),
         health_path: "/health".to_string(),
+        ready_path: "/ready".to_string(),
+        ready_channel_watermark: 0.9,
         error_mode: HttpErrorMode::Warn,
         auth: None,
         ip_whitelist: None,
+        allow_unauthenticated_level_change: false,
     };
 
     match config.error_mode {
@@ -552,9 +563,12 @@ async fn test_http_server_error_mode_strict() {
         port: 18083,
         metrics_path: "/metrics".to_string(),
         health_path: "/health".to_string(),
+        ready_path: "/ready".to_string(),
+        ready_channel_watermark: 0.9,
         error_mode: HttpErrorMode::Strict,
         auth: None,
         ip_whitelist: None,
+        allow_unauthenticated_level_change: false,
     };
 
     match config.error_mode {
@@ -973,6 +987,160 @@ fn test_parquet_schema_compatibility() {
     verify_parquet_schema(&result).expect("Schema verification should pass");
 }
 
+#[test]
+#[cfg(all(
+    any(feature = "sqlite", feature = "postgres", feature = "mysql"),
+    feature = "parquet"
+))]
+fn test_parquet_row_group_size_controls_row_group_count() {
+    let logs = create_test_logs(1_000);
+    let row_group_count = |config: &inklog::ParquetConfig| {
+        let data =
+            convert_logs_to_parquet(&logs, config).expect("Parquet conversion should succeed");
+        ParquetRecordBatchReaderBuilder::try_new(Bytes::from(data))
+            .expect("Parquet file should be readable")
+            .metadata()
+            .num_row_groups()
+    };
+
+    assert_eq!(row_group_count(&Default::default()), 1);
+
+    let config = inklog::ParquetConfig {
+        max_row_group_size: 300,
+        compression: inklog::ParquetCompression::Snappy,
+        enable_dictionary: false,
+        ..Default::default()
+    };
+    assert_eq!(row_group_count(&config), 4);
+
+    let data = convert_logs_to_parquet(&logs, &config).expect("Parquet conversion should succeed");
+    verify_parquet_file(&data).expect("Parquet file should be valid");
+    let metadata = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(data))
+        .expect("Parquet file should be readable")
+        .metadata()
+        .clone();
+    let column = metadata.row_group(0).column(0);
+    assert_eq!(column.compression(), parquet::basic::Compression::SNAPPY);
+    assert!(column.dictionary_page_offset().is_none());
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "sqlite", feature = "postgres", feature = "mysql"),
+    feature = "parquet"
+))]
+fn test_parquet_promoted_fields_become_typed_columns() {
+    use arrow_array::{Array, BooleanArray, Float64Array, Int64Array, StringArray};
+    use arrow_schema::DataType;
+    use inklog::{ParquetFieldType, ParquetPromotedField};
+
+    let mut logs = create_test_logs(3);
+    for (i, log) in logs.iter_mut().enumerate() {
+        log.fields
+            .insert("user_id".to_string(), serde_json::json!(1000 + i));
+        log.fields
+            .insert("duration_ms".to_string(), serde_json::json!(i as f64 * 1.5));
+        log.fields
+            .insert("route".to_string(), serde_json::json!("/checkout"));
+        log.fields
+            .insert("note".to_string(), serde_json::json!("kept in json"));
+    }
+    // 类型不符的值写为 null
+    logs[2]
+        .fields
+        .insert("cached".to_string(), serde_json::json!("yes"));
+    logs[0]
+        .fields
+        .insert("cached".to_string(), serde_json::json!(true));
+
+    let promoted = |name: &str, data_type| ParquetPromotedField {
+        name: name.to_string(),
+        data_type,
+    };
+    let config = inklog::ParquetConfig {
+        promoted_fields: vec![
+            promoted("user_id", ParquetFieldType::Int64),
+            promoted("duration_ms", ParquetFieldType::Float64),
+            promoted("cached", ParquetFieldType::Bool),
+            promoted("route", ParquetFieldType::Utf8),
+        ],
+        ..Default::default()
+    };
+    config.validate().expect("promoted fields should be valid");
+
+    let data = convert_logs_to_parquet(&logs, &config).expect("Parquet conversion should succeed");
+    let mut reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(data))
+        .expect("Parquet file should be readable")
+        .build()
+        .expect("reader should build");
+    let batch = reader
+        .next()
+        .expect("one batch")
+        .expect("batch should decode");
+
+    let schema = batch.schema();
+    let types: Vec<(&str, &DataType)> = schema.fields()[9..]
+        .iter()
+        .map(|f| (f.name().as_str(), f.data_type()))
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            ("user_id", &DataType::Int64),
+            ("duration_ms", &DataType::Float64),
+            ("cached", &DataType::Boolean),
+            ("route", &DataType::Utf8),
+        ]
+    );
+
+    let column = |name: &str| batch.column_by_name(name).expect("column should exist");
+    let user_ids = column("user_id")
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .expect("Int64 column");
+    assert_eq!(user_ids.values(), &[1000, 1001, 1002]);
+    let durations = column("duration_ms")
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .expect("Float64 column");
+    assert_eq!(durations.value(2), 3.0);
+    let cached = column("cached")
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .expect("Boolean column");
+    assert!(cached.value(0));
+    assert!(cached.is_null(1));
+    assert!(cached.is_null(2));
+    let routes = column("route")
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .expect("Utf8 column");
+    assert_eq!(routes.value(1), "/checkout");
+
+    let fields_json = column("fields")
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .expect("Utf8 column");
+    let remaining: serde_json::Value =
+        serde_json::from_str(fields_json.value(0)).expect("fields column is JSON");
+    assert_eq!(remaining, serde_json::json!({ "note": "kept in json" }));
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "sqlite", feature = "postgres", feature = "mysql"),
+    feature = "parquet"
+))]
+fn test_parquet_config_rejects_out_of_range_level() {
+    let config = inklog::ParquetConfig {
+        compression: inklog::ParquetCompression::Gzip,
+        compression_level: 15,
+        ..Default::default()
+    };
+    assert!(config.validate().is_err());
+    assert!(convert_logs_to_parquet(&create_test_logs(10), &config).is_err());
+}
+
 // ============ 稳定性集成测试 (integration::stability) ============
 
 use inklog::LoggerManager as StabilityLoggerManager;